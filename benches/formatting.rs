@@ -0,0 +1,86 @@
+/*
+ * Benchmarks for table formatting: 200 services rendered like a `--watch`
+ * refresh does, run with `cargo bench`.
+ *
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use yansi::{Color, Paint, Style};
+
+use vsv::formatting;
+
+/// The number of services in each table.
+const SERVICES: usize = 200;
+
+/// How long each benchmark runs for (after warming up for as long).
+const RUN_TIME: Duration = Duration::from_secs(2);
+
+/// Call `f` over and over for `RUN_TIME`, printing the mean time of a call.
+fn bench<F: FnMut()>(name: &str, mut f: F) {
+    let run = |f: &mut F| {
+        let start = Instant::now();
+        let mut calls = 0u32;
+        while start.elapsed() < RUN_TIME {
+            f();
+            calls += 1;
+        }
+        start.elapsed() / calls
+    };
+
+    run(&mut f);
+    println!("{:<40} {:>10.1?}/table", name, run(&mut f));
+}
+
+/// The cells of a table like `vsv status` has: status, name, state, pid...
+fn rows() -> Vec<Vec<(String, Style, usize)>> {
+    let green = Style::new(Color::Green);
+    (0..SERVICES)
+        .map(|i| {
+            vec![
+                ("✔".to_string(), green, 1),
+                (
+                    format!("service-with-a-long-name-{}", i),
+                    Style::default(),
+                    20,
+                ),
+                ("run".to_string(), green.bold(), 7),
+                ("enabled".to_string(), Style::default(), 9),
+                ((1000 + i).to_string(), Style::new(Color::Magenta), 8),
+                (format!("/usr/bin/daemon-{} --foreground", i), green, 17),
+                (format!("{} hours", i % 24), Style::default(), 0),
+            ]
+        })
+        .collect()
+}
+
+fn main() {
+    let rows = rows();
+
+    for color in [false, true] {
+        match color {
+            true => Paint::enable(),
+            false => Paint::disable(),
+        }
+        let suffix = if color { " (color)" } else { "" };
+
+        bench(&format!("format_status_line{}", suffix), || {
+            for row in &rows {
+                black_box(formatting::format_status_line(row));
+            }
+        });
+
+        let mut line = String::new();
+        bench(&format!("write_status_line, reused{}", suffix), || {
+            for row in &rows {
+                line.clear();
+                formatting::write_status_line(&mut line, row);
+                black_box(&line);
+            }
+        });
+    }
+}
@@ -0,0 +1,40 @@
+/*
+ * C interface to vsv, built with the `vsv-ffi` feature:
+ *
+ *     cargo rustc --release --lib --features vsv-ffi --crate-type cdylib
+ *
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+#ifndef VSV_H
+#define VSV_H
+
+#include <stddef.h>
+#include <sys/types.h>
+
+/*
+ * Write the services in svdir (like "/var/service") as a NUL-terminated JSON
+ * array into buf (of len bytes).  Returns the length of the JSON (without the
+ * NUL), if that's len or more nothing is written and the call should be
+ * retried with a bigger buffer.  Returns -1 on error.
+ */
+ssize_t vsv_services_json(const char *svdir, char *buf, size_t len);
+
+/*
+ * Get the services in svdir as a NUL-terminated JSON array, which must be
+ * freed with vsv_string_free().  Returns NULL on error.
+ */
+char *vsv_services_json_new(const char *svdir);
+
+/* Free a string returned by vsv_services_json_new(), NULL is ignored. */
+void vsv_string_free(char *s);
+
+/*
+ * Get the error message of the last failed call on this thread, NULL if there
+ * was none.  The string is valid until the next failed call.
+ */
+const char *vsv_last_error(void);
+
+#endif /* VSV_H */
@@ -6,9 +6,19 @@
 
 //! Argument parsing logic (via `clap`) for vsv.
 
+use std::env;
+use std::ffi::OsString;
 use std::path;
 
-use clap::{Parser, Subcommand};
+use anyhow::{bail, ensure, Context, Result};
+use clap::{
+    Arg, Args as ClapArgs, Command, CommandFactory, Parser, Subcommand,
+};
+
+use crate::config_file::ConfigFile;
+
+/// Prefix of the environment variables that set options, like `VSV_FORMAT`.
+pub const ENV_PREFIX: &str = "VSV_";
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about, verbatim_doc_comment, long_about = None)]
@@ -32,13 +42,113 @@ Common subcommands:
 "
 )]
 pub struct Args {
-    /// Enable or disable color output.
-    #[clap(short, long, value_name = "yes|no|auto")]
+    /// When to color output: auto (the default), always or never.
+    #[clap(short, long, value_name = "auto|always|never")]
     pub color: Option<String>,
 
+    /// Color theme, one of default, solarized, high-contrast or mono.
+    #[clap(long, value_name = "name")]
+    pub theme: Option<String>,
+
+    /// Status chars to use, one of unicode, ascii or nerd-font.
+    #[clap(long, value_name = "name")]
+    pub glyphs: Option<String>,
+
     /// Directory to look into, defaults to env SVDIR or /var/service if unset.
-    #[clap(short, long, parse(from_os_str), value_name = "dir")]
-    pub dir: Option<path::PathBuf>,
+    /// Can be given multiple times (or as a colon-separated list).
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        value_name = "dir",
+        multiple_occurrences = true
+    )]
+    pub dir: Vec<path::PathBuf>,
+
+    /// Output format for status, one of table, json, yaml, csv, markdown or
+    /// raw.
+    #[clap(short = 'F', long, global = true, value_name = "format")]
+    pub format: Option<String>,
+
+    /// Print each service using a format string like '%{name} %{state}'.
+    #[clap(long, global = true, value_name = "format")]
+    pub format_string: Option<String>,
+
+    /// Render all services through a handlebars-style template file.
+    #[clap(long, global = true, parse(from_os_str), value_name = "file")]
+    pub template: Option<path::PathBuf>,
+
+    /// Comma-separated list of columns to show (like `ps -o`), or a format.
+    #[clap(short = 'o', long, global = true, value_name = "columns")]
+    pub columns: Option<String>,
+
+    /// Separate raw output fields with NUL bytes, implies `-F raw`.
+    #[clap(short = '0', long, global = true)]
+    pub null: bool,
+
+    /// Only show services whose name matches this regex, repeatable.
+    #[clap(
+        short,
+        long = "match",
+        global = true,
+        multiple_occurrences = true,
+        value_name = "regex"
+    )]
+    pub matches: Vec<String>,
+
+    /// Hide services matching this glob (or /regex/), repeatable.
+    #[clap(
+        short = 'x',
+        long,
+        global = true,
+        multiple_occurrences = true,
+        value_name = "pattern"
+    )]
+    pub exclude: Vec<String>,
+
+    /// Only show services in this state (run, down, finish, zombie, stopped,
+    /// unsupervised), repeatable.
+    #[clap(
+        long,
+        global = true,
+        multiple_occurrences = true,
+        value_name = "state"
+    )]
+    pub state: Vec<String>,
+
+    /// Don't print the header (or blank lines around the table).
+    #[clap(long, global = true)]
+    pub no_header: bool,
+
+    /// Only show services with problems, print nothing if all are up.
+    #[clap(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Run each service's ./check script and show a pass/fail column.
+    #[clap(long, global = true)]
+    pub check: bool,
+
+    /// Exit non-zero if any service is down (2), flapping (4) or unreadable
+    /// (8), or'd together.
+    #[clap(long, global = true)]
+    pub exit_code: bool,
+
+    /// Print a summary footer with service counts.
+    #[clap(long, global = true)]
+    pub summary: bool,
+
+    /// Sort services by a column (like name, state, pid or uptime).
+    #[clap(long, global = true, value_name = "column")]
+    pub sort: Option<String>,
+
+    /// Reverse the sort order.
+    #[clap(long, global = true)]
+    pub reverse: bool,
+
+    /// Group table output into sections by "state" or "origin" (the default
+    /// with multiple directories).
+    #[clap(long, global = true, value_name = "field")]
+    pub group_by: Option<String>,
 
     /// Show log processes, this is a shortcut for `status -l`.
     #[clap(short, long)]
@@ -48,10 +158,115 @@ pub struct Args {
     #[clap(short, long)]
     pub tree: bool,
 
-    /// User mode, this is a shortcut for `-d ~/runit/service`.
+    /// User mode, a shortcut for `-d ~/runit/service` (or
+    /// `$XDG_CONFIG_HOME/service` if only that exists).
     #[clap(short, long)]
     pub user: bool,
 
+    /// Show both the system and user service trees (with an origin column).
+    #[clap(short = 'U', long, conflicts_with_all = &["dir", "user"])]
+    pub both: bool,
+
+    /// Show the services of another machine (like user@hostname) over ssh,
+    /// repeatable.
+    #[clap(
+        short = 'H',
+        long = "host",
+        multiple_occurrences = true,
+        value_name = "host"
+    )]
+    pub hosts: Vec<String>,
+
+    /// Redraw status output every interval seconds (--watch=2, the default).
+    #[clap(
+        long,
+        global = true,
+        value_name = "interval",
+        min_values = 0,
+        max_values = 1,
+        require_equals = true
+    )]
+    pub watch: Option<Option<f64>>,
+
+    /// Show recent state and pid transitions below the table in watch mode.
+    #[clap(long, global = true)]
+    pub events: bool,
+
+    /// Send desktop notifications when services go down or flap in watch mode.
+    #[clap(long, global = true)]
+    pub notify: bool,
+
+    /// Run a command (with `sh -c`) when a service changes state in watch mode.
+    #[clap(
+        long = "hook",
+        global = true,
+        multiple_occurrences = true,
+        value_name = "cmd"
+    )]
+    pub hooks: Vec<String>,
+
+    /// Never pipe output through a pager.
+    #[clap(long, global = true)]
+    pub no_pager: bool,
+
+    /// Print what would be done by state-changing subcommands without doing it.
+    #[clap(long, global = true)]
+    pub dry_run: bool,
+
+    /// Show the processes descended from each service's process.
+    #[clap(long, global = true)]
+    pub children: bool,
+
+    /// Percent of a process's open files limit at which its fds are shown in
+    /// red (default 80).
+    #[clap(long, global = true, value_name = "percent")]
+    pub fd_threshold: Option<f64>,
+
+    /// Show the full command line (every argument) in the command column.
+    #[clap(long, global = true)]
+    pub full_command: bool,
+
+    /// Show user names (instead of uids) in the user column.
+    #[clap(long, global = true)]
+    pub user_names: bool,
+
+    /// How to show times, one of relative (like "3 days", the default),
+    /// compact ("3d4h"), absolute or iso8601.
+    #[clap(long, global = true, value_name = "style")]
+    pub time_style: Option<String>,
+
+    /// strftime(3) format of absolute times, like '%Y-%m-%d %H:%M' (implies
+    /// --time-style absolute).
+    #[clap(long, global = true, value_name = "format")]
+    pub time_format: Option<String>,
+
+    /// Units of time to show in the time column, like 2 for "1 hour 59
+    /// minutes" (default 1).
+    #[clap(long, global = true, value_name = "n")]
+    pub time_units: Option<usize>,
+
+    /// Supervision suite to use: auto (default), runit, s6, daemontools or
+    /// systemd.
+    #[clap(long, global = true, value_name = "name")]
+    pub backend: Option<String>,
+
+    /// Seconds to wait for external programs and services (default 10, 0 for
+    /// none).
+    #[clap(long, global = true, value_name = "secs")]
+    pub timeout: Option<f64>,
+
+    /// Services to read at once (default the number of CPUs, at least 16).
+    #[clap(short, long, global = true, value_name = "n")]
+    pub jobs: Option<usize>,
+
+    /// Wide output, give twice (-ww) to never truncate columns.
+    #[clap(short, long, global = true, parse(from_occurrences))]
+    pub wide: usize,
+
+    /// Don't load defaults from the config file.
+    #[clap(long, global = true)]
+    pub no_config: bool,
+
     /// Increase Verbosity.
     #[clap(short, long, parse(from_occurrences))]
     pub verbose: usize,
@@ -77,16 +292,548 @@ pub enum Commands {
     },
 
     /// Enable service(s).
-    Enable { services: Vec<String> },
+    Enable {
+        /// Symlink the service from the source directory instead.
+        #[clap(long)]
+        link: bool,
+
+        /// Source directory for --link (implies --link).
+        #[clap(long, parse(from_os_str), value_name = "dir")]
+        source: Option<path::PathBuf>,
+
+        services: Vec<String>,
+    },
 
     /// Disable service(s).
-    Disable { services: Vec<String> },
+    Disable {
+        /// Remove the service symlink instead.
+        #[clap(long)]
+        link: bool,
+
+        /// Source directory for --link (implies --link).
+        #[clap(long, parse(from_os_str), value_name = "dir")]
+        source: Option<path::PathBuf>,
+
+        services: Vec<String>,
+    },
+
+    /// Start service(s) (and keep them running).
+    Up(ControlArgs),
+
+    /// Stop service(s) (and keep them stopped).
+    Down(ControlArgs),
+
+    /// Restart service(s).
+    Restart(ControlArgs),
+
+    /// Start service(s) once (don't restart them if they exit).
+    Once(ControlArgs),
+
+    /// Send SIGHUP to service(s).
+    Hup(ControlArgs),
+
+    /// Send SIGTERM to service(s).
+    Term(ControlArgs),
+
+    /// Send SIGKILL to service(s).
+    Kill(ControlArgs),
+
+    /// Send SIGALRM to service(s).
+    Alarm(ControlArgs),
+
+    /// Send SIGINT to service(s).
+    Interrupt(ControlArgs),
+
+    /// Send SIGQUIT to service(s).
+    Quit(ControlArgs),
+
+    /// Send SIGUSR1 to service(s).
+    Usr1(ControlArgs),
+
+    /// Send SIGUSR2 to service(s).
+    Usr2(ControlArgs),
+
+    /// Print Prometheus metrics for service(s).
+    Metrics {
+        /// Write metrics to this file (atomically) instead of stdout.
+        #[clap(long, parse(from_os_str), value_name = "file")]
+        textfile: Option<path::PathBuf>,
+
+        filter: Vec<String>,
+    },
+
+    /// Print a Nagios/Icinga check plugin status line (and exit with its code).
+    CheckPlugin {
+        /// Comma-separated services that are CRITICAL (not WARNING) when down
+        /// or missing.
+        #[clap(long, value_name = "services")]
+        critical_down: Option<String>,
+
+        filter: Vec<String>,
+    },
+
+    /// Serve Prometheus metrics over HTTP.
+    Exporter {
+        /// Address to listen on.
+        #[clap(long, value_name = "addr")]
+        listen: Option<String>,
+
+        filter: Vec<String>,
+    },
+
+    /// Serve service status (and restarts) as a JSON API over HTTP.
+    Serve {
+        /// Address to listen on.
+        #[clap(long, value_name = "addr")]
+        listen: Option<String>,
+
+        filter: Vec<String>,
+    },
+
+    /// List available services (in the source directory) that aren't enabled.
+    Avail {
+        /// Source directory to look in.
+        #[clap(long, parse(from_os_str), value_name = "dir")]
+        source: Option<path::PathBuf>,
+
+        filter: Vec<String>,
+    },
+
+    /// Print the most recent lines logged by a service's svlogd.
+    Log {
+        /// Number of lines to print (default 10).
+        #[clap(short = 'n', long, value_name = "num")]
+        lines: Option<usize>,
+
+        /// Keep printing lines as they are logged (following log rotations).
+        #[clap(short, long)]
+        follow: bool,
+
+        service: Vec<String>,
+    },
+
+    /// Show details about a single service.
+    Show {
+        /// Print the environment of the service's process.
+        #[clap(long)]
+        env: bool,
+
+        /// Print the resource limits of the service's process.
+        #[clap(long)]
+        limits: bool,
+
+        /// Hide the values of variables that look like secrets (with --env).
+        #[clap(long, requires = "env")]
+        redact: bool,
+
+        service: Vec<String>,
+    },
+
+    /// Fuzzy find a service and run an action (like restart) on it.
+    Pick { action: Option<String> },
+
+    /// Interactive, full screen service list.
+    Tui { filter: Vec<String> },
+
+    /// Print a shell completion script (bash, zsh or fish).
+    Completions {
+        /// List the service names to complete (used by the scripts).
+        #[clap(long, hide = true)]
+        services: bool,
+
+        shell: Option<String>,
+    },
 
     /// Pass arguments directly to `sv`.
     #[clap(external_subcommand)]
     External(Vec<String>),
 }
 
-pub fn parse() -> Args {
-    Args::parse()
+/// Arguments shared by every service control subcommand (like `vsv up`).
+#[derive(Debug, ClapArgs)]
+pub struct ControlArgs {
+    /// Act on every service in the service directory.
+    #[clap(short, long, conflicts_with = "services")]
+    pub all: bool,
+
+    /// Don't ask for confirmation when acting on multiple services.
+    #[clap(short, long)]
+    pub yes: bool,
+
+    pub services: Vec<String>,
+}
+
+impl Commands {
+    /// Get the arguments of a service control subcommand (like `vsv up`).
+    pub fn control_args(&self) -> Option<&ControlArgs> {
+        match self {
+            Commands::Up(c)
+            | Commands::Down(c)
+            | Commands::Restart(c)
+            | Commands::Once(c)
+            | Commands::Hup(c)
+            | Commands::Term(c)
+            | Commands::Kill(c)
+            | Commands::Alarm(c)
+            | Commands::Interrupt(c)
+            | Commands::Quit(c)
+            | Commands::Usr1(c)
+            | Commands::Usr2(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl Args {
+    /**
+     * Set any option not given on the command line from its `VSV_*`
+     * environment variable (looked up with `var`), like `VSV_FORMAT` for
+     * `--format` or `VSV_NO_HEADER=1` for `--no-header`.
+     *
+     * Flags take a boolean (`1`, `yes`, `true`, `on` or `0`, `no`, `false`,
+     * `off`) and options given multiple times (like `-x`) take a
+     * whitespace-separated list.  Empty variables are ignored.
+     */
+    pub fn apply_env<F>(&mut self, var: F) -> Result<()>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let get = |name: &str| {
+            var(&format!("{}{}", ENV_PREFIX, name)).filter(|s| !s.is_empty())
+        };
+        let context = |name: &str| format!("invalid {}{}", ENV_PREFIX, name);
+
+        let strings = [
+            ("COLOR", &mut self.color),
+            ("THEME", &mut self.theme),
+            ("GLYPHS", &mut self.glyphs),
+            ("TIME_STYLE", &mut self.time_style),
+            ("TIME_FORMAT", &mut self.time_format),
+            ("FORMAT", &mut self.format),
+            ("FORMAT_STRING", &mut self.format_string),
+            ("COLUMNS", &mut self.columns),
+            ("SORT", &mut self.sort),
+            ("GROUP_BY", &mut self.group_by),
+            ("BACKEND", &mut self.backend),
+        ];
+        for (name, opt) in strings {
+            if opt.is_none() {
+                *opt = get(name);
+            }
+        }
+
+        if self.template.is_none() {
+            self.template = get("TEMPLATE").map(path::PathBuf::from);
+        }
+
+        let numbers = [
+            ("FD_THRESHOLD", &mut self.fd_threshold),
+            ("TIMEOUT", &mut self.timeout),
+        ];
+        for (name, opt) in numbers {
+            if let (None, Some(s)) = (&opt, get(name)) {
+                *opt = Some(s.parse().with_context(|| context(name))?);
+            }
+        }
+
+        let sizes =
+            [("TIME_UNITS", &mut self.time_units), ("JOBS", &mut self.jobs)];
+        for (name, opt) in sizes {
+            if let (None, Some(s)) = (&opt, get(name)) {
+                *opt = Some(s.parse().with_context(|| context(name))?);
+            }
+        }
+
+        let counts = [("WIDE", &mut self.wide), ("VERBOSE", &mut self.verbose)];
+        for (name, count) in counts {
+            if let (0, Some(s)) = (*count, get(name)) {
+                *count = s.parse().with_context(|| context(name))?;
+            }
+        }
+
+        let lists = [
+            ("MATCH", &mut self.matches),
+            ("EXCLUDE", &mut self.exclude),
+            ("STATE", &mut self.state),
+            ("HOST", &mut self.hosts),
+        ];
+        for (name, list) in lists {
+            if let (true, Some(s)) = (list.is_empty(), get(name)) {
+                *list = s.split_whitespace().map(String::from).collect();
+            }
+        }
+
+        let flags = [
+            ("NULL", &mut self.null),
+            ("NO_HEADER", &mut self.no_header),
+            ("QUIET", &mut self.quiet),
+            ("CHECK", &mut self.check),
+            ("SUMMARY", &mut self.summary),
+            ("EXIT_CODE", &mut self.exit_code),
+            ("REVERSE", &mut self.reverse),
+            ("LOG", &mut self.log),
+            ("TREE", &mut self.tree),
+            ("USER", &mut self.user),
+            ("BOTH", &mut self.both),
+            ("EVENTS", &mut self.events),
+            ("NO_PAGER", &mut self.no_pager),
+            ("DRY_RUN", &mut self.dry_run),
+            ("CHILDREN", &mut self.children),
+            ("FULL_COMMAND", &mut self.full_command),
+            ("USER_NAMES", &mut self.user_names),
+            ("NO_CONFIG", &mut self.no_config),
+        ];
+        for (name, flag) in flags {
+            if let (false, Some(s)) = (*flag, get(name)) {
+                *flag = parse_bool(&s).with_context(|| context(name))?;
+            }
+        }
+
+        // clap only checks the command line
+        ensure!(
+            !self.both || (self.dir.is_empty() && !self.user),
+            "-U can't be used with -d or -u (or VSV_USER)"
+        );
+
+        Ok(())
+    }
+}
+
+/// Parse a boolean environment variable like `1` or `no`.
+fn parse_bool(s: &str) -> Result<bool> {
+    match s.to_lowercase().as_str() {
+        "1" | "yes" | "true" | "on" => Ok(true),
+        "0" | "no" | "false" | "off" => Ok(false),
+        _ => bail!("expected a boolean (like 1 or 0), found '{}'", s),
+    }
+}
+
+/**
+ * Parse the command line (after expanding any alias from the config file) and
+ * any `VSV_*` environment variables.
+ */
+pub fn parse() -> Result<Args> {
+    let mut argv: Vec<OsString> = env::args_os().collect();
+
+    let no_config = argv.iter().any(|arg| arg == "--no-config")
+        || env::var(format!("{}NO_CONFIG", ENV_PREFIX))
+            .ok()
+            .and_then(|s| parse_bool(&s).ok())
+            .unwrap_or(false);
+    if !no_config {
+        let aliases = ConfigFile::load()?.aliases;
+        argv = expand_alias(argv, &aliases)?;
+    }
+
+    let mut args = Args::parse_from(argv);
+
+    args.apply_env(|name| env::var(name).ok())?;
+
+    Ok(args)
+}
+
+/**
+ * Replace the subcommand (like `web` in `vsv -l web foo`) with the arguments
+ * of the alias it names, if it's one.  An alias can't replace a built-in
+ * subcommand, and the arguments it expands to aren't expanded again.
+ */
+fn expand_alias(
+    argv: Vec<OsString>,
+    aliases: &[(String, Vec<String>)],
+) -> Result<Vec<OsString>> {
+    if aliases.is_empty() {
+        return Ok(argv);
+    }
+
+    let cmd = Args::command();
+    let builtin = |name: &str| {
+        name == "help"
+            || cmd.get_subcommands().any(|sub| {
+                sub.get_name() == name
+                    || sub.get_all_aliases().any(|a| a == name)
+            })
+    };
+    for (name, _) in aliases {
+        ensure!(!builtin(name), "alias '{}' is a built-in subcommand", name);
+    }
+
+    let i = match subcommand_index(&cmd, &argv) {
+        Some(i) => i,
+        None => return Ok(argv),
+    };
+    let words = match argv[i]
+        .to_str()
+        .and_then(|name| aliases.iter().find(|(n, _)| n == name))
+    {
+        Some((_, words)) => words,
+        None => return Ok(argv),
+    };
+
+    let mut expanded = argv[..i].to_vec();
+    expanded.extend(words.iter().map(OsString::from));
+    expanded.extend_from_slice(&argv[i + 1..]);
+
+    Ok(expanded)
+}
+
+/**
+ * Find the index of the subcommand in `argv`: the first argument that isn't
+ * an option (or the value of one).
+ */
+fn subcommand_index(cmd: &Command, argv: &[OsString]) -> Option<usize> {
+    // a value given only with `=` (like `--watch=2`) is never the next one
+    let takes_value = |arg: Option<&Arg>| {
+        arg.is_some_and(|a| {
+            a.is_takes_value_set() && !a.is_require_equals_set()
+        })
+    };
+
+    let mut i = 1;
+    while let Some(arg) = argv.get(i).and_then(|arg| arg.to_str()) {
+        // an option's value is the next argument unless it's given with it
+        let needs_value = if let Some(long) = arg.strip_prefix("--") {
+            if long.is_empty() {
+                return None;
+            }
+            !long.contains('=')
+                && takes_value(
+                    cmd.get_arguments().find(|a| a.get_long() == Some(long)),
+                )
+        } else if let Some(shorts) =
+            arg.strip_prefix('-').filter(|s| !s.is_empty())
+        {
+            // like `-lt` or `-oname,pid`, only the last can be missing its
+            // value
+            let mut shorts = shorts.chars();
+            loop {
+                let Some(c) = shorts.next() else { break false };
+                let arg =
+                    cmd.get_arguments().find(|a| a.get_short() == Some(c));
+                if takes_value(arg) {
+                    break shorts.as_str().is_empty();
+                }
+            }
+        } else {
+            return Some(i);
+        };
+
+        i += 1;
+        // a missing optional value (like `--watch -l`) is another option
+        let next_is_value = argv
+            .get(i)
+            .and_then(|arg| arg.to_str())
+            .is_some_and(|arg| !arg.starts_with('-'));
+        if needs_value && next_is_value {
+            i += 1;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn args_with_env(argv: &[&str], vars: &[(&str, &str)]) -> Result<Args> {
+        let vars: HashMap<String, String> =
+            vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+
+        let mut args = Args::try_parse_from(argv)?;
+        args.apply_env(|name| vars.get(name).cloned())?;
+
+        Ok(args)
+    }
+
+    #[test]
+    fn test_apply_env() -> Result<()> {
+        let args = args_with_env(
+            &["vsv", "-o", "name"],
+            &[
+                ("VSV_COLUMNS", "name,pid"),
+                ("VSV_FORMAT", "json"),
+                ("VSV_EXCLUDE", "agetty-* udevd"),
+                ("VSV_NO_HEADER", "yes"),
+                ("VSV_QUIET", "0"),
+                ("VSV_TIMEOUT", "2.5"),
+                ("VSV_WIDE", "2"),
+                ("VSV_SORT", ""),
+            ],
+        )?;
+
+        assert_eq!(args.columns.as_deref(), Some("name"), "flag wins");
+        assert_eq!(args.format.as_deref(), Some("json"));
+        assert_eq!(args.exclude, ["agetty-*", "udevd"]);
+        assert!(args.no_header);
+        assert!(!args.quiet);
+        assert_eq!(args.timeout, Some(2.5));
+        assert_eq!(args.wide, 2);
+        assert_eq!(args.sort, None, "empty is unset");
+
+        let args =
+            args_with_env(&["vsv", "-x", "foo"], &[("VSV_EXCLUDE", "bar")])?;
+        assert_eq!(args.exclude, ["foo"], "flags replace lists");
+
+        assert!(args_with_env(&["vsv"], &[("VSV_TREE", "maybe")]).is_err());
+        assert!(args_with_env(&["vsv"], &[("VSV_TIMEOUT", "soon")]).is_err());
+        assert!(args_with_env(&["vsv", "-U"], &[("VSV_USER", "1")]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand_alias() -> Result<()> {
+        let aliases = vec![(
+            "web".to_string(),
+            vec!["status".to_string(), "nginx|php".to_string()],
+        )];
+        let expand = |argv: &[&str]| -> Result<Vec<String>> {
+            let argv = argv.iter().map(OsString::from).collect();
+            let expanded = expand_alias(argv, &aliases)?;
+            Ok(expanded.into_iter().map(|s| s.into_string().unwrap()).collect())
+        };
+
+        assert_eq!(expand(&["vsv", "web"])?, ["vsv", "status", "nginx|php"]);
+        assert_eq!(
+            expand(&["vsv", "-o", "name", "-lcno", "--sort=pid", "web", "-t"])?,
+            [
+                "vsv",
+                "-o",
+                "name",
+                "-lcno",
+                "--sort=pid",
+                "status",
+                "nginx|php",
+                "-t"
+            ],
+            "options before the alias"
+        );
+        assert_eq!(
+            expand(&["vsv", "--watch", "-l", "web"])?,
+            ["vsv", "--watch", "-l", "status", "nginx|php"],
+            "optional value"
+        );
+        assert_eq!(
+            expand(&["vsv", "--watch", "web"])?,
+            ["vsv", "--watch", "status", "nginx|php"],
+            "optional value without ="
+        );
+        assert_eq!(
+            expand(&["vsv", "-d", "web"])?,
+            ["vsv", "-d", "web"],
+            "value"
+        );
+        assert_eq!(
+            expand(&["vsv", "status", "web"])?,
+            ["vsv", "status", "web"]
+        );
+        assert_eq!(expand(&["vsv", "--", "web"])?, ["vsv", "--", "web"]);
+
+        let builtin = vec![("status".to_string(), vec!["-t".to_string()])];
+        assert!(expand_alias(vec!["vsv".into()], &builtin).is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,184 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! The daemontools backend (services supervised by `supervise(8)`).
+
+use libc::pid_t;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::{Status, Supervisor};
+use crate::error::{bail, Context, Result};
+use crate::runit::{ControlCommand, RunitServiceState};
+
+/// The size (in bytes) of the binary `supervise/status` file.
+const STATUS_SIZE: usize = 18;
+
+/**
+ * The contents of a service's binary `supervise/status` file, as written by
+ * daemontools' `supervise(8)`.
+ *
+ * The file is 18 bytes, the first 18 bytes of runit's format:
+ *
+ * - `0..12` - TAI64N timestamp of the last state change (big endian).
+ * - `12..16` - pid of the running process, `0` if none (little endian).
+ * - `16` - paused flag (`1` if sent `STOP`).
+ * - `17` - wanted state (`u` for up, `d` for down).
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaemontoolsStatus {
+    pub time: SystemTime,
+    pub pid: Option<pid_t>,
+    pub paused: bool,
+    pub want: Option<RunitServiceState>,
+}
+
+impl DaemontoolsStatus {
+    /// Parse the raw contents of a `supervise/status` file.
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        if buf.len() != STATUS_SIZE {
+            bail!(
+                "status must be {} bytes, got {} bytes",
+                STATUS_SIZE,
+                buf.len()
+            );
+        }
+
+        let time = super::parse_tai64n(&buf[0..12])?;
+
+        let pid = pid_t::from_le_bytes(buf[12..16].try_into()?);
+        let pid = if pid > 0 { Some(pid) } else { None };
+
+        let want = match buf[17] {
+            b'u' => Some(RunitServiceState::Run),
+            b'd' => Some(RunitServiceState::Down),
+            _ => None,
+        };
+
+        Ok(Self { time, pid, paused: buf[16] != 0, want })
+    }
+
+    /// Read and parse the `supervise/status` file of the service in `dir`.
+    pub fn read(dir: &Path) -> Result<Self> {
+        // "/service/<service>/supervise/status"
+        let p = dir.join("supervise").join("status");
+
+        let buf =
+            fs::read(&p).with_context(|| format!("failed to read {:?}", p))?;
+        Self::parse(&buf).with_context(|| format!("failed to parse {:?}", p))
+    }
+
+    /// Get the state of the service (daemontools has no finish state).
+    pub fn state(&self) -> RunitServiceState {
+        match self.pid {
+            Some(_) => RunitServiceState::Run,
+            None => RunitServiceState::Down,
+        }
+    }
+}
+
+/// Services supervised by daemontools' `supervise(8)` (started by `svscan`).
+#[derive(Debug)]
+pub struct Daemontools;
+
+impl Supervisor for Daemontools {
+    fn name(&self) -> &'static str {
+        "daemontools"
+    }
+
+    /**
+     * `svscan` leaves no mark on the scan directory, but `supervise` writes
+     * an 18 byte status file (runit's is 20 bytes) for every service.
+     */
+    fn detect(&self, svdir: &Path) -> bool {
+        let entries = match fs::read_dir(svdir) {
+            Ok(entries) => entries,
+            Err(_) => return false,
+        };
+
+        entries.flatten().any(|entry| {
+            // "/service/<service>/supervise/status"
+            let p = entry.path().join("supervise").join("status");
+            fs::metadata(p).is_ok_and(|meta| meta.len() == STATUS_SIZE as u64)
+        })
+    }
+
+    /// `supervise` holds the `supervise/ok` pipe open for reading (as runit).
+    fn is_supervised(&self, dir: &Path) -> bool {
+        // "/service/<service>/supervise/ok"
+        super::is_pipe_open(&dir.join("supervise").join("ok"))
+    }
+
+    fn status(&self, dir: &Path) -> Status {
+        let status = match DaemontoolsStatus::read(dir) {
+            Ok(status) => status,
+            Err(err) => return Status::unreadable(err),
+        };
+
+        Status {
+            state: status.state(),
+            pid: Ok(status.pid),
+            start_time: Ok(status.time),
+            want: status.want,
+            error: None,
+        }
+    }
+
+    fn control_bytes(&self, cmd: ControlCommand) -> &'static [u8] {
+        cmd.bytes()
+    }
+
+    /// `svc(8)` has no quit or user signals, `supervise` would ignore them.
+    fn control(&self, dir: &Path, cmd: ControlCommand) -> Result<()> {
+        if matches!(
+            cmd,
+            ControlCommand::Quit | ControlCommand::Usr1 | ControlCommand::Usr2
+        ) {
+            bail!("{} not supported by daemontools", cmd.name());
+        }
+
+        super::write_control(dir, self.control_bytes(cmd), "supervise")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::backend::TAI64_UNIX_EPOCH;
+
+    #[test]
+    fn test_daemontools_status_parse() -> Result<()> {
+        let mut buf = [0u8; STATUS_SIZE];
+        buf[0..8].copy_from_slice(&(TAI64_UNIX_EPOCH + 100).to_be_bytes());
+        buf[12..16].copy_from_slice(&1234i32.to_le_bytes());
+        buf[17] = b'u';
+
+        let status = DaemontoolsStatus::parse(&buf)?;
+        assert_eq!(
+            status,
+            DaemontoolsStatus {
+                time: UNIX_EPOCH + Duration::from_secs(100),
+                pid: Some(1234),
+                paused: false,
+                want: Some(RunitServiceState::Run),
+            },
+            "status parsed"
+        );
+        assert_eq!(status.state(), RunitServiceState::Run, "run");
+
+        buf[12..16].copy_from_slice(&0i32.to_le_bytes());
+        let status = DaemontoolsStatus::parse(&buf)?;
+        assert_eq!(status.state(), RunitServiceState::Down, "down");
+
+        // runit's status is longer
+        assert!(DaemontoolsStatus::parse(&[0u8; 20]).is_err(), "bad size");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Supervision backends.
+ *
+ * A backend knows how to read the status of a service directory and how to
+ * send it commands for a given supervision suite (runit, s6, daemontools,
+ * systemd).  Everything else about a service directory (`./down`, `./check`,
+ * `log/`) is shared.
+ */
+
+use libc::pid_t;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+
+use crate::error::{bail, Context, Error, Result};
+use crate::runit::{ControlCommand, RunitServiceState};
+
+pub mod daemontools;
+pub mod runit;
+pub mod s6;
+pub mod systemd;
+
+/// TAI64 label of the unix epoch (2^62 + 10 leap seconds).
+const TAI64_UNIX_EPOCH: u64 = 4611686018427387914;
+
+/// Every backend, in the order they are tried when auto-detecting.
+pub static BACKENDS: &[&dyn Supervisor] =
+    &[&s6::S6, &daemontools::Daemontools, &systemd::Systemd, &runit::Runit];
+
+/// The backend used when none is given and none is detected.
+pub static DEFAULT_BACKEND: &dyn Supervisor = &runit::Runit;
+
+/**
+ * The status of a service, all read at once (see `Supervisor::status`) so
+ * every field comes from the same write of the supervisor's status file.
+ */
+#[derive(Debug)]
+pub struct Status {
+    /// The state of the service.
+    pub state: RunitServiceState,
+
+    /**
+     * The pid of the process being supervised, `None` if no process is
+     * running (like a service that's down).
+     */
+    pub pid: Result<Option<pid_t>>,
+
+    /// The time the service entered its current state.
+    pub start_time: Result<SystemTime>,
+
+    /// The state the supervisor wants the service in (`Run` or `Down`).
+    pub want: Option<RunitServiceState>,
+
+    /**
+     * Why the state can't be read (like a permission error or a corrupt
+     * status file), `None` if it can be or if there is no status to read yet
+     * (before the service is supervised).
+     */
+    pub error: Option<Error>,
+}
+
+impl Status {
+    /// The status of a service whose status file couldn't be read.
+    pub(crate) fn unreadable(err: Error) -> Self {
+        Self {
+            state: RunitServiceState::Unknown,
+            pid: Err(Error::Other(anyhow!("{:#}", err))),
+            start_time: Err(Error::Other(anyhow!("{:#}", err))),
+            want: None,
+            error: read_error::<()>(Err(err)),
+        }
+    }
+}
+
+/**
+ * A supervision suite that services can be listed, read from and controlled
+ * with.
+ *
+ * runit (`runit::Runit`) is the reference implementation.  A new backend
+ * (like perp or nosh) implements the `status` and control methods for its
+ * on-disk format, overrides `list_services` if its services aren't simply the
+ * directories of the service directory, and is added to `BACKENDS`.  Every
+ * method is given the path of a service directory (or of the service
+ * directory itself for `detect` and `list_services`), so implementations are
+ * stateless.
+ */
+pub trait Supervisor: fmt::Debug + Send + Sync {
+    /// The name of the backend (as given to `--backend`).
+    fn name(&self) -> &'static str;
+
+    /// Check if a service directory (like `/var/service`) is managed by this.
+    fn detect(&self, svdir: &Path) -> bool;
+
+    /**
+     * List the services in a service directory as their names and paths
+     * (unsorted).  By default every directory is a service, except hidden
+     * ones (ignored by `runsvdir` and `s6-svscan`, and used by the latter for
+     * its own state).
+     */
+    fn list_services(&self, svdir: &Path) -> Result<Vec<(String, PathBuf)>> {
+        let mut services = vec![];
+
+        for entry in fs::read_dir(svdir)
+            .with_context(|| format!("failed to read dir {:?}", svdir))?
+        {
+            if let Some(service) = dir_entry_service(entry)? {
+                services.push(service);
+            }
+        }
+
+        Ok(services)
+    }
+
+    /**
+     * Whether `list_services` only reads the service directory (the default),
+     * so services can also be found one at a time as the directory is read
+     * (see `ServiceDir::iter`).
+     */
+    fn lists_dir(&self) -> bool {
+        true
+    }
+
+    /**
+     * Whether services can only be read (not controlled, enabled or
+     * disabled) with this backend.
+     */
+    fn read_only(&self) -> bool {
+        false
+    }
+
+    /// Check if a process is supervising the service in `dir`.
+    fn is_supervised(&self, dir: &Path) -> bool;
+
+    /// Check if the service in `dir` is enabled (has no `./down` file).
+    fn enabled(&self, dir: &Path) -> bool {
+        // "/<svdir>/<service>/down"
+        !dir.join("down").exists()
+    }
+
+    /// Read the status of the service in `dir`.
+    fn status(&self, dir: &Path) -> Status;
+
+    /// Get the bytes written to `supervise/control` for a command.
+    fn control_bytes(&self, cmd: ControlCommand) -> &'static [u8];
+
+    /// Send a command to the supervisor of the service in `dir`.
+    fn control(&self, dir: &Path, cmd: ControlCommand) -> Result<()> {
+        write_control(dir, self.control_bytes(cmd), self.name())
+    }
+}
+
+/// Get a backend by name (as given to `--backend`).
+pub fn parse(s: &str) -> Result<&'static dyn Supervisor> {
+    match BACKENDS.iter().find(|backend| backend.name() == s) {
+        Some(backend) => Ok(*backend),
+        None => bail!(
+            "unknown backend: '{}' (valid backends: auto,{})",
+            s,
+            BACKENDS
+                .iter()
+                .map(|backend| backend.name())
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+}
+
+/// Detect the backend managing a service directory (defaulting to runit).
+pub fn detect(svdir: &Path) -> &'static dyn Supervisor {
+    BACKENDS
+        .iter()
+        .find(|backend| backend.detect(svdir))
+        .copied()
+        .unwrap_or(DEFAULT_BACKEND)
+}
+
+/**
+ * Get the error of reading a status file, `None` if it was read or doesn't
+ * exist (the service hasn't been supervised yet).
+ */
+pub(crate) fn read_error<T>(ret: Result<T>) -> Option<Error> {
+    let err = ret.err()?;
+
+    match err.io_kind() {
+        Some(io::ErrorKind::NotFound) => None,
+        _ => Some(err),
+    }
+}
+
+/**
+ * Get the name and path of the service a service directory entry is, `None`
+ * if it isn't one (a file or hidden directory).
+ */
+pub(crate) fn dir_entry_service(
+    entry: io::Result<fs::DirEntry>,
+) -> Result<Option<(String, PathBuf)>> {
+    let p = entry?.path();
+
+    if !p.is_dir() {
+        return Ok(None);
+    }
+
+    // a name that isn't UTF-8 is shown lossily (the path is used to read it)
+    let name = p
+        .file_name()
+        .with_context(|| format!("{:?}: failed to get service name", p))?
+        .to_string_lossy()
+        .into_owned();
+
+    if name.starts_with('.') {
+        return Ok(None);
+    }
+
+    Ok(Some((name, p)))
+}
+
+/**
+ * Check if a supervisor holds the pipe at `p` open for reading.  Opening a
+ * pipe for writing fails with `ENXIO` if nothing is reading it, a missing
+ * pipe also means nothing is supervising.  Other errors (like permission
+ * denied) aren't taken to mean unsupervised.
+ */
+fn is_pipe_open(p: &Path) -> bool {
+    let ret = fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(p);
+
+    match ret {
+        Ok(_) => true,
+        Err(err) => !matches!(
+            err.raw_os_error(),
+            Some(libc::ENXIO | libc::ENOENT | libc::ENOTDIR)
+        ),
+    }
+}
+
+/**
+ * Write a command to a service's `supervise/control` pipe.  This fails
+ * (instead of blocking) if the supervisor (named `supervisor` in errors) isn't
+ * running or is hung (not reading from the pipe).
+ */
+fn write_control(dir: &Path, bytes: &[u8], supervisor: &str) -> Result<()> {
+    // "/<svdir>/<service>/supervise/control"
+    let p = dir.join("supervise").join("control");
+
+    let mut f = fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(&p)
+        .map_err(|err| match err.raw_os_error() {
+            Some(libc::ENXIO) => anyhow!("{} not running", supervisor),
+            _ => anyhow!("failed to open {:?}: {}", p, err),
+        })?;
+
+    // the pipe only fills up if the supervisor has stopped reading from it
+    f.write_all(bytes).map_err(|err| match err.kind() {
+        io::ErrorKind::WouldBlock => {
+            anyhow!("hung supervisor (control pipe is full)")
+        }
+        _ => anyhow!("failed to write to {:?}: {}", p, err),
+    })?;
+
+    Ok(())
+}
+
+/// Parse a TAI64N timestamp (8 bytes of seconds and 4 of nanoseconds).
+fn parse_tai64n(buf: &[u8]) -> Result<SystemTime> {
+    if buf.len() != 12 {
+        bail!("tai64n timestamp must be 12 bytes, got {}", buf.len());
+    }
+
+    let secs = u64::from_be_bytes(buf[0..8].try_into()?);
+    let nanos = u32::from_be_bytes(buf[8..12].try_into()?);
+    let secs = match secs.checked_sub(TAI64_UNIX_EPOCH) {
+        Some(secs) => secs,
+        None => bail!("invalid tai64 timestamp: {}", secs),
+    };
+
+    Ok(UNIX_EPOCH + Duration::new(secs, nanos.min(999_999_999)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        for backend in BACKENDS {
+            assert_eq!(parse(backend.name())?.name(), backend.name());
+        }
+        assert!(parse("bogus").is_err(), "unknown backend");
+
+        Ok(())
+    }
+}
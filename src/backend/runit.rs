@@ -0,0 +1,246 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! The runit backend (services supervised by `runsv(8)`).
+
+use libc::pid_t;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use anyhow::anyhow;
+
+use super::{Status, Supervisor};
+use crate::error::{bail, Context, Error, Result};
+use crate::runit::{ControlCommand, RunitServiceState};
+
+/// The size (in bytes) of the binary `supervise/status` file.
+const STATUS_SIZE: usize = 20;
+
+/**
+ * The contents of a service's binary `supervise/status` file, as written by
+ * `runsv(8)`.
+ *
+ * The file is 20 bytes:
+ *
+ * - `0..12` - TAI64N timestamp of the last state change (big endian).
+ * - `12..16` - pid of the running process, `0` if none (little endian).
+ * - `16` - paused flag (`1` if sent `STOP`).
+ * - `17` - wanted state (`u` for up, `d` for down).
+ * - `18` - term flag (`1` if sent `TERM`).
+ * - `19` - state (`0` down, `1` run, `2` finish).
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuperviseStatus {
+    pub time: SystemTime,
+    pub pid: Option<pid_t>,
+    pub paused: bool,
+    pub want: Option<RunitServiceState>,
+    pub term: bool,
+    pub state: RunitServiceState,
+}
+
+impl SuperviseStatus {
+    /// Parse the raw contents of a `supervise/status` file.
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        if buf.len() != STATUS_SIZE {
+            bail!(
+                "status must be {} bytes, got {} bytes",
+                STATUS_SIZE,
+                buf.len()
+            );
+        }
+
+        let time = super::parse_tai64n(&buf[0..12])?;
+
+        let pid = pid_t::from_le_bytes(buf[12..16].try_into()?);
+        let pid = if pid > 0 { Some(pid) } else { None };
+
+        let want = match buf[17] {
+            b'u' => Some(RunitServiceState::Run),
+            b'd' => Some(RunitServiceState::Down),
+            _ => None,
+        };
+
+        let state = match buf[19] {
+            0 => RunitServiceState::Down,
+            1 => RunitServiceState::Run,
+            2 => RunitServiceState::Finish,
+            _ => RunitServiceState::Unknown,
+        };
+
+        Ok(Self {
+            time,
+            pid,
+            paused: buf[16] != 0,
+            want,
+            term: buf[18] != 0,
+            state,
+        })
+    }
+
+    /**
+     * Read and parse the `supervise/status` file of the service in `dir`.
+     *
+     * The pid, state and start time are read from here when possible, with
+     * the plain text `supervise/pid` and `supervise/stat` files (and the
+     * latter's mtime) used as a fallback.
+     */
+    pub fn read(dir: &Path) -> Result<Self> {
+        // "/<svdir>/<service>/supervise/status"
+        let p = dir.join("supervise").join("status");
+
+        let buf =
+            fs::read(&p).with_context(|| format!("failed to read {:?}", p))?;
+        Self::parse(&buf).with_context(|| format!("failed to parse {:?}", p))
+    }
+}
+
+/**
+ * Read the plain text `supervise/pid` file of the service in `dir`, which is
+ * empty (or missing) without a process.
+ */
+fn read_pid(dir: &Path) -> Result<Option<pid_t>> {
+    // "/<svdir>/<service>/supervise/pid"
+    let p = dir.join("supervise").join("pid");
+
+    let s = match fs::read_to_string(p) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        s => s?,
+    };
+    let pid = match s.trim() {
+        "" => None,
+        s => Some(s.parse()?),
+    };
+
+    Ok(pid)
+}
+
+/// Services supervised by `runsv(8)` (started by `runsvdir(8)`).
+#[derive(Debug)]
+pub struct Runit;
+
+impl Supervisor for Runit {
+    fn name(&self) -> &'static str {
+        "runit"
+    }
+
+    /// runit leaves no mark on the service directory, so it is the fallback.
+    fn detect(&self, _svdir: &Path) -> bool {
+        false
+    }
+
+    /**
+     * `runsv` holds the `supervise/ok` pipe open for reading, so nothing is
+     * supervising the service if it can't be opened for writing (like right
+     * after it's enabled, before `runsvdir(8)` rescans).
+     */
+    fn is_supervised(&self, dir: &Path) -> bool {
+        // "/<svdir>/<service>/supervise/ok"
+        super::is_pipe_open(&dir.join("supervise").join("ok"))
+    }
+
+    /**
+     * The pid, state and start time are read from the binary
+     * `supervise/status` file when possible.  Without it (`runsv` versions
+     * before 1.6 don't write it) the plain text `supervise/pid` and
+     * `supervise/stat` files (and the latter's mtime) are read instead.  The
+     * state can't be read if `supervise/status` can't be read or is corrupt,
+     * or if it doesn't exist and `supervise/stat` can't be read.
+     */
+    fn status(&self, dir: &Path) -> Status {
+        let err = match SuperviseStatus::read(dir) {
+            Ok(status) => {
+                return Status {
+                    state: status.state,
+                    pid: Ok(status.pid),
+                    start_time: Ok(status.time),
+                    want: status.want,
+                    error: None,
+                }
+            }
+            Err(err) => err,
+        };
+
+        // "/<svdir>/<service>/supervise/stat"
+        let p = dir.join("supervise").join("stat");
+
+        let stat = fs::read_to_string(&p)
+            .with_context(|| format!("failed to read {:?}", p));
+        let state = match stat.as_deref().map(str::trim) {
+            Ok("run") => RunitServiceState::Run,
+            Ok("down") => RunitServiceState::Down,
+            Ok("finish") => RunitServiceState::Finish,
+            _ => RunitServiceState::Unknown,
+        };
+        let status_error = super::read_error::<()>(Err(err));
+
+        // the mtime only stands in for a missing (not corrupt) status file
+        let start_time = match &status_error {
+            Some(err) => Err(Error::Other(anyhow!("{:#}", err))),
+            None => fs::metadata(&p)
+                .and_then(|meta| meta.modified())
+                .map_err(Error::from),
+        };
+
+        let error = status_error.or_else(|| super::read_error(stat));
+
+        Status { state, pid: read_pid(dir), start_time, want: None, error }
+    }
+
+    fn control_bytes(&self, cmd: ControlCommand) -> &'static [u8] {
+        cmd.bytes()
+    }
+
+    fn control(&self, dir: &Path, cmd: ControlCommand) -> Result<()> {
+        super::write_control(dir, self.control_bytes(cmd), "runsv")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::backend::TAI64_UNIX_EPOCH;
+
+    #[test]
+    fn test_supervise_status_parse() -> Result<()> {
+        let mut buf = [0u8; 20];
+        buf[0..8].copy_from_slice(&(TAI64_UNIX_EPOCH + 100).to_be_bytes());
+        buf[8..12].copy_from_slice(&500u32.to_be_bytes());
+        buf[12..16].copy_from_slice(&1234i32.to_le_bytes());
+        buf[17] = b'd';
+        buf[18] = 1;
+        buf[19] = 1;
+
+        let status = SuperviseStatus::parse(&buf)?;
+        assert_eq!(
+            status,
+            SuperviseStatus {
+                time: UNIX_EPOCH + Duration::new(100, 500),
+                pid: Some(1234),
+                paused: false,
+                want: Some(RunitServiceState::Down),
+                term: true,
+                state: RunitServiceState::Run,
+            },
+            "status parsed"
+        );
+
+        buf[12..16].copy_from_slice(&0i32.to_le_bytes());
+        buf[19] = 2;
+        let status = SuperviseStatus::parse(&buf)?;
+        assert_eq!(status.pid, None, "no pid");
+        assert_eq!(status.state, RunitServiceState::Finish, "finish");
+
+        assert!(SuperviseStatus::parse(&buf[..19]).is_err(), "too short");
+        assert!(SuperviseStatus::parse(&[0u8; 20]).is_err(), "bad tai64");
+
+        Ok(())
+    }
+}
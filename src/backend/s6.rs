@@ -0,0 +1,204 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! The s6 backend (services supervised by `s6-supervise(8)`).
+
+use libc::pid_t;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::{Status, Supervisor};
+use crate::error::{bail, Context, Result};
+use crate::runit::{ControlCommand, RunitServiceState};
+
+/// The size (in bytes) of `supervise/status` written by older s6 versions.
+const STATUS_SIZE_OLD: usize = 35;
+
+/// The size (in bytes) of `supervise/status` (with the process group).
+const STATUS_SIZE: usize = 43;
+
+/// Flag bits of the last byte of `supervise/status`.
+const FLAG_PAUSED: u8 = 0x01;
+const FLAG_FINISHING: u8 = 0x02;
+const FLAG_WANTUP: u8 = 0x04;
+const FLAG_READY: u8 = 0x08;
+
+/**
+ * The contents of a service's binary `supervise/status` file, as written by
+ * `s6-supervise(8)`.
+ *
+ * The file is 43 bytes (35 for s6 before 2.10, without the pgid):
+ *
+ * - `0..12` - TAI64N timestamp of the last state change (big endian).
+ * - `12..24` - TAI64N timestamp of the service becoming ready.
+ * - `24..32` - pid of the running process, `0` if none (big endian).
+ * - `32..40` - process group of the running process (big endian).
+ * - `40..42` - wait status of the last process to exit (big endian).
+ * - `42` - flags (paused, finishing, wanted up and ready bits).
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S6Status {
+    pub time: SystemTime,
+    pub pid: Option<pid_t>,
+    pub paused: bool,
+    pub finishing: bool,
+    pub want_up: bool,
+    pub ready: bool,
+}
+
+impl S6Status {
+    /// Parse the raw contents of a `supervise/status` file.
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        let flags = match buf.len() {
+            STATUS_SIZE | STATUS_SIZE_OLD => buf[buf.len() - 1],
+            len => bail!(
+                "status must be {} or {} bytes, got {} bytes",
+                STATUS_SIZE,
+                STATUS_SIZE_OLD,
+                len
+            ),
+        };
+
+        let time = super::parse_tai64n(&buf[0..12])?;
+
+        let pid = u64::from_be_bytes(buf[24..32].try_into()?);
+        let pid = match pid {
+            0 => None,
+            pid => Some(pid_t::try_from(pid)?),
+        };
+
+        Ok(Self {
+            time,
+            pid,
+            paused: flags & FLAG_PAUSED != 0,
+            finishing: flags & FLAG_FINISHING != 0,
+            want_up: flags & FLAG_WANTUP != 0,
+            ready: flags & FLAG_READY != 0,
+        })
+    }
+
+    /// Read and parse the `supervise/status` file of the service in `dir`.
+    pub fn read(dir: &Path) -> Result<Self> {
+        // "/<scandir>/<service>/supervise/status"
+        let p = dir.join("supervise").join("status");
+
+        let buf =
+            fs::read(&p).with_context(|| format!("failed to read {:?}", p))?;
+        Self::parse(&buf).with_context(|| format!("failed to parse {:?}", p))
+    }
+
+    /// Get the state of the service.
+    pub fn state(&self) -> RunitServiceState {
+        match (self.pid, self.finishing) {
+            (_, true) => RunitServiceState::Finish,
+            (Some(_), false) => RunitServiceState::Run,
+            (None, false) => RunitServiceState::Down,
+        }
+    }
+}
+
+/// Services supervised by `s6-supervise(8)` (started by `s6-svscan(8)`).
+#[derive(Debug)]
+pub struct S6;
+
+impl Supervisor for S6 {
+    fn name(&self) -> &'static str {
+        "s6"
+    }
+
+    /// `s6-svscan` keeps its own control directory in the scan directory.
+    fn detect(&self, svdir: &Path) -> bool {
+        // "/<scandir>/.s6-svscan"
+        svdir.join(".s6-svscan").is_dir()
+    }
+
+    /// `s6-supervise` holds the `supervise/control` pipe open for reading.
+    fn is_supervised(&self, dir: &Path) -> bool {
+        // "/<scandir>/<service>/supervise/control"
+        super::is_pipe_open(&dir.join("supervise").join("control"))
+    }
+
+    fn status(&self, dir: &Path) -> Status {
+        let status = match S6Status::read(dir) {
+            Ok(status) => status,
+            Err(err) => return Status::unreadable(err),
+        };
+
+        Status {
+            state: status.state(),
+            pid: Ok(status.pid),
+            start_time: Ok(status.time),
+            want: Some(match status.want_up {
+                true => RunitServiceState::Run,
+                false => RunitServiceState::Down,
+            }),
+            error: None,
+        }
+    }
+
+    /**
+     * The same letters as runit (see `s6-svc(8)`), except restart is sent as
+     * up then term since `s6-supervise` restarts a wanted up service itself.
+     */
+    fn control_bytes(&self, cmd: ControlCommand) -> &'static [u8] {
+        match cmd {
+            ControlCommand::Restart => b"ut",
+            cmd => cmd.bytes(),
+        }
+    }
+
+    fn control(&self, dir: &Path, cmd: ControlCommand) -> Result<()> {
+        super::write_control(dir, self.control_bytes(cmd), "s6-supervise")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::backend::TAI64_UNIX_EPOCH;
+
+    #[test]
+    fn test_s6_status_parse() -> Result<()> {
+        let mut buf = [0u8; STATUS_SIZE];
+        buf[0..8].copy_from_slice(&(TAI64_UNIX_EPOCH + 100).to_be_bytes());
+        buf[8..12].copy_from_slice(&500u32.to_be_bytes());
+        buf[24..32].copy_from_slice(&1234u64.to_be_bytes());
+        buf[42] = FLAG_WANTUP | FLAG_READY;
+
+        let status = S6Status::parse(&buf)?;
+        assert_eq!(
+            status,
+            S6Status {
+                time: UNIX_EPOCH + Duration::new(100, 500),
+                pid: Some(1234),
+                paused: false,
+                finishing: false,
+                want_up: true,
+                ready: true,
+            },
+            "status parsed"
+        );
+        assert_eq!(status.state(), RunitServiceState::Run, "run");
+
+        // the old format is the same without the pgid
+        let mut old = buf[..32].to_vec();
+        old.extend([0, 0, FLAG_FINISHING]);
+        let status = S6Status::parse(&old)?;
+        assert_eq!(status.state(), RunitServiceState::Finish, "finish");
+        assert!(!status.want_up, "want down");
+
+        buf[24..32].copy_from_slice(&0u64.to_be_bytes());
+        let status = S6Status::parse(&buf)?;
+        assert_eq!(status.state(), RunitServiceState::Down, "down");
+
+        assert!(S6Status::parse(&buf[..20]).is_err(), "bad size");
+
+        Ok(())
+    }
+}
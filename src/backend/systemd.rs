@@ -0,0 +1,267 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * The systemd backend (read-only, by shelling out to `systemctl(1)`).
+ *
+ * This is for people with both runit and systemd machines wanting the same
+ * view of either.  Every loaded service unit is listed with a single
+ * `systemctl show` (the results are kept until the next listing), and
+ * services can't be controlled, enabled or disabled.
+ */
+
+use libc::pid_t;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use super::{Status, Supervisor};
+use crate::error::{bail, Context, Result};
+use crate::options;
+use crate::runit::{ControlCommand, RunitServiceState};
+use crate::utils;
+
+/// The `systemctl` program run, overridden by `$SYSTEMCTL_PROG`.
+pub const DEFAULT_SYSTEMCTL_PROG: &str = "systemctl";
+pub const ENV_SYSTEMCTL_PROG: &str = "SYSTEMCTL_PROG";
+
+/// Services are given paths under here (where units are enabled), nothing is
+/// read from them.
+const UNIT_DIR: &str = "/etc/systemd/system";
+
+/// The unit properties read with `systemctl show`.
+const PROPERTIES: &str =
+    "Id,ActiveState,MainPID,ActiveEnterTimestampMonotonic,UnitFileState";
+
+/// The status of every unit seen with `systemctl show`, by unit name.
+static UNITS: Mutex<BTreeMap<String, UnitStatus>> = Mutex::new(BTreeMap::new());
+
+/// The properties of a single unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UnitStatus {
+    state: RunitServiceState,
+    pid: Option<pid_t>,
+    since: Option<Duration>,
+    enabled: bool,
+}
+
+impl UnitStatus {
+    /**
+     * Parse the `KEY=VALUE` lines `systemctl show` prints for one unit,
+     * returning its name and status.
+     */
+    fn parse(block: &str) -> Option<(String, Self)> {
+        let mut id = None;
+        let mut status = Self {
+            state: RunitServiceState::Unknown,
+            pid: None,
+            since: None,
+            enabled: false,
+        };
+
+        for line in block.lines() {
+            let (key, value) = match line.split_once('=') {
+                Some(kv) => kv,
+                None => continue,
+            };
+
+            match key {
+                "Id" => id = Some(value.to_string()),
+                "ActiveState" => {
+                    status.state = match value {
+                        "active" | "activating" | "reloading" => {
+                            RunitServiceState::Run
+                        }
+                        "deactivating" => RunitServiceState::Finish,
+                        "inactive" | "failed" => RunitServiceState::Down,
+                        _ => RunitServiceState::Unknown,
+                    }
+                }
+                "MainPID" => {
+                    status.pid = value.parse().ok().filter(|pid| *pid > 0)
+                }
+                "ActiveEnterTimestampMonotonic" => {
+                    status.since = value
+                        .parse()
+                        .ok()
+                        .filter(|usecs| *usecs > 0)
+                        .map(Duration::from_micros)
+                }
+                "UnitFileState" => {
+                    status.enabled =
+                        matches!(value, "enabled" | "enabled-runtime")
+                }
+                _ => (),
+            }
+        }
+
+        Some((id?, status))
+    }
+}
+
+/// Units managed by systemd (read-only).
+#[derive(Debug)]
+pub struct Systemd;
+
+impl Systemd {
+    /// Run `systemctl show` for the given units (or patterns).
+    fn show(units: &[&str]) -> Result<Vec<(String, UnitStatus)>> {
+        let prog = env::var(ENV_SYSTEMCTL_PROG)
+            .unwrap_or_else(|_| DEFAULT_SYSTEMCTL_PROG.to_string());
+        let timeout = Duration::from_secs_f64(options::DEFAULT_TIMEOUT);
+
+        let mut args = vec!["show", "--no-pager", "-p", PROPERTIES];
+        args.extend(units);
+
+        let out = utils::run_program_get_output(&prog, &args, Some(timeout))?;
+
+        Ok(out.split("\n\n").filter_map(UnitStatus::parse).collect())
+    }
+
+    /// Get the status of the unit at `dir`, asking systemd if it isn't known.
+    fn unit(dir: &Path) -> Result<UnitStatus> {
+        let name = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .with_context(|| format!("{:?}: failed to get unit name", dir))?;
+
+        if let Some(status) = UNITS.lock().unwrap().get(name) {
+            return Ok(status.clone());
+        }
+
+        let (_, status) = Self::show(&[name])?
+            .into_iter()
+            .next()
+            .with_context(|| format!("unit {} not found", name))?;
+        UNITS.lock().unwrap().insert(name.to_string(), status.clone());
+
+        Ok(status)
+    }
+
+    /**
+     * systemd gives the time a unit became active as microseconds since
+     * boot, which is turned into wall clock time with the current uptime.
+     */
+    fn start_time(since: Option<Duration>) -> Result<SystemTime> {
+        let since = since.context("unit has not been active")?;
+
+        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) } != 0 {
+            bail!("failed to get monotonic time");
+        }
+        let now = Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32);
+
+        Ok(SystemTime::now() - now.saturating_sub(since))
+    }
+}
+
+impl Supervisor for Systemd {
+    fn name(&self) -> &'static str {
+        "systemd"
+    }
+
+    fn read_only(&self) -> bool {
+        true
+    }
+
+    /// Only used with `--backend systemd`, service directories aren't units.
+    fn detect(&self, _svdir: &Path) -> bool {
+        false
+    }
+
+    fn lists_dir(&self) -> bool {
+        false
+    }
+
+    /// Every loaded service unit (not the contents of the service directory).
+    fn list_services(&self, _svdir: &Path) -> Result<Vec<(String, PathBuf)>> {
+        let units = Self::show(&["*.service"])?;
+
+        let mut cache = UNITS.lock().unwrap();
+        cache.clear();
+
+        let mut services = vec![];
+        for (name, status) in units {
+            services.push((name.clone(), Path::new(UNIT_DIR).join(&name)));
+            cache.insert(name, status);
+        }
+
+        Ok(services)
+    }
+
+    /// systemd supervises every loaded unit.
+    fn is_supervised(&self, _dir: &Path) -> bool {
+        true
+    }
+
+    fn enabled(&self, dir: &Path) -> bool {
+        Self::unit(dir).is_ok_and(|status| status.enabled)
+    }
+
+    /// systemd doesn't record a wanted state like runit's.
+    fn status(&self, dir: &Path) -> Status {
+        let unit = match Self::unit(dir) {
+            Ok(unit) => unit,
+            // systemctl failing leaves the state unknown, it isn't an error
+            Err(err) => {
+                return Status { error: None, ..Status::unreadable(err) }
+            }
+        };
+
+        Status {
+            state: unit.state,
+            pid: Ok(unit.pid),
+            start_time: Self::start_time(unit.since),
+            want: None,
+            error: None,
+        }
+    }
+
+    fn control_bytes(&self, _cmd: ControlCommand) -> &'static [u8] {
+        b""
+    }
+
+    fn control(&self, _dir: &Path, cmd: ControlCommand) -> Result<()> {
+        bail!("cannot {}: the systemd backend is read-only", cmd.name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_status_parse() {
+        let block = "Id=sshd.service\nActiveState=active\nMainPID=42\n\
+                     ActiveEnterTimestampMonotonic=5000000\n\
+                     UnitFileState=enabled\n";
+
+        assert_eq!(
+            UnitStatus::parse(block),
+            Some((
+                "sshd.service".to_string(),
+                UnitStatus {
+                    state: RunitServiceState::Run,
+                    pid: Some(42),
+                    since: Some(Duration::from_secs(5)),
+                    enabled: true,
+                }
+            )),
+            "unit parsed"
+        );
+
+        let block = "Id=foo.service\nActiveState=failed\nMainPID=0\n\
+                     ActiveEnterTimestampMonotonic=0\nUnitFileState=static\n";
+        let (_, status) = UnitStatus::parse(block).unwrap();
+        assert_eq!(status.state, RunitServiceState::Down, "failed is down");
+        assert_eq!((status.pid, status.since), (None, None), "no pid");
+        assert!(!status.enabled, "static");
+
+        assert_eq!(UnitStatus::parse("ActiveState=active"), None, "no id");
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Facts that don't change from one refresh to the next (like where a service
+ * logs to), cached by the modes that refresh (`--watch` and `vsv tui`).
+ *
+ * Nothing is cached until `enable` is called, so a single listing reads
+ * everything as before.  Once enabled a fact is read only the first time it's
+ * looked up, and kept until a `sweep` finds it wasn't looked up since the last
+ * sweep (the service or process is gone).
+ */
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use libc::uid_t;
+
+use crate::backend::Supervisor;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The backend of every service directory, by path.
+static BACKENDS: Memo<PathBuf, &'static dyn Supervisor> = Memo::new(&ENABLED);
+
+/// The log directory of every service (see `RunitService::log_dir`), by path.
+static LOG_DIRS: Memo<PathBuf, Option<PathBuf>> = Memo::new(&ENABLED);
+
+/// The name of every user, by uid.
+static USER_NAMES: Memo<uid_t, Option<String>> = Memo::new(&ENABLED);
+
+/**
+ * Values by key, each noting if it was looked up since the last sweep.  Values
+ * are only cached while `enabled` is set.
+ */
+struct Memo<K, V> {
+    enabled: &'static AtomicBool,
+    values: Mutex<BTreeMap<K, (V, bool)>>,
+}
+
+impl<K: Ord, V: Clone> Memo<K, V> {
+    const fn new(enabled: &'static AtomicBool) -> Self {
+        Self { enabled, values: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Get the value of `key`, reading it with `read` if it isn't cached.
+    fn get(&self, key: K, read: impl FnOnce() -> V) -> V {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return read();
+        }
+
+        if let Some((value, used)) = self.lock().get_mut(&key) {
+            *used = true;
+            return value.clone();
+        }
+
+        // not locked while reading so other services are looked up meanwhile
+        let value = read();
+        self.lock().insert(key, (value.clone(), true));
+
+        value
+    }
+
+    /// Forget every value not looked up since the last sweep.
+    fn sweep(&self) {
+        self.lock().retain(|_, (_, used)| std::mem::take(used));
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<K, (V, bool)>> {
+        self.values.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Start caching, for every lookup from now on.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Forget everything not looked up since the last sweep, once per refresh.
+pub fn sweep() {
+    BACKENDS.sweep();
+    LOG_DIRS.sweep();
+    USER_NAMES.sweep();
+}
+
+/// Get the backend of a service directory, detecting it with `detect`.
+pub fn backend(
+    svdir: &Path,
+    detect: impl FnOnce() -> &'static dyn Supervisor,
+) -> &'static dyn Supervisor {
+    BACKENDS.get(svdir.to_path_buf(), detect)
+}
+
+/// Get the log directory of the service at `path`, finding it with `find`.
+pub fn log_dir(
+    path: &Path,
+    find: impl FnOnce() -> Option<PathBuf>,
+) -> Option<PathBuf> {
+    LOG_DIRS.get(path.to_path_buf(), find)
+}
+
+/// Get the name of the user `uid`, looking it up with `lookup`.
+pub fn user_name(
+    uid: uid_t,
+    lookup: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    USER_NAMES.get(uid, lookup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo() {
+        // a flag of its own so the global caches are left alone
+        static ENABLED: AtomicBool = AtomicBool::new(false);
+
+        let memo: Memo<u32, String> = Memo::new(&ENABLED);
+        let get = |key, value: &str| memo.get(key, || value.to_string());
+
+        // nothing is cached until enabled
+        assert_eq!(get(1, "a"), "a");
+        assert_eq!(get(1, "b"), "b");
+
+        ENABLED.store(true, Ordering::Relaxed);
+        assert_eq!(get(1, "a"), "a");
+        assert_eq!(get(1, "b"), "a", "cached");
+        assert_eq!(get(2, "c"), "c");
+
+        // a value looked up since the last sweep is kept
+        memo.sweep();
+        assert_eq!(get(1, "d"), "a", "kept");
+        memo.sweep();
+        assert_eq!(get(1, "e"), "a", "kept");
+        assert_eq!(get(2, "f"), "f", "swept");
+    }
+}
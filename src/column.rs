@@ -0,0 +1,305 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! Columns that can be selected for `vsv status` output (`-o`).
+
+use std::fmt;
+
+use crate::error::{bail, Result};
+
+/// A single column of `vsv status` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Status,
+    Name,
+    State,
+    Enabled,
+    Pid,
+    Command,
+    Uptime,
+    Origin,
+    Host,
+    LogSize,
+    Restarts,
+    Check,
+    Rss,
+    Cpu,
+    User,
+    Fds,
+    Threads,
+    Outdated,
+    Cgroup,
+    CgroupMemory,
+    Container,
+    Nice,
+    Class,
+    Oom,
+    OomAdj,
+    IoRead,
+    IoWrite,
+}
+
+/// The columns printed when `-o` is not given.
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Status,
+    Column::Name,
+    Column::State,
+    Column::Enabled,
+    Column::Pid,
+    Column::Command,
+    Column::Uptime,
+];
+
+/// Every column that can be selected (in the order listed in help output).
+pub const ALL_COLUMNS: &[Column] = &[
+    Column::Status,
+    Column::Name,
+    Column::State,
+    Column::Enabled,
+    Column::Pid,
+    Column::Command,
+    Column::Uptime,
+    Column::Origin,
+    Column::Host,
+    Column::LogSize,
+    Column::Restarts,
+    Column::Check,
+    Column::Rss,
+    Column::Cpu,
+    Column::User,
+    Column::Fds,
+    Column::Threads,
+    Column::Outdated,
+    Column::Cgroup,
+    Column::CgroupMemory,
+    Column::Container,
+    Column::Nice,
+    Column::Class,
+    Column::Oom,
+    Column::OomAdj,
+    Column::IoRead,
+    Column::IoWrite,
+];
+
+impl Column {
+    /**
+     * The name used to select this column with `-o` - also used as the field
+     * name in structured output formats.
+     */
+    pub fn key(&self) -> &'static str {
+        match self {
+            Column::Status => "status",
+            Column::Name => "name",
+            Column::State => "state",
+            Column::Enabled => "enabled",
+            Column::Pid => "pid",
+            Column::Command => "command",
+            Column::Uptime => "uptime",
+            Column::Origin => "origin",
+            Column::Host => "host",
+            Column::LogSize => "logsize",
+            Column::Restarts => "restarts",
+            Column::Check => "check",
+            Column::Rss => "rss",
+            Column::Cpu => "cpu",
+            Column::User => "user",
+            Column::Fds => "fds",
+            Column::Threads => "threads",
+            Column::Outdated => "outdated",
+            Column::Cgroup => "cgroup",
+            Column::CgroupMemory => "cgmem",
+            Column::Container => "container",
+            Column::Nice => "nice",
+            Column::Class => "class",
+            Column::Oom => "oom",
+            Column::OomAdj => "oomadj",
+            Column::IoRead => "ioread",
+            Column::IoWrite => "iowrite",
+        }
+    }
+
+    /// The header printed above this column in table output.
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Status => "",
+            Column::Name => "SERVICE",
+            Column::State => "STATE",
+            Column::Enabled => "ENABLED",
+            Column::Pid => "PID",
+            Column::Command => "COMMAND",
+            Column::Uptime => "TIME",
+            Column::Origin => "ORIGIN",
+            Column::Host => "HOST",
+            Column::LogSize => "LOGSIZE",
+            Column::Restarts => "RESTARTS",
+            Column::Check => "CHECK",
+            Column::Rss => "RSS",
+            Column::Cpu => "CPU%",
+            Column::User => "USER",
+            Column::Fds => "FDS",
+            Column::Threads => "THREADS",
+            Column::Outdated => "OUTDATED",
+            Column::Cgroup => "CGROUP",
+            Column::CgroupMemory => "CGMEM",
+            Column::Container => "CONTAINER",
+            Column::Nice => "NI",
+            Column::Class => "CLS",
+            Column::Oom => "OOM",
+            Column::OomAdj => "OOMADJ",
+            Column::IoRead => "READ",
+            Column::IoWrite => "WRITE",
+        }
+    }
+
+    /// The max width of this column in table output (0 for unlimited).
+    pub fn width(&self) -> usize {
+        match self {
+            Column::Status => 1,
+            Column::Name => 20,
+            Column::State => 7,
+            Column::Enabled => 9,
+            Column::Pid => 8,
+            Column::Command => 17,
+            Column::Uptime => 0,
+            Column::Origin => 10,
+            Column::Host => 10,
+            Column::LogSize => 7,
+            Column::Restarts => 8,
+            Column::Check => 5,
+            Column::Rss => 7,
+            Column::Cpu => 6,
+            Column::User => 8,
+            Column::Fds => 6,
+            Column::Threads => 7,
+            Column::Outdated => 8,
+            Column::Cgroup => 20,
+            Column::CgroupMemory => 7,
+            Column::Container => 9,
+            Column::Nice => 3,
+            Column::Class => 3,
+            Column::Oom => 4,
+            Column::OomAdj => 6,
+            Column::IoRead => 7,
+            Column::IoWrite => 7,
+        }
+    }
+
+    /// Whether this column makes sense in structured (non-table) output.
+    pub fn is_structured(&self) -> bool {
+        !matches!(self, Column::Status)
+    }
+
+    /// Whether this column can be shrunk or expanded to fit the terminal.
+    pub fn is_flexible(&self) -> bool {
+        matches!(
+            self,
+            Column::Name
+                | Column::Command
+                | Column::Origin
+                | Column::Host
+                | Column::Cgroup
+        )
+    }
+
+    /// Parse a column from its name (or an alias of it).
+    pub fn parse(s: &str) -> Result<Self> {
+        let column = match s {
+            "status" | "char" => Column::Status,
+            "name" | "service" => Column::Name,
+            "state" => Column::State,
+            "enabled" => Column::Enabled,
+            "pid" => Column::Pid,
+            "command" | "cmd" => Column::Command,
+            "uptime" | "time" => Column::Uptime,
+            "origin" => Column::Origin,
+            "host" | "hostname" => Column::Host,
+            "logsize" => Column::LogSize,
+            "restarts" => Column::Restarts,
+            "check" => Column::Check,
+            "rss" | "memory" | "mem" => Column::Rss,
+            "cpu" | "%cpu" => Column::Cpu,
+            "user" | "uid" => Column::User,
+            "fds" | "fd" | "files" => Column::Fds,
+            "threads" | "nlwp" => Column::Threads,
+            "outdated" | "deleted" => Column::Outdated,
+            "cgroup" => Column::Cgroup,
+            "cgmem" | "cgroup-memory" => Column::CgroupMemory,
+            "container" | "ns" => Column::Container,
+            "nice" | "ni" => Column::Nice,
+            "class" | "cls" | "sched" => Column::Class,
+            "oom" | "oom_score" => Column::Oom,
+            "oomadj" | "oom_score_adj" => Column::OomAdj,
+            "ioread" | "rbytes" | "read" => Column::IoRead,
+            "iowrite" | "wbytes" | "write" => Column::IoWrite,
+            _ => bail!(
+                "unknown column: '{}' (valid columns: {})",
+                s,
+                ALL_COLUMNS
+                    .iter()
+                    .map(Column::key)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        };
+
+        Ok(column)
+    }
+
+    /// Parse a comma-separated list of columns like `"name,state,pid"`.
+    pub fn parse_list(s: &str) -> Result<Vec<Self>> {
+        let columns = s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(Column::parse)
+            .collect::<Result<Vec<_>>>()?;
+
+        if columns.is_empty() {
+            bail!("at least one (1) column required");
+        }
+
+        Ok(columns)
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.key().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list() -> Result<()> {
+        let columns = Column::parse_list("name, state,,time")?;
+
+        assert_eq!(
+            columns,
+            [Column::Name, Column::State, Column::Uptime],
+            "columns parsed"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_list_bad() {
+        assert!(Column::parse_list("name,bogus").is_err(), "unknown column");
+        assert!(Column::parse_list(",").is_err(), "no columns");
+    }
+
+    #[test]
+    fn test_parse_round_trip() -> Result<()> {
+        for column in ALL_COLUMNS {
+            assert_eq!(Column::parse(column.key())?, *column, "round trip");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv avail`.
+
+use anyhow::{Context, Result};
+use yansi::Style;
+
+use crate::config::{self, Config};
+use crate::formatting;
+use crate::runit;
+use crate::theme;
+
+/// Char shown in the status column for services that aren't enabled.
+const AVAIL_CHAR: &str = "-";
+
+/**
+ * Handle `vsv avail`.
+ *
+ * Services that exist in the source directory (`/etc/sv` by default) but
+ * aren't linked into the service directory are listed.
+ */
+pub fn do_avail(cfg: &Config) -> Result<()> {
+    let source = cfg
+        .link_source
+        .clone()
+        .unwrap_or_else(|| config::DEFAULT_SOURCE_DIR.into());
+
+    // every name filter given must match and no exclusions can match
+    let filter = |name: &str| {
+        cfg.name_filters.iter().all(|re| re.is_match(name))
+            && !cfg.exclude_filters.iter().any(|re| re.is_match(name))
+    };
+
+    let names = runit::get_available_services(&source, &cfg.svdir, filter)
+        .with_context(|| format!("failed to list services in {:?}", source))?;
+
+    let rows: Vec<(String, String)> = names
+        .into_iter()
+        .map(|name| {
+            let path = source.join(&name).display().to_string();
+            (name, path)
+        })
+        .collect();
+
+    let width = rows
+        .iter()
+        .map(|(name, _)| formatting::str_width(name))
+        .chain(["SERVICE".len()])
+        .max()
+        .unwrap_or(0);
+
+    let style = Style::default();
+
+    if !cfg.no_header {
+        println!();
+        println!(
+            "{}",
+            formatting::format_status_line(&[
+                ("", style.bold(), 1),
+                ("SERVICE", style.bold(), width),
+                ("SOURCE", style.bold(), 0),
+            ])
+        );
+    }
+
+    for (name, path) in &rows {
+        println!(
+            "{}",
+            formatting::format_status_line(&[
+                (AVAIL_CHAR, theme::get().note, 1),
+                (name, style, width),
+                (path, style.dimmed(), 0),
+            ])
+        );
+    }
+
+    if !cfg.no_header {
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv check-plugin`.
+
+use anyhow::Result;
+
+use crate::commands::status;
+use crate::commands::ExitCode;
+use crate::config::verbose;
+use crate::config::Config;
+use crate::service::{Service, ServiceState};
+
+/// The exit codes (and names) of check plugin states.
+const OK: (i32, &str) = (0, "OK");
+const WARNING: (i32, &str) = (1, "WARNING");
+const CRITICAL: (i32, &str) = (2, "CRITICAL");
+const UNKNOWN: (i32, &str) = (3, "UNKNOWN");
+
+/**
+ * Handle `vsv check-plugin`.
+ *
+ * Like a Nagios (or Icinga) check plugin this prints a single line of the
+ * status and perfdata and exits with the status's code: OK (0) if every
+ * service is healthy, WARNING (1) if any is down, flapping or has an unknown
+ * state, CRITICAL (2) if any given with `--critical-down` is down (or missing)
+ * and UNKNOWN (3) if the services can't be read at all.
+ */
+pub fn do_check_plugin(cfg: &Config) -> Result<()> {
+    let (code, line) = match status::get_services(cfg) {
+        Ok(services) => {
+            for (_, messages) in &services {
+                for message in messages {
+                    verbose!(cfg, "{}", message);
+                }
+            }
+            check(&services, &cfg.critical_down)
+        }
+        Err(err) => (UNKNOWN.0, format!("VSV {} - {:#}", UNKNOWN.1, err)),
+    };
+
+    println!("{}", line);
+
+    if code != 0 {
+        return Err(ExitCode(code).into());
+    }
+
+    Ok(())
+}
+
+/**
+ * Check the gathered services, returning the exit code and status line (like
+ * `VSV WARNING - down: foo | total=2 run=1 down=1 flapping=0 unknown=0`).
+ */
+fn check(
+    services: &[(Service, Vec<String>)],
+    critical_down: &[String],
+) -> (i32, String) {
+    let services: Vec<&Service> = services.iter().map(|(s, _)| s).collect();
+    let is_down = |service: &&Service| {
+        service.state != ServiceState::Run && !service.is_normally_down()
+    };
+    let names = |services: Vec<&Service>| -> Vec<String> {
+        services.iter().map(|service| service.name.clone()).collect()
+    };
+
+    let (critical, down): (Vec<&Service>, Vec<&Service>) = services
+        .iter()
+        .copied()
+        .filter(is_down)
+        .partition(|service| critical_down.contains(&service.name));
+    let flapping: Vec<&Service> =
+        services.iter().copied().filter(|s| s.is_flapping()).collect();
+    let unknown: Vec<&Service> = services
+        .iter()
+        .copied()
+        .filter(|s| {
+            matches!(s.state, ServiceState::Unknown | ServiceState::Error)
+        })
+        .collect();
+    let missing: Vec<&String> = critical_down
+        .iter()
+        .filter(|name| !services.iter().any(|s| &s.name == *name))
+        .collect();
+
+    let perfdata = format!(
+        "total={} run={} down={} flapping={} unknown={}",
+        services.len(),
+        services.iter().filter(|s| s.state == ServiceState::Run).count(),
+        critical.len() + down.len(),
+        flapping.len(),
+        unknown.len()
+    );
+
+    let mut problems = vec![];
+    let mut critical = names(critical);
+    critical.extend(missing.iter().map(|name| format!("{} (missing)", name)));
+    for (label, names) in [
+        ("critical", critical),
+        ("down", names(down)),
+        ("flapping", names(flapping)),
+        ("unknown", names(unknown)),
+    ] {
+        if !names.is_empty() {
+            problems.push((label, names));
+        }
+    }
+
+    let (code, status) = match problems.first() {
+        None => OK,
+        Some(("critical", _)) => CRITICAL,
+        Some(_) => WARNING,
+    };
+
+    let summary = match problems.is_empty() {
+        true => format!("{} services healthy", services.len()),
+        false => problems
+            .iter()
+            .map(|(label, names)| format!("{}: {}", label, names.join(", ")))
+            .collect::<Vec<_>>()
+            .join("; "),
+    };
+
+    (code, format!("VSV {} - {} | {}", status, summary, perfdata))
+}
@@ -0,0 +1,608 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * `vsv completions` - print shell completion scripts.
+ *
+ * The scripts are generated from the argument definitions (see `arguments`),
+ * so every subcommand and option is completed.  Service names are completed
+ * by running `vsv completions --services` when completing, which lists the
+ * service directory (like `$SVDIR` or the config file's `dir`).
+ */
+
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+use clap::{Arg, Command, CommandFactory};
+
+use crate::arguments::Args;
+use crate::backend;
+use crate::column;
+use crate::config::Config;
+use crate::glyphs;
+use crate::runit::ControlCommand;
+use crate::theme;
+use crate::time_format;
+
+/// The shells completion scripts can be printed for.
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+/// Positional arguments that are service names (or filters of them).
+const SERVICE_ARGS: &[&str] = &["services", "service", "filter"];
+
+/// What to complete as an option's value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    /// The option is a flag.
+    None,
+
+    /// The option can be given with or without a value (like `--watch=2`).
+    Optional,
+
+    /// One of these words.
+    Words(Vec<String>),
+
+    Files,
+    Dirs,
+
+    /// Anything (nothing is completed).
+    Any,
+}
+
+/// An option to complete.
+#[derive(Debug)]
+struct Opt {
+    short: Option<char>,
+    long: Option<String>,
+    help: String,
+    value: Value,
+}
+
+/// A subcommand to complete (or the top-level command, with no name).
+#[derive(Debug)]
+struct Sub {
+    name: String,
+    help: String,
+    opts: Vec<Opt>,
+
+    /// What to complete as the arguments that aren't options.
+    operands: Value,
+
+    /// If the operands are services.
+    services: bool,
+}
+
+/// Handle `vsv completions`.
+pub fn do_completions(cfg: &Config) -> Result<()> {
+    if cfg.complete_services {
+        return list_services(cfg);
+    }
+
+    let shell = match cfg.operands.first() {
+        Some(shell) => shell.as_str(),
+        None => bail!("a shell is required: {}", SHELLS.join(", ")),
+    };
+
+    print!("{}", script(shell)?);
+
+    Ok(())
+}
+
+/// Print the name of every service, one per line, for the scripts.
+fn list_services(cfg: &Config) -> Result<()> {
+    let backend = cfg.backend_for(&cfg.svdir);
+    let mut names: Vec<String> = backend
+        .list_services(&cfg.svdir)
+        .with_context(|| format!("failed to list services in {:?}", cfg.svdir))?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+
+    for name in names {
+        println!("{}", name);
+    }
+
+    Ok(())
+}
+
+/// Get the completion script for a shell (one of `SHELLS`).
+pub fn script(shell: &str) -> Result<String> {
+    let cmd = Args::command();
+    let subs = subcommands(&cmd);
+
+    let script = match shell {
+        "bash" => bash(&subs),
+        "zsh" => zsh(&subs),
+        "fish" => fish(&subs),
+        _ => bail!(
+            "unknown shell: '{}' (expected one of {})",
+            shell,
+            SHELLS.join(", ")
+        ),
+    };
+
+    Ok(script)
+}
+
+/**
+ * Get the top-level command (first, with its own and every global option)
+ * and each subcommand (with its own and every global option).
+ */
+fn subcommands(cmd: &Command) -> Vec<Sub> {
+    let globals: Vec<&Arg> =
+        cmd.get_arguments().filter(|arg| arg.is_global_set()).collect();
+    let strings = |words: &[&str]| -> Vec<String> {
+        words.iter().map(|s| s.to_string()).collect()
+    };
+
+    let mut subs = vec![Sub {
+        name: String::new(),
+        help: String::new(),
+        opts: options(cmd.get_arguments()),
+        operands: Value::Words(
+            cmd.get_subcommands()
+                .filter(|sub| !sub.is_hide_set())
+                .map(|sub| sub.get_name().to_string())
+                .collect(),
+        ),
+        services: false,
+    }];
+
+    for sub in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        let positional = sub.get_positionals().next().map(Arg::get_id);
+        let services = sub.get_name() != "avail"
+            && positional.is_some_and(|id| SERVICE_ARGS.contains(&id));
+        let operands = match (sub.get_name(), positional) {
+            ("pick", _) => {
+                let mut actions = strings(&["enable", "disable"]);
+                // like `vsv pick restart`
+                actions.extend(
+                    cmd.get_subcommands()
+                        .map(|sub| sub.get_name())
+                        .filter(|name| ControlCommand::parse(name).is_some())
+                        .map(String::from),
+                );
+                Value::Words(actions)
+            }
+            ("completions", _) => Value::Words(strings(SHELLS)),
+            // services in the source directory, not the service directory
+            ("avail", _) => Value::Any,
+            (_, Some(_)) => Value::Any,
+            (_, None) => Value::None,
+        };
+
+        let args = sub.get_arguments().chain(globals.iter().copied());
+        subs.push(Sub {
+            name: sub.get_name().to_string(),
+            help: help(sub.get_about()),
+            opts: options(args),
+            operands,
+            services,
+        });
+    }
+
+    subs
+}
+
+/// Get the options (not positional arguments) to complete, each only once.
+fn options<'a, 'help: 'a>(
+    args: impl Iterator<Item = &'a Arg<'help>>,
+) -> Vec<Opt> {
+    let mut seen = vec![];
+    args.filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+        .filter(|arg| {
+            let new = !seen.contains(&arg.get_id());
+            seen.push(arg.get_id());
+            new
+        })
+        .map(|arg| Opt {
+            short: arg.get_short(),
+            long: arg.get_long().map(String::from),
+            help: help(arg.get_help()),
+            value: value(arg),
+        })
+        .collect()
+}
+
+/// Get what to complete as the value of an option.
+fn value(arg: &Arg) -> Value {
+    let long = arg.get_long().unwrap_or_default();
+    if !arg.is_takes_value_set() {
+        return Value::None;
+    }
+    // clap doesn't say the value is optional, but it must be given with `=`
+    if arg.is_require_equals_set() {
+        return Value::Optional;
+    }
+
+    let strings = |words: &[&str]| {
+        Value::Words(words.iter().map(|s| s.to_string()).collect())
+    };
+    let columns = || {
+        Value::Words(
+            column::ALL_COLUMNS.iter().map(|c| c.key().to_string()).collect(),
+        )
+    };
+
+    match long {
+        "color" => strings(&["auto", "always", "never"]),
+        "theme" => strings(theme::NAMES),
+        "glyphs" => strings(glyphs::NAMES),
+        "time-style" => strings(time_format::NAMES),
+        "format" => strings(&[
+            "table", "json", "yaml", "csv", "markdown", "raw", "snapshot",
+        ]),
+        "backend" => {
+            let mut names = vec!["auto".to_string()];
+            names.extend(backend::BACKENDS.iter().map(|b| b.name().into()));
+            Value::Words(names)
+        }
+        "columns" | "sort" => columns(),
+        "group-by" => strings(&["state", "origin"]),
+        "state" => strings(&[
+            "run",
+            "down",
+            "finish",
+            "zombie",
+            "stopped",
+            "unsupervised",
+            "unknown",
+            "error",
+        ]),
+        _ => match arg.get_value_names() {
+            Some(["dir"]) => Value::Dirs,
+            Some(["file"]) => Value::Files,
+            _ => Value::Any,
+        },
+    }
+}
+
+/// The first line of a help message, without a trailing period.
+fn help(s: Option<&str>) -> String {
+    let line = s.unwrap_or_default().lines().next().unwrap_or_default();
+
+    line.trim().trim_end_matches('.').to_string()
+}
+
+/// Get the ways an option can be given, like `-c` and `--color`.
+fn flags(opt: &Opt) -> Vec<String> {
+    let mut flags = vec![];
+    if let Some(c) = opt.short {
+        flags.push(format!("-{}", c));
+    }
+    if let Some(long) = &opt.long {
+        flags.push(format!("--{}", long));
+    }
+
+    flags
+}
+
+/// Quote a string for a shell in single quotes.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Generate the bash completion script.
+fn bash(subs: &[Sub]) -> String {
+    let top = &subs[0];
+    let mut s = String::new();
+
+    // options that take a value, which isn't the subcommand
+    let top_values: Vec<String> = top
+        .opts
+        .iter()
+        .filter(|opt| !matches!(opt.value, Value::None | Value::Optional))
+        .flat_map(flags)
+        .collect();
+
+    let _ = write!(
+        s,
+        r#"# bash completion for vsv, generated by `vsv completions bash`
+
+_vsv_services() {{
+    "${{COMP_WORDS[0]}}" completions --services 2>/dev/null
+}}
+
+_vsv() {{
+    local cur=${{COMP_WORDS[COMP_CWORD]}} prev=${{COMP_WORDS[COMP_CWORD-1]}}
+    local cmd= i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case ${{COMP_WORDS[i]}} in
+            {}) ((i++)) ;;
+            -*) ;;
+            *) cmd=${{COMP_WORDS[i]}}; break ;;
+        esac
+    done
+
+    case $prev in
+"#,
+        top_values.join("|")
+    );
+
+    // the value of an option, the same option in any subcommand is the same
+    let mut seen: Vec<String> = vec![];
+    for opt in subs.iter().flat_map(|sub| &sub.opts) {
+        let flags: Vec<String> =
+            flags(opt).into_iter().filter(|f| !seen.contains(f)).collect();
+        if flags.is_empty() {
+            continue;
+        }
+        let reply = match &opt.value {
+            Value::None | Value::Optional => continue,
+            Value::Words(words) => format!(
+                "COMPREPLY=($(compgen -W {} -- \"$cur\"))",
+                quote(&words.join(" "))
+            ),
+            Value::Files => "COMPREPLY=($(compgen -f -- \"$cur\"))".into(),
+            Value::Dirs => "COMPREPLY=($(compgen -d -- \"$cur\"))".into(),
+            Value::Any => "COMPREPLY=()".into(),
+        };
+        let _ =
+            writeln!(s, "        {}) {}; return ;;", flags.join("|"), reply);
+        seen.extend(flags);
+    }
+
+    s.push_str(
+        r#"    esac
+
+    if [[ $cur == -* ]]; then
+        local opts
+        case $cmd in
+"#,
+    );
+    for sub in subs {
+        let opts: Vec<String> = sub.opts.iter().flat_map(flags).collect();
+        let _ = writeln!(
+            s,
+            "            {}) opts={} ;;",
+            quote(&sub.name),
+            quote(&opts.join(" "))
+        );
+    }
+    s.push_str(
+        r#"        esac
+        COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+        return
+    fi
+
+    case $cmd in
+"#,
+    );
+    for sub in subs {
+        let reply = match (&sub.operands, sub.services) {
+            (_, true) => {
+                "COMPREPLY=($(compgen -W \"$(_vsv_services)\" -- \"$cur\"))"
+                    .to_string()
+            }
+            (Value::Words(words), _) => format!(
+                "COMPREPLY=($(compgen -W {} -- \"$cur\"))",
+                quote(&words.join(" "))
+            ),
+            _ => continue,
+        };
+        let _ = writeln!(s, "        {}) {} ;;", quote(&sub.name), reply);
+    }
+    s.push_str(
+        r#"    esac
+}
+
+complete -F _vsv vsv
+"#,
+    );
+
+    s
+}
+
+/// Generate the zsh completion script.
+fn zsh(subs: &[Sub]) -> String {
+    // an `_arguments` spec for each way an option can be given
+    let specs = |sub: &Sub| -> Vec<String> {
+        let mut specs = vec![];
+        for opt in &sub.opts {
+            let help = opt.help.replace('\\', r"\\").replace(['[', ']'], "");
+            let value = match &opt.value {
+                Value::None => String::new(),
+                Value::Optional => "::value: ".into(),
+                Value::Words(words) => format!(":value:({})", words.join(" ")),
+                Value::Files => ":file:_files".into(),
+                Value::Dirs => ":dir:_files -/".into(),
+                Value::Any => ":value: ".into(),
+            };
+            for mut flag in flags(opt) {
+                if opt.value == Value::Optional {
+                    flag.push_str("=-");
+                }
+                specs.push(quote(&format!("{}[{}]{}", flag, help, value)));
+            }
+        }
+        specs
+    };
+    let indent = |specs: Vec<String>| -> String {
+        specs
+            .iter()
+            .map(|spec| format!("                {} \\\n", spec))
+            .collect()
+    };
+
+    let mut s = String::new();
+    let _ = write!(
+        s,
+        r#"#compdef vsv
+# zsh completion for vsv, generated by `vsv completions zsh`
+
+_vsv_services() {{
+    local -a services
+    services=(${{(f)"$(_call_program services $vsv completions --services 2>/dev/null)"}})
+    _wanted services expl service compadd -a services
+}}
+
+_vsv() {{
+    local vsv=$words[1] curcontext=$curcontext state line
+    local -a commands=(
+"#
+    );
+    for sub in &subs[1..] {
+        let name = format!("{}:{}", sub.name, sub.help.replace(':', r"\:"));
+        let _ = writeln!(s, "        {}", quote(&name));
+    }
+    let _ = write!(
+        s,
+        r#"    )
+
+    _arguments -C \
+{}        '1: :->command' \
+        '*:: :->args'
+
+    case $state in
+        command)
+            _describe -t commands command commands
+            ;;
+        args)
+            curcontext=${{curcontext%:*:*}}:vsv-$words[1]:
+            case $words[1] in
+"#,
+        indent(specs(&subs[0])).replace("                ", "        ")
+    );
+    for sub in &subs[1..] {
+        let operands = match (&sub.operands, sub.services) {
+            (_, true) => "'*:service:_vsv_services'".to_string(),
+            (Value::Words(words), _) => {
+                format!("'1:{}:({})'", sub.name, words.join(" "))
+            }
+            _ => "'*: :'".to_string(),
+        };
+        let _ = write!(
+            s,
+            "            {})\n                _arguments \\\n{}                {}\n                ;;\n",
+            quote(&sub.name),
+            indent(specs(sub)),
+            operands
+        );
+    }
+    s.push_str(
+        r#"            esac
+            ;;
+    esac
+}
+
+_vsv "$@"
+"#,
+    );
+
+    s
+}
+
+/// Generate the fish completion script.
+fn fish(subs: &[Sub]) -> String {
+    let mut s = String::from(
+        r#"# fish completion for vsv, generated by `vsv completions fish`
+
+function __vsv_services
+    vsv completions --services 2>/dev/null
+end
+
+complete -c vsv -f
+"#,
+    );
+
+    for sub in subs {
+        let condition = match sub.name.is_empty() {
+            true => "__fish_use_subcommand".to_string(),
+            false => format!("__fish_seen_subcommand_from {}", sub.name),
+        };
+
+        if sub.name.is_empty() {
+            s.push_str("\n# vsv\n");
+        } else {
+            let _ = writeln!(s, "\n# vsv {}", sub.name);
+            let _ = writeln!(
+                s,
+                "complete -c vsv -n __fish_use_subcommand -a {} -d {}",
+                sub.name,
+                quote(&sub.help)
+            );
+        }
+
+        for opt in &sub.opts {
+            let mut line = format!("complete -c vsv -n {}", quote(&condition));
+            if let Some(c) = opt.short {
+                let _ = write!(line, " -s {}", c);
+            }
+            if let Some(long) = &opt.long {
+                let _ = write!(line, " -l {}", long);
+            }
+            match &opt.value {
+                Value::None | Value::Optional => (),
+                Value::Words(words) => {
+                    let _ = write!(line, " -x -a {}", quote(&words.join(" ")));
+                }
+                Value::Files => line.push_str(" -r -F"),
+                Value::Dirs => {
+                    line.push_str(" -x -a '(__fish_complete_directories)'")
+                }
+                Value::Any => line.push_str(" -x"),
+            }
+            let _ = writeln!(s, "{} -d {}", line, quote(&opt.help));
+        }
+
+        let operands = match (&sub.operands, sub.services) {
+            (_, true) => "'(__vsv_services)'".to_string(),
+            (Value::Words(words), _) if !sub.name.is_empty() => {
+                quote(&words.join(" "))
+            }
+            _ => continue,
+        };
+        let _ = writeln!(
+            s,
+            "complete -c vsv -n {} -a {}",
+            quote(&condition),
+            operands
+        );
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() -> Result<()> {
+        for shell in SHELLS {
+            let script = script(shell)?;
+            for word in ["restart", "check-plugin", "columns", "jobs"] {
+                assert!(script.contains(word), "{}: {}", shell, word);
+            }
+        }
+        assert!(script("powershell").is_err());
+
+        // hidden options aren't completed
+        let subs = subcommands(&Args::command());
+        let completions = subs.iter().find(|sub| sub.name == "completions");
+        let opts = &completions.expect("completions").opts;
+        assert!(opts.iter().all(|opt| opt.long.as_deref() != Some("services")));
+
+        // values of options
+        let bash = script("bash")?;
+        assert!(bash.contains("--theme) COMPREPLY=($(compgen -W 'default "));
+        assert!(bash.contains(
+            "'restart') COMPREPLY=($(compgen -W \"$(_vsv_services)\""
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("don't"), r"'don'\''t'");
+        assert_eq!(
+            help(Some("Restart service(s).\nMore.")),
+            "Restart service(s)"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv up`, `vsv down`, `vsv restart`, `vsv hup`, etc.
+
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, ensure, Context, Result};
+use rayon::prelude::*;
+use yansi::Style;
+
+use crate::column::Column;
+use crate::commands::status;
+use crate::config::Config;
+use crate::formatting;
+use crate::regex::Regex;
+use crate::runit::{self, ControlCommand, RunitService};
+use crate::service::Service;
+use crate::theme;
+use crate::utils;
+
+/// How long (at most) to wait for services to reach their wanted state.
+const WAIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/**
+ * Handle `vsv <up|down|restart|...> <service>...`.
+ *
+ * Services can be given by name or as shell-style globs (like `'php-fpm*'`)
+ * which are expanded against the service directory (or `--all` for every
+ * service).  Acting on more than one service must be confirmed unless `--yes`
+ * is given.
+ */
+pub fn do_control(cfg: &Config, cmd: ControlCommand) -> Result<()> {
+    let names = if cfg.all {
+        expand_services(cfg, &["*".to_string()])?
+    } else {
+        ensure!(!cfg.operands.is_empty(), "at least one (1) service required");
+        expand_services(cfg, &cfg.operands)?
+    };
+
+    if names.len() > 1 && !cfg.yes && !cfg.dry_run && !confirm(cmd, &names)? {
+        bail!("aborted");
+    }
+
+    control_services(cfg, &cfg.svdir, &names, cmd)
+}
+
+/**
+ * Ask the user (on stderr) to confirm sending a command to the given services,
+ * returns `true` if they answered yes.
+ */
+fn confirm(cmd: ControlCommand, names: &[String]) -> Result<bool> {
+    ensure!(
+        utils::isatty(0),
+        "refusing to {} {} services without confirmation (use --yes)",
+        cmd.name(),
+        names.len()
+    );
+
+    let mut stderr = io::stderr();
+    writeln!(stderr, "{} {} services:", cmd.name(), names.len())?;
+    for name in names {
+        writeln!(stderr, "  {}", name)?;
+    }
+    write!(stderr, "continue? [y/N] ")?;
+    stderr.flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "Yes" | "YES"))
+}
+
+/**
+ * Send a command to every service given by name (in `svdir`), then print a
+ * table of each service's new status alongside whether or not the command was
+ * sent.
+ */
+pub fn control_services(
+    cfg: &Config,
+    svdir: &Path,
+    names: &[String],
+    cmd: ControlCommand,
+) -> Result<()> {
+    let results: Vec<(&str, Result<RunitService>)> = names
+        .iter()
+        .map(|name| (name.as_str(), control_service(cfg, svdir, name, cmd)))
+        .collect();
+
+    if !cfg.dry_run {
+        let sent: Vec<&RunitService> =
+            results.iter().filter_map(|(_, ret)| ret.as_ref().ok()).collect();
+        wait_for_state(&sent, cmd, cfg.opts.timeout);
+    }
+
+    print_results(cfg, cmd, &results);
+
+    let failed = results.iter().filter(|(_, ret)| ret.is_err()).count();
+    ensure!(failed == 0, "failed to {} {} service(s)", cmd.name(), failed);
+
+    Ok(())
+}
+
+/**
+ * Expand any globs (operands containing `*`, `?` or `[`) to the names of the
+ * services they match, in order and without duplicates.  Other operands are
+ * kept as-is.
+ */
+pub fn expand_services(
+    cfg: &Config,
+    operands: &[String],
+) -> Result<Vec<String>> {
+    let mut names: Vec<String> = vec![];
+
+    for operand in operands {
+        if !operand.contains(['*', '?', '[']) {
+            if !names.contains(operand) {
+                names.push(operand.to_string());
+            }
+            continue;
+        }
+
+        let re = Regex::from_glob(operand)?;
+        let backend = cfg.backend_for(&cfg.svdir);
+        let services =
+            runit::get_services(&cfg.svdir, false, backend, |name| {
+                re.is_match(name)
+            })
+            .with_context(|| {
+                format!("failed to list services in {:?}", cfg.svdir)
+            })?;
+
+        if services.is_empty() {
+            bail!("no services match '{}'", operand);
+        }
+
+        for service in services {
+            if !names.contains(&service.name) {
+                names.push(service.name);
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+/// Send a command to a single service by name.
+fn control_service(
+    cfg: &Config,
+    svdir: &Path,
+    name: &str,
+    cmd: ControlCommand,
+) -> Result<RunitService> {
+    let svc =
+        RunitService::new(name, &svdir.join(name), cfg.backend_for(svdir));
+
+    ensure!(
+        !svc.backend.read_only(),
+        "the {} backend is read-only",
+        svc.backend.name()
+    );
+    ensure!(svc.valid(), "service not valid");
+    if !cfg.dry_run {
+        svc.control(cmd)?;
+    }
+
+    Ok(svc)
+}
+
+/**
+ * Wait (at most `WAIT_TIMEOUT`, or `--timeout` if shorter) for every service
+ * to reach the state wanted by the command so the status shown afterwards is
+ * up to date.
+ */
+fn wait_for_state(
+    services: &[&RunitService],
+    cmd: ControlCommand,
+    timeout: Option<Duration>,
+) {
+    let wanted = match cmd.wanted_state() {
+        Some(wanted) => wanted,
+        None => return,
+    };
+
+    let timeout = timeout.map_or(WAIT_TIMEOUT, |t| t.min(WAIT_TIMEOUT));
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if services.iter().all(|svc| svc.get_state() == wanted) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/**
+ * Print the status of every service with an extra `RESULT` column, services
+ * that couldn't be controlled only have their name shown.  With `--dry-run`
+ * the result is what would have been written.
+ */
+fn print_results(
+    cfg: &Config,
+    cmd: ControlCommand,
+    results: &[(&str, Result<RunitService>)],
+) {
+    let style = Style::default();
+
+    let rows: Vec<Vec<(String, Style)>> = results
+        .par_iter()
+        .map(|(name, ret)| {
+            let service = ret
+                .as_ref()
+                .ok()
+                .map(|svc| Service::from_runit_service(svc, &cfg.opts).0);
+
+            let mut row: Vec<(String, Style)> = cfg
+                .opts
+                .columns
+                .iter()
+                .map(|column| match (&service, column) {
+                    (Some(service), _) => service.format_column(*column),
+                    (None, Column::Name) => (name.to_string(), style),
+                    (None, _) => (String::new(), style),
+                })
+                .collect();
+
+            row.push(match ret {
+                Ok(svc) if cfg.dry_run => (
+                    format!(
+                        "dry run: would write {:?} to {:?}",
+                        String::from_utf8_lossy(svc.backend.control_bytes(cmd)),
+                        svc.path.join("supervise").join("control")
+                    ),
+                    theme::get().warn,
+                ),
+                Ok(_) => ("done".into(), theme::get().good),
+                Err(err) => (format!("failed: {}", err), theme::get().bad),
+            });
+
+            row
+        })
+        .collect();
+
+    // the result column is last (and never trimmed)
+    let term_width =
+        cfg.term_width.map(|width| width.saturating_sub("RESULT".len() + 1));
+    let mut widths = status::column_widths(cfg, &rows, term_width);
+    widths.push(0);
+
+    if !cfg.no_header {
+        let header: Vec<(&str, Style, usize)> = cfg
+            .opts
+            .columns
+            .iter()
+            .map(Column::header)
+            .chain(["RESULT"])
+            .zip(&widths)
+            .map(|(header, width)| (header, style.bold(), *width))
+            .collect();
+        println!();
+        println!("{}", formatting::format_status_line(&header));
+    }
+
+    for row in rows {
+        let line: Vec<(&str, Style, usize)> = row
+            .iter()
+            .zip(&widths)
+            .map(|((s, style), width)| (s.as_str(), *style, *width))
+            .collect();
+        println!("{}", formatting::format_status_line(&line));
+    }
+
+    if !cfg.no_header {
+        println!();
+    }
+}
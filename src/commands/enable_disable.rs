@@ -6,12 +6,15 @@
 
 //! `vsv enable` and `vsv disable`.
 
+use std::path::Path;
+
 use anyhow::{ensure, Result};
-use yansi::{Color, Style};
+use yansi::Style;
 
 use crate::config;
 use crate::config::Config;
-use crate::runit::RunitService;
+use crate::runit::{self, RunitService};
+use crate::theme;
 
 /// Handle `vsv enable`.
 pub fn do_enable(cfg: &Config) -> Result<()> {
@@ -27,39 +30,130 @@ pub fn do_disable(cfg: &Config) -> Result<()> {
 fn _do_enable_disable(cfg: &Config) -> Result<()> {
     ensure!(!cfg.operands.is_empty(), "at least one (1) service required");
 
+    let enable = match cfg.mode {
+        config::ProgramMode::Enable => true,
+        config::ProgramMode::Disable => false,
+        _ => unreachable!(),
+    };
+
     let mut had_error = false;
 
     for name in &cfg.operands {
-        let p = cfg.svdir.join(name);
-        let svc = RunitService::new(name, &p);
-        print!(
-            "{} service {}... ",
-            cfg.mode,
-            Style::default().bold().paint(name)
-        );
-
-        if !svc.valid() {
-            println!("{}", Color::Red.paint("failed! service not valid"));
+        let ok = match &cfg.link_source {
+            Some(source) => link_unlink_service(cfg, source, name, enable),
+            None => enable_disable_service(cfg, &cfg.svdir, name, enable),
+        };
+        if !ok {
             had_error = true;
-            continue;
         }
+    }
 
-        let ret = match cfg.mode {
-            config::ProgramMode::Enable => svc.enable(),
-            config::ProgramMode::Disable => svc.disable(),
-            _ => unreachable!(),
-        };
+    ensure!(!had_error, "failed to modify service(s)");
+
+    Ok(())
+}
+
+/**
+ * Enable or disable a single service by name (in `svdir`), printing the
+ * progress and result.  Returns `false` if the service could not be modified.
+ */
+pub fn enable_disable_service(
+    cfg: &Config,
+    svdir: &Path,
+    name: &str,
+    enable: bool,
+) -> bool {
+    let p = svdir.join(name);
+    let svc = RunitService::new(name, &p, cfg.backend_for(svdir));
+    let verb = if enable { "enable" } else { "disable" };
+    print!("{} service {}... ", verb, Style::default().bold().paint(name));
+
+    if svc.backend.read_only() {
+        let msg =
+            format!("failed! the {} backend is read-only", svc.backend.name());
+        println!("{}", theme::get().bad.paint(msg));
+        return false;
+    }
+
+    if !svc.valid() {
+        println!("{}", theme::get().bad.paint("failed! service not valid"));
+        return false;
+    }
 
-        match ret {
-            Err(err) => {
-                had_error = true;
-                println!("{}", Color::Red.paint(format!("failed! {}", err)));
-            }
-            Ok(()) => println!("{}.", Color::Green.paint("done")),
+    if cfg.dry_run {
+        // "/<svdir>/<service>/down"
+        let p = svc.path.join("down");
+        let msg = match (enable, p.exists()) {
+            (true, true) => format!("would remove {:?}", p),
+            (false, false) => format!("would create {:?}", p),
+            _ => format!("nothing to do (already {}d)", verb),
         };
+        println!("{}", theme::get().warn.paint(format!("dry run: {}", msg)));
+        return true;
     }
 
-    ensure!(!had_error, "failed to modify service(s)");
+    let ret = if enable { svc.enable() } else { svc.disable() };
 
-    Ok(())
+    match ret {
+        Err(err) => {
+            println!("{}", theme::get().bad.paint(format!("failed! {}", err)));
+            false
+        }
+        Ok(()) => {
+            println!("{}.", theme::get().good.paint("done"));
+            true
+        }
+    }
+}
+
+/**
+ * Enable or disable a single service by name by adding or removing its
+ * symlink from the `source` directory, printing the progress and result.
+ * Returns `false` if the service could not be modified.
+ */
+fn link_unlink_service(
+    cfg: &Config,
+    source: &Path,
+    name: &str,
+    enable: bool,
+) -> bool {
+    let verb = if enable { "enable" } else { "disable" };
+    print!("{} service {}... ", verb, Style::default().bold().paint(name));
+
+    let ret = if enable {
+        runit::link_service(source, &cfg.svdir, name, cfg.dry_run)
+    } else {
+        runit::unlink_service(&cfg.svdir, name, cfg.dry_run)
+    };
+
+    match ret {
+        Ok(true) if cfg.dry_run => {
+            let target = cfg.svdir.join(name);
+            let msg = if enable {
+                format!("would link {:?} -> {:?}", target, source.join(name))
+            } else {
+                format!("would remove {:?}", target)
+            };
+            println!(
+                "{}",
+                theme::get().warn.paint(format!("dry run: {}", msg))
+            );
+            true
+        }
+        Err(err) => {
+            println!("{}", theme::get().bad.paint(format!("failed! {}", err)));
+            false
+        }
+        Ok(true) => {
+            println!("{}.", theme::get().good.paint("done"));
+            true
+        }
+        Ok(false) => {
+            println!(
+                "{}.",
+                theme::get().good.paint(format!("done (already {}d)", verb))
+            );
+            true
+        }
+    }
 }
@@ -0,0 +1,62 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv exporter`.
+
+use anyhow::Result;
+
+use crate::commands::status;
+use crate::config::verbose;
+use crate::config::Config;
+use crate::http::{self, Request, Response};
+use crate::output;
+
+/// Default address for `vsv exporter` to listen on.
+const DEFAULT_LISTEN: &str = "0.0.0.0:9109";
+
+/// Content type of the Prometheus text exposition format.
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/**
+ * Handle `vsv exporter`.
+ *
+ * Serve Prometheus metrics over HTTP at `/metrics`.  Services are re-read on
+ * every scrape so the data is never stale.
+ */
+pub fn do_exporter(cfg: &Config) -> Result<()> {
+    let listen = cfg.listen.as_deref().unwrap_or(DEFAULT_LISTEN);
+
+    verbose!(cfg, "listening on http://{}/metrics", listen);
+
+    http::serve(listen, |request| handle_request(cfg, request))
+}
+
+/// Handle a single HTTP request.
+fn handle_request(cfg: &Config, request: &Request) -> Response {
+    verbose!(cfg, "{} {}", request.method, request.path);
+
+    if request.method != "GET" {
+        return Response::text(405, "method not allowed\n".into());
+    }
+
+    match request.path.as_str() {
+        "/metrics" => match status::get_services(cfg) {
+            Ok(services) => Response::new(
+                200,
+                METRICS_CONTENT_TYPE,
+                output::prometheus::to_string(&services),
+            ),
+            Err(err) => Response::text(500, format!("{:?}\n", err)),
+        },
+        "/" => Response::new(
+            200,
+            "text/html; charset=utf-8",
+            "<html><body><a href=\"/metrics\">metrics</a></body></html>\n"
+                .into(),
+        ),
+        _ => Response::text(404, "not found\n".into()),
+    }
+}
@@ -7,11 +7,12 @@
 //! `vsv <anything>`.
 
 use std::env;
+use std::path::Path;
 
 use anyhow::{bail, ensure, Context, Result};
 use clap::crate_name;
-use yansi::Color;
 
+use crate::theme;
 use crate::utils;
 use crate::{config, config::Config};
 
@@ -19,50 +20,67 @@ use crate::{config, config::Config};
 pub fn do_external(cfg: &Config) -> Result<()> {
     assert!(!cfg.operands.is_empty());
 
-    let sv = cfg.sv_prog.to_owned();
-
     ensure!(
         cfg.operands.len() >= 2,
         "argument expected for '{} {}'",
-        sv,
+        cfg.sv_prog,
         cfg.operands[0]
     );
 
+    run_sv(cfg, &cfg.svdir, &cfg.operands)
+}
+
+/**
+ * Run `sv` (or `SV_PROG`) with the given arguments and `svdir` as its
+ * `SVDIR`, printing the command run and its exit code.
+ */
+pub fn run_sv(cfg: &Config, svdir: &Path, args: &[String]) -> Result<()> {
+    let sv = cfg.sv_prog.to_owned();
+
     // format arguments
-    let args_s = cfg.operands.join(" ");
+    let args_s = args.join(" ");
 
     // set SVDIR env to match what user wanted
-    env::set_var(config::ENV_SVDIR, &cfg.svdir);
+    env::set_var(config::ENV_SVDIR, svdir);
 
     println!(
         "[{}] {}",
         crate_name!(),
-        Color::Cyan.paint(format!(
+        theme::get().info.paint(format!(
             "Running {} command ({}={:?} {} {})",
             sv,
             config::ENV_SVDIR,
-            &cfg.svdir,
+            svdir,
             sv,
             &args_s
         ))
     );
 
+    if cfg.dry_run {
+        println!(
+            "[{}] {}",
+            crate_name!(),
+            theme::get().warn.paint(format!("dry run: not running {}", sv))
+        );
+        return Ok(());
+    }
+
     // run the actual program
-    let status = utils::run_program_get_status(&sv, &cfg.operands)
+    let status = utils::run_program_get_status(&sv, args, cfg.opts.timeout)
         .with_context(|| format!("failed to execute {}", sv))?;
 
     // check the process status
     let code = status.code().unwrap_or(-1);
-    let color = match code {
-        0 => Color::Green,
-        _ => Color::Red,
+    let style = match code {
+        0 => theme::get().good,
+        _ => theme::get().bad,
     };
 
     // print exit code
     println!(
         "[{}] {}",
         crate_name!(),
-        color.paint(format!("[{} {}] exit code {}", sv, &args_s, code))
+        style.paint(format!("[{} {}] exit code {}", sv, &args_s, code))
     );
 
     match code {
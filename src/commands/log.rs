@@ -0,0 +1,122 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv log`.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{ensure, Context, Result};
+
+use crate::config::verbose;
+use crate::config::Config;
+use crate::runit::RunitService;
+
+/// How often the log is checked for new lines (and rotations) with `-f`.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Handle `vsv log <service>`.
+pub fn do_log(cfg: &Config) -> Result<()> {
+    ensure!(cfg.operands.len() == 1, "exactly one (1) service required");
+
+    let name = &cfg.operands[0];
+    let svc = RunitService::new(
+        name,
+        &cfg.svdir.join(name),
+        cfg.backend_for(&cfg.svdir),
+    );
+    ensure!(svc.path.is_dir(), "service {} not found in {:?}", name, cfg.svdir);
+
+    let dir = svc.log_dir().with_context(|| {
+        format!("failed to find log directory for {}", name)
+    })?;
+
+    // "/<logdir>/current"
+    let current = dir.join("current");
+    verbose!(cfg, "reading {:?}", current);
+
+    let (lines, len) = tail(&current, cfg.log_lines)?;
+    for line in lines {
+        println!("{}", line);
+    }
+
+    if cfg.log_follow {
+        follow(cfg, &current, len)?;
+    }
+
+    Ok(())
+}
+
+/// Get the last `n` lines of a file, and the length of the file read.
+pub fn tail(path: &Path, n: usize) -> Result<(Vec<String>, u64)> {
+    let data =
+        fs::read(path).with_context(|| format!("failed to read {:?}", path))?;
+    let s = String::from_utf8_lossy(&data);
+
+    let lines: Vec<&str> = s.lines().collect();
+    let start = lines.len().saturating_sub(n);
+    let lines = lines[start..].iter().map(|line| line.to_string()).collect();
+
+    Ok((lines, data.len() as u64))
+}
+
+/**
+ * Print data appended to `path` (starting at `offset`) forever, like
+ * `tail -F`.
+ *
+ * `svlogd` rotates logs by renaming `current` to `@<timestamp>.s` and creating
+ * a new `current`, when that happens the rest of the old file is printed and
+ * the new file is read from the start.  A file that shrinks (truncated) is
+ * also read again from the start.
+ */
+fn follow(cfg: &Config, path: &Path, offset: u64) -> Result<()> {
+    let open = |path: &Path| {
+        File::open(path).with_context(|| format!("failed to open {:?}", path))
+    };
+
+    let mut file = open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut pos = offset;
+
+    let mut stdout = io::stdout();
+    let mut buf = vec![];
+
+    loop {
+        // print anything new
+        buf.clear();
+        pos += file.read_to_end(&mut buf)? as u64;
+        stdout.write_all(&buf)?;
+        stdout.flush()?;
+
+        thread::sleep(FOLLOW_INTERVAL);
+
+        let meta = file.metadata()?;
+        let rotated = match fs::metadata(path) {
+            Ok(new) => new.dev() != meta.dev() || new.ino() != meta.ino(),
+            // `current` may briefly not exist while being rotated
+            Err(_) => false,
+        };
+
+        if rotated {
+            // finish the old file before switching to the new one
+            buf.clear();
+            file.read_to_end(&mut buf)?;
+            stdout.write_all(&buf)?;
+
+            verbose!(cfg, "{:?} rotated, reopening", path);
+            file = open(path)?;
+            pos = 0;
+        } else if meta.len() < pos {
+            verbose!(cfg, "{:?} truncated, reading from start", path);
+            file.seek(SeekFrom::Start(0))?;
+            pos = 0;
+        }
+    }
+}
@@ -0,0 +1,60 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv metrics`.
+
+use std::fs;
+use std::process;
+
+use anyhow::{Context, Result};
+
+use crate::commands::status;
+use crate::config::verbose;
+use crate::config::Config;
+use crate::output;
+
+/**
+ * Handle `vsv metrics`.
+ *
+ * Metrics are printed to stdout, or written to a file if `--textfile` is given.
+ * The file is written to a temporary file first and then renamed into place so
+ * the node_exporter textfile collector never sees a partially written file.
+ */
+pub fn do_metrics(cfg: &Config) -> Result<()> {
+    let services = status::get_services(cfg)?;
+
+    for (_, messages) in &services {
+        for message in messages {
+            verbose!(cfg, "{}", message);
+        }
+    }
+
+    let s = output::prometheus::to_string(&services);
+
+    let file = match &cfg.textfile {
+        Some(file) => file,
+        None => {
+            print!("{}", s);
+            return Ok(());
+        }
+    };
+
+    let mut tmp = file.clone().into_os_string();
+    tmp.push(format!(".{}.tmp", process::id()));
+
+    fs::write(&tmp, s).with_context(|| format!("failed to write {:?}", tmp))?;
+    fs::rename(&tmp, file)
+        .with_context(|| format!("failed to rename {:?} to {:?}", tmp, file))?;
+
+    verbose!(
+        cfg,
+        "wrote metrics for {} services to {:?}",
+        services.len(),
+        file
+    );
+
+    Ok(())
+}
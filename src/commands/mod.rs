@@ -6,6 +6,36 @@
 
 //! Subcommands for `vsv`.
 
+use std::error;
+use std::fmt;
+
+pub mod avail;
+pub mod check_plugin;
+pub mod completions;
+pub mod control;
 pub mod enable_disable;
+pub mod exporter;
 pub mod external;
+pub mod log;
+pub mod metrics;
+pub mod pick;
+pub mod serve;
+pub mod show;
 pub mod status;
+pub mod tui;
+pub mod watch;
+
+/**
+ * Returned (as an error) by subcommands to exit with a code but no error
+ * message, like `vsv status --exit-code` when a service is down.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitCode(pub i32);
+
+impl fmt::Display for ExitCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "exit code {}", self.0)
+    }
+}
+
+impl error::Error for ExitCode {}
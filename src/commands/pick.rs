@@ -0,0 +1,207 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv pick` - fuzzy find a service and run an action on it.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use anyhow::{bail, ensure, Context, Result};
+use yansi::Style;
+
+use crate::commands::{control, enable_disable, external, status, watch};
+use crate::config::Config;
+use crate::fuzzy;
+use crate::runit::ControlCommand;
+use crate::service::ServiceState;
+use crate::terminal::{self, Key, RawTerminal};
+
+/// Handle `vsv pick [action]`.
+pub fn do_pick(cfg: &Config) -> Result<()> {
+    let services = status::get_services(cfg)?;
+    let keys = watch::service_keys(&services);
+    let mut paths = watch::service_paths(cfg, &services);
+    let candidates: Vec<(String, ServiceState)> = keys
+        .into_iter()
+        .zip(services.iter().map(|(service, _)| service.state))
+        .collect();
+
+    ensure!(!candidates.is_empty(), "no services found in {:?}", cfg.svdir);
+
+    let i = match pick(&candidates)? {
+        Some(i) => i,
+        None => bail!("no service picked"),
+    };
+    let key = &candidates[i].0;
+
+    let action = match cfg.operands.first() {
+        Some(action) => action.as_str(),
+        None => {
+            // nothing to do, just print the service picked
+            println!("{}", key);
+            return Ok(());
+        }
+    };
+
+    // the service's real name (like "foo/log") in its own service directory
+    let (svdir, path) = match paths.swap_remove(i) {
+        Some(paths) => paths,
+        None => bail!("can't {} {}: it's on another host", action, key),
+    };
+    let name = path
+        .strip_prefix(&svdir)
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .into_owned();
+
+    match action {
+        "enable" | "disable" => {
+            let enable = action == "enable";
+            ensure!(
+                enable_disable::enable_disable_service(
+                    cfg, &svdir, &name, enable
+                ),
+                "failed to modify service"
+            );
+            Ok(())
+        }
+        _ => match ControlCommand::parse(action) {
+            Some(cmd) => control::control_services(cfg, &svdir, &[name], cmd),
+            None => external::run_sv(cfg, &svdir, &[action.to_string(), name]),
+        },
+    }
+}
+
+/**
+ * Show an interactive fuzzy finder over the given service names (and states),
+ * returning the index of the one picked or `None` if the picker was
+ * cancelled.
+ */
+fn pick(candidates: &[(String, ServiceState)]) -> Result<Option<usize>> {
+    let term = RawTerminal::new().context("failed to start picker")?;
+    let mut query = String::new();
+    let mut selected = 0;
+
+    loop {
+        let matches = matches(candidates, &query);
+        selected = selected.min(matches.len().saturating_sub(1));
+
+        draw(candidates, &matches, &query, selected)?;
+
+        let key = match term.read_key(Duration::from_secs(60))? {
+            Some(key) => key,
+            None => continue,
+        };
+
+        match key {
+            Key::Enter => match matches.get(selected) {
+                Some(i) => return Ok(Some(*i)),
+                None => continue,
+            },
+            Key::Esc | Key::Ctrl('c') | Key::Ctrl('g') => return Ok(None),
+            Key::Up | Key::Ctrl('p') | Key::Ctrl('k') => {
+                selected = selected.saturating_sub(1)
+            }
+            Key::Down | Key::Ctrl('n') | Key::Ctrl('j') | Key::Tab => {
+                selected += 1
+            }
+            Key::Backspace => {
+                query.pop();
+                selected = 0;
+            }
+            Key::Ctrl('u') => {
+                query.clear();
+                selected = 0;
+            }
+            Key::Char(c) => {
+                query.push(c);
+                selected = 0;
+            }
+            _ => (),
+        }
+    }
+}
+
+/**
+ * Get the indices of the candidates matching `query`, best match first (ties
+ * keep their original order).
+ */
+fn matches(candidates: &[(String, ServiceState)], query: &str) -> Vec<usize> {
+    let mut scored: Vec<(i64, usize)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (name, _))| Some((fuzzy::score(query, name)?, i)))
+        .collect();
+
+    scored.sort_by_key(|(score, i)| (-score, *i));
+
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Draw the picker: the query prompt followed by as many matches as fit.
+fn draw(
+    candidates: &[(String, ServiceState)],
+    matches: &[usize],
+    query: &str,
+    selected: usize,
+) -> Result<()> {
+    let (rows, cols) = terminal::size();
+    let bold = Style::default().bold();
+
+    let mut out = String::from("\x1b[H");
+    out.push_str(&format!("{} {}_\x1b[K\n", bold.paint(">"), query));
+    out.push_str(&format!("  {}/{}\x1b[K", matches.len(), candidates.len()));
+
+    // scroll so the selected match is always visible
+    let height = rows.saturating_sub(2).max(1);
+    let offset = (selected + 1).saturating_sub(height);
+
+    for (n, i) in matches.iter().enumerate().skip(offset).take(height) {
+        let (name, state) = &candidates[*i];
+        let name: String = name.chars().take(cols.saturating_sub(4)).collect();
+
+        out.push('\n');
+        if n == selected {
+            out.push_str(&format!(
+                "\x1b[7m> {} {}\x1b[K\x1b[0m",
+                state.get_char(),
+                name
+            ));
+        } else {
+            let style = state.get_style();
+            out.push_str(&format!(
+                "  {} {}\x1b[K",
+                style.paint(state.get_char()),
+                name
+            ));
+        }
+    }
+    out.push_str("\x1b[J");
+
+    let mut stdout = io::stdout();
+    stdout.write_all(out.as_bytes())?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let candidates: Vec<(String, ServiceState)> =
+            ["sshd", "agetty-tty1", "nginx", "node-exporter"]
+                .iter()
+                .map(|name| (name.to_string(), ServiceState::Run))
+                .collect();
+
+        assert_eq!(matches(&candidates, ""), [0, 1, 2, 3], "all match");
+        assert_eq!(matches(&candidates, "n"), [2, 3], "best first");
+        assert_eq!(matches(&candidates, "zzz"), Vec::<usize>::new(), "none");
+    }
+}
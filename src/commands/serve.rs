@@ -0,0 +1,178 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv serve`.
+
+use anyhow::Result;
+
+use crate::commands::status;
+use crate::config::verbose;
+use crate::config::{self, Config};
+use crate::http::{self, Request, Response};
+use crate::output::{self, json, Value};
+use crate::runit::{self, ControlCommand, RunitService};
+
+/// Default address for `vsv serve` to listen on.
+const DEFAULT_LISTEN: &str = "127.0.0.1:8999";
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/**
+ * Handle `vsv serve`.
+ *
+ * Serve a JSON API over HTTP:
+ *
+ * - `GET /services` - every service (like `vsv -F json`).
+ * - `GET /services/<name>` - a single service.
+ * - `POST /services/<name>/restart` - restart a service, only allowed if env
+ *   `VSV_SERVE_TOKEN` is set and given as `Authorization: Bearer <token>`.
+ *
+ * Services are re-read on every request.
+ */
+pub fn do_serve(cfg: &Config) -> Result<()> {
+    let listen = cfg.listen.as_deref().unwrap_or(DEFAULT_LISTEN);
+
+    verbose!(cfg, "listening on http://{}/services", listen);
+    if cfg.serve_token.is_none() {
+        verbose!(cfg, "restarts disabled, {} not set", config::ENV_SERVE_TOKEN);
+    }
+
+    http::serve(listen, |request| handle_request(cfg, request))
+}
+
+/// Handle a single HTTP request.
+fn handle_request(cfg: &Config, request: &Request) -> Response {
+    verbose!(cfg, "{} {}", request.method, request.path);
+
+    // ignore any query string
+    let path = request.path.split('?').next().unwrap_or_default();
+    let parts: Vec<&str> =
+        path.split('/').filter(|part| !part.is_empty()).collect();
+
+    match (request.method.as_str(), parts.as_slice()) {
+        ("GET", ["services"]) => get_services(cfg, None),
+        ("GET", ["services", name]) => get_services(cfg, Some(name)),
+        ("POST", ["services", name, "restart"]) => {
+            restart_service(cfg, request, name)
+        }
+        (_, ["services"] | ["services", _] | ["services", _, "restart"]) => {
+            error(405, "method not allowed")
+        }
+        _ => error(404, "not found"),
+    }
+}
+
+/// Respond with every service, or a single one by name.
+fn get_services(cfg: &Config, name: Option<&str>) -> Response {
+    let services = match status::get_services(cfg) {
+        Ok(services) => services,
+        Err(err) => return error(500, &format!("{:#}", err)),
+    };
+
+    let value = match name {
+        None => output::services_to_value(&services, &cfg.opts.columns),
+        Some(name) => {
+            let service = services
+                .into_iter()
+                .find(|(service, _)| !service.log && service.name == name);
+            let service = match service {
+                Some(service) => service,
+                None => return error(404, "service not found"),
+            };
+
+            let value =
+                output::services_to_value(&[service], &cfg.opts.columns);
+            match value {
+                Value::Array(mut arr) if arr.len() == 1 => arr.remove(0),
+                _ => unreachable!("one service given"),
+            }
+        }
+    };
+
+    json_response(200, &value)
+}
+
+/// Restart a service by name, if the request has the token.
+fn restart_service(cfg: &Config, request: &Request, name: &str) -> Response {
+    let token = match &cfg.serve_token {
+        Some(token) => token,
+        None => {
+            let message = format!(
+                "restarts are disabled ({} isn't set)",
+                config::ENV_SERVE_TOKEN
+            );
+            return error(403, &message);
+        }
+    };
+
+    let given = request
+        .header("Authorization")
+        .and_then(|auth| auth.strip_prefix("Bearer "));
+    if !given.is_some_and(|given| constant_time_eq(given, token)) {
+        return error(401, "invalid or missing token");
+    }
+
+    // only services that are listed (not `..` or hidden directories)
+    let backend = cfg.backend_for(&cfg.svdir);
+    let found = runit::get_services(&cfg.svdir, false, backend, |n| n == name);
+    match found {
+        Ok(found) if !found.is_empty() => (),
+        Ok(_) => return error(404, "service not found"),
+        Err(err) => return error(500, &format!("{:#}", err)),
+    }
+
+    let svc = RunitService::new(name, &cfg.svdir.join(name), backend);
+    if let Err(err) = svc.control(ControlCommand::Restart) {
+        return error(500, &format!("{:#}", err));
+    }
+
+    verbose!(cfg, "restarted {}", name);
+
+    let value = Value::Object(vec![
+        ("name".into(), name.into()),
+        ("restarted".into(), true.into()),
+    ]);
+    json_response(200, &value)
+}
+
+/// Respond with a JSON value.
+fn json_response(status: u16, value: &Value) -> Response {
+    let mut body = json::to_string_pretty(value);
+    body.push('\n');
+
+    Response::new(status, JSON_CONTENT_TYPE, body)
+}
+
+/// Respond with a JSON `{"error": "..."}` object.
+fn error(status: u16, message: &str) -> Response {
+    json_response(
+        status,
+        &Value::Object(vec![("error".into(), message.into())]),
+    )
+}
+
+/// Compare two strings without exiting early, so the token can't be guessed
+/// from how long a check takes.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.bytes().zip(b.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("secret", "secret"));
+        assert!(!constant_time_eq("secret", "secreT"));
+        assert!(!constant_time_eq("secret", "secret2"));
+        assert!(!constant_time_eq("", "secret"));
+    }
+}
@@ -0,0 +1,234 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv show`.
+
+use std::fs;
+
+use anyhow::{ensure, Context, Result};
+use yansi::Style;
+
+use crate::column::Column;
+use crate::commands::log;
+use crate::config::verbose;
+use crate::config::Config;
+use crate::options::Options;
+use crate::procfs;
+use crate::runit::RunitService;
+use crate::service::Service;
+
+/// Columns of data gathered about the service (CPU usage isn't, it takes a
+/// while to measure).
+const COLUMNS: &[Column] = &[
+    Column::State,
+    Column::Enabled,
+    Column::Pid,
+    Column::Command,
+    Column::Uptime,
+    Column::User,
+    Column::Rss,
+    Column::Threads,
+    Column::Fds,
+    Column::LogSize,
+];
+
+/// Number of lines of the service's log printed.
+const LOG_LINES: usize = 5;
+
+/// Parts of environment variable names that likely hold secrets.
+const SECRET_NAMES: &[&str] =
+    &["PASS", "SECRET", "TOKEN", "KEY", "AUTH", "CREDENTIAL", "PRIVATE"];
+
+/// Handle `vsv show <service>`.
+pub fn do_show(cfg: &Config) -> Result<()> {
+    ensure!(cfg.operands.len() == 1, "exactly one (1) service required");
+
+    let name = &cfg.operands[0];
+    let svc = RunitService::new(
+        name,
+        &cfg.svdir.join(name),
+        cfg.backend_for(&cfg.svdir),
+    );
+    ensure!(svc.path.is_dir(), "service {} not found in {:?}", name, cfg.svdir);
+
+    let opts = Options { columns: COLUMNS.to_vec(), ..cfg.opts.clone() };
+    let (service, messages) = Service::from_runit_service(&svc, &opts);
+    let pid = service.pid;
+
+    // "/<svdir>/<service>" -> "/etc/sv/<service>"
+    let path = match fs::read_link(&svc.path) {
+        Ok(target) => format!("{} -> {}", svc.path.display(), target.display()),
+        Err(_) => svc.path.display().to_string(),
+    };
+    let want = match service.want {
+        Some(want) => want.to_string(),
+        None => String::from("---"),
+    };
+    let log_dir = svc.log_dir();
+    let plain = |s: String| (s, Style::default());
+
+    let column = |column| service.format_column(column);
+    print_fields(&[
+        ("service", plain(name.to_string())),
+        ("path", plain(path)),
+        ("run", plain(svc.path.join("run").display().to_string())),
+        ("state", column(Column::State)),
+        ("want", plain(want)),
+        ("enabled", column(Column::Enabled)),
+        ("pid", column(Column::Pid)),
+        ("command", column(Column::Command)),
+        ("uptime", column(Column::Uptime)),
+        ("user", column(Column::User)),
+        ("rss", column(Column::Rss)),
+        ("threads", column(Column::Threads)),
+        ("fds", column(Column::Fds)),
+        (
+            "log",
+            plain(match &log_dir {
+                Some(dir) => dir.display().to_string(),
+                None => String::from("---"),
+            }),
+        ),
+        ("log size", column(Column::LogSize)),
+    ]);
+
+    // the most recent lines logged, if there are any
+    if let Some(dir) = log_dir {
+        if let Ok((lines, _)) = log::tail(&dir.join("current"), LOG_LINES) {
+            if !lines.is_empty() {
+                println!();
+                println!("{}", Style::default().bold().paint("recent logs"));
+                for line in lines {
+                    println!("  {}", line);
+                }
+            }
+        }
+    }
+
+    for message in messages {
+        verbose!(cfg, "{}", message);
+    }
+
+    if cfg.show_limits {
+        let pid = pid.with_context(|| format!("{} has no process", name))?;
+        let limits = procfs::limits(pid, &cfg.opts.proc_path)?;
+
+        println!();
+        println!("{}", Style::default().bold().paint("limits"));
+        print_limits(&limits);
+    }
+
+    if cfg.show_env {
+        let pid = pid.with_context(|| format!("{} has no process", name))?;
+        let env = procfs::environ(pid, &cfg.opts.proc_path)?;
+
+        println!();
+        println!("{}", Style::default().bold().paint("environment"));
+        for var in env {
+            println!("  {}", redact(&var, cfg.redact));
+        }
+    }
+
+    Ok(())
+}
+
+/// Print `key: value` lines with the values lined up.
+fn print_fields(fields: &[(&str, (String, Style))]) {
+    let width = fields.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+    for (key, (value, style)) in fields {
+        let key = format!("{}:", key);
+        println!(
+            "{} {}",
+            Style::default().bold().paint(format!("{:<1$}", key, width + 1)),
+            style.paint(value)
+        );
+    }
+}
+
+/// Print resource limits as a table (like `prlimit(1)`).
+fn print_limits(limits: &[procfs::Limit]) {
+    let header = ["RESOURCE", "SOFT", "HARD", "UNITS", "DESCRIPTION"];
+    let rows: Vec<[&str; 5]> = limits
+        .iter()
+        .map(|limit| {
+            [
+                limit.name,
+                &limit.soft,
+                &limit.hard,
+                &limit.units,
+                limit.description,
+            ]
+        })
+        .collect();
+
+    // the description is last and isn't padded
+    let mut widths = [0; 4];
+    for row in rows.iter().chain([&header]) {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.len());
+        }
+    }
+
+    let line = |row: &[&str; 5]| {
+        format!(
+            "  {:<w0$}  {:>w1$}  {:>w2$}  {:<w3$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        )
+    };
+
+    println!("{}", Style::default().bold().paint(line(&header)));
+    for row in &rows {
+        println!("{}", line(row));
+    }
+}
+
+/**
+ * Hide the value of a `NAME=value` environment variable if `redact` is set
+ * and its name looks like it holds a secret (like `API_TOKEN`).
+ */
+fn redact(var: &str, redact: bool) -> String {
+    let (key, _) = match var.split_once('=') {
+        Some(kv) if redact => kv,
+        _ => return var.to_string(),
+    };
+
+    let upper = key.to_uppercase();
+    if SECRET_NAMES.iter().any(|secret| upper.contains(secret)) {
+        format!("{}=<redacted>", key)
+    } else {
+        var.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(redact("HOME=/root", true), "HOME=/root", "not secret");
+        assert_eq!(
+            redact("api_token=abc", true),
+            "api_token=<redacted>",
+            "secret"
+        );
+        assert_eq!(
+            redact("DB_PASSWORD=hunter2", false),
+            "DB_PASSWORD=hunter2",
+            "not redacting"
+        );
+        assert_eq!(redact("NOEQUALS", true), "NOEQUALS", "no value");
+    }
+}
@@ -6,77 +6,964 @@
 
 //! `vsv status` subcommand.
 
-use anyhow::{Context, Result};
+use libc::pid_t;
+use std::path::Path;
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Context, Result};
 use rayon::prelude::*;
 use yansi::Style;
 
-use crate::config::Config;
-use crate::runit;
-use crate::service::Service;
-use crate::{utils, utils::verbose};
+use crate::backend::Status;
+use crate::column::Column;
+use crate::commands::watch::{self, Snapshot};
+use crate::commands::ExitCode;
+use crate::config::verbose;
+use crate::config::{Config, GroupBy, OutputFormat, ProgramMode};
+use crate::formatting::{self, Token};
+use crate::options::Options;
+use crate::output::Value;
+use crate::output::{self, template};
+use crate::pager::Pager;
+use crate::procfs;
+use crate::remote;
+use crate::restarts::RestartHistory;
+use crate::runit::{self, RunitService};
+use crate::service::{self, Service, ServiceState};
+use crate::theme;
+use crate::time_format;
+use crate::utils;
 
 /// Handle `vsv status` or `vsv` without a subcommand given.
 pub fn do_status(cfg: &Config) -> Result<()> {
-    // may or may not be set (option)
-    let filter = cfg.operands.get(0);
+    if let Some(interval) = cfg.watch {
+        return watch::do_watch(cfg, interval);
+    }
+
+    let services = get_services(cfg)?;
+
+    verbose!(cfg, "found {} services in {:?}", services.len(), cfg.svdir);
+
+    let code = match cfg.exit_code {
+        true => health_exit_code(&services),
+        false => 0,
+    };
+
+    // stdout is restored (and the pager waited on) when this is dropped
+    let pager = match &cfg.pager {
+        Some(cmd) => Some(Pager::start(cmd)?),
+        None => None,
+    };
+
+    print_services(cfg, services);
+    drop(pager);
+
+    if code != 0 {
+        return Err(ExitCode(code).into());
+    }
+
+    Ok(())
+}
+
+/// `--exit-code` bits: a service is down (and not on purpose).
+pub const EXIT_DOWN: i32 = 2;
+
+/// `--exit-code` bits: a service is flapping.
+pub const EXIT_FLAPPING: i32 = 4;
 
-    // find all services
-    let services = runit::get_services(&cfg.svdir, cfg.log, filter)
-        .with_context(|| {
-            format!("failed to list services in {:?}", cfg.svdir)
-        })?;
+/// `--exit-code` bits: a service's state is unknown or couldn't be read.
+pub const EXIT_UNREADABLE: i32 = 8;
+
+/**
+ * Get the `--exit-code` of the gathered services: 0 if they're all healthy,
+ * otherwise the `EXIT_*` bits of every problem found or'd together (so 1 is
+ * left for vsv itself failing).
+ */
+pub fn health_exit_code(services: &[(Service, Vec<String>)]) -> i32 {
+    let mut code = 0;
+
+    for (service, _) in services {
+        let unreadable = matches!(
+            service.state,
+            ServiceState::Unknown | ServiceState::Error
+        );
+
+        if unreadable {
+            code |= EXIT_UNREADABLE;
+        } else if service.state != ServiceState::Run
+            && !service.is_normally_down()
+        {
+            code |= EXIT_DOWN;
+        }
+        if service.is_flapping() {
+            code |= EXIT_FLAPPING;
+        }
+    }
+
+    code
+}
+
+/// Print the gathered services in the configured output format.
+pub fn print_services(cfg: &Config, services: Vec<(Service, Vec<String>)>) {
+    match &cfg.format {
+        OutputFormat::Table => print_table(cfg, services, None),
+        OutputFormat::Json => print_json(cfg, services),
+        OutputFormat::Yaml => print_yaml(cfg, services),
+        OutputFormat::Csv => print_csv(cfg, services),
+        OutputFormat::Markdown => print_markdown(cfg, services),
+        OutputFormat::Raw => print_raw(cfg, services),
+        OutputFormat::Snapshot => print_snapshot(cfg, services),
+        OutputFormat::Custom(tokens) => print_custom(cfg, tokens, services),
+        OutputFormat::Template(nodes) => print_template(cfg, nodes, services),
+    }
+}
+
+/**
+ * Find and gather data for all services in the configured `SVDIR`, honoring
+ * any name filters and exclusions given.
+ */
+pub fn get_services(cfg: &Config) -> Result<Vec<(Service, Vec<String>)>> {
+    // other machines do all of this themselves
+    if !cfg.hosts.is_empty() {
+        return get_remote_services(cfg);
+    }
 
-    // loop each service found (just gather data here, can be done in parallel)
-    let services: Vec<(Service, Vec<String>)> = services
+    // every name filter given must match and no exclusions can match
+    let filter = |name: &str| {
+        cfg.name_filters.iter().all(|re| re.is_match(name))
+            && !cfg.exclude_filters.iter().any(|re| re.is_match(name))
+    };
+
+    // find all services (in every tree with `-U`), noting where they came from
+    let trees: Vec<(Option<&str>, &Path)> = if cfg.trees.is_empty() {
+        vec![(None, cfg.svdir.as_path())]
+    } else {
+        cfg.trees
+            .iter()
+            .map(|(origin, dir)| (Some(origin.as_str()), dir.as_path()))
+            .collect()
+    };
+
+    // every tree is listed at once, kept in the order given
+    let found: Vec<Result<Vec<_>>> = trees
         .par_iter()
-        .map(|service| {
-            Service::from_runit_service(
-                service,
-                cfg.tree,
-                &cfg.proc_path,
-                &cfg.pstree_prog,
-            )
+        .map(|(_, dir)| {
+            let found =
+                runit::get_services(dir, cfg.log, cfg.backend_for(dir), filter)
+                    .with_context(|| {
+                        format!("failed to list services in {:?}", dir)
+                    })?;
+
+            // filter by state before doing any expensive lookups
+            Ok(match cfg.states.is_empty() {
+                true => {
+                    found.into_iter().map(|service| (service, None)).collect()
+                }
+                false => {
+                    filter_by_state(found, &cfg.states, &cfg.opts.proc_path)
+                }
+            })
         })
         .collect();
 
+    // the status of each service, if it was already read to filter by state
+    let mut runit_services: Vec<(RunitService, Option<&str>)> = vec![];
+    let mut statuses: Vec<Option<ReadStatus>> = vec![];
+    for ((origin, _), found) in trees.iter().zip(found) {
+        for (service, status) in found? {
+            runit_services.push((service, *origin));
+            statuses.push(status);
+        }
+    }
+
+    let columns = requested_columns(cfg);
+    let opts = Options { columns: columns.clone(), ..cfg.opts.clone() };
+
+    // gather data for each service found in parallel (see `--jobs`), order is
+    // kept
+    let mut services: Vec<(Service, Vec<String>)> = runit_services
+        .par_iter()
+        .zip(statuses)
+        .map(|((service, origin), status)| {
+            let (mut service, messages) = match status {
+                Some((status, stat)) => {
+                    Service::from_process(service, status, stat, &opts)
+                }
+                None => Service::from_runit_service(service, &opts),
+            };
+            service.origin = origin.map(String::from);
+            (service, messages)
+        })
+        .collect();
+
+    // restarts are only counted if they (or flapping) will be seen
+    let wants_restarts = columns.iter().any(|column| {
+        matches!(
+            column,
+            Column::Status | Column::State | Column::Uptime | Column::Restarts
+        )
+    });
+    if let (Some(path), true) = (&cfg.restart_history, wants_restarts) {
+        track_restarts(cfg, path, &runit_services, &mut services);
+    }
+
+    // every process is watched at once instead of one after another
+    if columns.contains(&Column::Cpu) {
+        measure_cpu(cfg, &mut services);
+    }
+
+    // every process is read once for all services
+    if cfg.children {
+        find_children(cfg, &mut services);
+    }
+
+    if cfg.quiet {
+        services = group_log_services(services, |(service, _)| service.log)
+            .into_iter()
+            .filter(|group| {
+                group.iter().any(|(service, _)| service.is_problem())
+            })
+            .flatten()
+            .collect();
+    }
+
+    if let Some(column) = cfg.sort {
+        services = sort_services(services, column, cfg.reverse);
+    }
+
+    Ok(services)
+}
+
+/**
+ * Get the columns to gather data for: only what's shown (and sorted by), or
+ * what the subcommand looks at, so nothing else is read (like `/proc` or the
+ * restart history) and a minimal listing barely touches anything outside the
+ * service directory.  Anything looking for problems (like `-q`) needs the
+ * status char's conditions (a stale pid or flapping).
+ */
+fn requested_columns(cfg: &Config) -> Vec<Column> {
+    let mut columns = match cfg.mode {
+        // only the state, pid and uptime are used
+        ProgramMode::Metrics | ProgramMode::Exporter | ProgramMode::Pick => {
+            return vec![]
+        }
+        ProgramMode::CheckPlugin => return vec![Column::Status],
+        _ => cfg.opts.columns.clone(),
+    };
+
+    // the sort column needs to be gathered even if it isn't shown
+    if let Some(column) = cfg.sort {
+        columns.push(column);
+    }
+
+    if cfg.quiet || cfg.exit_code || cfg.summary || cfg.notify_prog.is_some() {
+        columns.push(Column::Status);
+    }
+
+    columns
+}
+
+/**
+ * Gather services from every host given with `-H` (all at once), noting which
+ * host each came from.  A host that can't be reached is warned about and
+ * skipped, unless every host failed.
+ */
+fn get_remote_services(cfg: &Config) -> Result<Vec<(Service, Vec<String>)>> {
+    let results: Vec<_> = cfg
+        .hosts
+        .par_iter()
+        .map(|host| (host, remote::get_services(cfg, host)))
+        .collect();
+
+    let mut services = vec![];
+    let mut failed = vec![];
+    for (host, result) in results {
+        match result {
+            Ok(found) => {
+                verbose!(cfg, "found {} services on {}", found.len(), host);
+                services.extend(found.into_iter().map(|(mut service, m)| {
+                    service.host = Some(host.to_string());
+                    (service, m)
+                }));
+            }
+            Err(err) => failed.push(err),
+        }
+    }
+
+    if failed.len() == cfg.hosts.len() {
+        return Err(failed.remove(0));
+    }
+    // every error names the host it's about
+    for err in failed {
+        eprintln!("{}: {:#}", theme::get().warn.paint("warning"), err);
+    }
+
+    // each host sorted its own services
+    if let (Some(column), true) = (cfg.sort, cfg.hosts.len() > 1) {
+        services = sort_services(services, column, cfg.reverse);
+    }
+
+    Ok(services)
+}
+
+/// A service's status and the `stat` of its process, read to filter by state.
+type ReadStatus = (Status, Option<Result<procfs::Stat, vsv::Error>>);
+
+/**
+ * Only keep services that are in one of the given states, along with the
+ * status (and process `stat`) read for each so they aren't read again.
+ *
+ * A service and its log service are kept (or removed) together: if either of
+ * them is in one of the given states both are kept.
+ */
+fn filter_by_state(
+    services: Vec<RunitService>,
+    states: &[ServiceState],
+    proc_path: &Path,
+) -> Vec<(RunitService, Option<ReadStatus>)> {
+    group_log_services(services, |service| service.log)
+        .into_par_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|service| {
+                    let status = service.get_status();
+                    let stat = service::read_stat(&status, proc_path);
+                    (service, (status, stat))
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| {
+            group.iter().any(|(_, (status, stat))| {
+                let stat = stat.as_ref().and_then(|stat| stat.as_ref().ok());
+                let (state, _) = ServiceState::from_status(status, stat);
+                states.contains(&state)
+            })
+        })
+        .flatten()
+        .map(|(service, status)| (service, Some(status)))
+        .collect()
+}
+
+/**
+ * Sort services by the given column.
+ *
+ * Log services are kept directly underneath the service they belong to and
+ * services that compare equal keep their original (directory) order.
+ */
+fn sort_services(
+    services: Vec<(Service, Vec<String>)>,
+    column: Column,
+    reverse: bool,
+) -> Vec<(Service, Vec<String>)> {
+    let mut groups = group_log_services(services, |(service, _)| service.log);
+
+    groups.sort_by(|a, b| {
+        let ord = a[0].0.cmp_column(&b[0].0, column);
+        if reverse {
+            ord.reverse()
+        } else {
+            ord
+        }
+    });
+
+    groups.into_iter().flatten().collect()
+}
+
+/**
+ * Group each service with the log service(s) directly following it, `is_log`
+ * is used to determine if an item is a log service.
+ */
+fn group_log_services<T, F>(items: Vec<T>, is_log: F) -> Vec<Vec<T>>
+where
+    F: Fn(&T) -> bool,
+{
+    let mut groups: Vec<Vec<T>> = vec![];
+
+    for item in items {
+        match groups.last_mut() {
+            Some(group) if is_log(&item) => group.push(item),
+            _ => groups.push(vec![item]),
+        }
+    }
+
+    groups
+}
+
+/**
+ * Print the gathered services as a human-readable table.  Cells that changed
+ * since the `previous` snapshot (if given) are highlighted.
+ */
+pub fn print_table(
+    cfg: &Config,
+    services: Vec<(Service, Vec<String>)>,
+    previous: Option<&Snapshot>,
+) {
+    // when filtering print nothing at all if nothing matched
+    if (cfg.quiet || !cfg.states.is_empty()) && services.is_empty() {
+        return;
+    }
+
     // print gathared data
     let style = Style::default();
 
-    verbose!(cfg, "found {} services in {:?}", services.len(), cfg.svdir);
-    println!();
-    println!(
-        "{}",
-        utils::format_status_line(
-            ("", style.bold()),
-            ("SERVICE", style.bold()),
-            ("STATE", style.bold()),
-            ("ENABLED", style.bold()),
-            ("PID", style.bold()),
-            ("COMMAND", style.bold()),
-            ("TIME", style.bold()),
-        )
-    );
+    // format every cell up front so the column widths can be determined
+    let mut rows: Vec<Vec<(String, Style)>> = services
+        .iter()
+        .map(|(service, _)| {
+            cfg.opts
+                .columns
+                .iter()
+                .map(|column| service.format_column(*column))
+                .collect()
+        })
+        .collect();
+    let widths = column_widths(cfg, &rows, cfg.term_width);
+
+    // highlight anything that changed since the previous refresh
+    if let Some(previous) = previous {
+        let keys = watch::service_keys(&services);
+        for (row, key) in rows.iter_mut().zip(&keys) {
+            for (i, ((cell, style), column)) in
+                row.iter_mut().zip(&cfg.opts.columns).enumerate()
+            {
+                if previous.is_changed(key, i, *column, cell) {
+                    *style = style.invert();
+                }
+            }
+        }
+    }
 
-    // print each service found
-    for (service, messages) in services {
-        println!("{}", service);
+    // split the rows into titled sections if grouping
+    let sections: Vec<(Option<String>, Vec<usize>)> = match cfg.group_by {
+        Some(GroupBy::State) => group_by_state(&services),
+        Some(GroupBy::Origin) => group_by_origin(cfg, &services),
+        None => vec![(None, (0..rows.len()).collect())],
+    };
 
-        // print pstree if applicable
-        if cfg.tree {
-            let (tree_s, style) = service.format_pstree();
-            println!("{}", style.paint(tree_s));
+    // the same line (and cells) are reused for every row
+    let mut line = String::new();
+    let mut cells: Vec<(&str, Style, usize)> = vec![];
+
+    for (title, indices) in sections {
+        if !cfg.no_header {
+            println!();
+            if let Some(title) = title {
+                println!("{}", style.bold().paint(title));
+            }
+            cells.clear();
+            cells.extend(cfg.opts.columns.iter().zip(&widths).map(
+                |(column, width)| (column.header(), style.bold(), *width),
+            ));
+            line.clear();
+            formatting::write_status_line(&mut line, &cells);
+            println!("{}", line);
         }
 
-        // print any verbose messages/warnings generated by the service
-        for message in messages {
-            verbose!(cfg, "{}", message);
+        // print each service found
+        for i in indices {
+            let (service, messages) = &services[i];
+            cells.clear();
+            cells.extend(
+                rows[i]
+                    .iter()
+                    .zip(&widths)
+                    .map(|((s, style), width)| (s.as_str(), *style, *width)),
+            );
+            line.clear();
+            formatting::write_status_line(&mut line, &cells);
+            println!("{}", line);
+
+            // print pstree if applicable
+            if cfg.opts.tree {
+                let (tree_s, style) = service.format_pstree();
+                println!("{}", style.paint(tree_s));
+            }
+
+            // print child processes if applicable
+            if cfg.children {
+                let (children_s, style) = service.format_children();
+                if !children_s.is_empty() {
+                    println!("{}", style.paint(children_s));
+                }
+            }
+
+            // print any verbose messages/warnings generated by the service
+            for message in messages {
+                verbose!(cfg, "{}", message);
+            }
         }
     }
 
-    if !cfg.tree {
+    if !cfg.opts.tree && !cfg.no_header {
         // add a newline to the end of the output if no tree was printed
         println!();
     }
 
-    Ok(())
+    // services that couldn't be read are listed (as `error`) and noted here
+    if let Some(errors) = format_errors(&services) {
+        println!("{}", errors);
+        if !cfg.no_header {
+            println!();
+        }
+    }
+
+    if cfg.summary {
+        println!("{}", format_summary(&services));
+        if !cfg.no_header {
+            println!();
+        }
+    }
+}
+
+/**
+ * Format the errors of services whose state couldn't be read as a footnote to
+ * the table like (`None` if every service was read):
+ *
+ * ```text
+ * Errors
+ *   foo: failed to read "/var/service/foo/supervise/status": Permission denied
+ * ```
+ */
+fn format_errors(services: &[(Service, Vec<String>)]) -> Option<String> {
+    let theme = theme::get();
+
+    let lines: Vec<String> = services
+        .iter()
+        .filter_map(|(service, _)| {
+            let err = service.error.as_ref()?;
+            Some(format!("  {}: {}", service.name, theme.bad.paint(err)))
+        })
+        .collect();
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    let title = Style::default().bold().paint("Errors");
+    Some(format!("{}\n{}", title, lines.join("\n")))
+}
+
+/**
+ * Record the start time of every service in the restart history (to count
+ * restarts across invocations, see `restarts`).  Failing to read or write
+ * the history is not fatal.
+ */
+fn track_restarts(
+    cfg: &Config,
+    path: &Path,
+    runit_services: &[(RunitService, Option<&str>)],
+    services: &mut [(Service, Vec<String>)],
+) {
+    let mut history = match RestartHistory::load(path) {
+        Ok(history) => history,
+        Err(err) => {
+            verbose!(cfg, "restart history: {:#}", err);
+            return;
+        }
+    };
+
+    let now = SystemTime::now();
+    for ((runit_service, _), (service, _)) in
+        runit_services.iter().zip(services.iter_mut())
+    {
+        let start = match service.start_time {
+            Ok(start) => start,
+            Err(_) => continue,
+        };
+
+        let key = runit_service.path.to_string_lossy();
+        let running = service.state == ServiceState::Run;
+        service.restarts = Some(history.observe(&key, start, running, now));
+    }
+
+    if let Err(err) = history.save(path) {
+        verbose!(cfg, "restart history: {:#}", err);
+    }
+}
+
+/// Find the processes descended from each service's process.
+fn find_children(cfg: &Config, services: &mut [(Service, Vec<String>)]) {
+    let table = procfs::process_table(&cfg.opts.proc_path);
+
+    for (service, _) in services.iter_mut() {
+        let pid = match service.pid {
+            Some(pid) => pid,
+            None => continue,
+        };
+
+        service.children = Some(match &table {
+            Ok(table) => Ok(procfs::descendants(table, pid)),
+            Err(err) => Err(anyhow!("{:#}", err).into()),
+        });
+    }
+}
+
+/// Measure the CPU usage of each service's process.
+fn measure_cpu(cfg: &Config, services: &mut [(Service, Vec<String>)]) {
+    let pids: Vec<pid_t> =
+        services.iter().filter_map(|(service, _)| service.pid).collect();
+    let mut usage = procfs::cpu_usage(&pids, &cfg.opts.proc_path).into_iter();
+
+    for (service, messages) in services.iter_mut() {
+        let pid = match service.pid {
+            Some(pid) => pid,
+            None => continue,
+        };
+
+        match usage.next() {
+            Some(Ok(cpu)) => service.cpu = Some(cpu),
+            Some(Err(err)) => messages.push(format!(
+                "failed to get cpu usage for pid {}: {:#}",
+                pid, err
+            )),
+            None => (),
+        }
+    }
+}
+
+/**
+ * Split services into sections by state for `--group-by state`, returning the
+ * title and service indices of each non-empty section.  Problem states are
+ * listed first and log services stay in the section of their parent service.
+ */
+fn group_by_state(
+    services: &[(Service, Vec<String>)],
+) -> Vec<(Option<String>, Vec<usize>)> {
+    const ORDER: &[ServiceState] = &[
+        ServiceState::Down,
+        ServiceState::Finish,
+        ServiceState::Zombie,
+        ServiceState::Stopped,
+        ServiceState::Unsupervised,
+        ServiceState::Unknown,
+        ServiceState::Error,
+        ServiceState::Run,
+    ];
+
+    let groups = group_log_services((0..services.len()).collect(), |i| {
+        services[*i].0.log
+    });
+
+    ORDER
+        .iter()
+        .filter_map(|state| {
+            let groups: Vec<&Vec<usize>> = groups
+                .iter()
+                .filter(|group| services[group[0]].0.state == *state)
+                .collect();
+
+            if groups.is_empty() {
+                return None;
+            }
+
+            let title = format!("{} ({})", state, groups.len());
+            Some((Some(title), groups.into_iter().flatten().copied().collect()))
+        })
+        .collect()
+}
+
+/**
+ * Split services into sections by the tree they came from for `--group-by
+ * origin` (the default when multiple directories are given), in the order the
+ * trees were given.
+ */
+fn group_by_origin(
+    cfg: &Config,
+    services: &[(Service, Vec<String>)],
+) -> Vec<(Option<String>, Vec<usize>)> {
+    let count = |indices: &[usize]| {
+        indices.iter().filter(|i| !services[**i].0.log).count()
+    };
+
+    if cfg.trees.is_empty() {
+        let indices: Vec<usize> = (0..services.len()).collect();
+        let title = format!("{} ({})", cfg.svdir.display(), count(&indices));
+        return vec![(Some(title), indices)];
+    }
+
+    cfg.trees
+        .iter()
+        .filter_map(|(origin, _)| {
+            let indices: Vec<usize> = (0..services.len())
+                .filter(|i| services[*i].0.origin.as_ref() == Some(origin))
+                .collect();
+
+            if indices.is_empty() {
+                return None;
+            }
+
+            let title = format!("{} ({})", origin, count(&indices));
+            Some((Some(title), indices))
+        })
+        .collect()
+}
+
+/**
+ * Determine the width of each table column.
+ *
+ * When the terminal width is known the flexible columns (name and command) are
+ * sized to fit their values, shrinking the widest of them as needed so a line
+ * doesn't wrap.  Otherwise columns have a fixed max width (see
+ * `Column::width`), which `-w` doubles for the flexible columns.  Columns
+ * without a max width, or every column with `-ww`, are sized to fit their
+ * widest value so nothing is ever truncated.
+ */
+pub fn column_widths(
+    cfg: &Config,
+    rows: &[Vec<(String, Style)>],
+    term_width: Option<usize>,
+) -> Vec<usize> {
+    // flexible columns are never shrunk below this
+    const MIN_FLEXIBLE_WIDTH: usize = 10;
+
+    let content_width = |i: usize, column: &Column| {
+        rows.iter()
+            .map(|row| formatting::str_width(&row[i].0))
+            .chain([column.header().len()])
+            .max()
+            .unwrap_or(0)
+    };
+
+    let mut widths: Vec<usize> = cfg
+        .opts
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| match (column.width(), cfg.wide) {
+            (0, _) | (_, 2..) => content_width(i, column),
+            // full commands are only trimmed to fit the terminal
+            (_, 0) if *column == Column::Command && cfg.opts.full_command => {
+                content_width(i, column)
+            }
+            // states are never trimmed (only "unsupervised" is long)
+            (width, 0) if *column == Column::State => {
+                content_width(i, column).max(width)
+            }
+            (_, 0) if column.is_flexible() && term_width.is_some() => {
+                content_width(i, column)
+            }
+            // only the flexible columns truncate values worth widening
+            (width, 1) if column.is_flexible() => width * 2,
+            (width, _) => width,
+        })
+        .collect();
+
+    let term_width = match (term_width, cfg.wide) {
+        (Some(term_width), 0) => term_width,
+        _ => return widths,
+    };
+
+    // every column is preceded by a single space
+    let line_width = |widths: &[usize]| -> usize {
+        widths.iter().map(|width| width + 1).sum()
+    };
+
+    while line_width(&widths) > term_width {
+        let widest = cfg
+            .opts
+            .columns
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .filter(|(_, (column, width))| {
+                column.is_flexible() && **width > MIN_FLEXIBLE_WIDTH
+            })
+            .max_by_key(|(_, (_, width))| **width)
+            .map(|(i, _)| i);
+
+        match widest {
+            Some(i) => widths[i] -= 1,
+            None => break,
+        }
+    }
+
+    widths
+}
+
+/**
+ * Format a 1 line summary of the gathered services like:
+ *
+ * ```text
+ * 5 services: 3 up, 2 down, 1 missing pid, longest down: foo (3 hours)
+ * ```
+ */
+fn format_summary(services: &[(Service, Vec<String>)]) -> String {
+    // log services (`-l`) are counted as part of the service they follow
+    let services: Vec<&Service> = services
+        .iter()
+        .map(|(service, _)| service)
+        .filter(|service| !service.log)
+        .collect();
+
+    let total = services.len();
+    let count = |f: fn(&Service) -> bool| {
+        services.iter().filter(|service| f(service)).count()
+    };
+
+    let theme = theme::get();
+    let up = count(|service| service.state == ServiceState::Run);
+    let down = count(|service| service.state == ServiceState::Down);
+    let other = total - up - down;
+    let no_pid = count(|service| {
+        service.state == ServiceState::Run && service.pid.is_none()
+    });
+
+    let mut s = format!(
+        " {} service{}: {} up, {} down",
+        Style::default().bold().paint(total),
+        if total == 1 { "" } else { "s" },
+        theme.states.run.paint(up),
+        theme.states.down.paint(down),
+    );
+
+    if other > 0 {
+        s.push_str(&format!(", {} other", theme.warn.paint(other)));
+    }
+
+    s.push_str(&format!(", {} missing pid", no_pid));
+
+    let longest_down = services
+        .iter()
+        .filter(|service| service.state == ServiceState::Down)
+        .filter_map(|service| service.uptime().map(|t| (service, t)))
+        .max_by_key(|(_, t)| *t);
+
+    if let Some((service, t)) = longest_down {
+        s.push_str(&format!(
+            ", longest down: {} ({})",
+            theme.states.down.paint(&service.name),
+            time_format::get().duration(&t)
+        ));
+    }
+
+    s
+}
+
+/// Print the gathered services as a JSON array of objects.
+fn print_json(cfg: &Config, services: Vec<(Service, Vec<String>)>) {
+    print_messages(cfg, &services);
+
+    let value = output::services_to_value(&services, &cfg.opts.columns);
+    println!("{}", output::json::to_string_pretty(&value));
+}
+
+/**
+ * Print every field of the gathered services as JSON (see `vsv::snapshot`),
+ * with their messages in an `errors` array, for `--host` to read back.
+ */
+fn print_snapshot(cfg: &Config, services: Vec<(Service, Vec<String>)>) {
+    print_messages(cfg, &services);
+
+    let arr = services
+        .iter()
+        .map(|(service, messages)| {
+            let mut value = service.to_value();
+            if let Value::Object(fields) = &mut value {
+                let errors = messages
+                    .iter()
+                    .map(|message| Value::from(message.as_str()))
+                    .collect();
+                fields.push(("errors".into(), Value::Array(errors)));
+            }
+            value
+        })
+        .collect();
+
+    println!("{}", output::json::to_string_pretty(&Value::Array(arr)));
+}
+
+/**
+ * Build the top-level value given to formats that include some metadata about
+ * the invocation (YAML and templates).
+ */
+fn services_document(
+    cfg: &Config,
+    services: &[(Service, Vec<String>)],
+) -> Value {
+    let timestamp = utils::format_timestamp_utc(&SystemTime::now());
+
+    Value::Object(vec![
+        ("svdir".into(), cfg.svdir.to_string_lossy().as_ref().into()),
+        ("timestamp".into(), timestamp.into()),
+        (
+            "services".into(),
+            output::services_to_value(services, &cfg.opts.columns),
+        ),
+    ])
+}
+
+/**
+ * Print the gathered services as a single YAML document, with some metadata
+ * about the invocation at the top.
+ */
+fn print_yaml(cfg: &Config, services: Vec<(Service, Vec<String>)>) {
+    print_messages(cfg, &services);
+
+    let value = services_document(cfg, &services);
+    print!("{}", output::yaml::to_string(&value));
+}
+
+/// Print the gathered services as CSV (with a header row unless disabled).
+fn print_csv(cfg: &Config, services: Vec<(Service, Vec<String>)>) {
+    print_messages(cfg, &services);
+
+    let value = output::services_to_value(&services, &cfg.opts.columns);
+    let fields = output::fields(&cfg.opts.columns, true);
+    print!("{}", output::csv::to_string(&fields, &value, !cfg.no_header));
+}
+
+/// Print the gathered services as a Markdown table.
+fn print_markdown(cfg: &Config, services: Vec<(Service, Vec<String>)>) {
+    print_messages(cfg, &services);
+
+    let value = output::services_to_value(&services, &cfg.opts.columns);
+    let fields = output::fields(&cfg.opts.columns, true);
+    print!("{}", output::markdown::to_string(&fields, &value));
+}
+
+/// Print the gathered services as raw tab (or NUL) delimited records.
+fn print_raw(cfg: &Config, services: Vec<(Service, Vec<String>)>) {
+    print_messages(cfg, &services);
+
+    let value = output::services_to_value(&services, &cfg.opts.columns);
+    let fields = output::fields(&cfg.opts.columns, false);
+    print!("{}", output::raw::to_string(&fields, &value, cfg.null));
+}
+
+/// Print the gathered services rendered through a parsed template.
+fn print_template(
+    cfg: &Config,
+    nodes: &[template::Node],
+    services: Vec<(Service, Vec<String>)>,
+) {
+    print_messages(cfg, &services);
+
+    let value = services_document(cfg, &services);
+    print!("{}", template::render(nodes, &value));
+}
+
+/// Print each of the gathered services using a parsed format string.
+fn print_custom(
+    cfg: &Config,
+    tokens: &[Token],
+    services: Vec<(Service, Vec<String>)>,
+) {
+    print_messages(cfg, &services);
+
+    for (service, _) in services {
+        let line = formatting::render_format_string(tokens, |column| {
+            service.format_column(column)
+        });
+        println!("{}", line);
+    }
+}
+
+/// Print any verbose messages/warnings generated by the services.
+fn print_messages(cfg: &Config, services: &[(Service, Vec<String>)]) {
+    for (_, messages) in services {
+        for message in messages {
+            verbose!(cfg, "{}", message);
+        }
+    }
 }
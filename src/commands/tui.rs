@@ -0,0 +1,447 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv tui` - an interactive, full screen service list.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use yansi::Style;
+
+use crate::cache;
+use crate::column::Column;
+use crate::commands::{status, watch};
+use crate::config::Config;
+use crate::formatting;
+use crate::runit::{ControlCommand, RunitService};
+use crate::service::Service;
+use crate::terminal::{self, Key, RawTerminal};
+
+/// How often services are re-read (while idle).
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The height (in lines) of the detail pane.
+const DETAIL_HEIGHT: usize = 8;
+
+/// Help shown in the footer when there is no other message to show.
+const HELP: &str = "q quit  j/k move  / filter  enter details  \
+                    u up  d down  r restart  e enable  D disable  R refresh";
+
+/// Handle `vsv tui`.
+pub fn do_tui(cfg: &Config) -> Result<()> {
+    let term = RawTerminal::new().context("failed to start tui")?;
+    cache::enable();
+    let mut app = App::new(cfg)?;
+    let mut watcher = watch::start_watcher(cfg);
+
+    loop {
+        app.draw()?;
+
+        if let Some(key) = term.read_key(Duration::from_millis(250))? {
+            if !app.handle_key(key)? {
+                break;
+            }
+        }
+
+        let changed = match &mut watcher {
+            Some(watcher) => watcher.wait(Duration::ZERO)?,
+            None => false,
+        };
+
+        if changed || app.last_refresh.elapsed() >= REFRESH_INTERVAL {
+            app.refresh()?;
+            if let Some(watcher) = &mut watcher {
+                watch::watch_services(cfg, watcher);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The state of the tui.
+struct App<'a> {
+    cfg: &'a Config,
+    services: Vec<(Service, Vec<String>)>,
+    keys: Vec<String>,
+    /// The service directory and path of each service (see
+    /// `watch::service_paths`).
+    paths: Vec<Option<(PathBuf, PathBuf)>>,
+    last_refresh: Instant,
+
+    /// Key of the selected service (kept across refreshes).
+    selected: Option<String>,
+    /// Index of the first visible row (for scrolling).
+    offset: usize,
+
+    filter: String,
+    editing_filter: bool,
+    detail: bool,
+    message: Option<String>,
+}
+
+impl<'a> App<'a> {
+    fn new(cfg: &'a Config) -> Result<Self> {
+        let mut app = Self {
+            cfg,
+            services: vec![],
+            keys: vec![],
+            paths: vec![],
+            last_refresh: Instant::now(),
+            selected: None,
+            offset: 0,
+            filter: String::new(),
+            editing_filter: false,
+            detail: false,
+            message: None,
+        };
+        app.refresh()?;
+
+        Ok(app)
+    }
+
+    /// Re-read all services from disk.
+    fn refresh(&mut self) -> Result<()> {
+        self.services = status::get_services(self.cfg)?;
+        cache::sweep();
+        self.keys = watch::service_keys(&self.services);
+        self.paths = watch::service_paths(self.cfg, &self.services);
+        self.last_refresh = Instant::now();
+
+        Ok(())
+    }
+
+    /**
+     * Get the indices of the services matching the current filter (a case
+     * insensitive substring of the name, log services match their parent).
+     */
+    fn visible(&self) -> Vec<usize> {
+        let filter = self.filter.to_lowercase();
+
+        self.keys
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| key.to_lowercase().contains(&filter))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Get the position of the selected service in the visible list.
+    fn selected_position(&self, visible: &[usize]) -> usize {
+        self.selected
+            .as_ref()
+            .and_then(|key| visible.iter().position(|i| &self.keys[*i] == key))
+            .unwrap_or(0)
+    }
+
+    /// Move the selection by `delta` rows (clamped to the list).
+    fn move_selection(&mut self, delta: isize) {
+        let visible = self.visible();
+        if visible.is_empty() {
+            return;
+        }
+
+        let pos = self.selected_position(&visible) as isize + delta;
+        let pos = pos.clamp(0, visible.len() as isize - 1) as usize;
+        self.selected = Some(self.keys[visible[pos]].clone());
+    }
+
+    /**
+     * Handle a single key press, returns `false` if the program should exit.
+     */
+    fn handle_key(&mut self, key: Key) -> Result<bool> {
+        if self.editing_filter {
+            match key {
+                Key::Enter => self.editing_filter = false,
+                Key::Esc => {
+                    self.filter.clear();
+                    self.editing_filter = false;
+                }
+                Key::Backspace => {
+                    self.filter.pop();
+                }
+                Key::Char(c) => self.filter.push(c),
+                Key::Ctrl('c') => return Ok(false),
+                _ => (),
+            }
+            return Ok(true);
+        }
+
+        let (rows, _) = self.list_size();
+        let page = rows.max(1) as isize;
+
+        match key {
+            Key::Char('q') | Key::Ctrl('c') => return Ok(false),
+            Key::Char('j') | Key::Down => self.move_selection(1),
+            Key::Char('k') | Key::Up => self.move_selection(-1),
+            Key::Char('g') | Key::Home => self.move_selection(isize::MIN / 2),
+            Key::Char('G') | Key::End => self.move_selection(isize::MAX / 2),
+            Key::PageDown | Key::Ctrl('f') => self.move_selection(page),
+            Key::PageUp | Key::Ctrl('b') => self.move_selection(-page),
+            Key::Char('/') => {
+                self.editing_filter = true;
+                self.message = None;
+            }
+            Key::Esc => self.filter.clear(),
+            Key::Enter | Key::Tab => self.detail = !self.detail,
+            Key::Char('u') => self.control(ControlCommand::Up),
+            Key::Char('d') => self.control(ControlCommand::Down),
+            Key::Char('r') => self.control(ControlCommand::Restart),
+            Key::Char('e') => self.enable_disable(true),
+            Key::Char('D') => self.enable_disable(false),
+            Key::Char('R') => {
+                self.refresh()?;
+                self.message = Some("refreshed".into());
+            }
+            _ => (),
+        }
+
+        Ok(true)
+    }
+
+    /**
+     * Get the key of the selected service and the service itself to modify
+     * with `verb`, any reason it can't be (like it's on another machine) is
+     * shown as the message instead.
+     */
+    fn selected_service(
+        &mut self,
+        verb: &str,
+    ) -> Option<(String, RunitService)> {
+        let visible = self.visible();
+        let i = *visible.get(self.selected_position(&visible))?;
+        let key = self.keys[i].clone();
+
+        let (svdir, path) = match &self.paths[i] {
+            Some(paths) => paths,
+            None => {
+                self.message = Some(format!(
+                    "{} {} failed: services on other hosts can't be modified",
+                    verb, key
+                ));
+                return None;
+            }
+        };
+
+        if self.cfg.dry_run {
+            self.message = Some(format!("dry run: would {} {}", verb, key));
+            return None;
+        }
+
+        let svc = RunitService::new(&key, path, self.cfg.backend_for(svdir));
+
+        Some((key, svc))
+    }
+
+    /// Send a control command to the selected service.
+    fn control(&mut self, cmd: ControlCommand) {
+        let (key, svc) = match self.selected_service(cmd.name()) {
+            Some(selected) => selected,
+            None => return,
+        };
+
+        self.message = Some(match svc.control(cmd) {
+            Ok(()) => format!("{} {}: done", cmd.name(), key),
+            Err(err) => format!("{} {} failed: {}", cmd.name(), key, err),
+        });
+
+        let _ = self.refresh();
+    }
+
+    /// Enable or disable the selected service.
+    fn enable_disable(&mut self, enable: bool) {
+        let verb = if enable { "enable" } else { "disable" };
+        let (key, svc) = match self.selected_service(verb) {
+            Some(selected) => selected,
+            None => return,
+        };
+
+        let ret = match enable {
+            true => svc.enable(),
+            false => svc.disable(),
+        };
+
+        self.message = Some(match ret {
+            Ok(()) => format!("{} {}: done", verb, key),
+            Err(err) => format!("{} {} failed: {}", verb, key, err),
+        });
+
+        let _ = self.refresh();
+    }
+
+    /// Get the number of list rows that fit on screen, and the total columns.
+    fn list_size(&self) -> (usize, usize) {
+        let (rows, cols) = terminal::size();
+
+        // title, header and footer lines
+        let mut used = 3;
+        if self.detail {
+            used += DETAIL_HEIGHT;
+        }
+
+        (rows.saturating_sub(used), cols)
+    }
+
+    /// Draw the whole screen.
+    fn draw(&mut self) -> Result<()> {
+        let (list_rows, cols) = self.list_size();
+        let visible = self.visible();
+        let bold = Style::default().bold();
+
+        // keep the selection on a visible service and in view
+        let pos = self.selected_position(&visible);
+        self.selected = visible.get(pos).map(|i| self.keys[*i].clone());
+        if pos < self.offset {
+            self.offset = pos;
+        } else if list_rows > 0 && pos >= self.offset + list_rows {
+            self.offset = pos + 1 - list_rows;
+        }
+
+        let mut lines: Vec<String> = vec![];
+
+        // title
+        let mut title =
+            format!("vsv - {:?} - {} services", self.cfg.svdir, visible.len());
+        if self.editing_filter || !self.filter.is_empty() {
+            title.push_str(&format!("  filter: {}", self.filter));
+            if self.editing_filter {
+                title.push('_');
+            }
+        }
+        lines.push(bold.paint(fit(&title, cols)).to_string());
+
+        // every visible row is formatted so the column widths are stable
+        // while scrolling, 2 columns are used for the selection marker
+        let rows: Vec<Vec<(String, Style)>> = visible
+            .iter()
+            .map(|i| {
+                let service = &self.services[*i].0;
+                self.cfg
+                    .opts
+                    .columns
+                    .iter()
+                    .map(|column| service.format_column(*column))
+                    .collect()
+            })
+            .collect();
+        let widths = status::column_widths(
+            self.cfg,
+            &rows,
+            Some(cols.saturating_sub(2)),
+        );
+
+        let header: Vec<(&str, Style, usize)> = self
+            .cfg
+            .opts
+            .columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, width)| (column.header(), bold, *width))
+            .collect();
+        let mut line = String::from("  ");
+        formatting::write_status_line(&mut line, &header);
+        lines.push(line);
+
+        for (n, row) in
+            rows.iter().enumerate().skip(self.offset).take(list_rows)
+        {
+            let line = if n == pos {
+                // selected rows are shown inverted (without their colors)
+                let cells: Vec<(&str, Style, usize)> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|((s, _), width)| {
+                        (s.as_str(), Style::default(), *width)
+                    })
+                    .collect();
+                let mut line = String::from("\x1b[7m> ");
+                formatting::write_status_line(&mut line, &cells);
+                line.push_str("\x1b[K\x1b[0m");
+                line
+            } else {
+                let cells: Vec<(&str, Style, usize)> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|((s, style), width)| (s.as_str(), *style, *width))
+                    .collect();
+                let mut line = String::from("  ");
+                formatting::write_status_line(&mut line, &cells);
+                line
+            };
+            lines.push(line);
+        }
+        while lines.len() < list_rows + 2 {
+            lines.push(String::new());
+        }
+
+        if self.detail {
+            lines.extend(self.detail_lines(visible.get(pos).copied(), cols));
+        }
+
+        let footer = self.message.as_deref().unwrap_or(HELP);
+        lines.push(format!("\x1b[7m{}\x1b[K\x1b[0m", fit(footer, cols)));
+
+        let mut out = String::from("\x1b[H");
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push_str(line);
+            out.push_str("\x1b[K");
+        }
+        out.push_str("\x1b[J");
+
+        let mut stdout = io::stdout();
+        stdout.write_all(out.as_bytes())?;
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    /// Create the lines of the detail pane for the service at index `i`.
+    fn detail_lines(&self, i: Option<usize>, cols: usize) -> Vec<String> {
+        let mut lines = vec![fit(&"-".repeat(cols), cols)];
+
+        if let Some(i) = i {
+            let (service, messages) = &self.services[i];
+            let bold = Style::default().bold();
+            let field = |name: &str, column: Column| {
+                let (s, style) = service.format_column(column);
+                format!(
+                    "{} {}",
+                    bold.paint(format!("{:>9}", name)),
+                    style.paint(fit(&s, cols.saturating_sub(10)))
+                )
+            };
+
+            lines.push(format!(
+                "{} {}",
+                bold.paint(format!("{:>9}", "service")),
+                fit(&self.keys[i], cols.saturating_sub(10))
+            ));
+            lines.push(field("state", Column::State));
+            lines.push(field("enabled", Column::Enabled));
+            lines.push(field("pid", Column::Pid));
+            lines.push(field("command", Column::Command));
+            lines.push(field("uptime", Column::Uptime));
+            lines.push(format!(
+                "{} {}",
+                bold.paint(format!("{:>9}", "errors")),
+                fit(&messages.join("; "), cols.saturating_sub(10))
+            ));
+        }
+
+        lines.resize(DETAIL_HEIGHT, String::new());
+        lines
+    }
+}
+
+/// Trim a plain (uncolored) string to fit in `cols` columns.
+fn fit(s: &str, cols: usize) -> String {
+    s.chars().take(cols).collect()
+}
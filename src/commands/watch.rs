@@ -0,0 +1,424 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! `vsv --watch` - periodically redraw status output.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use libc::pid_t;
+use yansi::Style;
+
+use crate::cache;
+use crate::column::Column;
+use crate::commands::status;
+use crate::config::verbose;
+use crate::config::{Config, OutputFormat};
+use crate::inotify::Watcher;
+use crate::service::{Service, ServiceState};
+use crate::utils;
+
+/// The max number of events kept for `--events`.
+const MAX_EVENTS: usize = 10;
+
+pub const ENV_VSV_SERVICE: &str = "VSV_SERVICE";
+pub const ENV_VSV_OLD_STATE: &str = "VSV_OLD_STATE";
+pub const ENV_VSV_NEW_STATE: &str = "VSV_NEW_STATE";
+
+/**
+ * Redraw the status output every `interval` (or as soon as a service changes,
+ * with inotify) until killed, re-reading all services each time.  The screen
+ * is only cleared if stdout is a tty.
+ *
+ * Table cells that changed since the previous refresh are highlighted, and
+ * with `--events` a list of the most recent state and pid transitions is kept
+ * below the table.  Every `--hook` is run for each state transition, and with
+ * `--notify` a desktop notification is sent for services that go down (or
+ * start flapping).
+ */
+pub fn do_watch(cfg: &Config, interval: Duration) -> Result<()> {
+    let isatty = utils::isatty(1);
+    let mut previous: Option<Snapshot> = None;
+    let mut events: VecDeque<String> = VecDeque::new();
+    let mut watcher = start_watcher(cfg);
+    cache::enable();
+
+    loop {
+        let services = status::get_services(cfg)?;
+        cache::sweep();
+        let snapshot = Snapshot::new(cfg, &services);
+        let now = utils::format_timestamp_utc(&SystemTime::now());
+
+        if let Some(previous) = &previous {
+            for (key, old, new) in previous.state_changes(&snapshot) {
+                run_hooks(cfg, key, old, new);
+            }
+            if let Some(prog) = &cfg.notify_prog {
+                for (summary, body) in previous.notifications(&snapshot) {
+                    notify(cfg, prog, &summary, &body);
+                }
+            }
+            for event in previous.events(&snapshot) {
+                events.push_back(format!("{}  {}", now, event));
+            }
+            while events.len() > MAX_EVENTS {
+                events.pop_front();
+            }
+        }
+
+        if isatty {
+            // move the cursor home and clear the screen
+            print!("\x1b[H\x1b[2J");
+        }
+
+        let source = match cfg.hosts.is_empty() {
+            true => format!("{:?}", cfg.svdir),
+            false => cfg.hosts.join(", "),
+        };
+        let title =
+            format!("Every {}s: vsv {}", interval.as_secs_f64(), source);
+        println!("{}  {}", Style::default().bold().paint(title), now);
+
+        match &cfg.format {
+            OutputFormat::Table => {
+                status::print_table(cfg, services, previous.as_ref())
+            }
+            _ => status::print_services(cfg, services),
+        }
+
+        if cfg.events {
+            println!("{}", Style::default().bold().paint("Events"));
+            for event in &events {
+                println!("{}", event);
+            }
+        }
+
+        io::stdout().flush()?;
+        previous = Some(snapshot);
+
+        match &mut watcher {
+            Some(watcher) => {
+                watch_services(cfg, watcher);
+                watcher.wait(interval)?;
+            }
+            None => thread::sleep(interval),
+        }
+    }
+}
+
+/**
+ * Create an inotify watcher for the service directories, `None` (to poll
+ * instead) if inotify isn't available.
+ */
+pub fn start_watcher(cfg: &Config) -> Option<Watcher> {
+    // the services are on another machine
+    if !cfg.hosts.is_empty() {
+        return None;
+    }
+
+    let mut watcher = match Watcher::new() {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            verbose!(cfg, "not watching for changes: {:#}", err);
+            return None;
+        }
+    };
+
+    watch_services(cfg, &mut watcher);
+
+    Some(watcher)
+}
+
+/// Watch every service (including any new ones) for changes.
+pub fn watch_services(cfg: &Config, watcher: &mut Watcher) {
+    let svdirs: Vec<&Path> = match cfg.trees.is_empty() {
+        true => vec![&cfg.svdir],
+        false => cfg.trees.iter().map(|(_, dir)| dir.as_path()).collect(),
+    };
+
+    if !watcher.watch(&svdirs) {
+        verbose!(cfg, "not every service could be watched, polling those");
+    }
+}
+
+/// A single service as it was seen during a refresh.
+struct SnapshotRow {
+    state: ServiceState,
+    flapping: bool,
+    pid: Option<pid_t>,
+    cells: Vec<String>,
+}
+
+/**
+ * The services (and their formatted table cells) seen during a refresh, used
+ * to find what changed by the next one.
+ */
+pub struct Snapshot {
+    rows: HashMap<String, SnapshotRow>,
+    order: Vec<String>,
+}
+
+impl Snapshot {
+    /// Create a snapshot of the gathered services.
+    pub fn new(cfg: &Config, services: &[(Service, Vec<String>)]) -> Self {
+        let order = service_keys(services);
+
+        let rows = services
+            .iter()
+            .zip(&order)
+            .map(|((service, _), key)| {
+                let cells = cfg
+                    .opts
+                    .columns
+                    .iter()
+                    .map(|column| service.format_column(*column).0)
+                    .collect();
+                let row = SnapshotRow {
+                    state: service.state,
+                    flapping: service.is_flapping(),
+                    pid: service.pid,
+                    cells,
+                };
+                (key.clone(), row)
+            })
+            .collect();
+
+        Self { rows, order }
+    }
+
+    /**
+     * Check if the cell (the `i`th column, `column`) of the service given by
+     * `key` is different from when this snapshot was taken.  Every cell of a
+     * new service is considered changed, uptime is never considered changed
+     * as it ticks up on every refresh.
+     */
+    pub fn is_changed(
+        &self,
+        key: &str,
+        i: usize,
+        column: Column,
+        cell: &str,
+    ) -> bool {
+        if column == Column::Uptime {
+            return false;
+        }
+
+        match self.rows.get(key) {
+            Some(row) => row.cells.get(i).is_none_or(|old| old != cell),
+            None => true,
+        }
+    }
+
+    /**
+     * Get every service whose state differs in a newer snapshot, as the
+     * service key and its old and new state.
+     */
+    fn state_changes<'a>(
+        &self,
+        newer: &'a Snapshot,
+    ) -> Vec<(&'a str, ServiceState, ServiceState)> {
+        newer
+            .order
+            .iter()
+            .filter_map(|key| {
+                let old = self.rows.get(key)?.state;
+                let new = newer.rows[key].state;
+                (old != new).then_some((key.as_str(), old, new))
+            })
+            .collect()
+    }
+
+    /**
+     * Get the desktop notifications (summary and body) to send for services
+     * that stopped running or started flapping in a newer snapshot.
+     */
+    fn notifications(&self, newer: &Snapshot) -> Vec<(String, String)> {
+        let mut notifications = vec![];
+
+        for key in &newer.order {
+            let (old, new) = match self.rows.get(key) {
+                Some(old) => (old, &newer.rows[key]),
+                None => continue,
+            };
+
+            if old.state == ServiceState::Run && new.state != ServiceState::Run
+            {
+                notifications.push((
+                    format!("{} is {}", key, new.state),
+                    format!("{} -> {}", old.state, new.state),
+                ));
+            } else if !old.flapping && new.flapping {
+                notifications.push((
+                    format!("{} is flapping", key),
+                    "stuck in a restart loop".to_string(),
+                ));
+            }
+        }
+
+        notifications
+    }
+
+    /// Describe every transition between this snapshot and a newer one.
+    fn events(&self, newer: &Snapshot) -> Vec<String> {
+        let mut events = vec![];
+
+        for key in &newer.order {
+            let new = &newer.rows[key];
+
+            let old = match self.rows.get(key) {
+                Some(old) => old,
+                None => {
+                    events.push(format!("{}: added ({})", key, new.state));
+                    continue;
+                }
+            };
+
+            if old.state != new.state {
+                events.push(format!("{}: {} -> {}", key, old.state, new.state));
+            }
+
+            if old.pid != new.pid {
+                let pid = |pid: Option<pid_t>| {
+                    pid.map_or_else(|| "---".to_string(), |p| p.to_string())
+                };
+                events.push(format!(
+                    "{}: pid {} -> {}",
+                    key,
+                    pid(old.pid),
+                    pid(new.pid)
+                ));
+            }
+        }
+
+        for key in &self.order {
+            if !newer.rows.contains_key(key) {
+                events.push(format!("{}: removed", key));
+            }
+        }
+
+        events
+    }
+}
+
+/**
+ * Run every `--hook` (in the background, killed after `--timeout`) for a
+ * service changing state.  The hook is given the service (key), old state and
+ * new state as env `VSV_SERVICE`, `VSV_OLD_STATE` and `VSV_NEW_STATE`.
+ */
+fn run_hooks(cfg: &Config, key: &str, old: ServiceState, new: ServiceState) {
+    for hook in &cfg.hooks {
+        let mut cmd = process::Command::new("sh");
+        cmd.arg("-c")
+            .arg(hook)
+            .env(ENV_VSV_SERVICE, key)
+            .env(ENV_VSV_OLD_STATE, old.to_string())
+            .env(ENV_VSV_NEW_STATE, new.to_string());
+
+        spawn_background(cfg, cmd);
+    }
+}
+
+/// Send a desktop notification with `notify-send` (or env `NOTIFY_PROG`).
+fn notify(cfg: &Config, prog: &str, summary: &str, body: &str) {
+    let mut cmd = process::Command::new(prog);
+    cmd.args(["-a", "vsv", summary, body]);
+
+    spawn_background(cfg, cmd);
+}
+
+/**
+ * Run a command in the background (without any stdio), killing it after
+ * `--timeout`, so it can't hold up the next refresh.
+ */
+fn spawn_background(cfg: &Config, mut cmd: process::Command) {
+    let child = cmd
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(err) => {
+            verbose!(cfg, "failed to run {:?}: {}", cmd, err);
+            return;
+        }
+    };
+
+    let timeout = cfg.opts.timeout;
+    thread::spawn(move || utils::wait_timeout(&mut child, timeout));
+}
+
+/**
+ * Get a unique key for each service, log services (which are all named
+ * `"- log"`) are keyed by the service they follow like `"foo/log"` and
+ * services from multiple trees (`-U`) or hosts (`-H`) are prefixed like
+ * `"user:foo"` or `"web1:foo"`.
+ */
+pub fn service_keys(services: &[(Service, Vec<String>)]) -> Vec<String> {
+    let mut parent = String::new();
+
+    services
+        .iter()
+        .map(|(service, _)| {
+            if service.log {
+                return format!("{}/log", parent);
+            }
+
+            parent = match &service.origin {
+                Some(origin) => format!("{}:{}", origin, service.name),
+                None => service.name.clone(),
+            };
+            if let Some(host) = &service.host {
+                parent = format!("{}:{}", host, parent);
+            }
+            parent.clone()
+        })
+        .collect()
+}
+
+/**
+ * Get the service directory each service (as keyed by `service_keys`) is in
+ * and its own path, log services are in their parent's.  Services on other
+ * machines (`-H`) are `None` since they can't be touched from here.
+ */
+pub fn service_paths(
+    cfg: &Config,
+    services: &[(Service, Vec<String>)],
+) -> Vec<Option<(PathBuf, PathBuf)>> {
+    let mut parent: Option<(PathBuf, PathBuf)> = None;
+
+    services
+        .iter()
+        .map(|(service, _)| {
+            if service.log {
+                return parent
+                    .as_ref()
+                    .map(|(svdir, path)| (svdir.clone(), path.join("log")));
+            }
+
+            let svdir = match (&service.host, &service.origin) {
+                (Some(_), _) => None,
+                (None, Some(origin)) => cfg
+                    .trees
+                    .iter()
+                    .find(|(name, _)| name == origin)
+                    .map(|(_, dir)| dir.clone()),
+                (None, None) => Some(cfg.svdir.clone()),
+            };
+            parent = svdir.map(|svdir| {
+                let path = svdir.join(&service.name);
+                (svdir, path)
+            });
+            parent.clone()
+        })
+        .collect()
+}
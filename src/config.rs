@@ -13,29 +13,87 @@
  */
 
 use std::env;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::fmt;
-use std::path::PathBuf;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 
-use crate::arguments::{Args, Commands};
+use crate::arguments::{Args, Commands, ControlArgs};
+use crate::backend::{self, Supervisor};
+use crate::column::{self, Column};
 use crate::config;
+use crate::config_file::ConfigFile;
+use crate::formatting::{self, Token};
+use crate::glyphs::Glyphs;
+use crate::options::{
+    Options, DEFAULT_CGROUP_DIR, DEFAULT_FD_THRESHOLD, DEFAULT_PROC_DIR,
+    DEFAULT_PSTREE_PROG, DEFAULT_TIMEOUT,
+};
+use crate::output::template;
+use crate::regex::Regex;
+use crate::restarts::RestartHistory;
+use crate::runit::ControlCommand;
+use crate::service::ServiceState;
+use crate::theme::Theme;
+use crate::time_format::{TimeFormat, TimeStyle};
 use crate::utils;
 
 // default values
 pub const DEFAULT_SVDIR: &str = "/var/service";
-pub const DEFAULT_PROC_DIR: &str = "/proc";
 pub const DEFAULT_SV_PROG: &str = "sv";
-pub const DEFAULT_PSTREE_PROG: &str = "pstree";
+pub const DEFAULT_NOTIFY_PROG: &str = "notify-send";
+pub const DEFAULT_SSH_PROG: &str = "ssh";
+pub const DEFAULT_REMOTE_PROG: &str = "vsv";
 pub const DEFAULT_USER_DIR: &str = "runit/service";
+pub const DEFAULT_XDG_USER_DIR: &str = "service";
+pub const DEFAULT_SOURCE_DIR: &str = "/etc/sv";
+pub const DEFAULT_PAGER: &str = "less -R";
+pub const DEFAULT_WATCH_INTERVAL: f64 = 2.0;
+pub const DEFAULT_LOG_LINES: usize = 10;
+
+/**
+ * The fewest services read at once without `--jobs`, gathering mostly waits on
+ * files and programs (like `pstree`) so more than the number of CPUs helps.
+ */
+pub const MIN_DEFAULT_JOBS: usize = 16;
+
+/// The names of the output formats given to `-F` (or `-o`).
+const FORMATS: &[&str] =
+    &["table", "json", "yaml", "csv", "markdown", "md", "raw", "snapshot"];
 
 // env var name
 pub const ENV_NO_COLOR: &str = "NO_COLOR";
+pub const ENV_CLICOLOR: &str = "CLICOLOR";
+pub const ENV_CLICOLOR_FORCE: &str = "CLICOLOR_FORCE";
 pub const ENV_SVDIR: &str = "SVDIR";
 pub const ENV_PROC_DIR: &str = "PROC_DIR";
+pub const ENV_CGROUP_DIR: &str = "CGROUP_DIR";
+pub const ENV_SERVE_TOKEN: &str = "VSV_SERVE_TOKEN";
 pub const ENV_SV_PROG: &str = "SV_PROG";
 pub const ENV_PSTREE_PROG: &str = "PSTREE_PROG";
+pub const ENV_NOTIFY_PROG: &str = "NOTIFY_PROG";
+pub const ENV_SSH_PROG: &str = "SSH_PROG";
+pub const ENV_REMOTE_PROG: &str = "VSV_REMOTE_PROG";
+pub const ENV_PAGER: &str = "PAGER";
+pub const ENV_LESS: &str = "LESS";
+pub const ENV_XDG_CONFIG_HOME: &str = "XDG_CONFIG_HOME";
+
+/**
+ * A `println!()`-like macro that will only print if `-v` is set.
+ */
+macro_rules! verbose {
+    ($cfg:expr, $fmt:expr $(, $args:expr )* $(,)? ) => {
+        if $cfg.verbose > 0 {
+            let s = format!($fmt $(, $args)*);
+            eprintln!(">  {}", ::yansi::Style::default().dimmed().paint(s));
+        }
+    };
+}
+pub(crate) use verbose;
 
 /// vsv execution modes (subcommands).
 #[derive(Debug)]
@@ -43,6 +101,17 @@ pub enum ProgramMode {
     Status,
     Enable,
     Disable,
+    Control(ControlCommand),
+    Metrics,
+    CheckPlugin,
+    Exporter,
+    Serve,
+    Avail,
+    Log,
+    Show,
+    Pick,
+    Tui,
+    Completions,
     External,
 }
 
@@ -52,6 +121,17 @@ impl fmt::Display for ProgramMode {
             ProgramMode::Status => "status",
             ProgramMode::Enable => "enable",
             ProgramMode::Disable => "disable",
+            ProgramMode::Control(cmd) => cmd.name(),
+            ProgramMode::Metrics => "metrics",
+            ProgramMode::CheckPlugin => "check-plugin",
+            ProgramMode::Exporter => "exporter",
+            ProgramMode::Serve => "serve",
+            ProgramMode::Avail => "avail",
+            ProgramMode::Log => "log",
+            ProgramMode::Show => "show",
+            ProgramMode::Pick => "pick",
+            ProgramMode::Tui => "tui",
+            ProgramMode::Completions => "completions",
             ProgramMode::External => "<external>",
         };
 
@@ -59,6 +139,86 @@ impl fmt::Display for ProgramMode {
     }
 }
 
+/// Output formats for `vsv status`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Yaml,
+    Csv,
+    Markdown,
+    Raw,
+    Snapshot,
+    Custom(Vec<Token>),
+    Template(Vec<template::Node>),
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            OutputFormat::Table => "table",
+            OutputFormat::Json => "json",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Raw => "raw",
+            OutputFormat::Snapshot => "snapshot",
+            OutputFormat::Custom(_) => "custom",
+            OutputFormat::Template(_) => "template",
+        };
+
+        s.fmt(f)
+    }
+}
+
+/// When to color output (`--color`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color output if nothing else (like `NO_COLOR`) says otherwise.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /**
+     * Parse a color option: `auto`, `always` (or `yes` or `on`) or `never`
+     * (or `no` or `off`).
+     */
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" | "yes" | "on" => Ok(ColorChoice::Always),
+            "never" | "no" | "off" => Ok(ColorChoice::Never),
+            _ => bail!(
+                "unknown color option: '{}' (expected auto, always or never)",
+                s
+            ),
+        }
+    }
+}
+
+/// Fields that table output can be grouped by with `--group-by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    State,
+    Origin,
+}
+
+impl GroupBy {
+    /// Parse a group-by field from its name.
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "state" => Ok(GroupBy::State),
+            "origin" => Ok(GroupBy::Origin),
+            _ => bail!(
+                "unknown group-by field: '{}' (valid fields: state,origin)",
+                s
+            ),
+        }
+    }
+}
+
 /**
  * Configuration options derived from the environment and CLI arguments.
  *
@@ -68,19 +228,63 @@ impl fmt::Display for ProgramMode {
  */
 #[derive(Debug)]
 pub struct Config {
+    // how services are read (from env vars and CLI options)
+    pub opts: Options,
+
     // env vars only
-    pub proc_path: PathBuf,
     pub sv_prog: String,
-    pub pstree_prog: String,
+    pub notify_prog: Option<String>,
+    pub serve_token: Option<String>,
+    pub ssh_prog: String,
+    pub remote_prog: String,
 
     // env vars or CLI options
     pub colorize: bool,
+    pub theme: Theme,
+    pub glyphs: Glyphs,
+    pub time_format: TimeFormat,
     pub svdir: PathBuf,
 
     // CLI options only
-    pub tree: bool,
+    pub children: bool,
     pub log: bool,
+    pub format: OutputFormat,
+    pub sort: Option<Column>,
+    pub group_by: Option<GroupBy>,
+    pub reverse: bool,
+    pub states: Vec<ServiceState>,
+    pub name_filters: Vec<Regex>,
+    pub exclude_filters: Vec<Regex>,
+    pub summary: bool,
+    pub exit_code: bool,
+    pub quiet: bool,
+    pub no_header: bool,
+    pub wide: usize,
+    pub pager: Option<String>,
+    pub watch: Option<Duration>,
+    pub events: bool,
+    pub hooks: Vec<String>,
+    pub dry_run: bool,
+    pub jobs: usize,
+    pub restart_history: Option<PathBuf>,
+    pub term_width: Option<usize>,
+    pub null: bool,
     pub verbose: usize,
+    pub textfile: Option<PathBuf>,
+    pub critical_down: Vec<String>,
+    pub complete_services: bool,
+    pub listen: Option<String>,
+    pub link_source: Option<PathBuf>,
+    pub log_lines: usize,
+    pub log_follow: bool,
+    pub show_env: bool,
+    pub show_limits: bool,
+    pub redact: bool,
+    pub trees: Vec<(String, PathBuf)>,
+    pub hosts: Vec<String>,
+    pub remote_args: Vec<String>,
+    pub all: bool,
+    pub yes: bool,
     pub operands: Vec<String>,
     pub mode: ProgramMode,
 }
@@ -94,13 +298,133 @@ impl Config {
         let proc_path: PathBuf = env::var_os(config::ENV_PROC_DIR)
             .unwrap_or_else(|| OsString::from(DEFAULT_PROC_DIR))
             .into();
+        let cgroup_path: PathBuf = env::var_os(config::ENV_CGROUP_DIR)
+            .unwrap_or_else(|| OsString::from(DEFAULT_CGROUP_DIR))
+            .into();
         let sv_prog = env::var(config::ENV_SV_PROG)
             .unwrap_or_else(|_| DEFAULT_SV_PROG.to_string());
         let pstree_prog = env::var(config::ENV_PSTREE_PROG)
             .unwrap_or_else(|_| DEFAULT_PSTREE_PROG.to_string());
+        let serve_token =
+            env::var(ENV_SERVE_TOKEN).ok().filter(|token| !token.is_empty());
+        let ssh_prog = env::var(config::ENV_SSH_PROG)
+            .unwrap_or_else(|_| DEFAULT_SSH_PROG.to_string());
+        let remote_prog = env::var(config::ENV_REMOTE_PROG)
+            .unwrap_or_else(|_| DEFAULT_REMOTE_PROG.to_string());
+        let notify_prog = match args.notify {
+            true => Some(get_notify_prog()?),
+            false => None,
+        };
+
+        let file = match args.no_config {
+            true => ConfigFile::default(),
+            false => ConfigFile::load()?,
+        };
 
-        let colorize = should_colorize_output(&args.color)?;
-        let svdir = get_svdir(&args.dir, args.user)?;
+        let colorize =
+            should_colorize_output(&args.color, &file.color, |name| {
+                env::var(name).ok()
+            })?;
+        let theme = get_theme(&args.theme, &file)?;
+        let glyphs = get_glyphs(&args.glyphs, &file)?;
+        let time_format = get_time_format(
+            &args.time_style,
+            &args.time_format,
+            args.time_units,
+            &file,
+        )?;
+        let svdirs = get_svdirs(&args.dir, args.user, &file.dir)?;
+        let svdir = svdirs[0].clone();
+        let trees: Vec<(String, PathBuf)> = if args.both {
+            let user_svdir = get_user_svdir()?;
+            vec![("system".into(), svdir.clone()), ("user".into(), user_svdir)]
+        } else if svdirs.len() > 1 {
+            // multiple directories are labeled by their path
+            svdirs
+                .into_iter()
+                .map(|dir| (dir.display().to_string(), dir))
+                .collect()
+        } else {
+            vec![]
+        };
+        let backend = match args.backend.as_deref() {
+            None | Some("auto") => None,
+            Some(name) => Some(backend::parse(name)?),
+        };
+        // `-o <format>` (from before `-F`) still works, no column is named
+        // after a format
+        let (format_arg, columns_arg) = match (&args.format, &args.columns) {
+            (None, Some(s)) if FORMATS.contains(&s.as_str()) => {
+                (Some(s.clone()), None)
+            }
+            (format, columns) => (format.clone(), columns.as_ref()),
+        };
+        let format = get_output_format(
+            &format_arg,
+            &args.format_string,
+            &args.template,
+            args.null,
+        )?;
+        let null = args.null;
+        let columns_arg = columns_arg.or(file.columns.as_ref());
+        let mut columns = match (&format, columns_arg) {
+            (OutputFormat::Custom(tokens), _) => {
+                formatting::format_string_columns(tokens)
+            }
+            (_, Some(s)) => Column::parse_list(s)?,
+            (_, None) if args.both => {
+                // show where each service came from after the status char
+                let mut columns = column::DEFAULT_COLUMNS.to_vec();
+                columns.insert(1, Column::Origin);
+                columns
+            }
+            (_, None) => column::DEFAULT_COLUMNS.to_vec(),
+        };
+        if args.check && !columns.contains(&Column::Check) {
+            columns.push(Column::Check);
+        }
+        if args.user_names && !columns.contains(&Column::User) {
+            columns.push(Column::User);
+        }
+        // show which machine each service is on after the status char
+        if args.hosts.len() > 1
+            && columns_arg.is_none()
+            && !columns.contains(&Column::Host)
+        {
+            columns.insert(1, Column::Host);
+        }
+        let user_names = args.user_names;
+        let full_command = args.full_command;
+        let fd_threshold = get_fd_threshold(args.fd_threshold)?;
+        let children = args.children;
+        let sort = args.sort.as_deref().map(Column::parse).transpose()?;
+        let reverse = args.reverse;
+        let group_by = match args.group_by.as_deref() {
+            Some(s) => Some(GroupBy::parse(s)?),
+            // each directory given with `-d` gets its own section
+            None if trees.len() > 1 && !args.both => Some(GroupBy::Origin),
+            None => None,
+        };
+        let summary = args.summary;
+        let exit_code = args.exit_code;
+        let quiet = args.quiet;
+        let no_header = args.no_header;
+        let wide = args.wide;
+        let term_width = utils::terminal_width();
+        let watch = get_watch_interval(&args.watch)?;
+        let timeout = get_timeout(args.timeout)?;
+        let jobs = get_jobs(args.jobs, &file)?;
+        let restart_history = RestartHistory::default_path();
+        let pager = get_pager(args.no_pager || watch.is_some());
+        let events = args.events;
+        let hooks = file.hooks.iter().chain(&args.hooks).cloned().collect();
+        let dry_run = args.dry_run;
+        let states = args
+            .state
+            .iter()
+            .flat_map(|s| s.split(','))
+            .map(ServiceState::parse)
+            .collect::<Result<Vec<_>, _>>()?;
         let verbose = args.verbose;
 
         // let arguments after `vsv status` work as well.
@@ -115,7 +439,65 @@ impl Config {
             }
         };
 
+        let textfile = match &args.command {
+            Some(Commands::Metrics { textfile, filter: _ }) => textfile.clone(),
+            _ => None,
+        };
+        let critical_down = match &args.command {
+            Some(Commands::CheckPlugin {
+                critical_down: Some(names), ..
+            }) => names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect(),
+            _ => vec![],
+        };
+        let complete_services = matches!(
+            &args.command,
+            Some(Commands::Completions { services: true, .. })
+        );
+        let (log_lines, log_follow) = match &args.command {
+            Some(Commands::Log { lines, follow, .. }) => {
+                (lines.or(file.log_lines).unwrap_or(DEFAULT_LOG_LINES), *follow)
+            }
+            _ => (0, false),
+        };
+        let (show_env, show_limits, redact) = match &args.command {
+            Some(Commands::Show { env, limits, redact, .. }) => {
+                (*env, *limits, *redact)
+            }
+            _ => (false, false, false),
+        };
+        let listen = match &args.command {
+            Some(
+                Commands::Exporter { listen, .. }
+                | Commands::Serve { listen, .. },
+            ) => listen.clone(),
+            _ => None,
+        };
+        let link_source = match &args.command {
+            Some(
+                Commands::Enable { link, source, .. }
+                | Commands::Disable { link, source, .. },
+            ) if *link || source.is_some() => Some(
+                source.clone().unwrap_or_else(|| DEFAULT_SOURCE_DIR.into()),
+            ),
+            Some(Commands::Avail { source, .. }) => Some(
+                source.clone().unwrap_or_else(|| DEFAULT_SOURCE_DIR.into()),
+            ),
+            _ => None,
+        };
+        let control_args =
+            args.command.as_ref().and_then(Commands::control_args);
+        let all = control_args.is_some_and(|c| c.all);
+        let yes = control_args.is_some_and(|c| c.yes);
+
         // figure out subcommand to run
+        let control = |cmd, args: &ControlArgs| {
+            (ProgramMode::Control(cmd), args.services.to_vec())
+        };
         let (mode, operands) = match &args.command {
             // `vsv` (no subcommand)
             None => {
@@ -127,13 +509,68 @@ impl Config {
                 (ProgramMode::Status, operands.to_vec())
             }
             // `vsv enable ...`
-            Some(Commands::Enable { services }) => {
+            Some(Commands::Enable { services, .. }) => {
                 (ProgramMode::Enable, services.to_vec())
             }
             // `vsv disable ...`
-            Some(Commands::Disable { services }) => {
+            Some(Commands::Disable { services, .. }) => {
                 (ProgramMode::Disable, services.to_vec())
             }
+            // `vsv <up|down|restart|...> ...`
+            Some(Commands::Up(c)) => control(ControlCommand::Up, c),
+            Some(Commands::Down(c)) => control(ControlCommand::Down, c),
+            Some(Commands::Restart(c)) => control(ControlCommand::Restart, c),
+            Some(Commands::Once(c)) => control(ControlCommand::Once, c),
+            Some(Commands::Hup(c)) => control(ControlCommand::Hup, c),
+            Some(Commands::Term(c)) => control(ControlCommand::Term, c),
+            Some(Commands::Kill(c)) => control(ControlCommand::Kill, c),
+            Some(Commands::Alarm(c)) => control(ControlCommand::Alarm, c),
+            Some(Commands::Interrupt(c)) => {
+                control(ControlCommand::Interrupt, c)
+            }
+            Some(Commands::Quit(c)) => control(ControlCommand::Quit, c),
+            Some(Commands::Usr1(c)) => control(ControlCommand::Usr1, c),
+            Some(Commands::Usr2(c)) => control(ControlCommand::Usr2, c),
+            // `vsv metrics ...`
+            Some(Commands::Metrics { textfile: _, filter: operands }) => {
+                (ProgramMode::Metrics, operands.to_vec())
+            }
+            // `vsv check-plugin ...`
+            Some(Commands::CheckPlugin { filter: operands, .. }) => {
+                (ProgramMode::CheckPlugin, operands.to_vec())
+            }
+            // `vsv exporter ...`
+            Some(Commands::Exporter { listen: _, filter: operands }) => {
+                (ProgramMode::Exporter, operands.to_vec())
+            }
+            // `vsv serve ...`
+            Some(Commands::Serve { listen: _, filter: operands }) => {
+                (ProgramMode::Serve, operands.to_vec())
+            }
+            // `vsv avail ...`
+            Some(Commands::Avail { filter: operands, .. }) => {
+                (ProgramMode::Avail, operands.to_vec())
+            }
+            // `vsv log <service>`
+            Some(Commands::Log { service, .. }) => {
+                (ProgramMode::Log, service.to_vec())
+            }
+            // `vsv show <service>`
+            Some(Commands::Show { service, .. }) => {
+                (ProgramMode::Show, service.to_vec())
+            }
+            // `vsv pick [action]`
+            Some(Commands::Pick { action }) => {
+                (ProgramMode::Pick, action.iter().cloned().collect())
+            }
+            // `vsv tui ...`
+            Some(Commands::Tui { filter: operands }) => {
+                (ProgramMode::Tui, operands.to_vec())
+            }
+            // `vsv completions <shell>`
+            Some(Commands::Completions { shell, .. }) => {
+                (ProgramMode::Completions, shell.iter().cloned().collect())
+            }
             // `vsv <anything> ...`
             Some(Commands::External(args)) => {
                 // -t or -l will put the program into status mode
@@ -146,21 +583,207 @@ impl Config {
             }
         };
 
-        let o = Self {
+        // the config file's defaults (after `-t` and `-l` picked the mode)
+        tree |= file.tree.unwrap_or(false);
+        log |= file.log.unwrap_or(false);
+
+        // the first operand is a filter when showing status
+        let filter = match mode {
+            ProgramMode::Status
+            | ProgramMode::Metrics
+            | ProgramMode::CheckPlugin
+            | ProgramMode::Exporter
+            | ProgramMode::Serve
+            | ProgramMode::Avail
+            | ProgramMode::Tui => operands.first(),
+            _ => None,
+        };
+        let name_filters = filter
+            .into_iter()
+            .chain(&args.matches)
+            .map(|s| Regex::new(s))
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to parse service name filter")?;
+        let exclude_filters = file
+            .exclude
+            .iter()
+            .chain(&args.exclude)
+            .map(|s| parse_exclude(s))
+            .collect::<Result<Vec<_>>>()
+            .context("failed to parse service exclusion")?;
+
+        let hosts = args.hosts.to_vec();
+        let remote_args = match hosts.is_empty() {
+            false => {
+                ensure!(
+                    matches!(mode, ProgramMode::Status),
+                    "--host only works when showing status"
+                );
+                ensure!(
+                    !tree && !children,
+                    "--tree and --children can't be used with --host"
+                );
+                get_remote_args(args, &columns, log, filter)
+            }
+            true => vec![],
+        };
+
+        let opts = Options {
             proc_path,
-            sv_prog,
+            cgroup_path,
+            backend,
+            columns,
+            full_command,
+            user_names,
+            fd_threshold,
+            tree,
             pstree_prog,
+            timeout,
+        };
+
+        let o = Self {
+            opts,
+            sv_prog,
+            notify_prog,
+            serve_token,
+            ssh_prog,
+            remote_prog,
             colorize,
+            theme,
+            glyphs,
+            time_format,
             svdir,
-            tree,
+            children,
             log,
+            format,
+            sort,
+            group_by,
+            reverse,
+            states,
+            name_filters,
+            exclude_filters,
+            summary,
+            exit_code,
+            quiet,
+            no_header,
+            wide,
+            pager,
+            watch,
+            events,
+            hooks,
+            dry_run,
+            jobs,
+            restart_history,
+            term_width,
+            null,
             verbose,
+            textfile,
+            critical_down,
+            complete_services,
+            listen,
+            link_source,
+            log_lines,
+            log_follow,
+            show_env,
+            show_limits,
+            redact,
+            trees,
+            hosts,
+            remote_args,
+            all,
+            yes,
             operands,
             mode,
         };
 
         Ok(o)
     }
+
+    /**
+     * Get the backend used for a service directory: the one given with
+     * `--backend` or the one detected for the directory.
+     */
+    pub fn backend_for(&self, svdir: &Path) -> &'static dyn Supervisor {
+        self.opts.backend_for(svdir)
+    }
+}
+
+/**
+ * Get the arguments for the `vsv` run on another machine for `--host`: every
+ * option that changes what's gathered (or which services are) is passed
+ * along, the services are printed with `-F snapshot` and rendered here.
+ */
+fn get_remote_args(
+    args: &Args,
+    columns: &[Column],
+    log: bool,
+    filter: Option<&String>,
+) -> Vec<String> {
+    let mut remote: Vec<String> = vec!["--color".into(), "no".into()];
+    let mut opt = |name: &str, value: &str| {
+        remote.push(name.into());
+        remote.push(value.into());
+    };
+
+    opt("--format", "snapshot");
+    // the remote vsv doesn't know which host it is to us
+    let mut columns: Vec<&str> = columns
+        .iter()
+        .filter(|column| **column != Column::Host)
+        .map(Column::key)
+        .collect();
+    if columns.is_empty() {
+        columns.push(Column::Name.key());
+    }
+    opt("--columns", &columns.join(","));
+    for dir in &args.dir {
+        opt("--dir", &dir.to_string_lossy());
+    }
+    for re in &args.matches {
+        opt("--match", re);
+    }
+    for pattern in &args.exclude {
+        opt("--exclude", pattern);
+    }
+    for state in &args.state {
+        opt("--state", state);
+    }
+    if let Some(column) = &args.sort {
+        opt("--sort", column);
+    }
+    if let Some(name) = &args.backend {
+        opt("--backend", name);
+    }
+    if let Some(percent) = args.fd_threshold {
+        opt("--fd-threshold", &percent.to_string());
+    }
+    if let Some(secs) = args.timeout {
+        opt("--timeout", &secs.to_string());
+    }
+
+    let flags = [
+        (args.user, "--user"),
+        (args.both, "--both"),
+        (args.reverse, "--reverse"),
+        (args.quiet, "--quiet"),
+        (args.user_names, "--user-names"),
+        (args.full_command, "--full-command"),
+    ];
+    for (_, flag) in flags.iter().filter(|(given, _)| *given) {
+        remote.push(flag.to_string());
+    }
+
+    remote.push("status".into());
+    if log {
+        remote.push("--log".into());
+    }
+    // `--` keeps a filter like "-foo" from being taken as an option
+    if let Some(filter) = filter {
+        remote.push("--".into());
+        remote.push(filter.into());
+    }
+
+    remote
 }
 
 /**
@@ -169,23 +792,47 @@ impl Config {
  * Coloring output goes in order from highest priority to lowest priority
  * -highest priority (first in this list) wins:
  *
- * 1. CLI option (`-c`) given.
- * 2. env `NO_COLOR` given.
- * 3. stdout is a tty.
+ * 1. CLI option (`-c always` or `-c never`) given.
+ * 2. env `CLICOLOR_FORCE` set (to anything other than `0`).
+ * 3. env `NO_COLOR` set (to anything other than an empty string).
+ * 4. `color` set in the config file.
+ * 5. env `CLICOLOR` set to `0`.
+ * 6. stdout is a tty.
  */
-fn should_colorize_output(color_arg: &Option<String>) -> Result<bool> {
+fn should_colorize_output<F: Fn(&str) -> Option<String>>(
+    color_arg: &Option<String>,
+    file_color: &Option<String>,
+    var: F,
+) -> Result<bool> {
+    let parse = |color: &Option<String>| {
+        color.as_deref().map(ColorChoice::parse).transpose()
+    };
+    let is_set =
+        |name: &str, off: &str| var(name).is_some_and(|value| value != off);
+
     // check CLI option first
-    if let Some(s) = color_arg {
-        match s.as_str() {
-            "yes" | "on" | "always" => return Ok(true),
-            "no" | "off" | "never" => return Ok(false),
-            "auto" => (), // fall through
-            _ => bail!("unknown color option: '{}'", s),
-        }
+    match parse(color_arg)? {
+        Some(ColorChoice::Always) => return Ok(true),
+        Some(ColorChoice::Never) => return Ok(false),
+        Some(ColorChoice::Auto) | None => (),
+    }
+
+    // check env vars next
+    if is_set(config::ENV_CLICOLOR_FORCE, "0") {
+        return Ok(true);
+    }
+    if is_set(config::ENV_NO_COLOR, "") {
+        return Ok(false);
     }
 
-    // check env var next
-    if env::var_os(config::ENV_NO_COLOR).is_some() {
+    // then the config file
+    match parse(file_color)? {
+        Some(ColorChoice::Always) => return Ok(true),
+        Some(ColorChoice::Never) => return Ok(false),
+        Some(ColorChoice::Auto) | None => (),
+    }
+
+    if var(config::ENV_CLICOLOR).as_deref() == Some("0") {
         return Ok(false);
     }
 
@@ -196,33 +843,340 @@ fn should_colorize_output(color_arg: &Option<String>) -> Result<bool> {
 }
 
 /**
- * Determine the `SVDIR` the user wants.
+ * Get the status chars: the built-in glyphs named with `--glyphs` (or in the
+ * config file) with any chars from the config file replacing their own.
+ */
+fn get_glyphs(
+    glyphs_arg: &Option<String>,
+    file: &ConfigFile,
+) -> Result<Glyphs> {
+    let name =
+        glyphs_arg.as_deref().or(file.glyphs.as_deref()).unwrap_or("unicode");
+
+    let mut glyphs = Glyphs::builtin(name)?;
+    for (key, glyph) in &file.glyph_overrides {
+        glyphs.set(key, glyph)?;
+    }
+
+    Ok(glyphs)
+}
+
+/**
+ * Get how to show times from `--time-style`, `--time-format` and
+ * `--time-units` (or the config file).  The style defaults to `absolute` if a
+ * format is given and the number of units defaults to that of the style.
+ */
+fn get_time_format(
+    style_arg: &Option<String>,
+    format_arg: &Option<String>,
+    units_arg: Option<usize>,
+    file: &ConfigFile,
+) -> Result<TimeFormat> {
+    let format = format_arg.clone().or_else(|| file.time_format.clone());
+    if let Some(fmt) = &format {
+        ensure!(!fmt.contains('\0'), "time format can't contain NUL bytes");
+    }
+
+    let style = match style_arg.as_deref().or(file.time_style.as_deref()) {
+        Some(name) => TimeStyle::parse(name)?,
+        None if format.is_some() => TimeStyle::Absolute,
+        None => TimeStyle::default(),
+    };
+
+    let units = units_arg.or(file.time_units).unwrap_or(style.default_units());
+    ensure!(units >= 1, "time units must be at least 1: {}", units);
+
+    Ok(TimeFormat { style, units, format })
+}
+
+/**
+ * Get the theme: the built-in theme named with `--theme` (or in the config
+ * file) with any styles from the config file replacing its own.
+ */
+fn get_theme(theme_arg: &Option<String>, file: &ConfigFile) -> Result<Theme> {
+    let name =
+        theme_arg.as_deref().or(file.theme.as_deref()).unwrap_or("default");
+
+    let mut theme = Theme::builtin(name)?;
+    for (key, style) in &file.theme_styles {
+        theme.set(key, *style)?;
+    }
+
+    Ok(theme)
+}
+
+/**
+ * Determine the interval to redraw status output at for `--watch`, defaulting
+ * to `DEFAULT_WATCH_INTERVAL` seconds if no interval is given.
+ */
+fn get_watch_interval(
+    watch_arg: &Option<Option<f64>>,
+) -> Result<Option<Duration>> {
+    let secs = match watch_arg {
+        None => return Ok(None),
+        Some(secs) => secs.unwrap_or(DEFAULT_WATCH_INTERVAL),
+    };
+
+    if !secs.is_finite() || secs <= 0.0 {
+        bail!("watch interval must be a positive number of seconds: {}", secs);
+    }
+
+    Ok(Some(Duration::from_secs_f64(secs)))
+}
+
+/**
+ * Determine the program used to send desktop notifications for `--notify`
+ * (env `NOTIFY_PROG`, defaulting to `DEFAULT_NOTIFY_PROG`).  It is an error
+ * to not be running under a desktop session.
+ */
+fn get_notify_prog() -> Result<String> {
+    let desktop = ["DISPLAY", "WAYLAND_DISPLAY", "DBUS_SESSION_BUS_ADDRESS"]
+        .iter()
+        .any(|var| env::var_os(var).is_some_and(|s| !s.is_empty()));
+
+    ensure!(desktop, "--notify requires a desktop session (no $DISPLAY)");
+
+    Ok(env::var(ENV_NOTIFY_PROG)
+        .unwrap_or_else(|_| DEFAULT_NOTIFY_PROG.to_string()))
+}
+
+/**
+ * Determine how long to wait for external programs and services, defaulting
+ * to `DEFAULT_TIMEOUT` seconds.  A timeout of `0` means wait forever.
+ */
+fn get_timeout(timeout_arg: Option<f64>) -> Result<Option<Duration>> {
+    let secs = timeout_arg.unwrap_or(DEFAULT_TIMEOUT);
+
+    if !secs.is_finite() || secs < 0.0 {
+        bail!("timeout must be a non-negative number of seconds: {}", secs);
+    }
+
+    if secs == 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some(Duration::from_secs_f64(secs)))
+}
+
+/**
+ * Determine how many services to read at once, defaulting to the number of
+ * CPUs (or `MIN_DEFAULT_JOBS`, whichever is more).
+ */
+fn get_jobs(jobs_arg: Option<usize>, file: &ConfigFile) -> Result<usize> {
+    let jobs = match jobs_arg.or(file.jobs) {
+        Some(jobs) => jobs,
+        None => std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .max(MIN_DEFAULT_JOBS),
+    };
+    ensure!(jobs >= 1, "jobs must be at least 1: {}", jobs);
+
+    Ok(jobs)
+}
+
+/**
+ * Determine the percent of a process's open files limit at which its fd count
+ * is shown in red, defaulting to `DEFAULT_FD_THRESHOLD`.
+ */
+fn get_fd_threshold(threshold_arg: Option<f64>) -> Result<f64> {
+    let percent = threshold_arg.unwrap_or(DEFAULT_FD_THRESHOLD);
+
+    if !percent.is_finite() || percent <= 0.0 || percent > 100.0 {
+        bail!("fd threshold must be a percent between 0 and 100: {}", percent);
+    }
+
+    Ok(percent)
+}
+
+/**
+ * Determine the pager (if any) output should be piped through.
+ *
+ * No pager is used if `--no-pager` is given or stdout isn't a tty, otherwise
+ * env `PAGER` is used (defaulting to `DEFAULT_PAGER`).  A `PAGER` of `cat` or
+ * an empty string disables paging.
+ */
+fn get_pager(no_pager_arg: bool) -> Option<String> {
+    if no_pager_arg || !utils::isatty(1) {
+        return None;
+    }
+
+    let pager = env::var(config::ENV_PAGER)
+        .unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+
+    match pager.trim() {
+        "" | "cat" => None,
+        _ => Some(pager),
+    }
+}
+
+/**
+ * Determine the `SVDIR`(s) the user wants.
  *
  * Check svdir in this order:
  *
- * 1. CLI option (`-d`) given
- * 2. CLI option (`-u`) given
+ * 1. CLI option (`-d`) given, possibly multiple times
+ * 2. CLI option (`-u`) given (see `get_user_svdir`)
  * 3. env `SVDIR` given
- * 4. use `DEFAULT_SVDIR` (`"/var/service"`)
+ * 4. `dir` set in the config file
+ * 5. use `DEFAULT_SVDIR` (`"/var/service"`)
+ *
+ * Both `-d` and `SVDIR` can be a colon-separated list of directories (like
+ * `PATH`).
  */
-fn get_svdir(dir_arg: &Option<PathBuf>, user_arg: bool) -> Result<PathBuf> {
+fn get_svdirs(
+    dir_arg: &[PathBuf],
+    user_arg: bool,
+    file_dirs: &Option<Vec<PathBuf>>,
+) -> Result<Vec<PathBuf>> {
     // `-d <dir>`
-    if let Some(dir) = dir_arg {
-        return Ok(dir.to_path_buf());
+    if !dir_arg.is_empty() {
+        return Ok(dir_arg
+            .iter()
+            .flat_map(|dir| split_dirs(dir.as_os_str()))
+            .collect());
     }
 
     // `-u`
     if user_arg {
-        let home_dir = dirs::home_dir()
-            .context("failed to determine users home directory")?;
-        let buf = home_dir.join(DEFAULT_USER_DIR);
-        return Ok(buf);
+        return Ok(vec![get_user_svdir()?]);
+    }
+
+    // env, config file or default
+    let svdir = match (env::var_os(config::ENV_SVDIR), file_dirs) {
+        (Some(svdir), _) => svdir,
+        (None, Some(dirs)) if !dirs.is_empty() => {
+            return Ok(dirs
+                .iter()
+                .flat_map(|dir| split_dirs(dir.as_os_str()))
+                .collect());
+        }
+        (None, _) => OsString::from(config::DEFAULT_SVDIR),
+    };
+
+    Ok(split_dirs(&svdir))
+}
+
+/// Split a colon-separated list of directories, ignoring empty entries.
+fn split_dirs(s: &OsStr) -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = s
+        .as_bytes()
+        .split(|b| *b == b':')
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(OsStr::from_bytes(dir)))
+        .collect();
+
+    // an empty value (like `-d ''`) is kept as-is
+    if dirs.is_empty() {
+        return vec![PathBuf::from(s)];
+    }
+
+    dirs
+}
+
+/**
+ * Determine the per-user `SVDIR`.
+ *
+ * `~/runit/service` (`DEFAULT_USER_DIR`) is used if it exists, otherwise
+ * `$XDG_CONFIG_HOME/service` (`~/.config/service` if unset) is used if it
+ * exists, falling back to `~/runit/service`.
+ */
+fn get_user_svdir() -> Result<PathBuf> {
+    let home_dir =
+        dirs::home_dir().context("failed to determine users home directory")?;
+    let default = home_dir.join(DEFAULT_USER_DIR);
+
+    if default.is_dir() {
+        return Ok(default);
     }
 
-    // env or default
-    let svdir = env::var_os(config::ENV_SVDIR)
-        .unwrap_or_else(|| OsString::from(config::DEFAULT_SVDIR));
-    let buf = PathBuf::from(&svdir);
+    let config_dir =
+        xdg_config_home().unwrap_or_else(|| home_dir.join(".config"));
+    let xdg = config_dir.join(DEFAULT_XDG_USER_DIR);
+
+    if xdg.is_dir() {
+        return Ok(xdg);
+    }
+
+    Ok(default)
+}
+
+/// Get `$XDG_CONFIG_HOME`, defaulting to `~/.config` if it's unset.
+pub fn xdg_config_home() -> Option<PathBuf> {
+    env::var_os(ENV_XDG_CONFIG_HOME)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+}
+
+/**
+ * Determine the output format the user wants for `vsv status`.
+ *
+ * Defaults to `OutputFormat::Table` if the `-F` CLI option is not given, or
+ * `OutputFormat::Raw` if `-0` is given (which is only valid for raw output).
+ * `--format-string` and `--template` can't be combined with either (or each
+ * other).
+ */
+fn get_output_format(
+    format_arg: &Option<String>,
+    format_string_arg: &Option<String>,
+    template_arg: &Option<PathBuf>,
+    null_arg: bool,
+) -> Result<OutputFormat> {
+    if let Some(path) = template_arg {
+        if format_arg.is_some() || format_string_arg.is_some() || null_arg {
+            bail!("--template cannot be used with -F, -0 or --format-string");
+        }
+
+        let s = fs::read_to_string(path)
+            .with_context(|| format!("failed to read template {:?}", path))?;
+        let nodes = template::parse(&s)
+            .with_context(|| format!("failed to parse template {:?}", path))?;
+
+        return Ok(OutputFormat::Template(nodes));
+    }
+
+    if let Some(s) = format_string_arg {
+        if format_arg.is_some() || null_arg {
+            bail!("--format-string cannot be used with -F or -0");
+        }
+
+        let tokens = formatting::parse_format_string(s)
+            .context("failed to parse --format-string")?;
+
+        return Ok(OutputFormat::Custom(tokens));
+    }
+
+    let format = match format_arg.as_deref() {
+        None if null_arg => OutputFormat::Raw,
+        None | Some("table") => OutputFormat::Table,
+        Some("json") => OutputFormat::Json,
+        Some("yaml") => OutputFormat::Yaml,
+        Some("csv") => OutputFormat::Csv,
+        Some("markdown" | "md") => OutputFormat::Markdown,
+        Some("raw") => OutputFormat::Raw,
+        Some("snapshot") => OutputFormat::Snapshot,
+        Some(s) => bail!("unknown output format: '{}'", s),
+    };
+
+    if null_arg && format != OutputFormat::Raw {
+        bail!("-0 can only be used with the raw output format");
+    }
+
+    Ok(format)
+}
+
+/**
+ * Parse an exclusion pattern.
+ *
+ * Patterns surrounded by slashes (like `/^agetty-/`) are treated as regular
+ * expressions, anything else is treated as a shell-style glob that must match
+ * the entire service name (like `agetty-tty*`).
+ */
+fn parse_exclude(s: &str) -> Result<Regex> {
+    let re = match s.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+        Some(re) => Regex::new(re)?,
+        None => Regex::from_glob(s)?,
+    };
 
-    Ok(buf)
+    Ok(re)
 }
@@ -0,0 +1,544 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Defaults loaded from the config file.
+ *
+ * `/etc/vsv/config.toml` is read first, then `$XDG_CONFIG_HOME/vsv/config.toml`
+ * (`~/.config/vsv/config.toml` by default), with every setting in the
+ * user's file replacing the same setting from the system-wide one.  Either
+ * file can be missing.  For example:
+ *
+ * ```toml
+ * dir = "/var/service"      # or a list, like `-d` given multiple times
+ * color = "auto"            # like `-c`
+ * columns = ["name", "state", "pid", "uptime"]  # or "name,state,..."
+ * exclude = ["agetty-*"]    # added to any `-x` given
+ * tree = false              # like `-t`
+ * log = false               # like `-l`
+ * log_lines = 10            # like `vsv log -n`
+ * time_style = "compact"    # like `--time-style`
+ * time_format = "%H:%M"     # like `--time-format`
+ * time_units = 2            # like `--time-units`
+ * jobs = 32                 # like `-j`
+ * hooks = ["vsv-hook"]      # run before any `--hook` given
+ * glyphs = "ascii"         # like `--glyphs`, or a table:
+ * theme = "high-contrast"  # like `--theme`, or a table:
+ *
+ * # the `high-contrast` theme with some of its styles replaced
+ * [theme]
+ * name = "high-contrast"
+ * bad = "bold red"
+ * states.run = "bold green"
+ * columns.pid = "cyan"
+ *
+ * # the `unicode` status chars with some replaced
+ * [glyphs]
+ * name = "unicode"
+ * run = "●"
+ *
+ * # `vsv web` runs `vsv status 'nginx|php-fpm.*'`
+ * [aliases]
+ * web = "status 'nginx|php-fpm.*'"
+ * ```
+ */
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context, Result};
+
+use yansi::Style;
+
+use crate::config;
+use crate::glyphs::Glyphs;
+use crate::output::Value;
+use crate::theme::{self, Theme};
+use crate::time_format::TimeStyle;
+use crate::toml;
+
+/// The system-wide config file.
+pub const SYSTEM_CONFIG_FILE: &str = "/etc/vsv/config.toml";
+
+/// The per-user config file (in `$XDG_CONFIG_HOME`).
+pub const USER_CONFIG_FILE: &str = "vsv/config.toml";
+
+/// Settings from the config files, `None` (or empty) when not set.
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigFile {
+    pub dir: Option<Vec<PathBuf>>,
+    pub color: Option<String>,
+    pub columns: Option<String>,
+    pub exclude: Vec<String>,
+    pub tree: Option<bool>,
+    pub log: Option<bool>,
+    pub log_lines: Option<usize>,
+    pub time_style: Option<String>,
+    pub time_format: Option<String>,
+    pub time_units: Option<usize>,
+    pub jobs: Option<usize>,
+    pub hooks: Vec<String>,
+    pub glyphs: Option<String>,
+
+    /// Status chars replacing those of the glyph set, by state.
+    pub glyph_overrides: Vec<(String, String)>,
+
+    pub theme: Option<String>,
+
+    /// Styles replacing those of the theme, by key (like `states.run`).
+    pub theme_styles: Vec<(String, Style)>,
+
+    /// Subcommand aliases and the arguments they expand to.
+    pub aliases: Vec<(String, Vec<String>)>,
+}
+
+impl ConfigFile {
+    /// Load the system-wide config file and then the user's (if they exist).
+    pub fn load() -> Result<Self> {
+        let mut file = Self::default();
+
+        let user =
+            config::xdg_config_home().map(|dir| dir.join(USER_CONFIG_FILE));
+        for path in
+            [Some(PathBuf::from(SYSTEM_CONFIG_FILE)), user].iter().flatten()
+        {
+            file.load_file(path)?;
+        }
+
+        Ok(file)
+    }
+
+    /// Apply the settings in the file at `path`, doing nothing if it's missing.
+    fn load_file(&mut self, path: &Path) -> Result<()> {
+        let s = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed to read config file {:?}", path)
+                })
+            }
+        };
+
+        self.apply(&s)
+            .with_context(|| format!("invalid config file {:?}", path))
+    }
+
+    /// Apply the settings in a TOML document, replacing any already set.
+    fn apply(&mut self, s: &str) -> Result<()> {
+        let value = toml::from_str(s)?;
+        let settings = match &value {
+            Value::Object(settings) => settings,
+            _ => unreachable!("documents are tables"),
+        };
+
+        for (key, value) in settings {
+            match key.as_str() {
+                "dir" => {
+                    let dirs = strings(key, value)?;
+                    self.dir =
+                        Some(dirs.into_iter().map(PathBuf::from).collect());
+                }
+                "color" => self.color = Some(string(key, value)?),
+                "columns" => {
+                    self.columns = Some(strings(key, value)?.join(","))
+                }
+                "exclude" => self.exclude = strings(key, value)?,
+                "tree" => self.tree = Some(boolean(key, value)?),
+                "log" => self.log = Some(boolean(key, value)?),
+                "log_lines" => {
+                    let n =
+                        value.as_i64().and_then(|n| usize::try_from(n).ok());
+                    match n {
+                        Some(n) => self.log_lines = Some(n),
+                        None => bail!("'{}' must be a positive integer", key),
+                    }
+                }
+                "time_style" => {
+                    let s = string(key, value)?;
+                    TimeStyle::parse(&s)?;
+                    self.time_style = Some(s);
+                }
+                "time_format" => {
+                    let s = string(key, value)?;
+                    ensure!(!s.contains('\0'), "'{}' can't contain NUL", key);
+                    self.time_format = Some(s);
+                }
+                "time_units" => self.time_units = Some(positive(key, value)?),
+                "jobs" => self.jobs = Some(positive(key, value)?),
+                "hooks" => self.hooks = strings(key, value)?,
+                "glyphs" => self.apply_glyphs(key, value)?,
+                "theme" => self.apply_theme(key, value)?,
+                "aliases" => self.apply_aliases(key, value)?,
+                _ => bail!("unknown setting '{}'", key),
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Set the glyphs from their name or a `[glyphs]` table of their name and
+     * the chars replacing their own, like `run = "●"`.
+     */
+    fn apply_glyphs(&mut self, key: &str, value: &Value) -> Result<()> {
+        let table = match value {
+            Value::Object(table) => table,
+            _ => {
+                let name = string(key, value)?;
+                Glyphs::builtin(&name)?;
+                self.glyphs = Some(name);
+                return Ok(());
+            }
+        };
+
+        for (key, value) in table {
+            let s = string(&format!("glyphs.{}", key), value)?;
+            if key == "name" {
+                Glyphs::builtin(&s)?;
+                self.glyphs = Some(s);
+                continue;
+            }
+
+            Glyphs::default().set(key, &s)?;
+            self.glyph_overrides.retain(|(k, _)| k != key);
+            self.glyph_overrides.push((key.clone(), s));
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Set the theme from its name or a `[theme]` table of its name and the
+     * styles replacing its own, like `bad = "bold red"` or `states.run =
+     * "green"`.
+     */
+    fn apply_theme(&mut self, key: &str, value: &Value) -> Result<()> {
+        let table = match value {
+            Value::Object(table) => table,
+            _ => {
+                let name = string(key, value)?;
+                Theme::builtin(&name)?;
+                self.theme = Some(name);
+                return Ok(());
+            }
+        };
+
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("name", value) => {
+                    let name = string("theme.name", value)?;
+                    Theme::builtin(&name)?;
+                    self.theme = Some(name);
+                }
+                (group @ ("states" | "columns"), Value::Object(styles)) => {
+                    for (name, value) in styles {
+                        let key = format!("{}.{}", group, name);
+                        self.add_theme_style(key, value)?;
+                    }
+                }
+                (key, value) => self.add_theme_style(key.to_string(), value)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add a style replacing the theme's (checking that the key exists).
+    fn add_theme_style(&mut self, key: String, value: &Value) -> Result<()> {
+        let full_key = format!("theme.{}", key);
+        let style = theme::parse_style(&string(&full_key, value)?)
+            .with_context(|| format!("invalid style '{}'", full_key))?;
+        Theme::default().set(&key, style)?;
+
+        self.theme_styles.retain(|(k, _)| *k != key);
+        self.theme_styles.push((key, style));
+
+        Ok(())
+    }
+
+    /**
+     * Add the aliases in an `[aliases]` table, each a string (split like a
+     * shell would) or a list of arguments, replacing any with the same name.
+     */
+    fn apply_aliases(&mut self, key: &str, value: &Value) -> Result<()> {
+        let aliases = match value {
+            Value::Object(aliases) => aliases,
+            _ => bail!("'{}' must be a table", key),
+        };
+
+        for (name, value) in aliases {
+            let words = match value.as_str() {
+                Some(s) => split_words(s),
+                None => strings(name, value),
+            }
+            .with_context(|| format!("invalid alias '{}'", name))?;
+            ensure!(!words.is_empty(), "alias '{}' is empty", name);
+
+            self.aliases.retain(|(n, _)| n != name);
+            self.aliases.push((name.clone(), words));
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Split a string into words like a shell would, keeping quoted strings (`'...'`
+ * or `"..."`) together and honoring `\` escapes (outside of single quotes).
+ */
+fn split_words(s: &str) -> Result<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' | '"' => {
+                let quote = c;
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some(c) if c == quote => break,
+                        Some('\\') if quote == '"' => match chars.next() {
+                            Some(c) => w.push(c),
+                            None => bail!("unterminated quote"),
+                        },
+                        Some(c) => w.push(c),
+                        None => bail!("unterminated quote"),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => bail!("trailing backslash"),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+/// Get a string setting.
+fn string(key: &str, value: &Value) -> Result<String> {
+    match value.as_str() {
+        Some(s) => Ok(s.to_string()),
+        None => bail!("'{}' must be a string", key),
+    }
+}
+
+/// Get a positive integer setting.
+fn positive(key: &str, value: &Value) -> Result<usize> {
+    let n =
+        value.as_i64().and_then(|n| usize::try_from(n).ok()).filter(|n| *n > 0);
+    match n {
+        Some(n) => Ok(n),
+        None => bail!("'{}' must be a positive integer", key),
+    }
+}
+
+/// Get a setting that's a string or list of strings.
+fn strings(key: &str, value: &Value) -> Result<Vec<String>> {
+    if let Some(s) = value.as_str() {
+        return Ok(vec![s.to_string()]);
+    }
+
+    value
+        .as_array()
+        .and_then(|arr| {
+            arr.iter().map(|v| v.as_str().map(String::from)).collect()
+        })
+        .with_context(|| {
+            format!("'{}' must be a string or list of strings", key)
+        })
+}
+
+/// Get a boolean setting.
+fn boolean(key: &str, value: &Value) -> Result<bool> {
+    match value.as_bool() {
+        Some(b) => Ok(b),
+        None => bail!("'{}' must be true or false", key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() -> Result<()> {
+        assert_eq!(
+            split_words("status 'nginx|php-fpm.*'  -o name,pid")?,
+            ["status", "nginx|php-fpm.*", "-o", "name,pid"]
+        );
+        assert_eq!(
+            split_words(r#"a"b c"d \'e\\ 'f\g' "\"" """#)?,
+            ["ab cd", "'e\\", "f\\g", "\"", ""]
+        );
+        assert!(split_words(" ")?.is_empty(), "no words");
+        assert!(split_words("'unterminated").is_err());
+        assert!(split_words("trailing\\").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aliases() -> Result<()> {
+        let mut file = ConfigFile::default();
+        file.apply(
+            "[aliases]\nweb = \"status 'nginx|php'\"\ndb = ['-o', 'name']",
+        )?;
+        file.apply("[aliases]\nweb = 'status nginx'")?;
+
+        let words = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            file.aliases,
+            [
+                ("db".to_string(), words(&["-o", "name"])),
+                ("web".to_string(), words(&["status", "nginx"])),
+            ],
+            "later files replace aliases"
+        );
+
+        for bad in [
+            "aliases = 'web'",
+            "[aliases]\nweb = ''",
+            "[aliases]\nweb = 1",
+            "[aliases]\nweb = '\"unterminated'",
+        ] {
+            assert!(ConfigFile::default().apply(bad).is_err(), "{}", bad);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_glyphs() -> Result<()> {
+        let mut file = ConfigFile::default();
+        file.apply("glyphs = 'ascii'")?;
+        file.apply("[glyphs]\nrun = '●'\nflapping = '@'\n")?;
+        file.apply("[glyphs]\nrun = 'o'\n")?;
+
+        assert_eq!(file.glyphs.as_deref(), Some("ascii"));
+        assert_eq!(
+            file.glyph_overrides,
+            [("flapping".into(), "@".into()), ("run".into(), "o".into())]
+        );
+
+        for bad in [
+            "glyphs = 'emoji'",
+            "[glyphs]\nname = 'emoji'",
+            "[glyphs]\nsleeping = 'z'",
+            "[glyphs]\nrun = 'ok'",
+            "[glyphs]\nrun = 1",
+        ] {
+            assert!(ConfigFile::default().apply(bad).is_err(), "{}", bad);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_theme() -> Result<()> {
+        let mut file = ConfigFile::default();
+        file.apply("theme = 'solarized'")?;
+        assert_eq!(file.theme.as_deref(), Some("solarized"));
+
+        file.apply(
+            "[theme]\nbad = 'bold red'\nstates.run = 'blue'\ncolumns.pid = ''",
+        )?;
+        assert_eq!(file.theme.as_deref(), Some("solarized"), "kept");
+        assert_eq!(
+            file.theme_styles,
+            [
+                ("bad".into(), Style::new(yansi::Color::Red).bold()),
+                ("states.run".into(), Style::new(yansi::Color::Blue)),
+                ("columns.pid".into(), Style::default()),
+            ]
+        );
+
+        for bad in [
+            "theme = 'neon'",
+            "theme = 1",
+            "[theme]\nname = 'neon'",
+            "[theme]\nok = 'red'",
+            "[theme]\nstates.sleeping = 'red'",
+            "[theme]\nstates = 'red'",
+            "[theme]\nbad = 'rouge'",
+        ] {
+            assert!(ConfigFile::default().apply(bad).is_err(), "{}", bad);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply() -> Result<()> {
+        let mut file = ConfigFile::default();
+        file.apply(
+            r#"
+dir = "/etc/service"
+columns = ["name", "state"]
+exclude = "agetty-*"
+tree = true
+log_lines = 20
+time_style = "compact"
+time_units = 2
+jobs = 32
+hooks = "logger vsv"
+"#,
+        )?;
+
+        assert_eq!(
+            file,
+            ConfigFile {
+                dir: Some(vec!["/etc/service".into()]),
+                color: None,
+                columns: Some("name,state".into()),
+                exclude: vec!["agetty-*".into()],
+                tree: Some(true),
+                log: None,
+                log_lines: Some(20),
+                time_style: Some("compact".into()),
+                time_format: None,
+                time_units: Some(2),
+                jobs: Some(32),
+                hooks: vec!["logger vsv".into()],
+                glyphs: None,
+                glyph_overrides: vec![],
+                theme: None,
+                theme_styles: vec![],
+                aliases: vec![],
+            }
+        );
+
+        // a later file replaces settings it has
+        file.apply("dir = ['/a', '/b']\ncolor = 'no'\n")?;
+        assert_eq!(file.dir, Some(vec!["/a".into(), "/b".into()]));
+        assert_eq!(file.color.as_deref(), Some("no"));
+        assert_eq!(file.tree, Some(true), "kept");
+
+        for bad in [
+            "colour = 'no'",
+            "tree = 'yes'",
+            "columns = [1, 2]",
+            "log_lines = -1",
+            "time_units = 0",
+            "jobs = 1.5",
+            "hooks = [true]",
+            "time_style = 'short'",
+            "dir = ",
+        ] {
+            assert!(ConfigFile::default().apply(bad).is_err(), "{}", bad);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,299 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * The error type returned by the `vsv` library API (like `ServiceDir`), so
+ * callers can tell a missing service from one they aren't allowed to read.
+ *
+ * Every fallible function in the library returns this `Result`.  Errors
+ * without a kind of their own (like a malformed `/proc` file) are
+ * `Error::Other`, only the `vsv` binary works with `anyhow::Error`s directly.
+ */
+
+use std::any::Any;
+use std::array;
+use std::error;
+use std::fmt;
+use std::io;
+use std::num;
+use std::path::PathBuf;
+use std::str;
+use std::string;
+use std::time;
+
+/// A `Result` with the `vsv` library's `Error`.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors from the `vsv` library.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The service (or service directory) doesn't exist.
+    NotFound(PathBuf),
+
+    /// No supervisor (like `runsv`) is running for the service.
+    NotSupervised(String),
+
+    /// The file can't be read by this user (runit's `supervise` directories
+    /// are only readable by root).
+    PermissionDenied(PathBuf),
+
+    /// Data (like a saved snapshot) couldn't be parsed.
+    ParseError(String),
+
+    /// The procfs directory (`/proc` or `PROC_DIR`) isn't there.
+    ProcUnavailable(PathBuf),
+
+    /// Any other error.
+    Other(anyhow::Error),
+}
+
+impl Error {
+    /**
+     * Turn an `Other` error caused by the OS failing to find or read a file
+     * into a `NotFound` or `PermissionDenied` error for the file at `path`.
+     */
+    pub(crate) fn with_path(self, path: PathBuf) -> Self {
+        match (self.io_kind(), self) {
+            (Some(io::ErrorKind::PermissionDenied), Error::Other(_)) => {
+                Error::PermissionDenied(path)
+            }
+            (Some(io::ErrorKind::NotFound), Error::Other(_)) => {
+                Error::NotFound(path)
+            }
+            (_, err) => err,
+        }
+    }
+
+    /// Convert the payload of a caught panic (see `panic::catch_unwind`).
+    pub(crate) fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let msg = match payload.downcast::<String>() {
+            Ok(msg) => *msg,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(msg) => msg.to_string(),
+                Err(_) => "unknown cause".to_string(),
+            },
+        };
+
+        Error::Other(anyhow::anyhow!("panicked: {}", msg))
+    }
+
+    /**
+     * Get the kind of the OS error that caused this error (anywhere in its
+     * chain of causes), if any.
+     */
+    pub(crate) fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            Error::NotFound(_) => Some(io::ErrorKind::NotFound),
+            Error::PermissionDenied(_) => Some(io::ErrorKind::PermissionDenied),
+            Error::Other(err) => err
+                .chain()
+                .find_map(|cause| cause.downcast_ref::<io::Error>())
+                .map(io::Error::kind),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotFound(path) => write!(f, "{:?} not found", path),
+            Error::NotSupervised(name) => {
+                write!(f, "{} is not supervised", name)
+            }
+            Error::PermissionDenied(path) => {
+                write!(f, "permission denied reading {:?}", path)
+            }
+            Error::ParseError(s) => write!(f, "parse error: {}", s),
+            Error::ProcUnavailable(path) => {
+                write!(f, "procfs not available at {:?}", path)
+            }
+            // the whole chain of causes is only shown with `{:#}` (like
+            // anyhow), otherwise they're the `source`
+            Error::Other(err) if f.alternate() => write!(f, "{:#}", err),
+            Error::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Other(err) => err.chain().nth(1),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(err: anyhow::Error) -> Self {
+        // typed errors are kept, unless they've had context added to them
+        let typed = err.chain().next().is_some_and(|outer| outer.is::<Error>());
+
+        match typed {
+            true => match err.downcast::<Error>() {
+                Ok(err) => err,
+                Err(err) => Error::Other(err),
+            },
+            false => Error::Other(err),
+        }
+    }
+}
+
+/// Implement `From` for errors that are kept as the cause of an `Other`.
+macro_rules! from_other {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl From<$t> for Error {
+                fn from(err: $t) -> Self {
+                    Error::Other(err.into())
+                }
+            }
+        )*
+    };
+}
+
+from_other!(
+    io::Error,
+    array::TryFromSliceError,
+    num::ParseFloatError,
+    num::ParseIntError,
+    num::TryFromIntError,
+    str::Utf8Error,
+    string::FromUtf8Error,
+    time::SystemTimeError,
+);
+
+/**
+ * Add context to an error (or a missing value), like `anyhow::Context` for
+ * functions returning the library's `Result`.
+ */
+pub(crate) trait Context<T> {
+    fn context<C>(self, context: C) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static;
+
+    fn with_context<C, F>(self, f: F) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C;
+}
+
+impl<T, E> Context<T> for Result<T, E>
+where
+    E: error::Error + Send + Sync + 'static,
+{
+    fn context<C>(self, context: C) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+    {
+        anyhow::Context::context(self, context).map_err(Error::Other)
+    }
+
+    fn with_context<C, F>(self, f: F) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        anyhow::Context::with_context(self, f).map_err(Error::Other)
+    }
+}
+
+impl<T> Context<T> for Option<T> {
+    fn context<C>(self, context: C) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+    {
+        anyhow::Context::context(self, context).map_err(Error::Other)
+    }
+
+    fn with_context<C, F>(self, f: F) -> Result<T>
+    where
+        C: fmt::Display + Send + Sync + 'static,
+        F: FnOnce() -> C,
+    {
+        anyhow::Context::with_context(self, f).map_err(Error::Other)
+    }
+}
+
+/**
+ * Return early with an `Error::Other`, like `anyhow::bail!` for functions
+ * returning the library's `Result`.
+ */
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err($crate::error::Error::Other(anyhow::anyhow!($($arg)*)))
+    };
+}
+
+/// Return early with an `Error::Other` if a condition isn't true, see `bail`.
+macro_rules! ensure {
+    ($cond:expr, $($arg:tt)*) => {
+        if !$cond {
+            $crate::error::bail!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use {bail, ensure};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_path() {
+        let path = PathBuf::from("/var/service/foo/supervise/pid");
+        let denied = || {
+            Err::<(), _>(io::Error::from(io::ErrorKind::PermissionDenied))
+                .context("failed to read pid")
+                .unwrap_err()
+        };
+
+        assert!(
+            matches!(
+                denied().with_path(path.clone()),
+                Error::PermissionDenied(p) if p == path
+            ),
+            "permission denied (with context)"
+        );
+        assert!(matches!(denied(), Error::Other(_)), "no path given");
+        assert_eq!(
+            format!("{:#}", denied()),
+            "failed to read pid: permission denied",
+            "causes shown"
+        );
+
+        let typed = Error::NotSupervised("foo".into());
+        assert!(
+            matches!(
+                typed.with_path(path),
+                Error::NotSupervised(name) if name == "foo"
+            ),
+            "typed errors are kept"
+        );
+    }
+
+    #[test]
+    fn test_from_anyhow() {
+        let typed = anyhow::Error::new(Error::NotSupervised("foo".into()));
+        assert!(
+            matches!(
+                Error::from(typed),
+                Error::NotSupervised(name) if name == "foo"
+            ),
+            "typed errors are kept"
+        );
+
+        let typed = anyhow::Error::new(Error::NotSupervised("foo".into()))
+            .context("failed to read foo");
+        assert!(
+            matches!(Error::from(typed), Error::Other(_)),
+            "context is kept"
+        );
+    }
+}
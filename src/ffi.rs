@@ -0,0 +1,148 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * A C ABI (with the `vsv-ffi` feature) for programs like status bars, see
+ * `include/vsv.h`.
+ *
+ * Services are given as a JSON array of the objects described in `snapshot`.
+ * Functions that fail set an error message for the calling thread, read with
+ * `vsv_last_error`.
+ */
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, OsStr};
+use std::os::raw::c_char;
+use std::os::unix::ffi::OsStrExt;
+use std::panic;
+use std::ptr;
+
+use anyhow::{anyhow, Result};
+
+use crate::error::Error;
+use crate::output::{json, Value};
+use crate::service_dir::ServiceDir;
+
+thread_local! {
+    /// The error message of the last failed call on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/**
+ * Get the services in `svdir` as JSON, with a panic returned as an error
+ * (unwinding into C would abort the caller).
+ */
+fn services_json(svdir: *const c_char) -> Result<CString> {
+    panic::catch_unwind(|| read_services_json(svdir))
+        .unwrap_or_else(|payload| Err(Error::from_panic(payload).into()))
+}
+
+/// Read the services in `svdir` as JSON.
+fn read_services_json(svdir: *const c_char) -> Result<CString> {
+    if svdir.is_null() {
+        return Err(anyhow!("svdir is NULL"));
+    }
+    let svdir = unsafe { CStr::from_ptr(svdir) };
+    let svdir = OsStr::from_bytes(svdir.to_bytes());
+
+    let services = ServiceDir::open(svdir)?.services()?;
+    let value = Value::Array(
+        services.iter().map(|service| service.to_value()).collect(),
+    );
+
+    // JSON strings escape NUL bytes, there can't be any
+    Ok(CString::new(json::to_string_pretty(&value))?)
+}
+
+/// Remember an error for `vsv_last_error`.
+fn set_last_error(err: anyhow::Error) {
+    let message = CString::new(format!("{:#}", err).replace('\0', " "))
+        .unwrap_or_default();
+
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/**
+ * Write the services in `svdir` as a NUL-terminated JSON string into `buf`
+ * (of `len` bytes).
+ *
+ * Returns the length of the JSON (without the NUL), if that's `len` or more
+ * nothing is written and the call should be retried with a bigger buffer.
+ * Returns -1 on error.
+ *
+ * # Safety
+ *
+ * `svdir` must be a NUL-terminated string and `buf` must be valid for writes
+ * of `len` bytes (or NULL if `len` is 0).
+ */
+#[no_mangle]
+pub unsafe extern "C" fn vsv_services_json(
+    svdir: *const c_char,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let s = match services_json(svdir) {
+        Ok(s) => s,
+        Err(err) => {
+            set_last_error(err);
+            return -1;
+        }
+    };
+
+    let bytes = s.as_bytes_with_nul();
+    if bytes.len() <= len && !buf.is_null() {
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, bytes.len());
+    }
+
+    (bytes.len() - 1) as isize
+}
+
+/**
+ * Get the services in `svdir` as a NUL-terminated JSON string, which must be
+ * freed with `vsv_string_free`.  Returns NULL on error.
+ *
+ * # Safety
+ *
+ * `svdir` must be a NUL-terminated string.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn vsv_services_json_new(
+    svdir: *const c_char,
+) -> *mut c_char {
+    match services_json(svdir) {
+        Ok(s) => s.into_raw(),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/**
+ * Free a string returned by `vsv_services_json_new`, NULL is ignored.
+ *
+ * # Safety
+ *
+ * `s` must have come from `vsv_services_json_new` and not been freed yet.
+ */
+#[no_mangle]
+pub unsafe extern "C" fn vsv_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/**
+ * Get the error message of the last failed call on this thread, NULL if
+ * there was none.  The string is valid until the next failed call.
+ */
+#[no_mangle]
+pub extern "C" fn vsv_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| match &*last.borrow() {
+        Some(message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
@@ -0,0 +1,577 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Line formatting for vsv.
+ *
+ * This contains both the table renderer (`format_status_line`) and the
+ * tokenizer + renderer for user supplied format strings (`--format-string`).
+ *
+ * Widths are measured in terminal columns (see `str_width`) rather than
+ * chars, so CJK text and emoji (2 columns each) and combining marks (0
+ * columns) line up.
+ */
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use yansi::Style;
+
+use crate::column::Column;
+use crate::error::{bail, Context, Result};
+
+/// A single token of a parsed format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// Text to print as-is.
+    Literal(String),
+
+    /// A column to print, with an optional width to pad or trim it to.
+    Column(Column, Option<usize>),
+}
+
+/**
+ * Parse a format string like `"%{name} %{state:7} %{uptime}"` into tokens.
+ *
+ * Supported syntax:
+ *
+ * - `%{column}` - the value of a column (see `-o` for valid names).
+ * - `%{column:width}` - the same, padded or trimmed to `width` columns.
+ * - `%%` - a literal `%`.
+ * - `\n`, `\t`, `\0` and `\\` - newline, tab, NUL and backslash.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::formatting::parse_format_string;
+ *
+ * # fn main() -> anyhow::Result<()> {
+ * let tokens = parse_format_string("%{name}: %{pid}")?;
+ * assert_eq!(tokens.len(), 3);
+ * # Ok(())
+ * # }
+ * ```
+ */
+pub fn parse_format_string(s: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut literal = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => match chars.next() {
+                Some('%') => literal.push('%'),
+                Some('{') => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => spec.push(c),
+                            None => {
+                                bail!("unterminated '%{{' in format string")
+                            }
+                        }
+                    }
+
+                    if !literal.is_empty() {
+                        tokens
+                            .push(Token::Literal(std::mem::take(&mut literal)));
+                    }
+                    tokens.push(parse_column_spec(&spec)?);
+                }
+                Some(c) => bail!("invalid format sequence '%{}'", c),
+                None => bail!("format string cannot end with '%'"),
+            },
+            '\\' => match chars.next() {
+                Some('n') => literal.push('\n'),
+                Some('t') => literal.push('\t'),
+                Some('0') => literal.push('\0'),
+                Some('\\') => literal.push('\\'),
+                Some(c) => bail!("invalid escape sequence '\\{}'", c),
+                None => bail!("format string cannot end with '\\'"),
+            },
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+
+    Ok(tokens)
+}
+
+/// Parse the inside of a `%{...}` sequence.
+fn parse_column_spec(spec: &str) -> Result<Token> {
+    let (name, width) = match spec.split_once(':') {
+        Some((name, width)) => {
+            let width: usize = width
+                .parse()
+                .with_context(|| format!("invalid width in '%{{{}}}'", spec))?;
+            (name, Some(width))
+        }
+        None => (spec, None),
+    };
+
+    Ok(Token::Column(Column::parse(name.trim())?, width))
+}
+
+/// Get the columns (in order, without duplicates) used by a format string.
+pub fn format_string_columns(tokens: &[Token]) -> Vec<Column> {
+    let mut columns = vec![];
+
+    for token in tokens {
+        if let Token::Column(column, _) = token {
+            if !columns.contains(column) {
+                columns.push(*column);
+            }
+        }
+    }
+
+    columns
+}
+
+/**
+ * Render a parsed format string, calling `f` to get the text and style for
+ * each column.
+ */
+pub fn render_format_string<F>(tokens: &[Token], f: F) -> String
+where
+    F: Fn(Column) -> (String, Style),
+{
+    let mut line = String::new();
+
+    for token in tokens {
+        match token {
+            Token::Literal(s) => line.push_str(s),
+            Token::Column(column, width) => {
+                let (text, style) = f(*column);
+                match width {
+                    Some(0) | None => write_painted(&mut line, style, &text),
+                    Some(w) => {
+                        let suffix = if *w > 3 { "..." } else { "" };
+                        let text = trim_long_string(&text, *w, suffix);
+                        write_padded(&mut line, style, &text, *w);
+                    }
+                }
+            }
+        }
+    }
+
+    line
+}
+
+/**
+ * Format a status line - made specifically for vsv.
+ *
+ * Each column is given as a tuple of the text to print, the style to print it
+ * with, and the max width of the column (`0` for unlimited).  Text longer than
+ * the width is trimmed (with `"..."`) and shorter text is padded - except for
+ * the last column which is never padded to avoid trailing whitespace.
+ *
+ * # Example
+ * ```
+ * use vsv::formatting::format_status_line;
+ * use yansi::Style;
+ *
+ * let style = Style::default();
+ * println!(
+ *     "{}",
+ *     format_status_line(&[
+ *         ("", style.bold(), 1),
+ *         ("SERVICE", style.bold(), 20),
+ *         ("STATE", style.bold(), 7),
+ *         ("TIME", style.bold(), 0),
+ *     ])
+ * );
+ * ```
+ */
+pub fn format_status_line<T: AsRef<str>>(
+    columns: &[(T, Style, usize)],
+) -> String {
+    let mut line = String::new();
+    write_status_line(&mut line, columns);
+
+    line
+}
+
+/**
+ * Append a status line (see `format_status_line`) to `line`, so a single
+ * string can be reused for every line of a table.
+ *
+ * # Example
+ * ```
+ * use vsv::formatting::write_status_line;
+ * use yansi::Style;
+ *
+ * let style = Style::default();
+ * let rows = [[("nginx", style, 20)], [("sshd", style, 20)]];
+ *
+ * let mut line = String::new();
+ * for row in rows {
+ *     line.clear();
+ *     write_status_line(&mut line, &row);
+ *     println!("{}", line);
+ * }
+ * ```
+ */
+pub fn write_status_line<T: AsRef<str>>(
+    line: &mut String,
+    columns: &[(T, Style, usize)],
+) {
+    for (i, (text, style, max)) in columns.iter().enumerate() {
+        let (text, style, max) = (text.as_ref(), *style, *max);
+        let last = i == columns.len() - 1;
+
+        line.push(' ');
+        if max == 0 {
+            write_painted(line, style, text);
+            continue;
+        }
+
+        let suffix = if max > 3 { "..." } else { "" };
+        let text = trim_long_string(text, max, suffix);
+        match last {
+            true => write_painted(line, style, &text),
+            false => write_padded(line, style, &text, max),
+        }
+    }
+}
+
+/// Append `text` with `style` to `line`.
+fn write_painted(line: &mut String, style: Style, text: &str) {
+    // writing to a String can't fail
+    let _ = write!(line, "{}", style.paint(text));
+}
+
+/// Append `text` with `style` to `line`, padded with spaces to `width` columns.
+fn write_padded(line: &mut String, style: Style, text: &str, width: usize) {
+    write_painted(line, style, text);
+
+    let padding = width.saturating_sub(str_width(text));
+    line.extend(std::iter::repeat_n(' ', padding));
+}
+
+/**
+ * Trim a string to be (at most) a certain number of columns wide (see
+ * `str_width`) with an optional suffix.
+ *
+ * # Examples
+ *
+ * Trim the string `"hello world"` to be (at most) 8 characters and add
+ * `"..."`:
+ *
+ * ```
+ * use vsv::formatting::trim_long_string;
+ *
+ * let s = trim_long_string("hello world", 8, "...");
+ * assert_eq!(s, "hello...");
+ * ```
+ *
+ * The suffix will only be added (and a new string made) if the original string
+ * needed to be trimmed:
+ *
+ * ```
+ * # use vsv::formatting::trim_long_string;
+ * let s = trim_long_string("hello world", 100, "...");
+ * assert_eq!(s, "hello world");
+ * ```
+ */
+pub fn trim_long_string<'a>(
+    s: &'a str,
+    limit: usize,
+    suffix: &str,
+) -> Cow<'a, str> {
+    let suffix_len = str_width(suffix);
+
+    assert!(limit > suffix_len, "number too small");
+
+    // don't do anything if string already fits
+    if str_width(s) <= limit {
+        return Cow::Borrowed(s);
+    }
+
+    // make new string (without formatting), a wide char that doesn't fit is
+    // left out entirely
+    let mut trimmed = String::new();
+    let mut width = 0;
+    for c in s.chars() {
+        width += char_width(c);
+        if width > limit - suffix_len {
+            break;
+        }
+        trimmed.push(c);
+    }
+    trimmed.push_str(suffix);
+
+    Cow::Owned(trimmed)
+}
+
+/**
+ * Get the number of terminal columns a string takes up when printed (without
+ * any escape codes), see `char_width`.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::formatting::str_width;
+ *
+ * assert_eq!(str_width("nginx"), 5);
+ * assert_eq!(str_width("日本語"), 6);
+ * ```
+ */
+pub fn str_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+/**
+ * Get the number of terminal columns a char takes up: 2 for wide chars (East
+ * Asian wide and fullwidth chars, like CJK and most emoji), 0 for chars that
+ * don't advance the cursor (combining marks, zero width spaces and joiners,
+ * variation selectors and control chars) and 1 for everything else.
+ */
+pub fn char_width(c: char) -> usize {
+    // most names and values are ASCII, which isn't in any of the ranges
+    if c.is_ascii() {
+        return usize::from(!c.is_ascii_control());
+    }
+
+    let in_ranges = |ranges: &[(u32, u32)]| {
+        ranges
+            .binary_search_by(|(lo, hi)| {
+                if c as u32 > *hi {
+                    std::cmp::Ordering::Less
+                } else if (c as u32) < *lo {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    };
+
+    if c.is_control() || in_ranges(ZERO_WIDTH) {
+        0
+    } else if in_ranges(WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Chars that take up no columns (sorted, inclusive ranges).
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036f), // combining diacritical marks
+    (0x0483, 0x0489),
+    (0x0591, 0x05bd),
+    (0x0610, 0x061a),
+    (0x064b, 0x065f),
+    (0x0e31, 0x0e31),
+    (0x0e34, 0x0e3a),
+    (0x0e47, 0x0e4e),
+    (0x1ab0, 0x1aff),
+    (0x1dc0, 0x1dff),
+    (0x200b, 0x200f), // zero width space, joiners and direction marks
+    (0x202a, 0x202e),
+    (0x2060, 0x2064),
+    (0x20d0, 0x20ff),
+    (0xfe00, 0xfe0f), // variation selectors
+    (0xfe20, 0xfe2f),
+    (0xfeff, 0xfeff),
+    (0x1f3fb, 0x1f3ff), // emoji skin tone modifiers
+    (0xe0000, 0xe007f),
+    (0xe0100, 0xe01ef),
+];
+
+/// Chars that take up 2 columns (sorted, inclusive ranges).
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115f), // hangul jamo
+    (0x231a, 0x231b),
+    (0x2329, 0x232a),
+    (0x23e9, 0x23ec),
+    (0x23f0, 0x23f0),
+    (0x23f3, 0x23f3),
+    (0x25fd, 0x25fe),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267f, 0x267f),
+    (0x2693, 0x2693),
+    (0x26a1, 0x26a1),
+    (0x26aa, 0x26ab),
+    (0x26bd, 0x26be),
+    (0x26c4, 0x26c5),
+    (0x26ce, 0x26ce),
+    (0x26d4, 0x26d4),
+    (0x26ea, 0x26ea),
+    (0x26f2, 0x26f3),
+    (0x26f5, 0x26f5),
+    (0x26fa, 0x26fa),
+    (0x26fd, 0x26fd),
+    (0x2705, 0x2705),
+    (0x270a, 0x270b),
+    (0x2728, 0x2728),
+    (0x274c, 0x274c),
+    (0x274e, 0x274e),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27b0, 0x27b0),
+    (0x27bf, 0x27bf),
+    (0x2b1b, 0x2b1c),
+    (0x2b50, 0x2b50),
+    (0x2b55, 0x2b55),
+    (0x2e80, 0x303e), // CJK radicals, symbols and punctuation
+    (0x3041, 0x33ff), // hiragana, katakana, ...
+    (0x3400, 0x4dbf), // CJK unified ideographs extension A
+    (0x4e00, 0x9fff), // CJK unified ideographs
+    (0xa000, 0xa4cf), // yi
+    (0xa960, 0xa97f),
+    (0xac00, 0xd7a3), // hangul syllables
+    (0xf900, 0xfaff), // CJK compatibility ideographs
+    (0xfe10, 0xfe19),
+    (0xfe30, 0xfe6f),
+    (0xff00, 0xff60), // fullwidth forms
+    (0xffe0, 0xffe6),
+    (0x16fe0, 0x16fe4),
+    (0x17000, 0x18cff), // tangut
+    (0x1b000, 0x1b2ff), // kana supplement and extensions
+    (0x1f004, 0x1f004),
+    (0x1f0cf, 0x1f0cf),
+    (0x1f18e, 0x1f18e),
+    (0x1f191, 0x1f19a),
+    (0x1f200, 0x1f251),
+    (0x1f300, 0x1f64f), // emoji: pictographs and emoticons
+    (0x1f680, 0x1f6ff), // emoji: transport and map symbols
+    (0x1f7e0, 0x1f7eb),
+    (0x1f90c, 0x1f9ff), // emoji: supplemental symbols and pictographs
+    (0x1fa70, 0x1faff),
+    (0x20000, 0x2fffd), // CJK unified ideographs extension B and beyond
+    (0x30000, 0x3fffd),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_status_line() {
+        let style = Style::default();
+
+        let line = format_status_line(&[
+            ("x", style, 1),
+            ("some-really-long-service-name", style, 20),
+            ("run", style, 7),
+            ("5 seconds", style, 0),
+        ]);
+        assert_eq!(line, " x some-really-long-... run     5 seconds");
+
+        // the last column is never padded
+        let line = format_status_line(&[("foo", style, 20), ("run", style, 7)]);
+        assert_eq!(line, " foo                  run");
+
+        // text that fits exactly is left alone
+        let line =
+            format_status_line(&[("abcde", style, 5), ("run", style, 7)]);
+        assert_eq!(line, " abcde run");
+
+        // wide chars are padded and trimmed by their width
+        let line = format_status_line(&[
+            ("日本", style, 6),
+            ("サービス名前", style, 8),
+            ("run", style, 7),
+        ]);
+        assert_eq!(line, " 日本   サー...  run");
+
+        // lines are appended to, so one string can be reused
+        let mut line = String::from(">");
+        write_status_line(&mut line, &[("foo", style, 5), ("run", style, 0)]);
+        assert_eq!(line, "> foo   run");
+    }
+
+    #[test]
+    fn test_str_width() {
+        assert_eq!(str_width(""), 0);
+        assert_eq!(str_width("nginx"), 5);
+        assert_eq!(str_width("a\tb"), 2, "control");
+        assert_eq!(str_width("日本語"), 6, "CJK");
+        assert_eq!(str_width("web-🚀"), 6, "emoji");
+        assert_eq!(str_width("e\u{301}"), 1, "combining");
+        assert_eq!(str_width("✔"), 1, "narrow symbol");
+        assert_eq!(str_width("\u{f00c}"), 1, "private use");
+
+        // ranges are sorted and don't overlap
+        for ranges in [ZERO_WIDTH, WIDE] {
+            for pair in ranges.windows(2) {
+                assert!(pair[0].1 < pair[1].0, "{:x?}", pair);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trim_long_string() {
+        assert_eq!(trim_long_string("hello world", 8, "..."), "hello...");
+        assert_eq!(trim_long_string("日本語のサービス", 8, "..."), "日本...");
+        assert_eq!(trim_long_string("日本語のサービス", 9, "..."), "日本語...");
+        assert_eq!(trim_long_string("日本語", 6, "..."), "日本語", "fits");
+    }
+
+    #[test]
+    fn test_parse_format_string() -> Result<()> {
+        let tokens = parse_format_string("%{name} %{pid:5}\\t100%%\\n")?;
+
+        assert_eq!(
+            tokens,
+            [
+                Token::Column(Column::Name, None),
+                Token::Literal(" ".into()),
+                Token::Column(Column::Pid, Some(5)),
+                Token::Literal("\t100%\n".into()),
+            ],
+            "tokens parsed"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_format_string_bad() {
+        for s in ["%{name", "%{bogus}", "%{name:x}", "%d", "%", "\\z", "\\"] {
+            assert!(parse_format_string(s).is_err(), "'{}' is invalid", s);
+        }
+    }
+
+    #[test]
+    fn test_format_string_columns() -> Result<()> {
+        let tokens = parse_format_string("%{state} %{name} %{state}")?;
+
+        assert_eq!(
+            format_string_columns(&tokens),
+            [Column::State, Column::Name],
+            "columns deduplicated"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_format_string() -> Result<()> {
+        let tokens = parse_format_string("[%{name:6}] %{pid}")?;
+        let line = render_format_string(&tokens, |column| match column {
+            Column::Name => ("foo".into(), Style::default()),
+            _ => ("123".into(), Style::default()),
+        });
+
+        assert_eq!(line, "[foo   ] 123", "rendered line");
+
+        let line = render_format_string(&tokens, |column| match column {
+            Column::Name => ("日本".into(), Style::default()),
+            _ => ("123".into(), Style::default()),
+        });
+        assert_eq!(line, "[日本  ] 123", "padded by width");
+
+        Ok(())
+    }
+}
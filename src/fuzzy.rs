@@ -0,0 +1,119 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * fzf-style fuzzy matching.
+ */
+
+/**
+ * Score how well `pattern` fuzzily matches `text`, or `None` if it doesn't
+ * match at all.
+ *
+ * Every character of `pattern` must appear in `text` in order (ignoring case).
+ * Higher scores are better: consecutive matches, matches at the start of
+ * `text` and matches at the start of a word (after `-`, `_`, `.` or `/`) all
+ * score higher, while gaps between matches score lower.  An empty pattern
+ * matches everything equally.
+ *
+ * # Example
+ *
+ * ```
+ * assert!(score("ngx", "nginx").is_some());
+ * assert!(score("xn", "nginx").is_none());
+ * assert!(score("ng", "nginx") > score("ng", "sing"));
+ * ```
+ */
+pub fn score(pattern: &str, text: &str) -> Option<i64> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let pattern: Vec<char> =
+        pattern.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().collect();
+    let lower: Vec<Option<char>> =
+        text.iter().map(|c| c.to_lowercase().next()).collect();
+
+    // score for matching any character at position `i`
+    let bonus = |i: usize| -> i64 {
+        if i == 0 {
+            9
+        } else if matches!(text[i - 1], '-' | '_' | '.' | '/' | ' ') {
+            7
+        } else {
+            1
+        }
+    };
+
+    // best[i] is the best score with the current pattern character matched
+    // at position `i` of the text (found with dynamic programming so the best
+    // alignment is used, not just the first one)
+    let mut best: Vec<Option<i64>> = vec![];
+    let mut first = true;
+
+    for p in &pattern {
+        let mut next: Vec<Option<i64>> = vec![None; text.len()];
+
+        for (i, c) in lower.iter().enumerate() {
+            if *c != Some(*p) {
+                continue;
+            }
+
+            let prev = if first {
+                Some(-(i.min(5) as i64))
+            } else {
+                (0..i)
+                    .filter_map(|k| {
+                        let score = best[k]?;
+                        let gap = i - k - 1;
+                        let adjust = match gap {
+                            0 => 4,
+                            _ => -(gap.min(5) as i64),
+                        };
+                        Some(score + adjust)
+                    })
+                    .max()
+            };
+
+            next[i] = prev.map(|score| score + bonus(i));
+        }
+
+        best = next;
+        first = false;
+    }
+
+    // prefer shorter candidates when everything else is equal
+    let score = best.into_iter().flatten().max()?;
+
+    Some(score - (text.len() / 10) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_matches() {
+        assert!(score("", "anything").is_some(), "empty pattern");
+        assert!(score("ngx", "nginx").is_some(), "subsequence");
+        assert!(score("NGX", "nginx").is_some(), "case insensitive");
+        assert!(score("xn", "nginx").is_none(), "out of order");
+        assert!(score("nginxx", "nginx").is_none(), "too long");
+    }
+
+    #[test]
+    fn test_score_ordering() {
+        assert!(score("ng", "nginx") > score("ng", "sing"), "prefix");
+        assert!(
+            score("no", "node-exporter") > score("no", "nginx-ops"),
+            "consecutive"
+        );
+        assert!(
+            score("exp", "node-exporter") > score("exp", "nodeexporter"),
+            "word start"
+        );
+    }
+}
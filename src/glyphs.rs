@@ -0,0 +1,200 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Glyph sets: the chars shown in the status column for each state.
+ *
+ * Like themes (see `theme`), the glyphs set with `set` (or the default
+ * `unicode` glyphs) are used for all output, starting from one of the built-in
+ * sets (see `NAMES`) with any glyph replaced by a single char.
+ */
+
+use std::sync::OnceLock;
+
+use crate::error::{bail, ensure, Result};
+use crate::service::ServiceState;
+
+/// The names of the built-in glyph sets.
+pub const NAMES: &[&str] = &["unicode", "ascii", "nerd-font"];
+
+static GLYPHS: OnceLock<Glyphs> = OnceLock::new();
+
+/// The status chars of service states (and conditions shown like states).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glyphs {
+    pub run: String,
+    pub down: String,
+    pub finish: String,
+    pub zombie: String,
+    pub stopped: String,
+    pub unsupervised: String,
+    pub unknown: String,
+    pub error: String,
+
+    /// Not in the state runit wants it in.
+    pub mismatched: String,
+
+    /// The pid looks stale.
+    pub stale: String,
+
+    /// Restarting over and over.
+    pub flapping: String,
+
+    /// Down on purpose (with a `./down` file).
+    pub normally_down: String,
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self::from_chars([
+            '✔', 'X', 'X', 'Z', 'T', '~', '?', 'E', '!', '≠', '↺', '-',
+        ])
+    }
+}
+
+impl Glyphs {
+    /// Get a built-in glyph set by name (one of `NAMES`).
+    pub fn builtin(name: &str) -> Result<Self> {
+        let glyphs = match name {
+            "unicode" => Self::default(),
+            "ascii" => Self::from_chars([
+                '+', 'X', 'X', 'Z', 'T', '~', '?', 'E', '!', '=', '*', '-',
+            ]),
+            // Font Awesome icons from the Nerd Fonts private use area
+            "nerd-font" => Self::from_chars([
+                '\u{f00c}', // check
+                '\u{f00d}', // times
+                '\u{f017}', // clock-o
+                '\u{f188}', // bug
+                '\u{f04c}', // pause
+                '\u{f070}', // eye-slash
+                '\u{f128}', // question
+                '\u{f06a}', // exclamation-circle
+                '\u{f071}', // exclamation-triangle
+                '\u{f127}', // chain-broken
+                '\u{f021}', // refresh
+                '\u{f068}', // minus
+            ]),
+            _ => bail!(
+                "unknown glyphs: '{}' (expected one of {})",
+                name,
+                NAMES.join(", ")
+            ),
+        };
+
+        Ok(glyphs)
+    }
+
+    /// Create a glyph set from its chars (in the order of the fields).
+    fn from_chars(chars: [char; 12]) -> Self {
+        // fields are initialized in the order they're written
+        let mut chars = chars.iter().map(char::to_string);
+        let mut next = || chars.next().expect("a char per field");
+
+        Self {
+            run: next(),
+            down: next(),
+            finish: next(),
+            zombie: next(),
+            stopped: next(),
+            unsupervised: next(),
+            unknown: next(),
+            error: next(),
+            mismatched: next(),
+            stale: next(),
+            flapping: next(),
+            normally_down: next(),
+        }
+    }
+
+    /// Get the glyph of a state.
+    pub fn state(&self, state: ServiceState) -> &str {
+        match state {
+            ServiceState::Run => &self.run,
+            ServiceState::Down => &self.down,
+            ServiceState::Finish => &self.finish,
+            ServiceState::Zombie => &self.zombie,
+            ServiceState::Stopped => &self.stopped,
+            ServiceState::Unsupervised => &self.unsupervised,
+            ServiceState::Unknown => &self.unknown,
+            ServiceState::Error => &self.error,
+        }
+    }
+
+    /**
+     * Replace a glyph by its key: a state (like `run`) or condition
+     * (`mismatched`, `stale`, `flapping` or `normally_down`).  Glyphs are a
+     * single char so the status column lines up.
+     */
+    pub fn set(&mut self, key: &str, glyph: &str) -> Result<()> {
+        let field = match key {
+            "run" => &mut self.run,
+            "down" => &mut self.down,
+            "finish" => &mut self.finish,
+            "zombie" => &mut self.zombie,
+            "stopped" => &mut self.stopped,
+            "unsupervised" => &mut self.unsupervised,
+            "unknown" => &mut self.unknown,
+            "error" => &mut self.error,
+            "mismatched" => &mut self.mismatched,
+            "stale" => &mut self.stale,
+            "flapping" => &mut self.flapping,
+            "normally_down" => &mut self.normally_down,
+            _ => bail!("unknown glyph '{}'", key),
+        };
+
+        ensure!(
+            glyph.chars().count() == 1,
+            "glyph '{}' must be a single char: '{}'",
+            key,
+            glyph
+        );
+        *field = glyph.to_string();
+
+        Ok(())
+    }
+}
+
+/// Use `glyphs` for all output, only the first set is used.
+pub fn set(glyphs: Glyphs) {
+    let _ = GLYPHS.set(glyphs);
+}
+
+/// Get the glyphs set with `set` (or the default glyphs).
+pub fn get() -> &'static Glyphs {
+    GLYPHS.get_or_init(Glyphs::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyphs() -> Result<()> {
+        for name in NAMES {
+            Glyphs::builtin(name)?;
+        }
+        assert!(Glyphs::builtin("emoji").is_err());
+
+        let mut glyphs = Glyphs::builtin("ascii")?;
+        assert_eq!(glyphs.state(ServiceState::Run), "+");
+        assert_eq!(glyphs.state(ServiceState::Unknown), "?");
+        assert_eq!(glyphs.state(ServiceState::Error), "E");
+        assert_eq!(glyphs.stale, "=");
+        assert_eq!(glyphs.normally_down, "-");
+
+        glyphs.set("run", "●")?;
+        glyphs.set("flapping", "@")?;
+        assert_eq!(glyphs.state(ServiceState::Run), "●");
+        assert_eq!(glyphs.flapping, "@");
+
+        assert!(glyphs.set("sleeping", "z").is_err(), "unknown");
+        assert!(glyphs.set("run", "").is_err(), "empty");
+        assert!(glyphs.set("run", "ok").is_err(), "too long");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * A tiny HTTP/1.1 server used by the long-running `vsv` modes.
+ *
+ * This is intentionally minimal: every connection handles exactly 1 request
+ * (`Connection: close`), request bodies are only read if `Content-Length` is
+ * given, and each connection is handled in its own (scoped) thread, with at
+ * most `MAX_CONNECTIONS` handled at once.
+ */
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+/// Max size allowed for the request line + headers.
+const MAX_HEADER_SIZE: usize = 16 * 1024;
+
+/// Max size allowed for a request body.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// How long to wait on a slow client before giving up.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Max connections handled at once, more wait in the listen backlog.
+const MAX_CONNECTIONS: usize = 64;
+
+/// How long to wait before accepting again after failing to (like `EMFILE`).
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// A parsed HTTP request.
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Get a header value by (case-insensitive) name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// An HTTP response to send to a client.
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    /// Create a new response with the given status code, type and body.
+    pub fn new(status: u16, content_type: &'static str, body: String) -> Self {
+        Self { status, content_type, body }
+    }
+
+    /// Create a plain-text response.
+    pub fn text(status: u16, body: String) -> Self {
+        Self::new(status, "text/plain; charset=utf-8", body)
+    }
+}
+
+/**
+ * Listen on the given address and call `handler` for every request received.
+ * This function never returns unless binding to the address fails.
+ */
+pub fn serve<F>(addr: &str, handler: F) -> Result<()>
+where
+    F: Fn(&Request) -> Response + Sync,
+{
+    let listener = TcpListener::bind(addr)
+        .with_context(|| format!("failed to listen on {}", addr))?;
+
+    let slots = Slots::new(MAX_CONNECTIONS);
+
+    thread::scope(|s| loop {
+        let slot = slots.acquire();
+
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(_) => {
+                // don't spin while the error (like running out of fds) lasts
+                thread::sleep(ACCEPT_BACKOFF);
+                continue;
+            }
+        };
+
+        let handler = &handler;
+        s.spawn(move || {
+            // nothing useful can be done if the client goes away
+            let _ = handle_connection(stream, handler);
+            drop(slot);
+        });
+    })
+}
+
+/// A count of connections being handled, waited on when it reaches its max.
+struct Slots {
+    used: Mutex<usize>,
+    freed: Condvar,
+    max: usize,
+}
+
+/// A connection being handled, freeing its slot when dropped.
+struct Slot<'a>(&'a Slots);
+
+impl Slots {
+    fn new(max: usize) -> Self {
+        Self { used: Mutex::new(0), freed: Condvar::new(), max }
+    }
+
+    /// Wait for a free slot and take it.
+    fn acquire(&self) -> Slot<'_> {
+        let mut used = self
+            .freed
+            .wait_while(self.lock(), |used| *used >= self.max)
+            .unwrap_or_else(PoisonError::into_inner);
+        *used += 1;
+
+        Slot(self)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        self.used.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        *self.0.lock() -= 1;
+        self.0.freed.notify_one();
+    }
+}
+
+/// Read a single request from a connection and write back the response.
+fn handle_connection<F>(mut stream: TcpStream, handler: &F) -> Result<()>
+where
+    F: Fn(&Request) -> Response,
+{
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let response = match read_request(&stream) {
+        Ok(request) => handler(&request),
+        Err(err) => Response::text(400, format!("bad request: {}\n", err)),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+        response.body
+    )?;
+
+    stream.flush()?;
+
+    Ok(())
+}
+
+/// Parse an HTTP request from a stream.
+fn read_request(stream: &TcpStream) -> Result<Request> {
+    let mut reader = BufReader::new(stream.take(MAX_HEADER_SIZE as u64));
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => bail!("malformed request line"),
+    };
+
+    let mut headers = vec![];
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            bail!("unexpected end of headers");
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((k, v)) = line.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+
+    let mut request = Request { method, path, headers, body: vec![] };
+
+    if let Some(len) = request.header("Content-Length") {
+        let len: usize = len.parse().context("invalid Content-Length")?;
+        if len > MAX_BODY_SIZE {
+            bail!("request body too large");
+        }
+
+        // whatever is left in the buffer + the rest of the stream
+        let mut body = reader.buffer().to_vec();
+        body.truncate(len);
+        let mut rest = stream.take((len - body.len()) as u64);
+        rest.read_to_end(&mut body)?;
+        request.body = body;
+    }
+
+    Ok(request)
+}
+
+/// Get the reason phrase for the status codes used by vsv.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots() {
+        let slots = Slots::new(2);
+        let a = slots.acquire();
+        let _b = slots.acquire();
+        assert_eq!(*slots.lock(), 2);
+
+        thread::scope(|s| {
+            // waits for a slot to be freed
+            let waiter = s.spawn(|| {
+                let _c = slots.acquire();
+            });
+            thread::sleep(Duration::from_millis(50));
+            assert!(!waiter.is_finished(), "waiting while full");
+
+            drop(a);
+            waiter.join().unwrap();
+        });
+
+        assert_eq!(*slots.lock(), 1);
+    }
+}
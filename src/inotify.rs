@@ -0,0 +1,277 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Wait for services to change with inotify(7).
+ *
+ * Supervisors write a service's state to files in its `supervise` directory
+ * (`runsv` writes `status`, `pid` and `stat` by renaming a new file over the
+ * old one), so watching those directories (and the service directory itself,
+ * for services being added and removed) tells when anything shown could have
+ * changed, instead of re-reading everything on a timer.
+ */
+
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use libc::c_int;
+
+use crate::error::{bail, Result};
+
+/// Files in a `supervise` directory whose changes are worth waking up for.
+const SUPERVISE_FILES: &[&str] = &["status", "pid", "stat"];
+
+/// Events watched for, a file being written or renamed into place.
+const SUPERVISE_MASK: u32 = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO;
+
+/// Events watched for in a service directory (services coming or going).
+const SVDIR_MASK: u32 =
+    libc::IN_CREATE | libc::IN_DELETE | libc::IN_MOVED_FROM | libc::IN_MOVED_TO;
+
+/**
+ * How long to wait for more events after waking up, a supervisor writes a few
+ * files for each change.
+ */
+const SETTLE_TIME: Duration = Duration::from_millis(20);
+
+/// The size of `struct inotify_event` (without the name).
+const EVENT_SIZE: usize = 16;
+
+/// An inotify instance watching service directories.
+#[derive(Debug)]
+pub struct Watcher {
+    fd: OwnedFd,
+
+    /// Watch descriptors of `supervise` directories (the rest are service
+    /// directories).
+    supervise: HashSet<c_int>,
+
+    /// Whether the last `watch` couldn't add every watch.
+    incomplete: bool,
+}
+
+impl Watcher {
+    /// Create a new (non-blocking) inotify instance.
+    pub fn new() -> Result<Self> {
+        let fd = unsafe {
+            libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC)
+        };
+        if fd == -1 {
+            bail!("inotify_init1 failed: {}", io::Error::last_os_error());
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        Ok(Self { fd, supervise: HashSet::new(), incomplete: false })
+    }
+
+    /**
+     * Watch the service directories (like `/var/service`) and the
+     * `supervise` directories of every service (and log service) in them.
+     *
+     * This can be called again to pick up new services, directories already
+     * watched are skipped by the kernel.  Returns `false` if any watch
+     * couldn't be added (like for root's `supervise` directories, or when
+     * `fs.inotify.max_user_watches` is reached), in which case only waiting
+     * for the timeout will see changes to those services.
+     */
+    pub fn watch(&mut self, svdirs: &[&Path]) -> bool {
+        self.incomplete = false;
+
+        for svdir in svdirs {
+            if self.add(svdir, SVDIR_MASK).is_none() {
+                continue;
+            }
+
+            let entries = match fs::read_dir(svdir) {
+                Ok(entries) => entries,
+                Err(_) => {
+                    self.incomplete = true;
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let dir = entry.path();
+                for supervise in
+                    [dir.join("supervise"), dir.join("log").join("supervise")]
+                {
+                    if !supervise.is_dir() {
+                        continue;
+                    }
+                    if let Some(wd) = self.add(&supervise, SUPERVISE_MASK) {
+                        self.supervise.insert(wd);
+                    }
+                }
+            }
+        }
+
+        !self.incomplete
+    }
+
+    /// Add a watch, noting if it failed.
+    fn add(&mut self, path: &Path, mask: u32) -> Option<c_int> {
+        let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+        let wd = unsafe {
+            libc::inotify_add_watch(self.fd.as_raw_fd(), path.as_ptr(), mask)
+        };
+
+        if wd == -1 {
+            self.incomplete = true;
+            return None;
+        }
+
+        Some(wd)
+    }
+
+    /**
+     * Wait (at most `timeout`) for a service to change, returns `false` on
+     * timeout.
+     */
+    pub fn wait(&self, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if !self.poll(left)? {
+                return Ok(false);
+            }
+
+            // let the rest of the change land before it's read
+            thread::sleep(SETTLE_TIME);
+
+            if self.read_changes() {
+                return Ok(true);
+            }
+            if left.is_zero() {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Read every pending event, returns whether any was about a service.
+    fn read_changes(&self) -> bool {
+        let mut changed = false;
+        let mut buf = [0u8; 4096];
+
+        loop {
+            let n = unsafe {
+                libc::read(
+                    self.fd.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    buf.len(),
+                )
+            };
+            if n <= 0 {
+                return changed;
+            }
+
+            for (wd, name) in parse_events(&buf[..n as usize]) {
+                changed |= !self.supervise.contains(&wd)
+                    || SUPERVISE_FILES.contains(&name.as_str());
+            }
+        }
+    }
+
+    /// Wait for the inotify fd to become readable, `false` on timeout.
+    fn poll(&self, timeout: Duration) -> Result<bool> {
+        let mut fds = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ms =
+            timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+
+        match unsafe { libc::poll(&mut fds, 1, ms) } {
+            -1 => {
+                let err = io::Error::last_os_error();
+                match err.kind() {
+                    io::ErrorKind::Interrupted => Ok(false),
+                    _ => Err(err.into()),
+                }
+            }
+            0 => Ok(false),
+            _ => Ok(true),
+        }
+    }
+}
+
+/**
+ * Parse the events read from an inotify fd into their watch descriptor and
+ * the name of the file (in the watched directory) they are about.
+ */
+fn parse_events(buf: &[u8]) -> Vec<(c_int, String)> {
+    let mut events = vec![];
+    let mut buf = buf;
+
+    while buf.len() >= EVENT_SIZE {
+        let int = |i: usize| {
+            let bytes = buf[i..i + 4].try_into().expect("4 bytes");
+            u32::from_ne_bytes(bytes)
+        };
+        let wd = int(0) as c_int;
+        let len = int(12) as usize;
+
+        let name = match buf.get(EVENT_SIZE..EVENT_SIZE + len) {
+            Some(name) => name,
+            None => break,
+        };
+        // the name is padded with NUL bytes
+        let name = name.split(|b| *b == 0).next().unwrap_or_default();
+        events.push((wd, String::from_utf8_lossy(name).into_owned()));
+
+        buf = &buf[EVENT_SIZE + len..];
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(wd: c_int, mask: u32, name: &str) -> Vec<u8> {
+        let mut name = name.as_bytes().to_vec();
+        if !name.is_empty() {
+            name.resize((name.len() / 16 + 1) * 16, 0);
+        }
+
+        let mut buf = vec![];
+        buf.extend_from_slice(&wd.to_ne_bytes());
+        buf.extend_from_slice(&mask.to_ne_bytes());
+        buf.extend_from_slice(&0u32.to_ne_bytes());
+        buf.extend_from_slice(&(name.len() as u32).to_ne_bytes());
+        buf.extend_from_slice(&name);
+        buf
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let mut buf = event(1, libc::IN_MOVED_TO, "status");
+        buf.extend(event(2, libc::IN_CREATE, "a-long-service-name"));
+        buf.extend(event(3, libc::IN_IGNORED, ""));
+
+        assert_eq!(
+            parse_events(&buf),
+            [
+                (1, "status".to_string()),
+                (2, "a-long-service-name".to_string()),
+                (3, String::new())
+            ]
+        );
+
+        // a truncated event is dropped
+        assert_eq!(parse_events(&buf[..20]), []);
+    }
+}
@@ -0,0 +1,54 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * The `vsv` library: runit service discovery, status parsing and formatting.
+ *
+ * The `vsv` binary is a thin wrapper around this crate; the same code it uses
+ * to find and inspect services can be used directly:
+ *
+ * ```no_run
+ * use vsv::ServiceDir;
+ *
+ * # fn main() -> Result<(), vsv::Error> {
+ * for service in ServiceDir::open("/var/service")?.services()? {
+ *     println!("{} is {}", service.name, service.state);
+ * }
+ * # Ok(())
+ * # }
+ * ```
+ */
+
+#![allow(clippy::uninlined_format_args)]
+
+pub mod backend;
+pub mod cache;
+pub mod column;
+pub mod error;
+#[cfg(feature = "vsv-ffi")]
+pub mod ffi;
+pub mod formatting;
+pub mod glyphs;
+pub mod inotify;
+pub mod options;
+pub mod output;
+pub mod procfs;
+pub mod regex;
+pub mod restarts;
+pub mod runit;
+pub mod service;
+pub mod service_dir;
+pub mod snapshot;
+pub mod subscription;
+pub mod task;
+pub mod theme;
+pub mod time_format;
+pub mod utils;
+
+pub use error::Error;
+pub use options::Options;
+pub use service::{Service, ServiceState};
+pub use service_dir::ServiceDir;
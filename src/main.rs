@@ -8,31 +8,48 @@
  * A rust port of `vsv`
  *
  * Original: <https://github.com/bahamas10/vsv>
+ *
+ * This is the CLI, the work is done by the `vsv` library.
  */
 
 #![allow(clippy::uninlined_format_args)]
 
 use anyhow::{Context, Result};
-use yansi::{Color, Paint};
+use yansi::Paint;
 
 mod arguments;
 mod commands;
 mod config;
+mod config_file;
 mod die;
-mod runit;
-mod service;
-mod utils;
+mod fuzzy;
+mod http;
+mod pager;
+mod remote;
+mod terminal;
+mod toml;
+
+// the library modules, so the CLI modules find them at `crate::`
+use vsv::{
+    backend, cache, column, formatting, glyphs, inotify, options, output,
+    procfs, regex, restarts, runit, service, theme, time_format, utils,
+};
 
-use config::{Config, ProgramMode};
+use config::{verbose, Config, ProgramMode};
 use die::die;
-use utils::verbose;
 
 fn do_main() -> Result<()> {
+    // exit quietly (like most CLI tools) if stdout is closed early, for
+    // example when piped to `head` or when a pager or watch is quit
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+
     // disable color until we absolutely know we want it
     Paint::disable();
 
     // parse CLI options + env vars
-    let args = arguments::parse();
+    let args = arguments::parse()?;
     let cfg =
         Config::from_args(&args).context("failed to parse args into config")?;
 
@@ -40,6 +57,15 @@ fn do_main() -> Result<()> {
     if cfg.colorize {
         Paint::enable();
     }
+    theme::set(cfg.theme.clone());
+    glyphs::set(cfg.glyphs.clone());
+    time_format::set(cfg.time_format.clone());
+
+    // services are gathered in parallel on the global pool
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(cfg.jobs)
+        .build_global()
+        .context("failed to create thread pool")?;
 
     verbose!(
         cfg,
@@ -54,6 +80,19 @@ fn do_main() -> Result<()> {
         ProgramMode::Status => commands::status::do_status(&cfg),
         ProgramMode::Enable => commands::enable_disable::do_enable(&cfg),
         ProgramMode::Disable => commands::enable_disable::do_disable(&cfg),
+        ProgramMode::Control(cmd) => commands::control::do_control(&cfg, cmd),
+        ProgramMode::Exporter => commands::exporter::do_exporter(&cfg),
+        ProgramMode::Serve => commands::serve::do_serve(&cfg),
+        ProgramMode::Metrics => commands::metrics::do_metrics(&cfg),
+        ProgramMode::CheckPlugin => {
+            commands::check_plugin::do_check_plugin(&cfg)
+        }
+        ProgramMode::Avail => commands::avail::do_avail(&cfg),
+        ProgramMode::Log => commands::log::do_log(&cfg),
+        ProgramMode::Show => commands::show::do_show(&cfg),
+        ProgramMode::Pick => commands::pick::do_pick(&cfg),
+        ProgramMode::Tui => commands::tui::do_tui(&cfg),
+        ProgramMode::Completions => commands::completions::do_completions(&cfg),
         ProgramMode::External => commands::external::do_external(&cfg),
     }
 }
@@ -62,6 +101,9 @@ fn main() {
     let ret = do_main();
 
     if let Err(err) = ret {
-        die!(1, "{}: {:?}", Color::Red.paint("error"), err);
+        if let Some(commands::ExitCode(code)) = err.downcast_ref() {
+            die!(*code);
+        }
+        die!(1, "{}: {:?}", theme::get().bad.paint("error"), err);
     }
 }
@@ -0,0 +1,90 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Options for reading services, see `ServiceDir::with_options`.
+ *
+ * The `vsv` binary fills these in from its command line and environment, the
+ * defaults are what it uses when nothing is given.
+ */
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::backend::{self, Supervisor};
+use crate::cache;
+use crate::column::{self, Column};
+
+// default values
+pub const DEFAULT_PROC_DIR: &str = "/proc";
+pub const DEFAULT_CGROUP_DIR: &str = "/sys/fs/cgroup";
+pub const DEFAULT_PSTREE_PROG: &str = "pstree";
+pub const DEFAULT_FD_THRESHOLD: f64 = 80.0;
+
+/// Seconds to wait for external programs and services by default.
+pub const DEFAULT_TIMEOUT: f64 = 10.0;
+
+/// How services are read.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The procfs directory.
+    pub proc_path: PathBuf,
+
+    /// The cgroup filesystem (for the `cgroup_memory` column).
+    pub cgroup_path: PathBuf,
+
+    /// The supervision suite of every service directory, detected for each
+    /// directory if `None`.
+    pub backend: Option<&'static dyn Supervisor>,
+
+    /// The columns to read data for (like `/proc` for `command`), nothing
+    /// else is read.
+    pub columns: Vec<Column>,
+
+    /// Show every argument of the `command` column, not just the program.
+    pub full_command: bool,
+
+    /// Look up user names for the `user` column instead of showing uids.
+    pub user_names: bool,
+
+    /// Percent of a process's open files limit at which its fds are flagged.
+    pub fd_threshold: f64,
+
+    /// Read the process tree of each service (with `pstree_prog`).
+    pub tree: bool,
+    pub pstree_prog: String,
+
+    /// How long to wait for external programs, `None` to wait forever.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            proc_path: PathBuf::from(DEFAULT_PROC_DIR),
+            cgroup_path: PathBuf::from(DEFAULT_CGROUP_DIR),
+            backend: None,
+            columns: column::DEFAULT_COLUMNS.to_vec(),
+            full_command: false,
+            user_names: false,
+            fd_threshold: DEFAULT_FD_THRESHOLD,
+            tree: false,
+            pstree_prog: DEFAULT_PSTREE_PROG.to_string(),
+            timeout: Some(Duration::from_secs_f64(DEFAULT_TIMEOUT)),
+        }
+    }
+}
+
+impl Options {
+    /**
+     * Get the backend used for a service directory: `backend` if it's set or
+     * the one detected for the directory.
+     */
+    pub fn backend_for(&self, svdir: &Path) -> &'static dyn Supervisor {
+        self.backend
+            .unwrap_or_else(|| cache::backend(svdir, || backend::detect(svdir)))
+    }
+}
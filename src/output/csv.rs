@@ -0,0 +1,118 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! CSV (RFC 4180) serialization for `Value`.
+
+use super::Value;
+
+/**
+ * Serialize an array of objects as CSV, optionally with a header row.
+ *
+ * Each object becomes 1 row with the fields given in `header` (in that
+ * order) - fields missing from an object become empty cells.  Arrays are joined
+ * with `"; "` into a single cell.  The header row itself is only written if
+ * `with_header` is set.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::output::{csv::to_string, Value};
+ *
+ * let v = Value::Array(vec![Value::Object(vec![("a".into(), "b,c".into())])]);
+ * assert_eq!(to_string(&["a"], &v, true), "a\n\"b,c\"\n");
+ * ```
+ */
+pub fn to_string(
+    header: &[&str],
+    records: &Value,
+    with_header: bool,
+) -> String {
+    let mut s = String::new();
+
+    if with_header {
+        write_row(&mut s, header.iter().map(|h| h.to_string()));
+    }
+
+    let records = match records {
+        Value::Array(arr) => arr.as_slice(),
+        _ => &[],
+    };
+
+    for record in records {
+        write_row(
+            &mut s,
+            header.iter().map(|h| {
+                record.get(h).map_or_else(String::new, Value::to_cell)
+            }),
+        );
+    }
+
+    s
+}
+
+/// Write a single row of cells (and a trailing newline).
+fn write_row<I: Iterator<Item = String>>(s: &mut String, cells: I) {
+    for (i, c) in cells.enumerate() {
+        if i > 0 {
+            s.push(',');
+        }
+        s.push_str(&quote(&c));
+    }
+    s.push('\n');
+}
+
+/// Quote a cell if it contains any special characters.
+fn quote(st: &str) -> String {
+    let needs_quotes = st.contains([',', '"', '\n', '\r'])
+        || st.starts_with(' ')
+        || st.ends_with(' ');
+
+    if needs_quotes {
+        format!("\"{}\"", st.replace('"', "\"\""))
+    } else {
+        st.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_string() {
+        let v = Value::Array(vec![
+            Value::Object(vec![
+                ("name".into(), "foo".into()),
+                ("pid".into(), Value::Int(1)),
+                ("command".into(), "sh -c \"echo a, b\"".into()),
+            ]),
+            Value::Object(vec![
+                ("name".into(), "bar".into()),
+                ("pid".into(), Value::Null),
+                ("errors".into(), Value::Array(vec!["a".into(), "b".into()])),
+            ]),
+        ]);
+
+        let want = "name,pid,command,errors
+foo,1,\"sh -c \"\"echo a, b\"\"\",
+bar,,,a; b
+";
+
+        assert_eq!(
+            to_string(&["name", "pid", "command", "errors"], &v, true),
+            want,
+            "csv output"
+        );
+    }
+
+    #[test]
+    fn test_to_string_empty() {
+        let v = Value::Array(vec![]);
+
+        assert_eq!(to_string(&["a", "b"], &v, true), "a,b\n", "header only");
+        assert_eq!(to_string(&["a", "b"], &v, false), "", "nothing at all");
+    }
+}
@@ -0,0 +1,374 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! JSON serialization (and parsing) for `Value`.
+
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use super::Value;
+
+use crate::error::{bail, ensure, Context, Result};
+
+/**
+ * Serialize a `Value` as a pretty-printed (2 space indented) JSON string.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::output::{json::to_string_pretty, Value};
+ *
+ * let v = Value::Array(vec![Value::Int(1), Value::Null]);
+ * assert_eq!(to_string_pretty(&v), "[\n  1,\n  null\n]");
+ * ```
+ */
+pub fn to_string_pretty(value: &Value) -> String {
+    let mut s = String::new();
+    write_value(&mut s, value, 0);
+    s
+}
+
+/// Write a `Value` into the given string at the given indentation level.
+fn write_value(s: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Null => s.push_str("null"),
+        Value::Bool(b) => s.push_str(if *b { "true" } else { "false" }),
+        Value::Int(i) => s.push_str(&i.to_string()),
+        // JSON has no infinity or NaN
+        Value::Float(f) if !f.is_finite() => s.push_str("null"),
+        Value::Float(f) => s.push_str(&f.to_string()),
+        Value::String(st) => write_string(s, st),
+        Value::Array(arr) if arr.is_empty() => s.push_str("[]"),
+        Value::Array(arr) => {
+            s.push('[');
+            for (i, item) in arr.iter().enumerate() {
+                if i > 0 {
+                    s.push(',');
+                }
+                newline(s, indent + 1);
+                write_value(s, item, indent + 1);
+            }
+            newline(s, indent);
+            s.push(']');
+        }
+        Value::Object(obj) if obj.is_empty() => s.push_str("{}"),
+        Value::Object(obj) => {
+            s.push('{');
+            for (i, (key, item)) in obj.iter().enumerate() {
+                if i > 0 {
+                    s.push(',');
+                }
+                newline(s, indent + 1);
+                write_string(s, key);
+                s.push_str(": ");
+                write_value(s, item, indent + 1);
+            }
+            newline(s, indent);
+            s.push('}');
+        }
+    }
+}
+
+/// Write a newline followed by the indentation for the given level.
+fn newline(s: &mut String, indent: usize) {
+    s.push('\n');
+    for _ in 0..indent {
+        s.push_str("  ");
+    }
+}
+
+/// Write a quoted and escaped JSON string.
+fn write_string(s: &mut String, st: &str) {
+    s.push('"');
+    for c in st.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(s, "\\u{:04x}", c as u32);
+            }
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+}
+
+/**
+ * Parse a JSON string (like one created by `to_string_pretty`) into a `Value`.
+ *
+ * Numbers without a fraction or exponent that fit in an `i64` become
+ * `Value::Int`, all others become `Value::Float`.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::output::{json::from_str, Value};
+ *
+ * # fn main() -> anyhow::Result<()> {
+ * let v = from_str("[1, null]")?;
+ * assert_eq!(v, Value::Array(vec![Value::Int(1), Value::Null]));
+ * # Ok(())
+ * # }
+ * ```
+ */
+pub fn from_str(s: &str) -> Result<Value> {
+    let mut parser = Parser { chars: s.chars().peekable() };
+
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    ensure!(parser.chars.peek().is_none(), "trailing characters after JSON");
+
+    Ok(value)
+}
+
+/// A recursive descent JSON parser.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+    }
+
+    /// Consume the given character (after any whitespace) or fail.
+    fn expect(&mut self, want: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == want => Ok(()),
+            Some(c) => bail!("expected '{}', found '{}'", want, c),
+            None => bail!("expected '{}', found end of input", want),
+        }
+    }
+
+    /// Consume the given keyword (like `true`) or fail.
+    fn keyword(&mut self, word: &str, value: Value) -> Result<Value> {
+        for want in word.chars() {
+            ensure!(self.chars.next() == Some(want), "invalid keyword");
+        }
+
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('n') => self.keyword("null", Value::Null),
+            Some('t') => self.keyword("true", Value::Bool(true)),
+            Some('f') => self.keyword("false", Value::Bool(false)),
+            Some('"') => Ok(Value::String(self.parse_string()?)),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_object(),
+            Some(c) if *c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => bail!("unexpected character: '{}'", c),
+            None => bail!("unexpected end of input"),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value> {
+        self.expect('[')?;
+        let mut arr = vec![];
+
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&']').is_some() {
+            return Ok(Value::Array(arr));
+        }
+
+        loop {
+            arr.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(arr)),
+                _ => bail!("expected ',' or ']' in array"),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value> {
+        self.expect('{')?;
+        let mut obj = vec![];
+
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Ok(Value::Object(obj));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            obj.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Value::Object(obj)),
+                _ => bail!("expected ',' or '}}' in object"),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value> {
+        let mut s = String::new();
+        while let Some(c) =
+            self.chars.next_if(|c| c.is_ascii_digit() || "+-.eE".contains(*c))
+        {
+            s.push(c);
+        }
+
+        if let Ok(i) = s.parse() {
+            return Ok(Value::Int(i));
+        }
+
+        let f =
+            s.parse().with_context(|| format!("invalid number: '{}'", s))?;
+
+        Ok(Value::Float(f))
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut s = String::new();
+
+        loop {
+            let c = self.chars.next().context("unterminated string")?;
+
+            match c {
+                '"' => return Ok(s),
+                '\\' => s.push(self.parse_escape()?),
+                c => s.push(c),
+            }
+        }
+    }
+
+    /// Parse the character after a `\` in a string.
+    fn parse_escape(&mut self) -> Result<char> {
+        let c = match self.chars.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                let high = self.parse_hex4()?;
+                if !(0xd800..0xdc00).contains(&high) {
+                    return char::from_u32(high).context("invalid \\u escape");
+                }
+
+                // a surrogate pair (like "😀")
+                self.expect('\\')?;
+                self.expect('u')?;
+                let low = self.parse_hex4()?;
+                ensure!(
+                    (0xdc00..0xe000).contains(&low),
+                    "invalid surrogate pair"
+                );
+                let c = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                char::from_u32(c).context("invalid \\u escape")?
+            }
+            Some(c) => bail!("invalid escape: '\\{}'", c),
+            None => bail!("unterminated string"),
+        };
+
+        Ok(c)
+    }
+
+    /// Parse the 4 hex digits of a `\uXXXX` escape.
+    fn parse_hex4(&mut self) -> Result<u32> {
+        let s: String = self.chars.by_ref().take(4).collect();
+        ensure!(s.len() == 4, "truncated \\u escape");
+
+        u32::from_str_radix(&s, 16)
+            .with_context(|| format!("invalid \\u escape: '{}'", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_string_pretty_scalars() {
+        let arr = [
+            (Value::Null, "null"),
+            (Value::Bool(true), "true"),
+            (Value::Bool(false), "false"),
+            (Value::Int(-5), "-5"),
+            (Value::Float(12.5), "12.5"),
+            (Value::Float(f64::NAN), "null"),
+            (Value::from("foo"), "\"foo\""),
+            (Value::Array(vec![]), "[]"),
+            (Value::Object(vec![]), "{}"),
+        ];
+
+        for (value, s) in arr {
+            assert_eq!(to_string_pretty(&value), s, "scalar mismatch");
+        }
+    }
+
+    #[test]
+    fn test_to_string_pretty_escapes() {
+        let value = Value::from("a \"quoted\"\tline\n\\ \u{1}");
+
+        assert_eq!(
+            to_string_pretty(&value),
+            r#""a \"quoted\"\tline\n\\ \u0001""#,
+            "string is escaped"
+        );
+    }
+
+    #[test]
+    fn test_from_str() -> Result<()> {
+        let value = Value::Array(vec![
+            Value::Object(vec![
+                ("name".into(), "foo \"bar\"\n\u{1}".into()),
+                ("pid".into(), Value::Int(-123)),
+                ("cpu".into(), Value::Float(1.5)),
+                ("errors".into(), Value::Array(vec![])),
+            ]),
+            Value::Null,
+            Value::Bool(true),
+            Value::Object(vec![]),
+        ]);
+
+        let s = to_string_pretty(&value);
+        assert_eq!(from_str(&s)?, value, "round trip");
+
+        assert_eq!(
+            from_str(r#" "é😀\/" "#)?,
+            Value::from("\u{e9}\u{1f600}/"),
+            "unicode escapes"
+        );
+        assert_eq!(from_str("1e3")?, Value::Float(1000.0), "exponent");
+
+        for bad in ["", "[1,", "{\"a\" 1}", "tru", "\"abc", "1 2", "[1,]"] {
+            assert!(from_str(bad).is_err(), "invalid json: {:?}", bad);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_string_pretty_nested() {
+        let value = Value::Array(vec![Value::Object(vec![
+            ("name".into(), "foo".into()),
+            ("pid".into(), Value::Int(123)),
+        ])]);
+
+        assert_eq!(
+            to_string_pretty(&value),
+            "[\n  {\n    \"name\": \"foo\",\n    \"pid\": 123\n  }\n]",
+            "nested output is indented"
+        );
+    }
+}
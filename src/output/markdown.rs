@@ -0,0 +1,100 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! GitHub-flavored Markdown table serialization for `Value`.
+
+use super::Value;
+
+/**
+ * Serialize an array of objects as a Markdown table.
+ *
+ * Each object becomes 1 row with the fields given in `header` (in that
+ * order) - fields missing from an object become empty cells.  A header row is
+ * always written as Markdown tables require one.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::output::{markdown::to_string, Value};
+ *
+ * let v = Value::Array(vec![Value::Object(vec![("a".into(), "b|c".into())])]);
+ * assert_eq!(to_string(&["a"], &v), "| a |\n| --- |\n| b\\|c |\n");
+ * ```
+ */
+pub fn to_string(header: &[&str], records: &Value) -> String {
+    let mut s = String::new();
+
+    write_row(&mut s, header.iter().map(|h| h.to_string()));
+    write_row(&mut s, header.iter().map(|_| "---".to_string()));
+
+    let records = match records {
+        Value::Array(arr) => arr.as_slice(),
+        _ => &[],
+    };
+
+    for record in records {
+        write_row(
+            &mut s,
+            header.iter().map(|h| {
+                record.get(h).map_or_else(String::new, Value::to_cell)
+            }),
+        );
+    }
+
+    s
+}
+
+/// Write a single row of cells (and a trailing newline).
+fn write_row<I: Iterator<Item = String>>(s: &mut String, cells: I) {
+    s.push('|');
+    for c in cells {
+        s.push(' ');
+        s.push_str(&escape(&c));
+        s.push_str(" |");
+    }
+    s.push('\n');
+}
+
+/// Escape characters that would break out of a table cell.
+fn escape(st: &str) -> String {
+    st.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_string() {
+        let v = Value::Array(vec![
+            Value::Object(vec![
+                ("name".into(), "foo".into()),
+                ("pid".into(), Value::Int(1)),
+                ("command".into(), "a | b".into()),
+            ]),
+            Value::Object(vec![
+                ("name".into(), "bar".into()),
+                ("pid".into(), Value::Null),
+                ("errors".into(), Value::Array(vec!["a\nb".into()])),
+            ]),
+        ]);
+
+        let want = "| name | pid | command | errors |
+| --- | --- | --- | --- |
+| foo | 1 | a \\| b |  |
+| bar |  |  | a<br>b |
+";
+
+        assert_eq!(
+            to_string(&["name", "pid", "command", "errors"], &v),
+            want,
+            "markdown output"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! Machine-readable output formats for `vsv status`.
+
+pub mod csv;
+pub mod json;
+pub mod markdown;
+pub mod prometheus;
+pub mod raw;
+pub mod template;
+mod value;
+pub mod yaml;
+
+pub use value::Value;
+
+use crate::column::Column;
+use crate::service::Service;
+
+/**
+ * Get the field names (in order) of each object created by `services_to_value`
+ * for the given columns, optionally including the trailing `errors` field.
+ */
+pub fn fields(columns: &[Column], errors: bool) -> Vec<&'static str> {
+    let mut fields: Vec<&str> = columns
+        .iter()
+        .filter(|column| column.is_structured())
+        .map(Column::key)
+        .collect();
+
+    if errors {
+        fields.push("errors");
+    }
+
+    fields
+}
+
+/**
+ * Convert a list of gathered services (and any messages generated while
+ * gathering them) into a generic `Value` suitable for serializing.
+ *
+ * Each service becomes an object with a field for each of the given columns
+ * (that make sense outside of a table) and an `errors` array.
+ */
+pub fn services_to_value(
+    services: &[(Service, Vec<String>)],
+    columns: &[Column],
+) -> Value {
+    let arr = services
+        .iter()
+        .map(|(service, messages)| service_to_value(service, messages, columns))
+        .collect();
+
+    Value::Array(arr)
+}
+
+/// Convert a single service into a generic `Value` object.
+fn service_to_value(
+    service: &Service,
+    messages: &[String],
+    columns: &[Column],
+) -> Value {
+    let mut obj: Vec<(String, Value)> = columns
+        .iter()
+        .filter(|column| column.is_structured())
+        .map(|column| {
+            (column.key().to_string(), column_value(service, *column))
+        })
+        .collect();
+
+    let errors =
+        messages.iter().map(|message| Value::from(message.as_str())).collect();
+    obj.push(("errors".into(), Value::Array(errors)));
+
+    Value::Object(obj)
+}
+
+/// Get the (typed) value of a single column for a service.
+fn column_value(service: &Service, column: Column) -> Value {
+    match column {
+        Column::Status => service.format_column(column).0.into(),
+        Column::Name => service.name.as_str().into(),
+        Column::State => service.state.into(),
+        Column::Enabled => service.enabled.into(),
+        Column::Pid => service.pid.map(i64::from).into(),
+        Column::Command => service.command.clone().into(),
+        Column::Uptime => service.uptime().map(|t| t.as_secs() as i64).into(),
+        Column::Origin => service.origin.clone().into(),
+        Column::Host => service.host.clone().into(),
+        Column::LogSize => service.log_size.map(|size| size as i64).into(),
+        Column::Restarts => service.restarts.map(|n| n as i64).into(),
+        Column::Check => service.check.into(),
+        Column::Rss => service.rss.map(|rss| rss as i64).into(),
+        Column::Cpu => service.cpu.into(),
+        Column::Fds => service.fds.map(|fds| fds as i64).into(),
+        Column::Threads => service.threads.map(|n| n as i64).into(),
+        Column::Outdated => service.outdated.into(),
+        Column::Cgroup => service.cgroup.as_deref().into(),
+        Column::Nice => service.nice.into(),
+        Column::Class => service.class.into(),
+        Column::Oom => service.oom_score.into(),
+        Column::OomAdj => service.oom_score_adj.into(),
+        Column::IoRead => service.io_read.map(|n| n as i64).into(),
+        Column::IoWrite => service.io_write.map(|n| n as i64).into(),
+        Column::Container => service
+            .namespaces
+            .as_ref()
+            .map(|namespaces| !namespaces.is_empty())
+            .into(),
+        Column::CgroupMemory => {
+            service.cgroup_memory.map(|mem| mem as i64).into()
+        }
+        Column::User => match &service.user {
+            Some(user) => user.as_str().into(),
+            None => service.uid.map(i64::from).into(),
+        },
+    }
+}
@@ -0,0 +1,105 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! Prometheus text exposition format for services.
+
+use std::fmt::Write;
+
+use crate::service::{Service, ServiceState};
+
+/// A gauge name, its help text, and how to get its value for a service.
+type Gauge = (&'static str, &'static str, fn(&Service) -> Option<i64>);
+
+/**
+ * Format the given services as Prometheus gauges in the text exposition format
+ * (suitable for the node_exporter textfile collector).
+ *
+ * Samples that can't be determined (like the pid of a down service) are
+ * omitted rather than reported as `0`.  Log services (`-l`) are labeled with
+ * the service they follow and `log="true"`, and services from several trees
+ * or hosts with their `origin` or `host`, so no two samples share labels.
+ */
+pub fn to_string(services: &[(Service, Vec<String>)]) -> String {
+    let gauges: [Gauge; 3] = [
+        (
+            "vsv_service_up",
+            "Whether the service is in the run state (1) or not (0).",
+            |svc| Some(matches!(svc.state, ServiceState::Run).into()),
+        ),
+        (
+            "vsv_service_uptime_seconds",
+            "Seconds the service has been in its current state.",
+            |svc| svc.uptime().map(|t| t.as_secs() as i64),
+        ),
+        ("vsv_service_pid", "Process ID of the supervised process.", |svc| {
+            svc.pid.map(i64::from)
+        }),
+    ];
+
+    // log services follow the service they belong to
+    let mut parent = "";
+    let labels: Vec<String> = services
+        .iter()
+        .map(|(service, _)| {
+            if !service.log {
+                parent = &service.name;
+            }
+            format_labels(service, parent)
+        })
+        .collect();
+
+    let mut s = String::new();
+
+    for (name, help, f) in gauges {
+        let _ = writeln!(s, "# HELP {} {}", name, help);
+        let _ = writeln!(s, "# TYPE {} gauge", name);
+
+        for ((service, _), labels) in services.iter().zip(&labels) {
+            if let Some(value) = f(service) {
+                let _ = writeln!(s, "{}{{{}}} {}", name, labels, value);
+            }
+        }
+    }
+
+    s
+}
+
+/// Format the labels of a service's samples, `parent` is used for log services.
+fn format_labels(service: &Service, parent: &str) -> String {
+    let mut labels = vec![];
+    match service.log {
+        true => labels.extend([("service", parent), ("log", "true")]),
+        false => labels.push(("service", &service.name)),
+    }
+    if let Some(origin) = &service.origin {
+        labels.push(("origin", origin));
+    }
+    if let Some(host) = &service.host {
+        labels.push(("host", host));
+    }
+
+    labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Escape a label value for the Prometheus text format.
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("foo"), "foo");
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -0,0 +1,95 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! Raw (delimited, headerless) serialization for `Value`.
+
+use super::Value;
+
+/**
+ * Serialize an array of objects as raw, delimited records.
+ *
+ * Each object becomes 1 record with the fields given in `fields` (in that
+ * order) and no header.  By default fields are separated by tabs and records
+ * end with a newline.  If `null` is set every field is instead terminated by a
+ * NUL byte (like `find -print0`), so every record is exactly `fields.len()`
+ * NUL-terminated fields - suitable for `xargs -0 -n <num-fields>` or `read -d
+ * ''`.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::output::{raw::to_string, Value};
+ *
+ * let v = Value::Array(vec![Value::Object(vec![("a".into(), "b".into())])]);
+ * assert_eq!(to_string(&["a"], &v, true), "b\0");
+ * ```
+ */
+pub fn to_string(fields: &[&str], records: &Value, null: bool) -> String {
+    let mut s = String::new();
+
+    let records = match records {
+        Value::Array(arr) => arr.as_slice(),
+        _ => &[],
+    };
+
+    for record in records {
+        for (i, field) in fields.iter().enumerate() {
+            let cell =
+                record.get(field).map_or_else(String::new, Value::to_cell);
+
+            if null {
+                s.push_str(&cell);
+                s.push('\0');
+            } else {
+                if i > 0 {
+                    s.push('\t');
+                }
+                // tabs and newlines would break the record structure
+                s.push_str(&cell.replace(['\t', '\n'], " "));
+            }
+        }
+
+        if !null {
+            s.push('\n');
+        }
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Value {
+        Value::Array(vec![
+            Value::Object(vec![
+                ("name".into(), "foo".into()),
+                ("pid".into(), Value::Int(1)),
+                ("command".into(), "foo\targ one".into()),
+            ]),
+            Value::Object(vec![
+                ("name".into(), "bar".into()),
+                ("pid".into(), Value::Null),
+                ("command".into(), Value::Null),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn test_to_string_tabs() {
+        let s = to_string(&["name", "pid", "command"], &records(), false);
+
+        assert_eq!(s, "foo\t1\tfoo arg one\nbar\t\t\n", "tab output");
+    }
+
+    #[test]
+    fn test_to_string_null() {
+        let s = to_string(&["name", "pid", "command"], &records(), true);
+
+        assert_eq!(s, "foo\x001\0foo\targ one\0bar\0\0\0", "null output");
+    }
+}
@@ -0,0 +1,347 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * A small handlebars-style template engine for rendering `Value`.
+ *
+ * Supported tags:
+ *
+ * - `{{path}}` - the value at `path` (HTML escaped), like `name` or `a.b`.
+ * - `{{{path}}}` - the value at `path` (not escaped).
+ * - `{{#each path}} ... {{else}} ... {{/each}}` - loop over an array.
+ * - `{{#if path}} ... {{else}} ... {{/if}}` - render if the value is truthy.
+ * - `{{#unless path}} ... {{else}} ... {{/unless}}` - the inverse of `#if`.
+ * - `{{! comment }}` - ignored.
+ *
+ * Paths are looked up in the current scope (the item being looped over) and
+ * then each enclosing scope.  `this` refers to the current scope itself and
+ * `@index`, `@first` and `@last` are available inside of `#each`.
+ */
+
+use super::Value;
+
+use crate::error::{bail, Result};
+
+/// A single parsed piece of a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Text(String),
+    Var { path: String, escape: bool },
+    Each { path: String, body: Vec<Node>, otherwise: Vec<Node> },
+    If { path: String, negate: bool, body: Vec<Node>, otherwise: Vec<Node> },
+}
+
+/**
+ * Parse a template into a list of nodes to be given to `render`.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::output::template::parse;
+ *
+ * # fn main() -> anyhow::Result<()> {
+ * let nodes = parse("{{#each services}}{{name}}\n{{/each}}")?;
+ * assert_eq!(nodes.len(), 1);
+ * # Ok(())
+ * # }
+ * ```
+ */
+pub fn parse(s: &str) -> Result<Vec<Node>> {
+    let mut parser = Parser { s, pos: 0 };
+    let (nodes, end) = parser.parse_nodes()?;
+
+    if let Some(tag) = end {
+        bail!("unexpected '{{{{{}}}}}'", tag);
+    }
+
+    Ok(nodes)
+}
+
+/// Render parsed template nodes using `value` as the top-level scope.
+pub fn render(nodes: &[Node], value: &Value) -> String {
+    let mut out = String::new();
+    let mut scopes = vec![Scope { value, index: None }];
+
+    render_nodes(nodes, &mut scopes, &mut out);
+
+    out
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /**
+     * Parse nodes until the end of input or a closing (or `else`) tag, which
+     * is returned (without braces) alongside the nodes.
+     */
+    fn parse_nodes(&mut self) -> Result<(Vec<Node>, Option<String>)> {
+        let mut nodes = vec![];
+
+        loop {
+            let rest = &self.s[self.pos..];
+
+            let start = match rest.find("{{") {
+                Some(start) => start,
+                None => {
+                    if !rest.is_empty() {
+                        nodes.push(Node::Text(rest.to_string()));
+                    }
+                    self.pos = self.s.len();
+                    return Ok((nodes, None));
+                }
+            };
+
+            if start > 0 {
+                nodes.push(Node::Text(rest[..start].to_string()));
+            }
+            self.pos += start;
+
+            let raw = rest[start..].starts_with("{{{");
+            let (open, close) = if raw { ("{{{", "}}}") } else { ("{{", "}}") };
+            let inner_start = self.pos + open.len();
+            let len = match self.s[inner_start..].find(close) {
+                Some(len) => len,
+                None => bail!("unterminated tag at offset {}", self.pos),
+            };
+            let tag = self.s[inner_start..inner_start + len].trim();
+            self.pos = inner_start + len + close.len();
+
+            if raw {
+                nodes.push(Node::Var { path: tag.to_string(), escape: false });
+                continue;
+            }
+
+            if tag.starts_with('!') {
+                continue;
+            }
+
+            if tag == "else" || tag.starts_with('/') {
+                return Ok((nodes, Some(tag.to_string())));
+            }
+
+            if let Some(block) = tag.strip_prefix('#') {
+                nodes.push(self.parse_block(block)?);
+                continue;
+            }
+
+            if tag.is_empty() {
+                bail!("empty tag at offset {}", self.pos);
+            }
+
+            nodes.push(Node::Var { path: tag.to_string(), escape: true });
+        }
+    }
+
+    /// Parse the body of a block like `#each services` (given without `#`).
+    fn parse_block(&mut self, block: &str) -> Result<Node> {
+        let (name, path) = match block.split_once(char::is_whitespace) {
+            Some((name, path)) => (name, path.trim().to_string()),
+            None => bail!("block '{{{{#{}}}}}' is missing a path", block),
+        };
+
+        if !["each", "if", "unless"].contains(&name) {
+            bail!("unknown block '{{{{#{}}}}}'", name);
+        }
+
+        let closing = format!("/{}", name);
+        let (body, end) = self.parse_nodes()?;
+        let otherwise = match end.as_deref() {
+            Some("else") => match self.parse_nodes()? {
+                (otherwise, Some(end)) if end == closing => otherwise,
+                _ => bail!("block '{{{{#{}}}}}' is not closed", name),
+            },
+            Some(end) if end == closing => vec![],
+            _ => bail!("block '{{{{#{}}}}}' is not closed", name),
+        };
+
+        let node = match name {
+            "each" => Node::Each { path, body, otherwise },
+            _ => Node::If { path, negate: name == "unless", body, otherwise },
+        };
+
+        Ok(node)
+    }
+}
+
+/// A scope values are looked up in, `index` is set when inside of `#each`.
+struct Scope<'v> {
+    value: &'v Value,
+    index: Option<(usize, usize)>,
+}
+
+fn render_nodes<'v>(
+    nodes: &[Node],
+    scopes: &mut Vec<Scope<'v>>,
+    out: &mut String,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(s) => out.push_str(s),
+            Node::Var { path, escape } => {
+                let s = lookup(scopes, path).to_cell();
+                if *escape {
+                    out.push_str(&escape_html(&s));
+                } else {
+                    out.push_str(&s);
+                }
+            }
+            Node::Each { path, body, otherwise } => {
+                let items = match lookup_ref(scopes, path) {
+                    Some(Value::Array(items)) if !items.is_empty() => items,
+                    _ => {
+                        render_nodes(otherwise, scopes, out);
+                        continue;
+                    }
+                };
+
+                for (i, value) in items.iter().enumerate() {
+                    scopes.push(Scope { value, index: Some((i, items.len())) });
+                    render_nodes(body, scopes, out);
+                    scopes.pop();
+                }
+            }
+            Node::If { path, negate, body, otherwise } => {
+                if is_truthy(&lookup(scopes, path)) != *negate {
+                    render_nodes(body, scopes, out);
+                } else {
+                    render_nodes(otherwise, scopes, out);
+                }
+            }
+        }
+    }
+}
+
+/// Look up a path (including `@` variables), missing values become `Null`.
+fn lookup(scopes: &[Scope], path: &str) -> Value {
+    let index = scopes.iter().rev().find_map(|scope| scope.index);
+
+    match (path, index) {
+        ("@index", Some((i, _))) => Value::Int(i as i64),
+        ("@first", Some((i, _))) => Value::Bool(i == 0),
+        ("@last", Some((i, len))) => Value::Bool(i + 1 == len),
+        _ => lookup_ref(scopes, path).cloned().unwrap_or(Value::Null),
+    }
+}
+
+/**
+ * Look up a dotted path like `a.b`.  The first part is searched for in each
+ * scope (innermost first) and the rest are looked up in the value found.
+ */
+fn lookup_ref<'v>(scopes: &[Scope<'v>], path: &str) -> Option<&'v Value> {
+    let mut parts = path.split('.');
+    let first = parts.next()?;
+
+    let mut value = if first == "this" {
+        scopes.last()?.value
+    } else {
+        scopes.iter().rev().find_map(|scope| scope.value.get(first))?
+    };
+
+    for part in parts {
+        value = value.get(part)?;
+    }
+
+    Some(value)
+}
+
+/// Whether a value is considered "true" by `#if` and `#unless`.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Int(i) => *i != 0,
+        Value::Float(f) => *f != 0.0,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(arr) => !arr.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+/// Escape text for safe use in HTML.
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> Value {
+        let service = |name: &str, pid: Option<i64>| {
+            Value::Object(vec![
+                ("name".into(), name.into()),
+                ("pid".into(), pid.into()),
+            ])
+        };
+
+        Value::Object(vec![
+            ("svdir".into(), "/var/service".into()),
+            (
+                "services".into(),
+                Value::Array(vec![
+                    service("a<b>", Some(1)),
+                    service("c", None),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let template = "{{! comment }}{{svdir}}:
+{{#each services}}{{@index}} {{name}} {{{name}}} {{#if pid}}{{pid}}{{else}}down{{/if}} {{svdir}}{{#unless @last}},{{/unless}}
+{{/each}}";
+        let nodes = parse(template)?;
+
+        let want = "/var/service:
+0 a&lt;b&gt; a<b> 1 /var/service,
+1 c c down /var/service
+";
+        assert_eq!(render(&nodes, &context()), want, "template rendered");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_each_else() -> Result<()> {
+        let nodes = parse("{{#each missing}}x{{else}}none{{/each}}")?;
+        assert_eq!(render(&nodes, &context()), "none", "empty each");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bad() {
+        for s in [
+            "{{name",
+            "{{}}",
+            "{{#each services}}",
+            "{{#if x}}{{/each}}",
+            "{{#bogus x}}{{/bogus}}",
+            "{{#each}}{{/each}}",
+            "{{/if}}",
+            "{{else}}",
+        ] {
+            assert!(parse(s).is_err(), "'{}' is invalid", s);
+        }
+    }
+}
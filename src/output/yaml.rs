@@ -0,0 +1,218 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! YAML serialization for `Value`.
+
+use super::{json, Value};
+
+/**
+ * Serialize a `Value` as a single YAML document (starting with `---`).
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::output::{yaml::to_string, Value};
+ *
+ * let v = Value::Object(vec![("name".into(), "foo".into())]);
+ * assert_eq!(to_string(&v), "---\nname: foo\n");
+ * ```
+ */
+pub fn to_string(value: &Value) -> String {
+    let mut s = String::from("---");
+
+    match value {
+        Value::Object(obj) if !obj.is_empty() => {
+            s.push('\n');
+            write_entries(&mut s, obj, 0, false);
+        }
+        Value::Array(arr) if !arr.is_empty() => {
+            s.push('\n');
+            write_items(&mut s, arr, 0);
+        }
+        _ => write_node(&mut s, value, 0),
+    }
+
+    s
+}
+
+/**
+ * Write a value that follows a `key:` or `-` marker.  Scalars (and empty
+ * containers) are written inline, everything else is written as a block on the
+ * following lines at the given indentation level.
+ */
+fn write_node(s: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(obj) if !obj.is_empty() => {
+            s.push('\n');
+            write_entries(s, obj, indent, false);
+        }
+        Value::Array(arr) if !arr.is_empty() => {
+            s.push('\n');
+            write_items(s, arr, indent);
+        }
+        _ => {
+            s.push(' ');
+            s.push_str(&scalar(value));
+            s.push('\n');
+        }
+    }
+}
+
+/// Write an object's entries, optionally skipping the first line's padding.
+fn write_entries(
+    s: &mut String,
+    obj: &[(String, Value)],
+    indent: usize,
+    inline_first: bool,
+) {
+    for (i, (key, value)) in obj.iter().enumerate() {
+        if i > 0 || !inline_first {
+            pad(s, indent);
+        }
+        s.push_str(&string(key));
+        s.push(':');
+        write_node(s, value, indent + 2);
+    }
+}
+
+/// Write an array's items as a block sequence.
+fn write_items(s: &mut String, arr: &[Value], indent: usize) {
+    for item in arr {
+        pad(s, indent);
+        s.push('-');
+        match item {
+            // objects in a sequence start on the same line as the dash
+            Value::Object(obj) if !obj.is_empty() => {
+                s.push(' ');
+                write_entries(s, obj, indent + 2, true);
+            }
+            _ => write_node(s, item, indent + 2),
+        }
+    }
+}
+
+/// Write the indentation (in spaces) for the given indentation level.
+fn pad(s: &mut String, indent: usize) {
+    for _ in 0..indent {
+        s.push(' ');
+    }
+}
+
+/// Format a scalar (or empty container) value.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".into(),
+        Value::String(st) => string(st),
+        Value::Array(_) => "[]".into(),
+        Value::Object(_) => "{}".into(),
+        _ => json::to_string_pretty(value),
+    }
+}
+
+/**
+ * Format a string, only quoting it if YAML could otherwise interpret it as
+ * something other than a plain string.
+ */
+fn string(st: &str) -> String {
+    let reserved = [
+        "", "~", "null", "Null", "NULL", "true", "True", "TRUE", "false",
+        "False", "FALSE", "yes", "Yes", "YES", "no", "No", "NO", "on", "On",
+        "ON", "off", "Off", "OFF",
+    ];
+    let special = |c: char| {
+        c.is_control()
+            || matches!(
+                c,
+                ':' | '#'
+                    | '"'
+                    | '\''
+                    | '{'
+                    | '}'
+                    | '['
+                    | ']'
+                    | ','
+                    | '&'
+                    | '*'
+                    | '!'
+                    | '|'
+                    | '>'
+                    | '%'
+                    | '@'
+                    | '`'
+                    | '\\'
+            )
+    };
+
+    let needs_quotes = reserved.contains(&st)
+        || st.starts_with(|c: char| c.is_whitespace() || c == '-' || c == '?')
+        || st.ends_with(char::is_whitespace)
+        || st.parse::<f64>().is_ok()
+        || st.contains(special);
+
+    if needs_quotes {
+        // a JSON string is a valid YAML double-quoted string
+        json::to_string_pretty(&Value::from(st))
+    } else {
+        st.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_string_quoting() {
+        let arr = [
+            ("foo", "foo"),
+            ("foo bar", "foo bar"),
+            ("/var/service", "/var/service"),
+            ("", "\"\""),
+            ("true", "\"true\""),
+            ("null", "\"null\""),
+            ("123", "\"123\""),
+            ("- log", "\"- log\""),
+            ("a: b", "\"a: b\""),
+            ("trailing ", "\"trailing \""),
+        ];
+
+        for (input, want) in arr {
+            let v = Value::Object(vec![("k".into(), input.into())]);
+            let want = format!("---\nk: {}\n", want);
+            assert_eq!(to_string(&v), want, "quoting mismatch for {}", input);
+        }
+    }
+
+    #[test]
+    fn test_to_string_nested() {
+        let v = Value::Object(vec![
+            ("svdir".into(), "/var/service".into()),
+            (
+                "services".into(),
+                Value::Array(vec![Value::Object(vec![
+                    ("name".into(), "foo".into()),
+                    ("pid".into(), Value::Int(123)),
+                    ("command".into(), Value::Null),
+                    ("errors".into(), Value::Array(vec!["oops".into()])),
+                    ("warnings".into(), Value::Array(vec![])),
+                ])]),
+            ),
+        ]);
+
+        let want = "---
+svdir: /var/service
+services:
+  - name: foo
+    pid: 123
+    command: null
+    errors:
+      - oops
+    warnings: []
+";
+
+        assert_eq!(to_string(&v), want, "nested output is indented");
+    }
+}
@@ -0,0 +1,79 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Pipe output through a pager (like `git` does).
+ */
+
+use std::io::{self, Write};
+use std::os::unix::io::AsRawFd;
+use std::process::{Child, Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+
+use crate::config;
+
+/**
+ * A running pager that stdout has been redirected to.  Dropping it restores
+ * the original stdout and waits for the pager to exit.
+ */
+pub struct Pager {
+    child: Child,
+    stdout: libc::c_int,
+}
+
+impl Pager {
+    /**
+     * Spawn the given pager command (with `sh -c`) and redirect stdout to it.
+     *
+     * # Example
+     *
+     * ```
+     * let pager = Pager::start("less -R")?;
+     * println!("this goes to less");
+     * drop(pager);
+     * ```
+     */
+    pub fn start(cmd: &str) -> Result<Self> {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(cmd).stdin(Stdio::piped());
+
+        // like git: quit if the output fits on 1 screen, keep colors and don't
+        // clear the screen
+        if std::env::var_os(config::ENV_LESS).is_none() {
+            command.env(config::ENV_LESS, "FRX");
+        }
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("failed to spawn pager: {}", cmd))?;
+
+        let stdin = child.stdin.take().expect("pager stdin is piped");
+
+        io::stdout().flush()?;
+        let stdout = unsafe { libc::dup(1) };
+        if stdout < 0 {
+            bail!("failed to dup stdout: {}", io::Error::last_os_error());
+        }
+        if unsafe { libc::dup2(stdin.as_raw_fd(), 1) } < 0 {
+            bail!("failed to redirect stdout: {}", io::Error::last_os_error());
+        }
+
+        Ok(Self { child, stdout })
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        // restoring stdout closes the pipe so the pager sees EOF
+        let _ = io::stdout().flush();
+        unsafe {
+            libc::dup2(self.stdout, 1);
+            libc::close(self.stdout);
+        }
+        let _ = self.child.wait();
+    }
+}
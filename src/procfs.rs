@@ -0,0 +1,854 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! Process information read from a Linux procfs (like `/proc`).
+
+use libc::pid_t;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::error::{bail, Context, Result};
+
+/**
+ * How long processes are watched to measure their CPU usage when there is no
+ * earlier sample of them (from the last refresh in watch mode).
+ */
+pub const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The last CPU time (in clock ticks) seen for every process, by pid.
+static CPU_SAMPLES: Mutex<BTreeMap<pid_t, (u64, Instant)>> =
+    Mutex::new(BTreeMap::new());
+
+/**
+ * Read a file that can contain a process's command name (like `stat`), which
+ * can be any bytes (not just UTF-8), replacing invalid UTF-8 with `U+FFFD`.
+ */
+fn read_lossy(p: &Path) -> io::Result<String> {
+    let data = fs::read(p)?;
+
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+/**
+ * Read the value of a field (like `VmRSS`) from `/proc/<pid>/status`.
+ *
+ * Returns `None` if the process has no such field (kernel threads and zombies
+ * have no memory fields, for example).
+ */
+pub fn status_field(
+    pid: pid_t,
+    proc_path: &Path,
+    key: &str,
+) -> Result<Option<String>> {
+    // /<proc_path>/<pid>/status
+    let p = proc_path.join(pid.to_string()).join("status");
+
+    let data =
+        read_lossy(&p).with_context(|| format!("failed to read {:?}", p))?;
+
+    Ok(parse_status_field(&data, key))
+}
+
+/// Find a field in the contents of a `/proc/<pid>/status` file.
+fn parse_status_field(data: &str, key: &str) -> Option<String> {
+    data.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k == key).then(|| v.trim().to_string())
+    })
+}
+
+/**
+ * Get the resident memory (in bytes) of a process from `VmRSS` in
+ * `/proc/<pid>/status`, `None` if the process has none.
+ */
+pub fn rss_from_pid(pid: pid_t, proc_path: &Path) -> Result<Option<u64>> {
+    let value = match status_field(pid, proc_path, "VmRSS")? {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    // "1234 kB"
+    let kb = match value.strip_suffix("kB") {
+        Some(kb) => kb.trim(),
+        None => bail!("unknown VmRSS unit: '{}'", value),
+    };
+
+    let kb: u64 =
+        kb.parse().with_context(|| format!("invalid VmRSS: '{}'", value))?;
+
+    Ok(Some(kb * 1024))
+}
+
+/**
+ * Get the (effective) user id a process runs as from `Uid` in
+ * `/proc/<pid>/status`.
+ */
+pub fn uid_from_pid(pid: pid_t, proc_path: &Path) -> Result<libc::uid_t> {
+    let value = status_field(pid, proc_path, "Uid")?.context("no Uid found")?;
+
+    // real, effective, saved set and filesystem uids
+    let uid = value
+        .split_whitespace()
+        .nth(1)
+        .with_context(|| format!("no effective uid in '{}'", value))?;
+
+    uid.parse().with_context(|| format!("invalid uid: '{}'", uid))
+}
+
+/// Get the number of threads in a process from `Threads` in
+/// `/proc/<pid>/status`.
+pub fn threads_from_pid(pid: pid_t, proc_path: &Path) -> Result<u64> {
+    let value =
+        status_field(pid, proc_path, "Threads")?.context("no Threads found")?;
+
+    value.parse().with_context(|| format!("invalid Threads: '{}'", value))
+}
+
+/**
+ * Get the cgroup a process is in from `/proc/<pid>/cgroup`: its cgroup2 path
+ * (like `/system.slice/sshd.service`) if there is one, otherwise the path in
+ * the first cgroup1 hierarchy listed.
+ */
+pub fn cgroup_from_pid(pid: pid_t, proc_path: &Path) -> Result<String> {
+    // /<proc_path>/<pid>/cgroup
+    let p = proc_path.join(pid.to_string()).join("cgroup");
+
+    let data = fs::read_to_string(&p)
+        .with_context(|| format!("failed to read {:?}", p))?;
+
+    parse_cgroup(&data).with_context(|| format!("no cgroup found in {:?}", p))
+}
+
+/// Find the cgroup path in the contents of a `/proc/<pid>/cgroup` file.
+fn parse_cgroup(data: &str) -> Option<String> {
+    // "<id>:<controllers>:<path>", cgroup2 is "0::<path>"
+    let paths: Vec<(&str, &str)> = data
+        .lines()
+        .filter_map(|line| {
+            let (id, rest) = line.split_once(':')?;
+            let (_, path) = rest.split_once(':')?;
+            Some((id, path))
+        })
+        .collect();
+
+    paths
+        .iter()
+        .find(|(id, _)| *id == "0")
+        .or_else(|| paths.first())
+        .map(|(_, path)| path.to_string())
+}
+
+/**
+ * Get the memory used by everything in a cgroup (2) from its `memory.current`
+ * file, under `cgroup_path` (where cgroup2 is mounted, like `/sys/fs/cgroup`).
+ */
+pub fn cgroup_memory(cgroup_path: &Path, cgroup: &str) -> Result<u64> {
+    // /<cgroup_path>/<cgroup>/memory.current
+    let p =
+        cgroup_path.join(cgroup.trim_start_matches('/')).join("memory.current");
+
+    let data = fs::read_to_string(&p)
+        .with_context(|| format!("failed to read {:?}", p))?;
+
+    data.trim()
+        .parse()
+        .with_context(|| format!("invalid memory.current: '{}'", data.trim()))
+}
+
+/**
+ * Get the namespaces (of `kinds`, like `pid` and `mnt`) a process is in that
+ * are different from the ones `vsv` itself is in, by comparing the
+ * `/proc/<pid>/ns/<kind>` links (like `pid:[4026531836]`) with
+ * `/proc/self/ns/<kind>`.
+ */
+pub fn foreign_namespaces(
+    pid: pid_t,
+    proc_path: &Path,
+    kinds: &[&'static str],
+) -> Result<Vec<&'static str>> {
+    let ns = |dir: &str, kind: &str| {
+        // /<proc_path>/<pid>/ns/<kind>
+        let p = proc_path.join(dir).join("ns").join(kind);
+        fs::read_link(&p)
+            .with_context(|| format!("failed to read link {:?}", p))
+    };
+
+    let mut foreign = vec![];
+    for kind in kinds {
+        if ns(&pid.to_string(), kind)? != ns("self", kind)? {
+            foreign.push(*kind);
+        }
+    }
+
+    Ok(foreign)
+}
+
+/// Get the environment (`NAME=value` strings) of a process from
+/// `/proc/<pid>/environ`.
+pub fn environ(pid: pid_t, proc_path: &Path) -> Result<Vec<String>> {
+    // /<proc_path>/<pid>/environ
+    let p = proc_path.join(pid.to_string()).join("environ");
+
+    let data =
+        fs::read(&p).with_context(|| format!("failed to read {:?}", p))?;
+
+    Ok(data
+        .split(|b| *b == 0)
+        .filter(|var| !var.is_empty())
+        .map(|var| String::from_utf8_lossy(var).into_owned())
+        .collect())
+}
+
+/**
+ * Check if the executable a process was started from has since been deleted
+ * (or replaced, like by a package upgrade) from where `/proc/<pid>/exe`
+ * points.
+ */
+pub fn exe_deleted(pid: pid_t, proc_path: &Path) -> Result<bool> {
+    // /<proc_path>/<pid>/exe
+    let p = proc_path.join(pid.to_string()).join("exe");
+
+    let exe = fs::read_link(&p)
+        .with_context(|| format!("failed to read link {:?}", p))?;
+
+    Ok(exe.to_string_lossy().ends_with(" (deleted)"))
+}
+
+/**
+ * Get how likely a process is to be killed when out of memory from
+ * `/proc/<pid>/oom_score` (higher is more likely), and the adjustment made to
+ * it from `/proc/<pid>/oom_score_adj` (`-1000` to `1000`).
+ */
+pub fn oom_from_pid(pid: pid_t, proc_path: &Path) -> Result<(i64, i64)> {
+    let read = |file: &str| -> Result<i64> {
+        // /<proc_path>/<pid>/<file>
+        let p = proc_path.join(pid.to_string()).join(file);
+
+        let data = fs::read_to_string(&p)
+            .with_context(|| format!("failed to read {:?}", p))?;
+
+        data.trim()
+            .parse()
+            .with_context(|| format!("invalid {}: '{}'", file, data.trim()))
+    };
+
+    Ok((read("oom_score")?, read("oom_score_adj")?))
+}
+
+/**
+ * Get the bytes a process has read from and written to storage (not the page
+ * cache) from `read_bytes` and `write_bytes` in `/proc/<pid>/io`.
+ */
+pub fn io_from_pid(pid: pid_t, proc_path: &Path) -> Result<(u64, u64)> {
+    // /<proc_path>/<pid>/io
+    let p = proc_path.join(pid.to_string()).join("io");
+
+    let data = fs::read_to_string(&p)
+        .with_context(|| format!("failed to read {:?}", p))?;
+
+    // same "key: value" lines as the status file
+    let field = |key: &str| -> Result<u64> {
+        let value = parse_status_field(&data, key)
+            .with_context(|| format!("no {} found in {:?}", key, p))?;
+        value.parse().with_context(|| format!("invalid {}: '{}'", key, value))
+    };
+
+    Ok((field("read_bytes")?, field("write_bytes")?))
+}
+
+/// Count the open file descriptors of a process in `/proc/<pid>/fd`.
+pub fn fd_count(pid: pid_t, proc_path: &Path) -> Result<usize> {
+    // /<proc_path>/<pid>/fd
+    let p = proc_path.join(pid.to_string()).join("fd");
+
+    let entries =
+        fs::read_dir(&p).with_context(|| format!("failed to read {:?}", p))?;
+
+    Ok(entries.count())
+}
+
+/**
+ * Get the (soft) limit on open files for a process from `Max open files` in
+ * `/proc/<pid>/limits`, `None` if it is unlimited.
+ */
+pub fn open_files_limit(pid: pid_t, proc_path: &Path) -> Result<Option<u64>> {
+    // /<proc_path>/<pid>/limits
+    let p = proc_path.join(pid.to_string()).join("limits");
+
+    let data = fs::read_to_string(&p)
+        .with_context(|| format!("failed to read {:?}", p))?;
+
+    parse_limit(&data, "Max open files")
+        .with_context(|| format!("failed to parse {:?}", p))
+}
+
+/// The names of resource limits in `limits` files, and the names `prlimit(1)`
+/// (and `RLIMIT_*`) give them.
+const LIMIT_NAMES: &[(&str, &str)] = &[
+    ("Max cpu time", "cpu"),
+    ("Max file size", "fsize"),
+    ("Max data size", "data"),
+    ("Max stack size", "stack"),
+    ("Max core file size", "core"),
+    ("Max resident set", "rss"),
+    ("Max processes", "nproc"),
+    ("Max open files", "nofile"),
+    ("Max locked memory", "memlock"),
+    ("Max address space", "as"),
+    ("Max file locks", "locks"),
+    ("Max pending signals", "sigpending"),
+    ("Max msgqueue size", "msgqueue"),
+    ("Max nice priority", "nice"),
+    ("Max realtime priority", "rtprio"),
+    ("Max realtime timeout", "rttime"),
+];
+
+/// A single resource limit of a process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limit {
+    /// Short name (like `nofile`).
+    pub name: &'static str,
+
+    /// Name as given in the `limits` file (like `Max open files`).
+    pub description: &'static str,
+
+    /// Soft and hard limits, like `1024` or `unlimited`.
+    pub soft: String,
+    pub hard: String,
+
+    /// Units of the limits, like `files`, empty if they have none.
+    pub units: String,
+}
+
+/// Get every resource limit of a process from `/proc/<pid>/limits`.
+pub fn limits(pid: pid_t, proc_path: &Path) -> Result<Vec<Limit>> {
+    // /<proc_path>/<pid>/limits
+    let p = proc_path.join(pid.to_string()).join("limits");
+
+    let data = fs::read_to_string(&p)
+        .with_context(|| format!("failed to read {:?}", p))?;
+
+    Ok(parse_limits(&data))
+}
+
+/// Parse every (known) limit in the contents of a `limits` file.
+fn parse_limits(data: &str) -> Vec<Limit> {
+    data.lines()
+        .filter_map(|line| {
+            let (description, name, rest) =
+                LIMIT_NAMES.iter().find_map(|(description, name)| {
+                    let rest = line.strip_prefix(description)?;
+                    Some((*description, *name, rest))
+                })?;
+
+            let mut fields = rest.split_whitespace();
+            Some(Limit {
+                name,
+                description,
+                soft: fields.next()?.to_string(),
+                hard: fields.next()?.to_string(),
+                units: fields.next().unwrap_or("").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Find the soft limit called `name` in the contents of a `limits` file.
+fn parse_limit(data: &str, name: &str) -> Result<Option<u64>> {
+    // "Max open files            1024                 524288               files"
+    let line = data
+        .lines()
+        .find_map(|line| line.strip_prefix(name))
+        .with_context(|| format!("no {} limit found", name))?;
+
+    match line.split_whitespace().next() {
+        Some("unlimited") => Ok(None),
+        Some(soft) => Ok(Some(
+            soft.parse()
+                .with_context(|| format!("invalid {}: '{}'", name, soft))?,
+        )),
+        None => bail!("no {} limit found", name),
+    }
+}
+
+/**
+ * A process's `/proc/<pid>/stat`, read once (see `Stat::read`) for every field
+ * wanted from it.
+ */
+#[derive(Debug)]
+pub struct Stat {
+    path: PathBuf,
+    data: String,
+}
+
+impl Stat {
+    /// Read `/proc/<pid>/stat`.
+    pub fn read(pid: pid_t, proc_path: &Path) -> Result<Self> {
+        // /<proc_path>/<pid>/stat
+        let path = proc_path.join(pid.to_string()).join("stat");
+
+        let data = read_lossy(&path)
+            .with_context(|| format!("failed to read {:?}", path))?;
+
+        Ok(Self { path, data })
+    }
+
+    /// Add the file parsed to the error of parsing a field.
+    fn parse<T>(&self, parse: fn(&str) -> Result<T>) -> Result<T> {
+        parse(&self.data)
+            .with_context(|| format!("failed to parse {:?}", self.path))
+    }
+
+    /**
+     * Get the state letter of the process (like `R` running, `S` sleeping,
+     * `Z` zombie or `T` stopped).
+     */
+    pub fn state(&self) -> Result<char> {
+        self.parse(parse_stat_state)
+    }
+
+    /**
+     * Get the nice value and scheduling class (like `TS`, `FF` or `RR`, as
+     * `ps -o cls` shows them) of the process.
+     */
+    pub fn sched(&self) -> Result<(i64, &'static str)> {
+        self.parse(parse_stat_sched)
+    }
+
+    /**
+     * Get the CPU time (in clock ticks) used by the process: its user and
+     * system time plus that of its children that have exited (and been
+     * waited for).
+     */
+    pub fn cpu_ticks(&self) -> Result<u64> {
+        self.parse(parse_stat_ticks)
+    }
+
+    /**
+     * Get the time the process started from its `starttime` (clock ticks
+     * after boot) and the boot time (`btime`) in `/proc/stat`.
+     */
+    pub fn start_time(&self, proc_path: &Path) -> Result<SystemTime> {
+        let ticks = self.parse(parse_stat_start)?;
+
+        // /<proc_path>/stat
+        let p = proc_path.join("stat");
+        let data = fs::read_to_string(&p)
+            .with_context(|| format!("failed to read {:?}", p))?;
+        let btime = data
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))
+            .with_context(|| format!("no btime found in {:?}", p))?;
+        let btime: u64 = btime
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid btime: '{}'", btime))?;
+
+        Ok(UNIX_EPOCH
+            + Duration::from_secs(btime)
+            + Duration::from_secs_f64(ticks as f64 / clock_ticks()))
+    }
+}
+
+/**
+ * Split a `stat` file into the command name and the fields after it (so the
+ * state, field 3 counting from 1, is the first).
+ */
+fn parse_stat(data: &str) -> Result<(&str, Vec<&str>)> {
+    // the command name (in parens) can contain spaces and parens itself
+    let (start, rest) =
+        data.rsplit_once(')').context("no command name found")?;
+    let (_, name) = start.split_once('(').context("no command name found")?;
+
+    Ok((name, rest.split_whitespace().collect()))
+}
+
+/// Get the state letter (field 3) from a `stat` file.
+fn parse_stat_state(data: &str) -> Result<char> {
+    let (_, fields) = parse_stat(data)?;
+    let state = fields.first().context("no state found")?;
+
+    state.chars().next().context("no state found")
+}
+
+/// Scheduling classes (like `ps -o cls`) `Stat::sched` can return.
+pub const SCHED_CLASSES: &[&str] = &["TS", "FF", "RR", "B", "IDL", "DLN", "?"];
+
+/// Get the nice value (field 19) and policy (field 41) from a `stat` file.
+fn parse_stat_sched(data: &str) -> Result<(i64, &'static str)> {
+    let (_, fields) = parse_stat(data)?;
+    let field = |n: usize| fields.get(n - 3).context("too few fields");
+
+    let nice = field(19)?;
+    let nice = nice
+        .parse()
+        .with_context(|| format!("invalid nice value: '{}'", nice))?;
+
+    // SCHED_* from sched.h
+    let class = match *field(41)? {
+        "0" => "TS",
+        "1" => "FF",
+        "2" => "RR",
+        "3" => "B",
+        "5" => "IDL",
+        "6" => "DLN",
+        _ => "?",
+    };
+
+    Ok((nice, class))
+}
+
+/// Add up `utime`, `stime`, `cutime` and `cstime` from a `stat` file.
+fn parse_stat_ticks(data: &str) -> Result<u64> {
+    let (_, fields) = parse_stat(data)?;
+
+    // fields 14 through 17 (1-indexed), the state is field 3
+    let mut ticks = 0;
+    for field in fields.get(11..15).context("too few fields")? {
+        ticks += field.parse::<u64>()?;
+    }
+
+    Ok(ticks)
+}
+
+/**
+ * Measure the CPU usage of processes as a percent of one CPU (like `top`,
+ * a busy multithreaded process can go over 100).
+ *
+ * Usage is measured since the last time a process was measured if it was
+ * (like the last refresh in watch mode), otherwise over
+ * `CPU_SAMPLE_INTERVAL` - all processes are watched at the same time.
+ */
+pub fn cpu_usage(pids: &[pid_t], proc_path: &Path) -> Vec<Result<f64>> {
+    let hz = clock_ticks();
+
+    let mut samples = CPU_SAMPLES.lock().unwrap();
+
+    // take a first sample of any process not seen before
+    let new: Vec<pid_t> =
+        pids.iter().filter(|pid| !samples.contains_key(pid)).copied().collect();
+    if !new.is_empty() {
+        for pid in new {
+            if let Ok(ticks) =
+                Stat::read(pid, proc_path).and_then(|stat| stat.cpu_ticks())
+            {
+                samples.insert(pid, (ticks, Instant::now()));
+            }
+        }
+        thread::sleep(CPU_SAMPLE_INTERVAL);
+    }
+
+    let mut next = BTreeMap::new();
+    let usage = pids
+        .iter()
+        .map(|pid| {
+            let ticks = Stat::read(*pid, proc_path)?.cpu_ticks()?;
+            let now = Instant::now();
+            next.insert(*pid, (ticks, now));
+
+            let (last_ticks, last) =
+                samples.get(pid).context("no earlier sample")?;
+
+            Ok(percent(*last_ticks, ticks, now - *last, hz))
+        })
+        .collect();
+
+    // processes that went away are forgotten
+    *samples = next;
+
+    usage
+}
+
+/**
+ * A process and every process descended from it, as found by walking the
+ * parent pids of every process.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessTree {
+    pub pid: pid_t,
+    pub name: String,
+    pub children: Vec<ProcessTree>,
+}
+
+/**
+ * Read the parent pid and name of every process from `/proc/<pid>/stat`, by
+ * pid.  Processes that exit while they are being read are skipped.
+ */
+pub fn process_table(
+    proc_path: &Path,
+) -> Result<BTreeMap<pid_t, (pid_t, String)>> {
+    let entries = fs::read_dir(proc_path)
+        .with_context(|| format!("failed to read {:?}", proc_path))?;
+
+    let mut table = BTreeMap::new();
+    for entry in entries.flatten() {
+        let pid: pid_t = match entry.file_name().to_str().map(str::parse) {
+            Some(Ok(pid)) => pid,
+            _ => continue,
+        };
+
+        let data = match read_lossy(&entry.path().join("stat")) {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+
+        if let Ok(parent) = parse_stat_parent(&data) {
+            table.insert(pid, parent);
+        }
+    }
+
+    Ok(table)
+}
+
+/// Get the parent pid and command name from a `stat` file.
+fn parse_stat_parent(data: &str) -> Result<(pid_t, String)> {
+    let (name, fields) = parse_stat(data)?;
+
+    // field 4 (1-indexed), the state is field 3
+    let ppid = fields.get(1).context("too few fields")?;
+    let ppid =
+        ppid.parse().with_context(|| format!("invalid ppid: '{}'", ppid))?;
+
+    Ok((ppid, name.to_string()))
+}
+
+/// Get the trees of every process descended from `pid` in `table`.
+pub fn descendants(
+    table: &BTreeMap<pid_t, (pid_t, String)>,
+    pid: pid_t,
+) -> Vec<ProcessTree> {
+    table
+        .iter()
+        .filter(|(child, (ppid, _))| *ppid == pid && **child != pid)
+        .map(|(child, (_, name))| ProcessTree {
+            pid: *child,
+            name: name.clone(),
+            children: descendants(table, *child),
+        })
+        .collect()
+}
+
+/// Get the number of clock ticks per second times in `/proc` are given in.
+fn clock_ticks() -> f64 {
+    let hz = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if hz > 0 {
+        hz as f64
+    } else {
+        100.0
+    }
+}
+
+/// Get the start time (field 22, in clock ticks) from a `stat` file.
+fn parse_stat_start(data: &str) -> Result<u64> {
+    let (_, fields) = parse_stat(data)?;
+    let start = fields.get(22 - 3).context("too few fields")?;
+
+    start.parse().with_context(|| format!("invalid starttime: '{}'", start))
+}
+
+/// Get the percent of one CPU used going from `last` to `ticks` over `dur`.
+fn percent(last: u64, ticks: u64, dur: Duration, hz: f64) -> f64 {
+    let secs = dur.as_secs_f64();
+    if secs <= 0.0 {
+        return 0.0;
+    }
+
+    // a lower count means the pid was reused by a new process
+    ticks.saturating_sub(last) as f64 / hz / secs * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status_field() {
+        let data = "Name:\tsshd\nState:\tS (sleeping)\nVmRSS:\t    1234 kB\n";
+
+        assert_eq!(
+            parse_status_field(data, "VmRSS").as_deref(),
+            Some("1234 kB"),
+            "field found"
+        );
+        assert_eq!(
+            parse_status_field(data, "State").as_deref(),
+            Some("S (sleeping)"),
+            "field with spaces"
+        );
+        assert_eq!(parse_status_field(data, "VmSwap"), None, "no field");
+    }
+
+    #[test]
+    fn test_parse_cgroup() {
+        assert_eq!(
+            parse_cgroup("0::/system.slice/sshd.service\n").as_deref(),
+            Some("/system.slice/sshd.service"),
+            "cgroup2"
+        );
+        assert_eq!(
+            parse_cgroup("12:pids:/foo\n1:name=systemd:/bar\n0::/baz\n")
+                .as_deref(),
+            Some("/baz"),
+            "hybrid"
+        );
+        assert_eq!(
+            parse_cgroup("12:pids:/foo\n1:name=systemd:/bar\n").as_deref(),
+            Some("/foo"),
+            "cgroup1"
+        );
+        assert_eq!(parse_cgroup(""), None, "no cgroup");
+    }
+
+    #[test]
+    fn test_parse_limit() -> Result<()> {
+        let data = "Limit                     Soft Limit           Hard Limit           Units     \n\
+                    Max cpu time              unlimited            unlimited            seconds   \n\
+                    Max open files            1024                 524288               files     \n";
+
+        assert_eq!(parse_limit(data, "Max open files")?, Some(1024), "soft");
+        assert_eq!(parse_limit(data, "Max cpu time")?, None, "unlimited");
+        assert!(parse_limit(data, "Max processes").is_err(), "no limit");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let data = "Limit                     Soft Limit           Hard Limit           Units     \n\
+                    Max cpu time              unlimited            unlimited            seconds   \n\
+                    Max open files            1024                 524288               files     \n\
+                    Max nice priority         0                    0                    \n";
+
+        let limits = parse_limits(data);
+        assert_eq!(limits.len(), 3, "header skipped");
+        assert_eq!(
+            limits[1],
+            Limit {
+                name: "nofile",
+                description: "Max open files",
+                soft: "1024".into(),
+                hard: "524288".into(),
+                units: "files".into(),
+            },
+            "limit parsed"
+        );
+        assert_eq!(limits[2].units, "", "no units");
+    }
+
+    #[test]
+    fn test_parse_stat() -> Result<()> {
+        let (name, fields) = parse_stat("42 (my (odd) cmd) S 1 42\n")?;
+        assert_eq!(name, "my (odd) cmd", "name with parens");
+        assert_eq!(fields, ["S", "1", "42"], "fields after the name");
+
+        assert!(parse_stat("42 cmd S 1").is_err(), "no name");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stat_ticks() -> Result<()> {
+        let data = "42 (my (odd) cmd) S 1 42 42 0 -1 4194560 100 0 0 0 \
+                    30 20 5 1 20 0 1 0 100 1000 50 18446744073709551615\n";
+        assert_eq!(parse_stat_ticks(data)?, 56, "ticks added");
+
+        assert!(parse_stat_ticks("42 (cmd) S 1").is_err(), "too short");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stat_sched() -> Result<()> {
+        let data = "42 (my (odd) cmd) S 1 42 42 0 -1 4194560 100 0 0 0 \
+                    30 20 5 1 20 5 1 0 100 1000 50 18446744073709551615 \
+                    1 1 0 0 0 0 0 0 0 0 0 0 17 3 0 2 0 0 0\n";
+        assert_eq!(parse_stat_sched(data)?, (5, "RR"), "nice and class");
+
+        assert!(parse_stat_sched("42 (cmd) S 1 42").is_err(), "too short");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stat_start() -> Result<()> {
+        let data = "42 (my (odd) cmd) S 1 42 42 0 -1 4194560 100 0 0 0 \
+                    30 20 5 1 20 0 1 0 12345 1000 50\n";
+        assert_eq!(parse_stat_start(data)?, 12345, "starttime");
+
+        assert!(parse_stat_start("42 (cmd) S 1").is_err(), "too short");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stat_state() -> Result<()> {
+        assert_eq!(
+            parse_stat_state("42 (my (odd) cmd) Z 1 42")?,
+            'Z',
+            "zombie"
+        );
+        assert!(parse_stat_state("42 (cmd)").is_err(), "no state");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stat_parent() -> Result<()> {
+        let data = "42 (my (odd) cmd) S 7 42 42 0 -1 4194560 100 0\n";
+        assert_eq!(
+            parse_stat_parent(data)?,
+            (7, "my (odd) cmd".to_string()),
+            "parent parsed"
+        );
+
+        assert!(parse_stat_parent("42 (cmd) S").is_err(), "too short");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_descendants() {
+        let table = BTreeMap::from([
+            (1, (0, "init".to_string())),
+            (10, (1, "runsv".to_string())),
+            (11, (10, "nginx".to_string())),
+            (12, (11, "worker".to_string())),
+            (13, (11, "worker".to_string())),
+            (20, (1, "other".to_string())),
+        ]);
+
+        let node = |pid, name: &str, children| ProcessTree {
+            pid,
+            name: name.to_string(),
+            children,
+        };
+        assert_eq!(
+            descendants(&table, 10),
+            vec![node(
+                11,
+                "nginx",
+                vec![node(12, "worker", vec![]), node(13, "worker", vec![])]
+            )],
+            "tree found"
+        );
+        assert_eq!(descendants(&table, 12), vec![], "no children");
+    }
+
+    #[test]
+    fn test_percent() {
+        let sec = Duration::from_secs(1);
+
+        assert_eq!(percent(100, 150, sec, 100.0), 50.0, "half a cpu");
+        assert_eq!(percent(100, 500, sec * 2, 100.0), 200.0, "two cpus");
+        assert_eq!(percent(100, 50, sec, 100.0), 0.0, "pid reused");
+        assert_eq!(percent(100, 150, Duration::ZERO, 100.0), 0.0, "no time");
+    }
+}
@@ -0,0 +1,526 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * A small backtracking regular expression engine for matching service names.
+ *
+ * Service names are short so a simple backtracking matcher is more than fast
+ * enough.  The supported syntax is a (commonly used) subset of what the
+ * `regex` crate or `grep -E` support:
+ *
+ * - Literals, `.`, `^` and `$`.
+ * - Character classes like `[a-z_]`, `[^0-9]`, `\d`, `\w` and `\s` (and their
+ *   negated forms `\D`, `\W` and `\S`).
+ * - Groups `(...)` and `(?:...)`, and alternation `a|b`.
+ * - Quantifiers `*`, `+`, `?`, `{n}`, `{n,}` and `{n,m}`, optionally lazy with
+ *   a trailing `?`.
+ *
+ * Matching is unanchored (like `grep`) unless `^` or `$` are used.
+ */
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::error::{bail, Result};
+
+/// A single node of a parsed regular expression.
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Vec<(char, char)>, bool),
+    Start,
+    End,
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat(Box<Node>, usize, Option<usize>, bool),
+}
+
+/// A compiled regular expression.
+#[derive(Debug, Clone)]
+pub struct Regex {
+    source: String,
+    root: Node,
+}
+
+impl Regex {
+    /**
+     * Compile a regular expression.
+     *
+     * # Example
+     *
+     * ```
+     * use vsv::regex::Regex;
+     *
+     * # fn main() -> anyhow::Result<()> {
+     * let re = Regex::new("^php-fpm.*")?;
+     * assert!(re.is_match("php-fpm8.1"));
+     * # Ok(())
+     * # }
+     * ```
+     */
+    pub fn new(source: &str) -> Result<Self> {
+        let mut parser = Parser { chars: source.chars().peekable() };
+        let root = parser.parse_alt()?;
+
+        if let Some(c) = parser.chars.next() {
+            bail!("invalid regex '{}': unexpected '{}'", source, c);
+        }
+
+        Ok(Self { source: source.to_string(), root })
+    }
+
+    /**
+     * Compile a shell-style glob (`*`, `?`, `[...]` and `[!...]`) into a regex
+     * that must match an entire string.
+     *
+     * # Example
+     *
+     * ```
+     * use vsv::regex::Regex;
+     *
+     * # fn main() -> anyhow::Result<()> {
+     * let re = Regex::from_glob("agetty-tty*")?;
+     * assert!(re.is_match("agetty-tty1"));
+     * assert!(!re.is_match("my-agetty-tty1"));
+     * # Ok(())
+     * # }
+     * ```
+     */
+    pub fn from_glob(glob: &str) -> Result<Self> {
+        let mut source = String::from("^");
+        let mut chars = glob.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '*' => source.push_str(".*"),
+                '?' => source.push('.'),
+                '[' => {
+                    source.push('[');
+                    if chars.peek() == Some(&'!') {
+                        chars.next();
+                        source.push('^');
+                    }
+                    for c in chars.by_ref() {
+                        source.push(c);
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                c if c.is_alphanumeric() => source.push(c),
+                c => {
+                    source.push('\\');
+                    source.push(c);
+                }
+            }
+        }
+        source.push('$');
+
+        let mut regex = Regex::new(&source)?;
+        regex.source = glob.to_string();
+
+        Ok(regex)
+    }
+
+    /// Check if the regex matches anywhere in the given string.
+    pub fn is_match(&self, s: &str) -> bool {
+        let input: Vec<char> = s.chars().collect();
+
+        (0..=input.len())
+            .any(|start| match_node(&self.root, &input, start, &mut |_| true))
+    }
+}
+
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+/// Recursive descent parser for regular expressions.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// alt := concat ('|' concat)*
+    fn parse_alt(&mut self) -> Result<Node> {
+        let mut alts = vec![self.parse_concat()?];
+
+        while self.chars.peek() == Some(&'|') {
+            self.chars.next();
+            alts.push(self.parse_concat()?);
+        }
+
+        Ok(if alts.len() == 1 { alts.remove(0) } else { Node::Alt(alts) })
+    }
+
+    /// concat := repeat*
+    fn parse_concat(&mut self) -> Result<Node> {
+        let mut nodes = vec![];
+
+        while let Some(&c) = self.chars.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            nodes.push(self.parse_repeat()?);
+        }
+
+        Ok(Node::Concat(nodes))
+    }
+
+    /// repeat := atom quantifier*
+    fn parse_repeat(&mut self) -> Result<Node> {
+        let mut node = self.parse_atom()?;
+
+        loop {
+            let (min, max) = match self.chars.peek() {
+                // `parse_counts` consumes the braces itself
+                Some('{') => match self.parse_counts() {
+                    Some(counts) => counts,
+                    None => break,
+                },
+                Some('*') => {
+                    self.chars.next();
+                    (0, None)
+                }
+                Some('+') => {
+                    self.chars.next();
+                    (1, None)
+                }
+                Some('?') => {
+                    self.chars.next();
+                    (0, Some(1))
+                }
+                _ => break,
+            };
+
+            let greedy = if self.chars.peek() == Some(&'?') {
+                self.chars.next();
+                false
+            } else {
+                true
+            };
+
+            if matches!(node, Node::Start | Node::End) {
+                bail!("nothing to repeat");
+            }
+
+            node = Node::Repeat(Box::new(node), min, max, greedy);
+        }
+
+        Ok(node)
+    }
+
+    /**
+     * Try to parse `{n}`, `{n,}` or `{n,m}`.  If the braces don't form a valid
+     * count nothing is consumed and `None` is returned (so `{` is treated as a
+     * literal).
+     */
+    fn parse_counts(&mut self) -> Option<(usize, Option<usize>)> {
+        let rest: String = self.chars.clone().skip(1).collect();
+        let end = rest.find('}')?;
+        let inner = &rest[..end];
+
+        let (min, max) = match inner.split_once(',') {
+            Some((min, "")) => (min.parse().ok()?, None),
+            Some((min, max)) => (min.parse().ok()?, Some(max.parse().ok()?)),
+            None => {
+                let n = inner.parse().ok()?;
+                (n, Some(n))
+            }
+        };
+
+        if max.is_some_and(|max| max < min) {
+            return None;
+        }
+
+        // consume `{...}`
+        for _ in 0..end + 2 {
+            self.chars.next();
+        }
+
+        Some((min, max))
+    }
+
+    /// atom := '(' alt ')' | '[' class ']' | '.' | '^' | '$' | escape | char
+    fn parse_atom(&mut self) -> Result<Node> {
+        let c = match self.chars.next() {
+            Some(c) => c,
+            None => bail!("unexpected end of regex"),
+        };
+
+        let node = match c {
+            '(' => {
+                if self.chars.peek() == Some(&'?') {
+                    self.chars.next();
+                    if self.chars.next() != Some(':') {
+                        bail!("unsupported group syntax '(?'");
+                    }
+                }
+
+                let node = self.parse_alt()?;
+                if self.chars.next() != Some(')') {
+                    bail!("unclosed group");
+                }
+                node
+            }
+            '[' => self.parse_class()?,
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => self.parse_escape()?,
+            '*' | '+' | '?' => bail!("nothing to repeat before '{}'", c),
+            c => Node::Char(c),
+        };
+
+        Ok(node)
+    }
+
+    /// Parse the part of an escape sequence after the `\`.
+    fn parse_escape(&mut self) -> Result<Node> {
+        let c = match self.chars.next() {
+            Some(c) => c,
+            None => bail!("trailing '\\' in regex"),
+        };
+
+        let node = match c {
+            'd' | 'w' | 's' => Node::Class(class_ranges(c), false),
+            'D' | 'W' | 'S' => {
+                Node::Class(class_ranges(c.to_ascii_lowercase()), true)
+            }
+            'n' => Node::Char('\n'),
+            't' => Node::Char('\t'),
+            c if c.is_ascii_alphanumeric() => {
+                bail!("unsupported escape sequence '\\{}'", c)
+            }
+            c => Node::Char(c),
+        };
+
+        Ok(node)
+    }
+
+    /// Parse a bracketed character class (after the `[`).
+    fn parse_class(&mut self) -> Result<Node> {
+        let mut ranges = vec![];
+        let mut negated = false;
+
+        if self.chars.peek() == Some(&'^') {
+            self.chars.next();
+            negated = true;
+        }
+
+        let mut first = true;
+        loop {
+            let c = match self.chars.next() {
+                Some(']') if !first => break,
+                Some('\\') => match self.chars.next() {
+                    Some(e @ ('d' | 'w' | 's')) => {
+                        ranges.extend(class_ranges(e));
+                        first = false;
+                        continue;
+                    }
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some(c) => c,
+                    None => bail!("unclosed character class"),
+                },
+                Some(c) => c,
+                None => bail!("unclosed character class"),
+            };
+            first = false;
+
+            // ranges like `a-z` (a trailing `-` is a literal)
+            let mut lookahead = self.chars.clone();
+            if lookahead.next() == Some('-') {
+                match lookahead.next() {
+                    Some(']') | None => (),
+                    Some(end) => {
+                        self.chars.next();
+                        self.chars.next();
+                        if end < c {
+                            bail!("invalid class range '{}-{}'", c, end);
+                        }
+                        ranges.push((c, end));
+                        continue;
+                    }
+                }
+            }
+
+            ranges.push((c, c));
+        }
+
+        Ok(Node::Class(ranges, negated))
+    }
+}
+
+/// Get the character ranges for the `\d`, `\w` and `\s` classes.
+fn class_ranges(c: char) -> Vec<(char, char)> {
+    match c {
+        'd' => vec![('0', '9')],
+        'w' => vec![('0', '9'), ('A', 'Z'), ('a', 'z'), ('_', '_')],
+        's' => vec![(' ', ' '), ('\t', '\r')],
+        _ => unreachable!(),
+    }
+}
+
+/**
+ * Try to match a node at the given position, calling `k` with the position
+ * after the match for every way the node can match (until `k` returns
+ * `true`).
+ */
+fn match_node(
+    node: &Node,
+    input: &[char],
+    pos: usize,
+    k: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    match node {
+        Node::Char(c) => input.get(pos) == Some(c) && k(pos + 1),
+        Node::Any => pos < input.len() && k(pos + 1),
+        Node::Class(ranges, negated) => match input.get(pos) {
+            Some(c) => {
+                let found = ranges.iter().any(|(a, b)| a <= c && c <= b);
+                found != *negated && k(pos + 1)
+            }
+            None => false,
+        },
+        Node::Start => pos == 0 && k(pos),
+        Node::End => pos == input.len() && k(pos),
+        Node::Concat(nodes) => match_concat(nodes, input, pos, k),
+        Node::Alt(alts) => {
+            alts.iter().any(|alt| match_node(alt, input, pos, k))
+        }
+        Node::Repeat(node, min, max, greedy) => {
+            match_repeat(node, *min, *max, *greedy, 0, input, pos, k)
+        }
+    }
+}
+
+/// Match a sequence of nodes one after another.
+fn match_concat(
+    nodes: &[Node],
+    input: &[char],
+    pos: usize,
+    k: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    match nodes.split_first() {
+        None => k(pos),
+        Some((first, rest)) => match_node(first, input, pos, &mut |p| {
+            match_concat(rest, input, p, k)
+        }),
+    }
+}
+
+/// Match a node repeatedly, `count` is how many times it has matched so far.
+#[allow(clippy::too_many_arguments)]
+fn match_repeat(
+    node: &Node,
+    min: usize,
+    max: Option<usize>,
+    greedy: bool,
+    count: usize,
+    input: &[char],
+    pos: usize,
+    k: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let can_stop = count >= min;
+    let can_continue = max.is_none_or(|max| count < max);
+
+    if !greedy && can_stop && k(pos) {
+        return true;
+    }
+
+    if can_continue {
+        let matched = match_node(node, input, pos, &mut |p| {
+            // an empty match can't make progress, stop to avoid looping forever
+            if p == pos && can_stop {
+                return false;
+            }
+            match_repeat(node, min, max, greedy, count + 1, input, p, k)
+        });
+
+        if matched {
+            return true;
+        }
+    }
+
+    greedy && can_stop && k(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_match() -> Result<()> {
+        let arr = [
+            ("foo", "foo", true),
+            ("foo", "a-foo-b", true),
+            ("foo", "fo", false),
+            ("^php-fpm.*", "php-fpm8.1", true),
+            ("^php-fpm.*", "my-php-fpm", false),
+            ("^agetty-tty[0-9]+$", "agetty-tty12", true),
+            ("^agetty-tty[0-9]+$", "agetty-ttyS0", false),
+            ("^(nginx|php-fpm.*)$", "nginx", true),
+            ("^(nginx|php-fpm.*)$", "php-fpm7", true),
+            ("^(nginx|php-fpm.*)$", "nginx2", false),
+            ("^a?b{2,3}c*$", "bb", true),
+            ("^a?b{2,3}c*$", "abbbccc", true),
+            ("^a?b{2,3}c*$", "abbbbc", false),
+            ("^x{2}$", "xx", true),
+            ("^x{2}$", "xxx", false),
+            ("^\\d+\\.\\w$", "12.a", true),
+            ("^\\d+\\.\\w$", "12xa", false),
+            ("[^a-z]", "abc", false),
+            ("[^a-z]", "abc1", true),
+            ("^[a-]+$", "a-a", true),
+            ("^(?:ab)+$", "ababab", true),
+            ("^(a|)+$", "aaa", true),
+            ("^.*?x$", "aax", true),
+            ("a{,", "a{,", true),
+            ("", "anything", true),
+        ];
+
+        for (re, s, want) in arr {
+            let regex = Regex::new(re)?;
+            assert_eq!(regex.is_match(s), want, "'{}' =~ '{}'", s, re);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_glob() -> Result<()> {
+        let arr = [
+            ("agetty-tty*", "agetty-tty1", true),
+            ("agetty-tty*", "my-agetty-tty1", false),
+            ("php-fpm?", "php-fpm7", true),
+            ("php-fpm?", "php-fpm", false),
+            ("tty[0-9]", "tty4", true),
+            ("tty[!0-9]", "tty4", false),
+            ("a.b", "a.b", true),
+            ("a.b", "axb", false),
+            ("*", "anything", true),
+        ];
+
+        for (glob, s, want) in arr {
+            let regex = Regex::from_glob(glob)?;
+            assert_eq!(regex.is_match(s), want, "'{}' =~ glob '{}'", s, glob);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_bad() {
+        for re in ["(", "a)", "[a", "*a", "a\\", "\\q", "[z-a]", "(?x)"] {
+            assert!(Regex::new(re).is_err(), "'{}' is invalid", re);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Gather services on another machine over SSH (`--host`).
+ *
+ * The remote `vsv` does all of the work (reading `/proc` and the service
+ * directory has to happen there anyway) and prints every service with
+ * `-F snapshot`, which is read back into `Service`s with
+ * `Service::from_value` and rendered here like local services.
+ */
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::config::Config;
+use crate::output::{json, Value};
+use crate::service::Service;
+use crate::utils;
+
+/**
+ * Run `vsv` on `host` (with the arguments in `cfg.remote_args`) and get the
+ * services it found, with any messages generated while gathering them.
+ */
+pub fn get_services(
+    cfg: &Config,
+    host: &str,
+) -> Result<Vec<(Service, Vec<String>)>> {
+    let out = run(cfg, host)?;
+
+    parse_services(&out)
+        .with_context(|| format!("invalid output from vsv on {}", host))
+}
+
+/// Run the remote `vsv` and get its stdout.
+fn run(cfg: &Config, host: &str) -> Result<String> {
+    // ssh joins every argument into one string given to the remote shell
+    let remote: Vec<String> = [&cfg.remote_prog]
+        .into_iter()
+        .chain(&cfg.remote_args)
+        .map(|arg| shell_quote(arg))
+        .collect();
+
+    let mut cmd = Command::new(&cfg.ssh_prog);
+    // never prompt for a password (or anything else) on the terminal
+    cmd.args(["-o", "BatchMode=yes"]);
+    if let Some(timeout) = cfg.opts.timeout {
+        let secs = timeout.as_secs().max(1);
+        cmd.arg("-o").arg(format!("ConnectTimeout={}", secs));
+    }
+    cmd.args([host, "--", &remote.join(" ")]);
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run '{}'", cfg.ssh_prog))?;
+
+    // read both pipes in their own threads so neither can fill up and block
+    let read = |mut pipe: Box<dyn Read + Send>| {
+        thread::spawn(move || {
+            let mut buf = vec![];
+            pipe.read_to_end(&mut buf).map(|_| buf)
+        })
+    };
+    let stdout = read(Box::new(child.stdout.take().expect("stdout is piped")));
+    let stderr = read(Box::new(child.stderr.take().expect("stderr is piped")));
+
+    let status = utils::wait_timeout(&mut child, cfg.opts.timeout)
+        .with_context(|| format!("'{}' to {} failed", cfg.ssh_prog, host))?;
+
+    let join = |reader: thread::JoinHandle<_>| -> Result<Vec<u8>> {
+        let buf: std::io::Result<Vec<u8>> =
+            reader.join().map_err(|_| anyhow!("failed to read output"))?;
+        Ok(buf?)
+    };
+    let stdout = join(stdout)?;
+    let stderr = join(stderr)?;
+
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        match stderr.trim() {
+            "" => bail!("'{}' to {} returned non-zero", cfg.ssh_prog, host),
+            msg => bail!("'{}' to {} failed: {}", cfg.ssh_prog, host, msg),
+        }
+    }
+
+    String::from_utf8(stdout).context("output is not UTF-8")
+}
+
+/**
+ * Parse the output of `vsv -F snapshot`: an array of `Service::to_value`
+ * objects, each with an `errors` array of messages.
+ */
+fn parse_services(s: &str) -> Result<Vec<(Service, Vec<String>)>> {
+    let value = json::from_str(s)?;
+    let arr = value.as_array().ok_or_else(|| anyhow!("not an array"))?;
+
+    arr.iter()
+        .map(|obj| {
+            let service = Service::from_value(obj)?;
+            let messages = obj
+                .get("errors")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect();
+            Ok((service, messages))
+        })
+        .collect()
+}
+
+/// Quote a string for a POSIX shell (unless it's obviously safe).
+fn shell_quote(s: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./,:=@%+".contains(c);
+
+    if !s.is_empty() && s.chars().all(safe) {
+        return s.to_string();
+    }
+
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("status"), "status", "safe");
+        assert_eq!(shell_quote("name,pid"), "name,pid", "list");
+        assert_eq!(shell_quote(""), "''", "empty");
+        assert_eq!(shell_quote("nginx|php.*"), "'nginx|php.*'", "regex");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'", "single quote");
+    }
+
+    #[test]
+    fn test_parse_services() -> Result<()> {
+        let s = r#"[
+            {"name": "sshd", "state": "run", "pid": 123, "errors": []},
+            {"name": "ntpd", "state": "down", "errors": ["no pid"]}
+        ]"#;
+        let services = parse_services(s)?;
+
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[0].0.name, "sshd");
+        assert_eq!(services[0].0.pid, Some(123));
+        assert!(services[0].1.is_empty());
+        assert_eq!(services[1].0.name, "ntpd");
+        assert_eq!(services[1].1, ["no pid"]);
+
+        assert!(parse_services("{}").is_err(), "not an array");
+        assert!(parse_services("[{}]").is_err(), "no name");
+        assert!(parse_services("vsv: not found").is_err(), "not json");
+
+        Ok(())
+    }
+}
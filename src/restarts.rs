@@ -0,0 +1,220 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Restart tracking across vsv invocations (for flapping detection).
+ *
+ * The start time of every service seen is recorded in a small state file.  A
+ * service seen running with a different start time than last time has been
+ * restarted, and a service restarted `FLAP_THRESHOLD` or more times within
+ * `FLAP_WINDOW` is considered to be flapping (stuck in a restart loop).
+ *
+ * The state file has one line per service: its path, the last start time seen
+ * and the times of recent restarts (all in seconds since the epoch) separated
+ * by tabs, like `/var/service/sshd\t1700000000\t1699999990,1699999995`.
+ */
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{Context, Result};
+
+/// Restarts older than this are forgotten.
+pub const FLAP_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// The number of restarts (within `FLAP_WINDOW`) that makes a service flap.
+pub const FLAP_THRESHOLD: usize = 3;
+
+/// The name of the state file (in the state directory).
+const STATE_FILE_NAME: &str = "restarts";
+
+/// The state directory used by root (if `$XDG_STATE_HOME` is not set).
+const ROOT_STATE_DIR: &str = "/run/vsv";
+
+/// The state directory (relative to `$HOME`) if `$XDG_STATE_HOME` is not set.
+const DEFAULT_XDG_STATE_DIR: &str = ".local/state";
+
+pub const ENV_XDG_STATE_HOME: &str = "XDG_STATE_HOME";
+
+/// What is known about a single service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    start: u64,
+    restarts: Vec<u64>,
+}
+
+/// Recorded start times and recent restarts of services, by path.
+#[derive(Debug, Default)]
+pub struct RestartHistory {
+    entries: HashMap<String, Entry>,
+}
+
+impl RestartHistory {
+    /**
+     * Get the path of the state file: `$XDG_STATE_HOME/vsv/restarts`, or
+     * `/run/vsv/restarts` for root and `~/.local/state/vsv/restarts` for
+     * everyone else.
+     */
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = match env::var_os(ENV_XDG_STATE_HOME) {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("vsv"),
+            _ if unsafe { libc::geteuid() } == 0 => {
+                PathBuf::from(ROOT_STATE_DIR)
+            }
+            _ => dirs::home_dir()?.join(DEFAULT_XDG_STATE_DIR).join("vsv"),
+        };
+
+        Some(dir.join(STATE_FILE_NAME))
+    }
+
+    /// Load the history from a state file, a missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to read {:?}", path))
+            }
+        };
+
+        Ok(Self::parse(&data))
+    }
+
+    /// Parse the contents of a state file, ignoring any malformed lines.
+    fn parse(data: &str) -> Self {
+        let mut entries = HashMap::new();
+
+        for line in data.lines() {
+            let mut fields = line.split('\t');
+            let (key, start) = match (fields.next(), fields.next()) {
+                (Some(key), Some(start)) => (key, start),
+                _ => continue,
+            };
+            let start = match start.parse() {
+                Ok(start) => start,
+                Err(_) => continue,
+            };
+            let restarts = fields
+                .next()
+                .unwrap_or("")
+                .split(',')
+                .filter_map(|t| t.parse().ok())
+                .collect();
+
+            entries.insert(key.to_string(), Entry { start, restarts });
+        }
+
+        Self { entries }
+    }
+
+    /**
+     * Record the start time of a service (given by path), returning the
+     * number of restarts seen within `FLAP_WINDOW`.  Only a new start time
+     * seen while the service is running counts as a restart.
+     */
+    pub fn observe(
+        &mut self,
+        key: &str,
+        start: SystemTime,
+        running: bool,
+        now: SystemTime,
+    ) -> usize {
+        let start = secs(start);
+        let now = secs(now);
+
+        let entry = self
+            .entries
+            .entry(key.to_string())
+            .or_insert(Entry { start, restarts: vec![] });
+
+        if entry.start != start && running {
+            entry.restarts.push(now);
+            entry.start = start;
+        }
+
+        let oldest = now.saturating_sub(FLAP_WINDOW.as_secs());
+        entry.restarts.retain(|t| *t >= oldest);
+
+        entry.restarts.len()
+    }
+
+    /// Write the history to a state file (creating its directory if needed).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {:?}", dir))?;
+        }
+
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+
+        let mut data = String::new();
+        for key in keys {
+            let entry = &self.entries[key];
+            let restarts: Vec<String> =
+                entry.restarts.iter().map(u64::to_string).collect();
+            data.push_str(&format!(
+                "{}\t{}\t{}\n",
+                key,
+                entry.start,
+                restarts.join(",")
+            ));
+        }
+
+        // write then rename so concurrent readers never see a partial file
+        let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp, data)
+            .with_context(|| format!("failed to write {:?}", tmp))?;
+        fs::rename(&tmp, path)
+            .with_context(|| format!("failed to rename {:?}", tmp))?;
+
+        Ok(())
+    }
+}
+
+/// Get the time as seconds since the epoch.
+fn secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe() {
+        let mut history = RestartHistory::default();
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+
+        assert_eq!(history.observe("a", at(100), true, at(110)), 0, "new");
+        assert_eq!(history.observe("a", at(100), true, at(120)), 0, "same");
+        assert_eq!(history.observe("a", at(125), false, at(130)), 0, "down");
+        assert_eq!(history.observe("a", at(135), true, at(140)), 1, "restart");
+        assert_eq!(history.observe("a", at(145), true, at(150)), 2, "again");
+
+        let later = 150 + FLAP_WINDOW.as_secs() + 1;
+        assert_eq!(history.observe("a", at(145), true, at(later)), 0, "old");
+    }
+
+    #[test]
+    fn test_parse() {
+        let data = "/a\t100\t90,95\n/b\t200\t\nbad line\n/c\tx\t\n";
+        let history = RestartHistory::parse(data);
+
+        assert_eq!(history.entries.len(), 2, "bad lines skipped");
+        assert_eq!(
+            history.entries["/a"],
+            Entry { start: 100, restarts: vec![90, 95] },
+            "entry parsed"
+        );
+    }
+}
@@ -6,41 +6,153 @@
 
 //! Runit service related structs and enums.
 
-use libc::pid_t;
 use path::{Path, PathBuf};
+use std::cmp;
 use std::fs;
 use std::io;
 use std::path;
+use std::process;
 use std::time;
 
-use anyhow::{anyhow, Context, Result};
+use crate::backend::{Status, Supervisor};
+use crate::cache;
+use crate::error::{bail, ensure, Context, Result};
+use crate::utils;
+
+/// Where `svlogd(8)` is assumed to log to if it can't be found in `log/run`.
+pub const DEFAULT_LOG_DIR: &str = "/var/log";
 
 /// Possible states for a runit service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RunitServiceState {
     Run,
     Down,
     Finish,
+    Unsupervised,
     Unknown,
 }
 
+/**
+ * Commands that can be sent to a service's `runsv(8)` through its
+ * `supervise/control` pipe.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+    Up,
+    Down,
+    Restart,
+    Once,
+    Hup,
+    Term,
+    Kill,
+    Alarm,
+    Interrupt,
+    Quit,
+    Usr1,
+    Usr2,
+}
+
+impl ControlCommand {
+    /// Parse a control command from its (subcommand) name.
+    pub fn parse(s: &str) -> Option<Self> {
+        let cmd = match s {
+            "up" => ControlCommand::Up,
+            "down" => ControlCommand::Down,
+            "restart" => ControlCommand::Restart,
+            "once" => ControlCommand::Once,
+            "hup" => ControlCommand::Hup,
+            "term" => ControlCommand::Term,
+            "kill" => ControlCommand::Kill,
+            "alarm" => ControlCommand::Alarm,
+            "interrupt" => ControlCommand::Interrupt,
+            "quit" => ControlCommand::Quit,
+            "usr1" => ControlCommand::Usr1,
+            "usr2" => ControlCommand::Usr2,
+            _ => return None,
+        };
+
+        Some(cmd)
+    }
+
+    /// The name of the command (as given on the command line).
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlCommand::Up => "up",
+            ControlCommand::Down => "down",
+            ControlCommand::Restart => "restart",
+            ControlCommand::Once => "once",
+            ControlCommand::Hup => "hup",
+            ControlCommand::Term => "term",
+            ControlCommand::Kill => "kill",
+            ControlCommand::Alarm => "alarm",
+            ControlCommand::Interrupt => "interrupt",
+            ControlCommand::Quit => "quit",
+            ControlCommand::Usr1 => "usr1",
+            ControlCommand::Usr2 => "usr2",
+        }
+    }
+
+    /**
+     * The bytes written to `supervise/control` for this command, restart is
+     * the same as `sv restart` (term, cont, then up) and the signal commands
+     * are sent as-is to the service process.
+     */
+    pub fn bytes(&self) -> &'static [u8] {
+        match self {
+            ControlCommand::Up => b"u",
+            ControlCommand::Down => b"d",
+            ControlCommand::Restart => b"tcu",
+            ControlCommand::Once => b"o",
+            ControlCommand::Hup => b"h",
+            ControlCommand::Term => b"t",
+            ControlCommand::Kill => b"k",
+            ControlCommand::Alarm => b"a",
+            ControlCommand::Interrupt => b"i",
+            ControlCommand::Quit => b"q",
+            ControlCommand::Usr1 => b"1",
+            ControlCommand::Usr2 => b"2",
+        }
+    }
+
+    /// The state a service is expected to reach after this command (if any).
+    pub fn wanted_state(&self) -> Option<RunitServiceState> {
+        match self {
+            ControlCommand::Up | ControlCommand::Restart => {
+                Some(RunitServiceState::Run)
+            }
+            ControlCommand::Down => Some(RunitServiceState::Down),
+            _ => None,
+        }
+    }
+}
+
 /**
  * A runit service.
  *
  * This struct defines an object that can represent an individual service for
  * Runit.
  */
-#[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Debug)]
 pub struct RunitService {
     pub path: PathBuf,
     pub name: String,
+    pub log: bool,
+    pub backend: &'static dyn Supervisor,
 }
 
 impl RunitService {
-    /// Create a new runit service object from a given path and name.
-    pub fn new(name: &str, path: &Path) -> Self {
+    /**
+     * Create a new runit service object from a given path and name, read and
+     * controlled with the given backend.
+     */
+    pub fn new(
+        name: &str,
+        path: &Path,
+        backend: &'static dyn Supervisor,
+    ) -> Self {
         let name = name.to_string();
         let path = path.to_path_buf();
-        Self { path, name }
+        Self { path, name, log: false, backend }
     }
 
     /// Check if service is valid.
@@ -52,14 +164,24 @@ impl RunitService {
 
     /// Check if a service is enabled.
     pub fn enabled(&self) -> bool {
-        // "/<svdir>/<service>/down"
-        let p = self.path.join("down");
+        self.backend.enabled(&self.path)
+    }
 
-        !p.exists()
+    /// Fail if the service's backend can't modify services.
+    fn ensure_writable(&self) -> Result<()> {
+        ensure!(
+            !self.backend.read_only(),
+            "the {} backend is read-only",
+            self.backend.name()
+        );
+
+        Ok(())
     }
 
     /// Enable the service.
     pub fn enable(&self) -> Result<()> {
+        self.ensure_writable()?;
+
         // "/<svdir>/<service>/down"
         let p = self.path.join("down");
 
@@ -76,6 +198,8 @@ impl RunitService {
 
     /// Disable the service.
     pub fn disable(&self) -> Result<()> {
+        self.ensure_writable()?;
+
         // "/<svdir>/<service>/down"
         let p = self.path.join("down");
 
@@ -84,64 +208,274 @@ impl RunitService {
         Ok(())
     }
 
-    /// Get the service PID if possible.
-    pub fn get_pid(&self) -> Result<pid_t> {
-        // "/<svdir>/<service>/supervise/pid"
-        let p = self.path.join("supervise").join("pid");
+    /**
+     * Send a command to the service's supervisor by writing to its
+     * `supervise/control` pipe.  This fails (instead of blocking) if the
+     * supervisor isn't running or is hung (not reading from the pipe).
+     */
+    pub fn control(&self, cmd: ControlCommand) -> Result<()> {
+        self.ensure_writable()?;
 
-        let pid: pid_t = fs::read_to_string(p)?.trim().parse()?;
+        self.backend.control(&self.path, cmd)
+    }
 
-        Ok(pid)
+    /**
+     * Find the directory the service's `svlogd(8)` writes to.
+     *
+     * The directory given to `svlogd` in `log/run` is used if found (relative
+     * to the log service directory), otherwise `/var/log/<service>` is used
+     * if it exists.
+     */
+    pub fn log_dir(&self) -> Option<PathBuf> {
+        cache::log_dir(&self.path, || self.find_log_dir())
+    }
+
+    /// Find the log directory (see `log_dir`) without the cache.
+    fn find_log_dir(&self) -> Option<PathBuf> {
+        // "/<svdir>/<service>/log/run"
+        let log_path = self.path.join("log");
+
+        if let Ok(script) = fs::read_to_string(log_path.join("run")) {
+            if let Some(dir) = parse_svlogd_dir(&script) {
+                return Some(log_path.join(dir));
+            }
+        }
+
+        let dir = Path::new(DEFAULT_LOG_DIR).join(&self.name);
+        dir.is_dir().then_some(dir)
+    }
+
+    /**
+     * Get the total size (in bytes) of the files in the service's log
+     * directory (see `log_dir`), `None` if there's no log directory.
+     */
+    pub fn log_size(&self) -> Result<Option<u64>> {
+        let dir = match self.log_dir() {
+            Some(dir) => dir,
+            None => return Ok(None),
+        };
+
+        let mut size = 0;
+        for entry in fs::read_dir(&dir)
+            .with_context(|| format!("failed to read dir {:?}", dir))?
+        {
+            let meta = entry?.metadata()?;
+            if meta.is_file() {
+                size += meta.len();
+            }
+        }
+
+        Ok(Some(size))
+    }
+
+    /**
+     * Read the service's status (its state, pid, start time and wanted
+     * state) from its supervisor, all at once.  An unsupervised service's
+     * leftover status doesn't say anything about its state.
+     */
+    pub fn get_status(&self) -> Status {
+        let mut status = self.backend.status(&self.path);
+        if !self.is_supervised() {
+            status.state = RunitServiceState::Unsupervised;
+            status.error = None;
+        }
+
+        status
     }
 
     /// Get the service state.
     pub fn get_state(&self) -> RunitServiceState {
-        // "/<svdir>/<service>/supervise/stat"
-        let p = self.path.join("supervise").join("stat");
+        self.get_status().state
+    }
 
-        let s =
-            fs::read_to_string(p).unwrap_or_else(|_| String::from("unknown"));
+    /**
+     * Check if a supervisor process is supervising the service (see
+     * `Supervisor::is_supervised`).
+     */
+    pub fn is_supervised(&self) -> bool {
+        self.backend.is_supervised(&self.path)
+    }
 
-        match s.trim() {
-            "run" => RunitServiceState::Run,
-            "down" => RunitServiceState::Down,
-            "finish" => RunitServiceState::Finish,
-            _ => RunitServiceState::Unknown,
+    /**
+     * Run the service's `./check` script (like `sv check` does) from the
+     * service directory, killing it after `timeout`.
+     *
+     * Returns `None` if the service has no check script, otherwise whether
+     * the check exited successfully.
+     */
+    pub fn run_check(
+        &self,
+        timeout: Option<time::Duration>,
+    ) -> Option<Result<bool>> {
+        if !self.path.join("check").is_file() {
+            return None;
         }
+
+        let ret = process::Command::new("./check")
+            .current_dir(&self.path)
+            .stdin(process::Stdio::null())
+            .stdout(process::Stdio::null())
+            .stderr(process::Stdio::null())
+            .spawn()
+            .context("failed to run ./check")
+            .and_then(|mut child| utils::wait_timeout(&mut child, timeout))
+            .map(|status| status.success());
+
+        Some(ret)
+    }
+}
+
+// services are compared (and sorted) by their path and name only
+impl PartialEq for RunitService {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.path, &self.name, self.log)
+            == (&other.path, &other.name, other.log)
     }
+}
+
+impl Eq for RunitService {}
 
-    /// Get the service uptime.
-    pub fn get_start_time(&self) -> Result<time::SystemTime> {
-        // "/<svdir>/<service>/supervise/stat"
-        let p = self.path.join("supervise").join("stat");
+impl PartialOrd for RunitService {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-        Ok(fs::metadata(p)?.modified()?)
+impl Ord for RunitService {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        (&self.path, &self.name, self.log).cmp(&(
+            &other.path,
+            &other.name,
+            other.log,
+        ))
     }
 }
 
 /**
- * List the services in a given runit service directory.
+ * Enable a service by symlinking it from a source directory (like `/etc/sv`)
+ * into a runit service directory.
  *
- * This function optionally allows you to specify the `log` boolean.  If set,
- * this will return the correponding log service for each base-level service
- * found.
+ * Returns `false` if the service was already linked.  It is an error for the
+ * source service to not exist, or for anything other than a link to the
+ * source service to already exist in the service directory.  With `dry_run`
+ * everything is checked but the link isn't created.
+ */
+pub fn link_service(
+    source_dir: &Path,
+    svdir: &Path,
+    name: &str,
+    dry_run: bool,
+) -> Result<bool> {
+    let source = source_dir.join(name);
+    let target = svdir.join(name);
+
+    let source_meta = fs::metadata(&source)
+        .with_context(|| format!("no such service {:?}", source))?;
+    if !source_meta.is_dir() {
+        bail!("{:?} is not a directory", source);
+    }
+
+    match fs::read_link(&target) {
+        Ok(dest) if dest == source => return Ok(false),
+        Ok(dest) => {
+            bail!("{:?} already links to {:?}", target, dest)
+        }
+        Err(_) if fs::symlink_metadata(&target).is_ok() => {
+            bail!("{:?} exists and is not a symlink", target)
+        }
+        Err(_) => (),
+    }
+
+    if dry_run {
+        return Ok(true);
+    }
+
+    std::os::unix::fs::symlink(&source, &target)
+        .with_context(|| format!("failed to link {:?}", target))?;
+
+    Ok(true)
+}
+
+/**
+ * Disable a service by removing its symlink from a runit service directory.
  *
- * You may also specify an optional filter to only allow services that contain a
- * given string.
+ * Returns `false` if the service wasn't linked at all.  Anything that isn't a
+ * symlink is never removed.  With `dry_run` everything is checked but the link
+ * isn't removed.
  */
-pub fn get_services<T>(
-    path: &Path,
-    log: bool,
-    filter: Option<T>,
-) -> Result<Vec<RunitService>>
+pub fn unlink_service(svdir: &Path, name: &str, dry_run: bool) -> Result<bool> {
+    let target = svdir.join(name);
+
+    let meta = match fs::symlink_metadata(&target) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err.into()),
+    };
+
+    if !meta.file_type().is_symlink() {
+        bail!("refusing to remove non-symlink {:?}", target);
+    }
+
+    if dry_run {
+        return Ok(true);
+    }
+
+    fs::remove_file(&target)
+        .with_context(|| format!("failed to remove {:?}", target))?;
+
+    Ok(true)
+}
+
+/**
+ * Find the (first) log directory given to `svlogd` in a `log/run` script like
+ * `exec svlogd -tt /var/log/foo`.
+ */
+fn parse_svlogd_dir(script: &str) -> Option<String> {
+    for line in script.lines() {
+        // ignore comments
+        let line = line.split('#').next().unwrap_or_default();
+
+        let mut words = line
+            .split_whitespace()
+            .skip_while(|word| !word.ends_with("svlogd"))
+            .skip(1);
+
+        while let Some(word) = words.next() {
+            match word {
+                // options that take a value
+                "-r" | "-R" | "-l" | "-b" => {
+                    words.next();
+                }
+                word if word.starts_with('-') => (),
+                word => {
+                    return Some(word.trim_matches(['"', '\'']).to_string())
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * List the services in a source directory (like `/etc/sv`) that aren't in the
+ * given runit service directory, sorted by name.
+ *
+ * A filter function that is given each service name must also be specified,
+ * it returns whether or not the service should be included.
+ */
+pub fn get_available_services<F>(
+    source_dir: &Path,
+    svdir: &Path,
+    filter: F,
+) -> Result<Vec<String>>
 where
-    T: AsRef<str>,
+    F: Fn(&str) -> bool,
 {
-    // loop services directory and collect service names
-    let mut dirs = Vec::new();
+    let mut names = Vec::new();
 
-    for entry in fs::read_dir(path)
-        .with_context(|| format!("failed to read dir {:?}", path))?
+    for entry in fs::read_dir(source_dir)
+        .with_context(|| format!("failed to read dir {:?}", source_dir))?
     {
         let entry = entry?;
         let p = entry.path();
@@ -150,26 +484,61 @@ where
             continue;
         }
 
-        let name = p
-            .file_name()
-            .ok_or_else(|| anyhow!("{:?}: failed to get service name", p))?
-            .to_str()
-            .ok_or_else(|| anyhow!("{:?}: failed to parse service name", p))?
-            .to_string();
+        let name = match p.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
 
-        if let Some(ref filter) = filter {
-            if !name.contains(filter.as_ref()) {
-                continue;
-            }
+        // anything already in the service directory counts as linked
+        if !filter(&name) || fs::symlink_metadata(svdir.join(&name)).is_ok() {
+            continue;
         }
 
-        let service = RunitService::new(&name, &p);
+        names.push(name);
+    }
+
+    names.sort();
+
+    Ok(names)
+}
+
+/**
+ * List the services in a given runit service directory.
+ *
+ * This function optionally allows you to specify the `log` boolean.  If set,
+ * this will return the correponding log service for each base-level service
+ * found that has one (a `log/` directory).
+ *
+ * Every service is read and controlled with the given `backend`.  You must
+ * also specify a filter function that is given each service name and returns
+ * whether or not the service should be included.
+ */
+pub fn get_services<F>(
+    path: &Path,
+    log: bool,
+    backend: &'static dyn Supervisor,
+    filter: F,
+) -> Result<Vec<RunitService>>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut dirs = Vec::new();
+
+    for (name, p) in backend.list_services(path)? {
+        if !filter(&name) {
+            continue;
+        }
+
+        // only services that have a log service (`log/`) get one listed
+        let log_path = p.join("log");
+
+        let service = RunitService::new(&name, &p, backend);
         dirs.push(service);
 
-        if log {
-            let p = entry.path().join("log");
+        if log && log_path.is_dir() {
             let name = "- log";
-            let service = RunitService::new(name, &p);
+            let mut service = RunitService::new(name, &log_path, backend);
+            service.log = true;
             dirs.push(service);
         }
     }
@@ -178,3 +547,31 @@ where
 
     Ok(dirs)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_svlogd_dir() {
+        let arr = [
+            ("#!/bin/sh\nexec svlogd -tt /var/log/foo\n", Some("/var/log/foo")),
+            (
+                "exec chpst -u log /usr/bin/svlogd -r x -l 10 ./main",
+                Some("./main"),
+            ),
+            ("exec svlogd -tt '/var/log/foo'", Some("/var/log/foo")),
+            ("# exec svlogd /nope\nexec vlogger -t foo", None),
+            ("exec svlogd -tt", None),
+        ];
+
+        for (script, want) in arr {
+            assert_eq!(
+                parse_svlogd_dir(script).as_deref(),
+                want,
+                "{:?}",
+                script
+            );
+        }
+    }
+}
@@ -7,49 +7,116 @@
 //! Generic service related structs and enums.
 
 use libc::pid_t;
+use std::cmp::Ordering;
 use std::fmt;
 use std::path::Path;
 use std::time;
 
-use anyhow::Result;
-use yansi::{Color, Style};
+use anyhow::anyhow;
+use yansi::Style;
 
+use crate::backend::Status;
+use crate::cache;
+use crate::column::Column;
+use crate::error::{bail, Error, Result};
+use crate::glyphs;
+use crate::options::Options;
+use crate::procfs;
+use crate::restarts;
 use crate::runit::{RunitService, RunitServiceState};
+use crate::theme;
+use crate::time_format;
 use crate::utils;
 
+/**
+ * How far apart the time a process started (from `/proc`) and the time its
+ * supervisor says it started can be before the row is flagged.
+ */
+const MAX_START_SKEW: time::Duration = time::Duration::from_secs(10);
+
+/// Namespaces checked to tell if a process is running in a container.
+pub const NAMESPACES: &[&str] = &["pid", "mnt"];
+
 /// Possible states for a service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ServiceState {
     Run,
     Down,
     Finish,
+    Zombie,
+    Stopped,
+    Unsupervised,
     Unknown,
+
+    /// The state couldn't be read (like a permission error or a corrupt
+    /// supervise directory).
+    Error,
 }
 
 impl ServiceState {
-    /// Get a suitable `yansi::Style` for the state.
-    pub fn get_style(&self) -> Style {
-        let style = Style::default();
+    /// Parse a state from its name as shown in the `STATE` column.
+    pub fn parse(s: &str) -> Result<Self> {
+        let state = match s {
+            "run" => ServiceState::Run,
+            "down" => ServiceState::Down,
+            "finish" => ServiceState::Finish,
+            "zombie" => ServiceState::Zombie,
+            "stopped" => ServiceState::Stopped,
+            "unsupervised" => ServiceState::Unsupervised,
+            "n/a" | "unknown" => ServiceState::Unknown,
+            "error" => ServiceState::Error,
+            _ => bail!("unknown state: '{}'", s),
+        };
+
+        Ok(state)
+    }
 
-        let color = match self {
-            ServiceState::Run => Color::Green,
-            ServiceState::Down => Color::Red,
-            ServiceState::Finish => Color::Yellow,
-            ServiceState::Unknown => Color::Yellow,
+    /**
+     * Get the state of a service from the state its supervisor reports and
+     * the `stat` of its process (see `read_stat`): a running service whose
+     * process is a zombie (exited but not waited for) or stopped (like with
+     * `SIGSTOP`) isn't healthy.
+     */
+    pub fn from_process(
+        state: RunitServiceState,
+        stat: Option<&procfs::Stat>,
+    ) -> Self {
+        let state = Self::from(state);
+        let stat = match (state, stat) {
+            (ServiceState::Run, Some(stat)) => stat,
+            _ => return state,
         };
 
-        style.fg(color)
+        match stat.state() {
+            Ok('Z') => ServiceState::Zombie,
+            Ok('T' | 't') => ServiceState::Stopped,
+            _ => state,
+        }
+    }
+
+    /**
+     * Get the state of a service from its status (see `from_process`), or
+     * `Error` and why if it couldn't be read.
+     */
+    pub fn from_status(
+        status: &Status,
+        stat: Option<&procfs::Stat>,
+    ) -> (Self, Option<String>) {
+        if let Some(err) = &status.error {
+            return (ServiceState::Error, Some(format!("{:#}", err)));
+        }
+
+        (Self::from_process(status.state, stat), None)
+    }
+
+    /// Get a suitable `yansi::Style` for the state (from the theme).
+    pub fn get_style(&self) -> Style {
+        theme::get().state(*self)
     }
 
     /// Get a suitable char for the state (as a `String`).
     pub fn get_char(&self) -> String {
-        let s = match self {
-            ServiceState::Run => "✔",
-            ServiceState::Down => "X",
-            ServiceState::Finish => "X",
-            ServiceState::Unknown => "?",
-        };
-
-        s.to_string()
+        glyphs::get().state(*self).to_string()
     }
 }
 
@@ -59,13 +126,42 @@ impl fmt::Display for ServiceState {
             ServiceState::Run => "run",
             ServiceState::Down => "down",
             ServiceState::Finish => "finish",
+            ServiceState::Zombie => "zombie",
+            ServiceState::Stopped => "stopped",
+            ServiceState::Unsupervised => "unsupervised",
             ServiceState::Unknown => "n/a",
+            ServiceState::Error => "error",
         };
 
         s.fmt(f)
     }
 }
 
+impl From<RunitServiceState> for ServiceState {
+    fn from(state: RunitServiceState) -> Self {
+        match state {
+            RunitServiceState::Run => ServiceState::Run,
+            RunitServiceState::Down => ServiceState::Down,
+            RunitServiceState::Finish => ServiceState::Finish,
+            RunitServiceState::Unsupervised => ServiceState::Unsupervised,
+            RunitServiceState::Unknown => ServiceState::Unknown,
+        }
+    }
+}
+
+/**
+ * Read the `stat` of the process in a status (see `procfs::Stat`), `None` if
+ * there is no process.
+ */
+pub fn read_stat(
+    status: &Status,
+    proc_path: &Path,
+) -> Option<Result<procfs::Stat>> {
+    let pid = status.pid.as_ref().ok().copied().flatten()?;
+
+    Some(procfs::Stat::read(pid, proc_path))
+}
+
 /**
  * A struct suitable for describing an abstract service.
  *
@@ -73,33 +169,162 @@ impl fmt::Display for ServiceState {
  * service and knows how to format it to look pretty.
  */
 pub struct Service {
-    name: String,
-    state: ServiceState,
-    enabled: bool,
-    command: Option<String>,
-    pid: Option<pid_t>,
-    start_time: Result<time::SystemTime>,
-    pstree: Option<Result<String>>,
+    pub name: String,
+    pub log: bool,
+    pub state: ServiceState,
+
+    /// The state runit wants the service in (`Run` or `Down`), if known.
+    pub want: Option<ServiceState>,
+
+    pub enabled: bool,
+    pub command: Option<String>,
+    pub pid: Option<pid_t>,
+    pub start_time: Result<time::SystemTime>,
+    pub pstree: Option<Result<String>>,
+
+    /// The service tree this service came from (with `-U`).
+    pub origin: Option<String>,
+
+    /// The machine this service is on (with `-H`).
+    pub host: Option<String>,
+
+    /// Size of the service's log directory (only gathered when wanted).
+    pub log_size: Option<u64>,
+
+    /// Restarts seen recently across invocations (see `restarts`).
+    pub restarts: Option<usize>,
+
+    /// Whether `./check` passed (only run with `--check`).
+    pub check: Option<bool>,
+
+    /// Resident memory (in bytes) of the process (only gathered when wanted).
+    pub rss: Option<u64>,
+
+    /// CPU usage (percent of one CPU) of the process, see `procfs::cpu_usage`.
+    pub cpu: Option<f64>,
+
+    /// The user id the process runs as, and its name with `--user-names`.
+    pub uid: Option<libc::uid_t>,
+    pub user: Option<String>,
+
+    /// Open file descriptors of the process, and the count at which they're
+    /// shown in red (`--fd-threshold` percent of its open files limit).
+    pub fds: Option<usize>,
+    pub fds_warn: Option<u64>,
+
+    /// Number of threads in the process.
+    pub threads: Option<u64>,
+
+    /// Whether the process's executable was deleted (it needs a restart to
+    /// run the new one after an upgrade).
+    pub outdated: Option<bool>,
+
+    /// The cgroup of the process, and the memory used by everything in it
+    /// (cgroup2 only).
+    pub cgroup: Option<String>,
+    pub cgroup_memory: Option<u64>,
+
+    /// The namespaces (`pid` and `mnt`) the process is in that `vsv` isn't,
+    /// any means it's likely running in a container.
+    pub namespaces: Option<Vec<&'static str>>,
+
+    /// The nice value and scheduling class (like `ps -o ni,cls`) of the
+    /// process.
+    pub nice: Option<i64>,
+    pub class: Option<&'static str>,
+
+    /// The OOM killer's score for the process, and the adjustment to it.
+    pub oom_score: Option<i64>,
+    pub oom_score_adj: Option<i64>,
+
+    /// Bytes the process has read from and written to storage.
+    pub io_read: Option<u64>,
+    pub io_write: Option<u64>,
+
+    /// Whether the process started at a very different time than the
+    /// supervisor says (the pid was reused or the supervise data is stale).
+    pub stale: bool,
+
+    /// Every process descended from the service's process (with
+    /// `--children`).
+    pub children: Option<Result<Vec<procfs::ProcessTree>>>,
+
+    /// Why the state couldn't be read (the state is `Error`).
+    pub error: Option<String>,
 }
 
 impl Service {
-    /// Create a new service from a `RunitService`.
+    /**
+     * Create a new service from a `RunitService`.
+     *
+     * Only the data required for `opts.columns` is gathered (for example,
+     * `/proc` is not read unless the command column is wanted).
+     */
     pub fn from_runit_service(
         service: &RunitService,
-        want_pstree: bool,
-        proc_path: &Path,
-        pstree_prog: &str,
+        opts: &Options,
+    ) -> (Self, Vec<String>) {
+        Self::from_status(service, service.get_status(), opts)
+    }
+
+    /**
+     * Create a new service from a `RunitService` and its status (already
+     * read with `RunitService::get_status`), see `from_runit_service`.
+     */
+    pub fn from_status(
+        service: &RunitService,
+        status: Status,
+        opts: &Options,
     ) -> (Self, Vec<String>) {
+        let stat = read_stat(&status, &opts.proc_path);
+
+        Self::from_process(service, status, stat, opts)
+    }
+
+    /**
+     * Create a new service from a `RunitService`, its status and the `stat`
+     * of its process (already read with `read_stat`), see `from_status`.
+     */
+    pub fn from_process(
+        service: &RunitService,
+        status: Status,
+        stat: Option<Result<procfs::Stat>>,
+        opts: &Options,
+    ) -> (Self, Vec<String>) {
+        let columns = &opts.columns;
+        let proc_path = &opts.proc_path;
+        let timeout = opts.timeout;
         let mut messages: Vec<String> = vec![];
         let name = service.name.to_string();
+        let log = service.log;
         let enabled = service.enabled();
-        let pid = service.get_pid();
-        let state = service.get_state();
-        let start_time = service.get_start_time();
+        // the stat is only reported on for the columns read from it
+        let stat_read = stat.as_ref().and_then(|stat| stat.as_ref().ok());
+        // a service that can't be read is still listed, with its error
+        let (state, error) = ServiceState::from_status(&status, stat_read);
+
+        // a service without a process (like one that's down) has no pid
+        let pid = match status.pid {
+            Ok(pid) => pid,
+            Err(err) => {
+                messages.push(format!(
+                    "{:?}: failed to get pid: {}",
+                    service.path, err
+                ));
+                None
+            }
+        };
+        let want = status.want.map(ServiceState::from);
+        let start_time = status.start_time;
 
         let mut command = None;
-        if let Ok(p) = pid {
-            match utils::cmd_from_pid(p, proc_path) {
+        if let (Some(p), true) = (&pid, columns.contains(&Column::Command)) {
+            let p = *p;
+            let cmd = match opts.full_command {
+                true => utils::full_cmd_from_pid(p, proc_path),
+                false => utils::cmd_from_pid(p, proc_path),
+            };
+            match cmd {
                 Ok(cmd) => {
                     command = Some(cmd);
                 }
@@ -112,58 +337,475 @@ impl Service {
             };
         }
 
-        let pid = match pid {
-            Ok(pid) => Some(pid),
-            Err(ref err) => {
-                messages.push(format!(
-                    "{:?}: failed to get pid: {}",
+        let mut rss = None;
+        if let (Some(p), true) = (&pid, columns.contains(&Column::Rss)) {
+            match procfs::rss_from_pid(*p, proc_path) {
+                Ok(r) => rss = r,
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get rss for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        let mut uid = None;
+        let mut user = None;
+        if let (Some(p), true) = (&pid, columns.contains(&Column::User)) {
+            match procfs::uid_from_pid(*p, proc_path) {
+                Ok(u) => {
+                    uid = Some(u);
+                    if opts.user_names {
+                        user = cache::user_name(u, || utils::user_name(u));
+                    }
+                }
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get user for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        let mut threads = None;
+        if let (Some(p), true) = (&pid, columns.contains(&Column::Threads)) {
+            match procfs::threads_from_pid(*p, proc_path) {
+                Ok(n) => threads = Some(n),
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get threads for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        let mut outdated = None;
+        if let (Some(p), true) = (&pid, columns.contains(&Column::Outdated)) {
+            match procfs::exe_deleted(*p, proc_path) {
+                Ok(deleted) => outdated = Some(deleted),
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get exe for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        let mut cgroup = None;
+        let mut cgroup_memory = None;
+        let wants_cgroup = columns.contains(&Column::Cgroup)
+            || columns.contains(&Column::CgroupMemory);
+        if let (Some(p), true) = (&pid, wants_cgroup) {
+            match procfs::cgroup_from_pid(*p, proc_path) {
+                Ok(cg) => cgroup = Some(cg),
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get cgroup for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+        if let (Some(cg), true) =
+            (&cgroup, columns.contains(&Column::CgroupMemory))
+        {
+            match procfs::cgroup_memory(&opts.cgroup_path, cg) {
+                Ok(mem) => cgroup_memory = Some(mem),
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get cgroup memory: {:#}",
                     service.path, err
-                ));
-                None
+                )),
             }
-        };
+        }
+
+        let mut namespaces = None;
+        if let (Some(p), true) = (&pid, columns.contains(&Column::Container)) {
+            match procfs::foreign_namespaces(*p, proc_path, NAMESPACES) {
+                Ok(ns) => namespaces = Some(ns),
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get namespaces for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        let mut nice = None;
+        let mut class = None;
+        let wants_sched =
+            columns.contains(&Column::Nice) || columns.contains(&Column::Class);
+        if let (Some(p), Some(stat), true) = (&pid, &stat, wants_sched) {
+            let sched = match stat {
+                Ok(stat) => stat.sched(),
+                Err(err) => Err(Error::Other(anyhow!("{:#}", err))),
+            };
+            match sched {
+                Ok((n, c)) => {
+                    nice = Some(n);
+                    class = Some(c);
+                }
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get priority for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        let mut oom_score = None;
+        let mut oom_score_adj = None;
+        let wants_oom =
+            columns.contains(&Column::Oom) || columns.contains(&Column::OomAdj);
+        if let (Some(p), true) = (&pid, wants_oom) {
+            match procfs::oom_from_pid(*p, proc_path) {
+                Ok((score, adj)) => {
+                    oom_score = Some(score);
+                    oom_score_adj = Some(adj);
+                }
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get oom score for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        let mut io_read = None;
+        let mut io_write = None;
+        let wants_io = columns.contains(&Column::IoRead)
+            || columns.contains(&Column::IoWrite);
+        if let (Some(p), true) = (&pid, wants_io) {
+            match procfs::io_from_pid(*p, proc_path) {
+                Ok((read, write)) => {
+                    io_read = Some(read);
+                    io_write = Some(write);
+                }
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get io for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        // the process should have started right when the supervisor says
+        // (shown with the status char and state)
+        let mut stale = false;
+        let wants_stale = columns
+            .iter()
+            .any(|column| matches!(column, Column::Status | Column::State));
+        if let (Some(p), Some(stat), Ok(time), true) =
+            (&pid, stat_read, &start_time, wants_stale)
+        {
+            if let Ok(started) = stat.start_time(proc_path) {
+                let skew = match started.duration_since(*time) {
+                    Ok(d) => d,
+                    Err(err) => err.duration(),
+                };
+                if skew > MAX_START_SKEW {
+                    stale = true;
+                    messages.push(format!(
+                        "{:?}: pid {} started {} {} the supervisor says \
+                         (reused pid or stale supervise data?)",
+                        service.path,
+                        p,
+                        utils::relative_duration(&skew),
+                        if started > *time { "after" } else { "before" }
+                    ));
+                }
+            }
+        }
+
+        let mut fds = None;
+        let mut fds_warn = None;
+        if let (Some(p), true) = (&pid, columns.contains(&Column::Fds)) {
+            match procfs::fd_count(*p, proc_path) {
+                Ok(n) => fds = Some(n),
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get fds for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+            match procfs::open_files_limit(*p, proc_path) {
+                Ok(limit) => {
+                    fds_warn = limit.map(|limit| {
+                        (limit as f64 * opts.fd_threshold / 100.0).ceil() as u64
+                    })
+                }
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get open files limit for pid {}: {:#}",
+                    service.path, p, err
+                )),
+            }
+        }
+
+        // log services don't have their own logs
+        let mut log_size = None;
+        if !log && columns.contains(&Column::LogSize) {
+            match service.log_size() {
+                Ok(size) => log_size = size,
+                Err(err) => messages.push(format!(
+                    "{:?}: failed to get log size: {}",
+                    service.path, err
+                )),
+            }
+        }
+
+        let mut check = None;
+        if columns.contains(&Column::Check) {
+            match service.run_check(timeout) {
+                Some(Ok(ok)) => check = Some(ok),
+                Some(Err(err)) => {
+                    messages.push(format!(
+                        "{:?}: check failed: {:#}",
+                        service.path, err
+                    ));
+                    check = Some(false);
+                }
+                None => (),
+            }
+        }
 
         // optionally get pstree.  None if the user wants it, Some if the user
         // wants it regardless of execution success.
-        let pstree = if want_pstree {
-            pid.map(|pid| get_pstree(pid, pstree_prog))
+        let pstree = if opts.tree {
+            pid.map(|pid| get_pstree(pid, &opts.pstree_prog, timeout))
         } else {
             None
         };
 
-        let state = match state {
-            RunitServiceState::Run => ServiceState::Run,
-            RunitServiceState::Down => ServiceState::Down,
-            RunitServiceState::Finish => ServiceState::Finish,
-            RunitServiceState::Unknown => ServiceState::Unknown,
-        };
+        if let Some(err) = &error {
+            messages.push(format!(
+                "{:?}: failed to read state: {}",
+                service.path, err
+            ));
+        }
 
-        let svc =
-            Self { name, state, enabled, command, pid, start_time, pstree };
+        let svc = Self {
+            name,
+            log,
+            state,
+            want,
+            enabled,
+            command,
+            pid,
+            start_time,
+            pstree,
+            origin: None,
+            host: None,
+            log_size,
+            restarts: None,
+            check,
+            rss,
+            cpu: None,
+            uid,
+            user,
+            fds,
+            fds_warn,
+            threads,
+            outdated,
+            cgroup,
+            cgroup_memory,
+            namespaces,
+            nice,
+            class,
+            oom_score,
+            oom_score_adj,
+            io_read,
+            io_write,
+            stale,
+            children: None,
+            error,
+        };
 
         (svc, messages)
     }
 
-    /// Format the service name as a string.
+    /// Check if the service is in a state that needs attention.
+    pub fn is_problem(&self) -> bool {
+        if self.is_mismatched()
+            || self.stale
+            || self.is_flapping()
+            || self.check == Some(false)
+        {
+            return true;
+        }
+
+        self.state != ServiceState::Run && !self.is_normally_down()
+    }
+
+    /// Check if the service keeps restarting (stuck in a crash loop).
+    pub fn is_flapping(&self) -> bool {
+        self.restarts.is_some_and(|n| n >= restarts::FLAP_THRESHOLD)
+    }
+
+    /**
+     * Check if the service is down on purpose - it has a `./down` file (is
+     * disabled) and is not running, as opposed to down because it's broken.
+     */
+    pub fn is_normally_down(&self) -> bool {
+        !self.enabled && self.state == ServiceState::Down
+    }
+
+    /**
+     * Check if the service isn't in the state runit wants it in - for example
+     * wanted up but stuck in a `finish` loop, or running despite being wanted
+     * down.
+     */
+    pub fn is_mismatched(&self) -> bool {
+        // the status file is stale if nothing is supervising the service
+        if self.state == ServiceState::Unsupervised {
+            return false;
+        }
+
+        match self.want {
+            Some(ServiceState::Run) => self.state != ServiceState::Run,
+            Some(ServiceState::Down) => self.state == ServiceState::Run,
+            _ => false,
+        }
+    }
+
+    /// Get how long the service has been in its current state, if known.
+    pub fn uptime(&self) -> Option<time::Duration> {
+        self.start_time.as_ref().ok()?.elapsed().ok()
+    }
+
+    /**
+     * Compare 2 services by the (typed) value of a given column.  Unknown
+     * values (like a missing pid) always sort after known values.
+     */
+    pub fn cmp_column(&self, other: &Self, column: Column) -> Ordering {
+        match column {
+            Column::Status | Column::State => self.state.cmp(&other.state),
+            Column::Name => self.name.cmp(&other.name),
+            Column::Enabled => self.enabled.cmp(&other.enabled),
+            Column::Pid => cmp_option(&self.pid, &other.pid),
+            Column::Command => cmp_option(&self.command, &other.command),
+            Column::Uptime => cmp_option(&self.uptime(), &other.uptime()),
+            Column::Origin => cmp_option(&self.origin, &other.origin),
+            Column::Host => cmp_option(&self.host, &other.host),
+            Column::LogSize => cmp_option(&self.log_size, &other.log_size),
+            Column::Restarts => cmp_option(&self.restarts, &other.restarts),
+            Column::Check => cmp_option(&self.check, &other.check),
+            Column::Rss => cmp_option(&self.rss, &other.rss),
+            Column::Fds => cmp_option(&self.fds, &other.fds),
+            Column::Threads => cmp_option(&self.threads, &other.threads),
+            Column::Outdated => cmp_option(&self.outdated, &other.outdated),
+            Column::Cgroup => cmp_option(&self.cgroup, &other.cgroup),
+            Column::Nice => cmp_option(&self.nice, &other.nice),
+            Column::Class => cmp_option(&self.class, &other.class),
+            Column::Oom => cmp_option(&self.oom_score, &other.oom_score),
+            Column::IoRead => cmp_option(&self.io_read, &other.io_read),
+            Column::IoWrite => cmp_option(&self.io_write, &other.io_write),
+            Column::OomAdj => {
+                cmp_option(&self.oom_score_adj, &other.oom_score_adj)
+            }
+            Column::Container => {
+                cmp_option(&self.namespaces, &other.namespaces)
+            }
+            Column::CgroupMemory => {
+                cmp_option(&self.cgroup_memory, &other.cgroup_memory)
+            }
+            Column::User => cmp_option(&self.user, &other.user)
+                .then_with(|| cmp_option(&self.uid, &other.uid)),
+            Column::Cpu => {
+                cmp_option_by(&self.cpu, &other.cpu, |a, b| a.total_cmp(b))
+            }
+        }
+    }
+
+    /// Format the given column for this service as a string.
+    pub fn format_column(&self, column: Column) -> (String, Style) {
+        match column {
+            Column::Status => self.format_status_char(),
+            Column::Name => self.format_name(),
+            Column::State => self.format_state(),
+            Column::Enabled => self.format_enabled(),
+            Column::Pid => self.format_pid(),
+            Column::Command => self.format_command(),
+            Column::Uptime => self.format_time(),
+            Column::Origin => self.format_origin(),
+            Column::Host => self.format_host(),
+            Column::LogSize => self.format_log_size(),
+            Column::Restarts => self.format_restarts(),
+            Column::Check => self.format_check(),
+            Column::Rss => self.format_rss(),
+            Column::Cpu => self.format_cpu(),
+            Column::User => self.format_user(),
+            Column::Fds => self.format_fds(),
+            Column::Threads => self.format_threads(),
+            Column::Outdated => self.format_outdated(),
+            Column::Cgroup => self.format_cgroup(),
+            Column::Nice => self.format_nice(),
+            Column::Class => self.format_class(),
+            Column::Oom => self.format_oom(),
+            Column::IoRead => format_bytes(self.io_read, column),
+            Column::IoWrite => format_bytes(self.io_write, column),
+            Column::OomAdj => self.format_oom_adj(),
+            Column::Container => self.format_container(),
+            Column::CgroupMemory => self.format_cgroup_memory(),
+        }
+    }
+
+    /// Format the service name as a string, log services are indented.
     fn format_name(&self) -> (String, Style) {
-        (self.name.to_string(), Style::default())
+        if self.log {
+            return (format!("  {}", self.name), Style::default().dimmed());
+        }
+
+        (self.name.to_string(), theme::get().column(Column::Name))
     }
 
     /// Format the service char as a string.
     fn format_status_char(&self) -> (String, Style) {
-        (self.state.get_char(), self.state.get_style())
+        let glyphs = glyphs::get();
+
+        if self.is_mismatched() {
+            return (glyphs.mismatched.clone(), self.state_style());
+        }
+
+        if self.stale {
+            return (glyphs.stale.clone(), self.state_style());
+        }
+
+        if self.is_flapping() {
+            return (glyphs.flapping.clone(), self.state_style());
+        }
+
+        if self.is_normally_down() {
+            return (glyphs.normally_down.clone(), self.state_style());
+        }
+
+        (self.state.get_char(), self.state_style())
     }
 
     /// Format the service state as a string.
     fn format_state(&self) -> (String, Style) {
-        (self.state.to_string(), self.state.get_style())
+        (self.state.to_string(), self.state_style())
+    }
+
+    /**
+     * Get the style for the state - the theme's style for a service that
+     * isn't in the wanted state, has a stale pid, is flapping or is normally
+     * down (in that order), or else for its state.
+     */
+    fn state_style(&self) -> Style {
+        let states = &theme::get().states;
+
+        if self.is_mismatched() {
+            return states.mismatched;
+        }
+
+        if self.stale {
+            return states.stale;
+        }
+
+        if self.is_flapping() {
+            return states.flapping;
+        }
+
+        if self.is_normally_down() {
+            return states.normally_down;
+        }
+
+        self.state.get_style()
     }
 
     /// Format the service enabled status as a string.
     fn format_enabled(&self) -> (String, Style) {
         let style = match self.enabled {
-            true => Style::default().fg(Color::Green),
-            false => Style::default().fg(Color::Red),
+            true => theme::get().good,
+            false => theme::get().bad,
         };
 
         let s = self.enabled.to_string();
@@ -173,7 +815,7 @@ impl Service {
 
     /// Format the service pid as a string.
     fn format_pid(&self) -> (String, Style) {
-        let style = Style::default().fg(Color::Magenta);
+        let style = theme::get().column(Column::Pid);
 
         let s = match self.pid {
             Some(pid) => pid.to_string(),
@@ -185,7 +827,7 @@ impl Service {
 
     /// Format the service command a string.
     fn format_command(&self) -> (String, Style) {
-        let style = Style::default().fg(Color::Green);
+        let style = theme::get().column(Column::Command);
 
         let s = match &self.command {
             Some(cmd) => cmd.clone(),
@@ -197,28 +839,283 @@ impl Service {
 
     /// Format the service time as a string.
     fn format_time(&self) -> (String, Style) {
-        let style = Style::default();
+        let theme = theme::get();
+
+        // the time can't be read either, the error is shown with the state
+        if self.state == ServiceState::Error {
+            return (String::from("---"), theme.column(Column::Uptime));
+        }
 
         let time = match &self.start_time {
             Ok(time) => time,
-            Err(err) => return (err.to_string(), style.fg(Color::Red)),
+            Err(err) => return (err.to_string(), theme.bad),
         };
 
         let t = match time.elapsed() {
             Ok(t) => t,
-            Err(err) => return (err.to_string(), style.fg(Color::Red)),
+            Err(err) => return (err.to_string(), theme.bad),
         };
 
-        let s = utils::relative_duration(&t);
+        let mut s = time_format::get().since(time, &t);
+        if let (true, Some(n)) = (self.is_flapping(), self.restarts) {
+            s.push_str(&format!(" (flapping, {} restarts)", n));
+            return (s, self.state_style());
+        }
+
         let style = match t.as_secs() {
-            t if t < 5 => style.fg(Color::Red),
-            t if t < 30 => style.fg(Color::Yellow),
-            _ => style.dimmed(),
+            t if t < 5 => theme.bad,
+            t if t < 30 => theme.warn,
+            _ => theme.column(Column::Uptime),
+        };
+
+        (s, style)
+    }
+
+    /// Format the service origin (tree) as a string.
+    fn format_origin(&self) -> (String, Style) {
+        let style = theme::get().column(Column::Origin);
+
+        let s = match &self.origin {
+            Some(origin) => origin.clone(),
+            None => String::from("---"),
+        };
+
+        (s, style)
+    }
+
+    /// Format the host the service is on as a string.
+    fn format_host(&self) -> (String, Style) {
+        let style = theme::get().column(Column::Host);
+
+        let s = match &self.host {
+            Some(host) => host.clone(),
+            None => String::from("---"),
+        };
+
+        (s, style)
+    }
+
+    /// Format the service log directory size as a string.
+    fn format_log_size(&self) -> (String, Style) {
+        let style = theme::get().column(Column::LogSize);
+
+        let s = match self.log_size {
+            Some(size) => utils::human_size(size),
+            None => String::from("---"),
+        };
+
+        (s, style)
+    }
+
+    /// Format the number of recent restarts as a string.
+    fn format_restarts(&self) -> (String, Style) {
+        let style = match self.is_flapping() {
+            true => self.state_style(),
+            false => theme::get().column(Column::Restarts),
+        };
+
+        let s = match self.restarts {
+            Some(n) => n.to_string(),
+            None => String::from("---"),
+        };
+
+        (s, style)
+    }
+
+    /// Format the result of the service's `./check` as a string.
+    fn format_check(&self) -> (String, Style) {
+        match self.check {
+            Some(true) => ("pass".into(), theme::get().good),
+            Some(false) => ("fail".into(), theme::get().bad),
+            None => ("---".into(), theme::get().column(Column::Check)),
+        }
+    }
+
+    /// Format the resident memory of the process as a string.
+    fn format_rss(&self) -> (String, Style) {
+        let style = theme::get().column(Column::Rss);
+
+        let s = match self.rss {
+            Some(rss) => utils::human_size(rss),
+            None => String::from("---"),
+        };
+
+        (s, style)
+    }
+
+    /// Format the CPU usage of the process as a string.
+    fn format_cpu(&self) -> (String, Style) {
+        let theme = theme::get();
+
+        match self.cpu {
+            Some(cpu) => {
+                let style = match cpu {
+                    cpu if cpu >= 90.0 => theme.bad,
+                    cpu if cpu >= 50.0 => theme.warn,
+                    _ => theme.column(Column::Cpu),
+                };
+                (format!("{:.1}", cpu), style)
+            }
+            None => ("---".into(), theme.column(Column::Cpu)),
+        }
+    }
+
+    /// Format the user the process runs as (red for root) as a string.
+    fn format_user(&self) -> (String, Style) {
+        let style = match self.uid {
+            Some(0) => theme::get().bad,
+            _ => theme::get().column(Column::User),
+        };
+
+        let s = match (&self.user, self.uid) {
+            (Some(user), _) => user.clone(),
+            (None, Some(uid)) => uid.to_string(),
+            (None, None) => String::from("---"),
         };
 
         (s, style)
     }
 
+    /// Format the open file descriptors (red near the limit) as a string.
+    fn format_fds(&self) -> (String, Style) {
+        let theme = theme::get();
+
+        match (self.fds, self.fds_warn) {
+            (Some(fds), Some(warn)) if fds as u64 >= warn => {
+                (fds.to_string(), theme.bad)
+            }
+            (Some(fds), _) => (fds.to_string(), theme.column(Column::Fds)),
+            (None, _) => ("---".into(), theme.column(Column::Fds)),
+        }
+    }
+
+    /// Format the number of threads in the process as a string.
+    fn format_threads(&self) -> (String, Style) {
+        let s = match self.threads {
+            Some(threads) => threads.to_string(),
+            None => String::from("---"),
+        };
+
+        (s, theme::get().column(Column::Threads))
+    }
+
+    /// Format whether the process needs a restart (a deleted executable).
+    fn format_outdated(&self) -> (String, Style) {
+        let theme = theme::get();
+
+        match self.outdated {
+            Some(true) => ("yes".into(), theme.bad),
+            Some(false) => ("no".into(), theme.column(Column::Outdated)),
+            None => ("---".into(), theme.column(Column::Outdated)),
+        }
+    }
+
+    /// Format the cgroup of the process as a string.
+    fn format_cgroup(&self) -> (String, Style) {
+        let s = match &self.cgroup {
+            Some(cgroup) => cgroup.clone(),
+            None => String::from("---"),
+        };
+
+        (s, theme::get().column(Column::Cgroup))
+    }
+
+    /// Format the memory used by the process's cgroup as a string.
+    fn format_cgroup_memory(&self) -> (String, Style) {
+        let style = theme::get().column(Column::CgroupMemory);
+
+        let s = match self.cgroup_memory {
+            Some(mem) => utils::human_size(mem),
+            None => String::from("---"),
+        };
+
+        (s, style)
+    }
+
+    /// Format the nice value of the process (yellow if not 0) as a string.
+    fn format_nice(&self) -> (String, Style) {
+        let theme = theme::get();
+
+        match self.nice {
+            Some(0) => ("0".into(), theme.column(Column::Nice)),
+            Some(nice) => (nice.to_string(), theme.warn),
+            None => ("---".into(), theme.column(Column::Nice)),
+        }
+    }
+
+    /**
+     * Format the scheduling class of the process as a string: realtime
+     * classes are red and batch and idle classes yellow.
+     */
+    fn format_class(&self) -> (String, Style) {
+        let theme = theme::get();
+
+        let class = match self.class {
+            Some(class) => class,
+            None => return ("---".into(), theme.column(Column::Class)),
+        };
+
+        let style = match class {
+            "FF" | "RR" | "DLN" => theme.bad,
+            "B" | "IDL" => theme.warn,
+            _ => theme.column(Column::Class),
+        };
+
+        (class.into(), style)
+    }
+
+    /**
+     * Format the OOM score of the process as a string: red if it's likely to
+     * be killed first (500 or more) and yellow if at risk (200 or more).
+     */
+    fn format_oom(&self) -> (String, Style) {
+        let theme = theme::get();
+
+        match self.oom_score {
+            Some(score) => {
+                let style = match score {
+                    score if score >= 500 => theme.bad,
+                    score if score >= 200 => theme.warn,
+                    _ => theme.column(Column::Oom),
+                };
+                (score.to_string(), style)
+            }
+            None => ("---".into(), theme.column(Column::Oom)),
+        }
+    }
+
+    /**
+     * Format the OOM score adjustment of the process as a string: green if
+     * it's never killed (`-1000`) and yellow if it's made more likely.
+     */
+    fn format_oom_adj(&self) -> (String, Style) {
+        let theme = theme::get();
+
+        match self.oom_score_adj {
+            Some(adj) => {
+                let style = match adj {
+                    -1000 => theme.good,
+                    adj if adj > 0 => theme.warn,
+                    _ => theme.column(Column::OomAdj),
+                };
+                (adj.to_string(), style)
+            }
+            None => ("---".into(), theme.column(Column::OomAdj)),
+        }
+    }
+
+    /**
+     * Format whether the process is in a container as a string: the
+     * namespaces it doesn't share with `vsv` (like `pid,mnt`) or `no`.
+     */
+    fn format_container(&self) -> (String, Style) {
+        match &self.namespaces {
+            Some(ns) if ns.is_empty() => ("no".into(), Style::default()),
+            Some(ns) => (ns.join(","), theme::get().column(Column::Container)),
+            None => ("---".into(), Style::default()),
+        }
+    }
+
     /// Format the service `pstree` output as a string.
     pub fn format_pstree(&self) -> (String, Style) {
         let style = Style::default();
@@ -231,34 +1128,100 @@ impl Service {
         let (tree_s, style) = match tree {
             Ok(stdout) => (stdout.trim().into(), style.dimmed()),
             Err(err) => {
-                (format!("pstree call failed: {}", err), style.fg(Color::Red))
+                (format!("pstree call failed: {}", err), theme::get().bad)
             }
         };
 
         (format!("\n{}\n", tree_s), style)
     }
+
+    /**
+     * Format the processes descended from the service's process (with
+     * `--children`) as lines like:
+     *
+     * ```text
+     *     ├─ 124 nginx
+     *     │  └─ 125 nginx
+     *     └─ 126 sh
+     * ```
+     *
+     * Nothing is returned if there are no child processes.
+     */
+    pub fn format_children(&self) -> (String, Style) {
+        let style = Style::default();
+
+        let children = match &self.children {
+            Some(children) => children,
+            None => return ("".into(), style),
+        };
+
+        match children {
+            Ok(children) => {
+                let mut lines = vec![];
+                format_process_trees(children, "    ", &mut lines);
+                (lines.join("\n"), style.dimmed())
+            }
+            Err(err) => (
+                format!("    failed to find child processes: {}", err),
+                theme::get().bad,
+            ),
+        }
+    }
 }
 
-impl fmt::Display for Service {
-    /// Format the service as a string suitable for output by `vsv`.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let base = utils::format_status_line(
-            self.format_status_char(),
-            self.format_name(),
-            self.format_state(),
-            self.format_enabled(),
-            self.format_pid(),
-            self.format_command(),
-            self.format_time(),
-        );
-
-        base.fmt(f)
+/// Draw process trees (and their children) as lines prefixed with `prefix`.
+fn format_process_trees(
+    trees: &[procfs::ProcessTree],
+    prefix: &str,
+    lines: &mut Vec<String>,
+) {
+    for (i, tree) in trees.iter().enumerate() {
+        let last = i == trees.len() - 1;
+        let (branch, indent) =
+            if last { ("└─", "   ") } else { ("├─", "│  ") };
+
+        lines.push(format!("{}{} {} {}", prefix, branch, tree.pid, tree.name));
+
+        let prefix = format!("{}{}", prefix, indent);
+        format_process_trees(&tree.children, &prefix, lines);
+    }
+}
+
+/// Format a number of bytes (like I/O done) as a human readable string.
+fn format_bytes(bytes: Option<u64>, column: Column) -> (String, Style) {
+    let s = match bytes {
+        Some(bytes) => utils::human_size(bytes),
+        None => String::from("---"),
+    };
+
+    (s, theme::get().column(column))
+}
+
+/// Compare 2 options, with `None` sorting after any `Some` value.
+fn cmp_option<T: Ord>(a: &Option<T>, b: &Option<T>) -> Ordering {
+    cmp_option_by(a, b, T::cmp)
+}
+
+/// Compare 2 options with a function, `None` sorts after any `Some` value.
+fn cmp_option_by<T, F>(a: &Option<T>, b: &Option<T>, f: F) -> Ordering
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    match (a, b) {
+        (Some(a), Some(b)) => f(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
     }
 }
 
 /// Get the `pstree` for a given pid.
-fn get_pstree(pid: pid_t, pstree_prog: &str) -> Result<String> {
+fn get_pstree(
+    pid: pid_t,
+    pstree_prog: &str,
+    timeout: Option<time::Duration>,
+) -> Result<String> {
     let cmd = pstree_prog.to_string();
     let args = ["-ac".to_string(), pid.to_string()];
-    utils::run_program_get_output(&cmd, &args)
+    utils::run_program_get_output(&cmd, &args, timeout)
 }
@@ -0,0 +1,184 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+//! Iterate the services in a service directory.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::vec;
+
+use crate::backend::{self, Supervisor};
+use crate::error::{Context, Error};
+use crate::options::Options;
+use crate::runit::{self, RunitService, RunitServiceState};
+use crate::service::Service;
+
+/**
+ * A service directory (like `/var/service`) and the supervision suite found
+ * there.
+ *
+ * Problems reading a single service (like a missing `supervise` directory)
+ * don't fail listing the directory, they are reflected in the `Service`
+ * returned (for example an "unknown" state).
+ */
+#[derive(Debug)]
+pub struct ServiceDir {
+    svdir: PathBuf,
+    opts: Options,
+}
+
+impl ServiceDir {
+    /// Open the service directory at `path` with the default `Options`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::with_options(path, Options::default())
+    }
+
+    /// Open the service directory at `path`, reading services with `opts`.
+    pub fn with_options<P: AsRef<Path>>(
+        path: P,
+        opts: Options,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+
+        if !opts.proc_path.is_dir() {
+            return Err(Error::ProcUnavailable(opts.proc_path));
+        }
+
+        let dir = Self { svdir: path.to_path_buf(), opts };
+        if dir.backend().lists_dir() {
+            fs::read_dir(path)
+                .with_context(|| format!("failed to read dir {:?}", path))
+                .map_err(|err| err.with_path(path.to_path_buf()))?;
+        }
+
+        Ok(dir)
+    }
+
+    /// Get the path of the service directory.
+    pub fn path(&self) -> &Path {
+        &self.svdir
+    }
+
+    fn backend(&self) -> &'static dyn Supervisor {
+        self.opts.backend_for(&self.svdir)
+    }
+
+    /// List every service (sorted by name).
+    fn list(&self) -> Result<Vec<RunitService>, Error> {
+        let svdir = &self.svdir;
+
+        runit::get_services(svdir, false, self.backend(), |_| true)
+            .map_err(|err| err.with_path(svdir.clone()))
+    }
+
+    /// Get the status of every service, sorted by name.
+    pub fn services(&self) -> Result<Vec<Service>, Error> {
+        let services = self.list()?;
+
+        Ok(services.iter().map(|service| self.read(service)).collect())
+    }
+
+    /**
+     * Iterate the services as the directory is read (in no particular
+     * order), reading the status of each only when it's reached.  Stopping
+     * early (like with `.any()`) skips the rest of the directory.
+     */
+    pub fn iter(&self) -> Iter<'_> {
+        let entries = match self.backend().lists_dir() {
+            true => fs::read_dir(&self.svdir)
+                .map(Entries::Dir)
+                .map_err(|err| Error::from(err).with_path(self.svdir.clone())),
+            false => self
+                .list()
+                .map(|services| Entries::Listed(services.into_iter())),
+        };
+
+        let entries = entries.unwrap_or_else(|err| Entries::Failed(Some(err)));
+
+        Iter { dir: self, entries }
+    }
+
+    /**
+     * Get a single service by name, failing if it doesn't exist, isn't
+     * supervised or its status can't be read.
+     */
+    pub fn service(&self, name: &str) -> Result<Service, Error> {
+        let path = self.svdir.join(name);
+        if !path.is_dir() {
+            return Err(Error::NotFound(path));
+        }
+
+        let svc = RunitService::new(name, &path, self.opts.backend_for(&path));
+
+        // the supervise directory must be readable (not EACCES) to read it
+        let status = svc.get_status();
+        let denied = status.error.as_ref().and_then(Error::io_kind)
+            == Some(io::ErrorKind::PermissionDenied);
+        if denied {
+            return Err(Error::PermissionDenied(path.join("supervise")));
+        }
+
+        if status.state == RunitServiceState::Unsupervised {
+            return Err(Error::NotSupervised(name.to_string()));
+        }
+
+        let (service, _) = Service::from_status(&svc, status, &self.opts);
+
+        Ok(service)
+    }
+
+    /// Read the status of a service.
+    fn read(&self, service: &RunitService) -> Service {
+        let (service, _) = Service::from_runit_service(service, &self.opts);
+
+        service
+    }
+}
+
+/// Where `Iter` gets service names and paths from.
+enum Entries {
+    /// Read from the service directory one at a time.
+    Dir(fs::ReadDir),
+
+    /// Listed all at once by the backend (like systemd's units).
+    Listed(vec::IntoIter<RunitService>),
+
+    /// The directory couldn't be read, the error is yielded once.
+    Failed(Option<Error>),
+}
+
+/// An iterator over the services of a `ServiceDir`, see `ServiceDir::iter`.
+pub struct Iter<'a> {
+    dir: &'a ServiceDir,
+    entries: Entries,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<Service, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let service = match &mut self.entries {
+            Entries::Dir(entries) => loop {
+                match backend::dir_entry_service(entries.next()?) {
+                    Ok(Some((name, path))) => {
+                        break RunitService::new(
+                            &name,
+                            &path,
+                            self.dir.backend(),
+                        )
+                    }
+                    Ok(None) => continue,
+                    Err(err) => return Some(Err(err)),
+                }
+            },
+            Entries::Listed(services) => services.next()?,
+            Entries::Failed(err) => return err.take().map(Err),
+        };
+
+        Some(Ok(self.dir.read(&service)))
+    }
+}
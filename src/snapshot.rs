@@ -0,0 +1,382 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Convert services to and from generic `Value`s, to save a snapshot of them
+ * (for example as JSON with `output::json`) and load it back later.
+ *
+ * Every service becomes an object with these fields, whose names won't change
+ * (new fields may be added):
+ *
+ * | field           | type                 | notes                           |
+ * |-----------------|----------------------|---------------------------------|
+ * | `name`          | string               |                                 |
+ * | `log`           | bool                 | a `log/` service                |
+ * | `state`         | string               | as in the `STATE` column        |
+ * | `want`          | string or null       | `run` or `down`                 |
+ * | `enabled`       | bool                 |                                 |
+ * | `pid`           | int or null          |                                 |
+ * | `command`       | string or null       |                                 |
+ * | `start_time`    | float or null        | seconds since the unix epoch    |
+ * | `origin`        | string or null       |                                 |
+ * | `host`          | string or null       | set by `-H`                     |
+ * | `log_size`      | int or null          | bytes                           |
+ * | `restarts`      | int or null          |                                 |
+ * | `check`         | bool or null         |                                 |
+ * | `rss`           | int or null          | bytes                           |
+ * | `cpu`           | float or null        | percent of one CPU              |
+ * | `uid`           | int or null          |                                 |
+ * | `user`          | string or null       |                                 |
+ * | `fds`           | int or null          |                                 |
+ * | `fds_warn`      | int or null          |                                 |
+ * | `threads`       | int or null          |                                 |
+ * | `outdated`      | bool or null         |                                 |
+ * | `cgroup`        | string or null       |                                 |
+ * | `cgroup_memory` | int or null          | bytes                           |
+ * | `namespaces`    | string array or null | like `["pid", "mnt"]`           |
+ * | `nice`          | int or null          |                                 |
+ * | `class`         | string or null       | like `TS`                       |
+ * | `oom_score`     | int or null          |                                 |
+ * | `oom_score_adj` | int or null          |                                 |
+ * | `io_read`       | int or null          | bytes                           |
+ * | `io_write`      | int or null          | bytes                           |
+ * | `stale`         | bool                 |                                 |
+ * | `error`         | string or null       | why the state couldn't be read  |
+ *
+ * Durations are given in (fractional) seconds.  Process trees (`pstree` and
+ * `children`) are not saved.
+ */
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::anyhow;
+
+use crate::error::{bail, Context, Error, Result};
+use crate::output::Value;
+use crate::procfs;
+use crate::service::{self, Service, ServiceState};
+
+impl From<ServiceState> for Value {
+    fn from(state: ServiceState) -> Self {
+        state.to_string().into()
+    }
+}
+
+impl TryFrom<&Value> for ServiceState {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        let s = value.as_str().context("state not a string")?;
+
+        ServiceState::parse(s)
+    }
+}
+
+impl From<Duration> for Value {
+    fn from(dur: Duration) -> Self {
+        Value::Float(dur.as_secs_f64())
+    }
+}
+
+impl TryFrom<&Value> for Duration {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self> {
+        let secs = value.as_f64().context("duration not a number")?;
+
+        Duration::try_from_secs_f64(secs)
+            .with_context(|| format!("invalid duration: {}", secs))
+    }
+}
+
+impl Service {
+    /// Convert the service into a `Value` object (see the module docs).
+    pub fn to_value(&self) -> Value {
+        let int = |n: Option<u64>| n.map(|n| n as i64);
+        let start_time = self
+            .start_time
+            .as_ref()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok());
+        let namespaces = self.namespaces.as_ref().map(|namespaces| {
+            Value::Array(namespaces.iter().map(|ns| Value::from(*ns)).collect())
+        });
+
+        let obj: Vec<(&str, Value)> = vec![
+            ("name", self.name.as_str().into()),
+            ("log", self.log.into()),
+            ("state", self.state.into()),
+            ("want", self.want.into()),
+            ("enabled", self.enabled.into()),
+            ("pid", self.pid.map(i64::from).into()),
+            ("command", self.command.as_deref().into()),
+            ("start_time", start_time.into()),
+            ("origin", self.origin.as_deref().into()),
+            ("host", self.host.as_deref().into()),
+            ("log_size", int(self.log_size).into()),
+            ("restarts", self.restarts.map(|n| n as i64).into()),
+            ("check", self.check.into()),
+            ("rss", int(self.rss).into()),
+            ("cpu", self.cpu.into()),
+            ("uid", self.uid.map(i64::from).into()),
+            ("user", self.user.as_deref().into()),
+            ("fds", self.fds.map(|n| n as i64).into()),
+            ("fds_warn", int(self.fds_warn).into()),
+            ("threads", int(self.threads).into()),
+            ("outdated", self.outdated.into()),
+            ("cgroup", self.cgroup.as_deref().into()),
+            ("cgroup_memory", int(self.cgroup_memory).into()),
+            ("namespaces", namespaces.into()),
+            ("nice", self.nice.into()),
+            ("class", self.class.into()),
+            ("oom_score", self.oom_score.into()),
+            ("oom_score_adj", self.oom_score_adj.into()),
+            ("io_read", int(self.io_read).into()),
+            ("io_write", int(self.io_write).into()),
+            ("stale", self.stale.into()),
+            ("error", self.error.as_deref().into()),
+        ];
+
+        Value::Object(
+            obj.into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /**
+     * Create a service from a `Value` object (like one created by
+     * `to_value`).  Only `name` and `state` are required, any other missing
+     * (or `null`) field is unknown.
+     */
+    pub fn from_value(value: &Value) -> Result<Self, Error> {
+        Self::parse_value(value)
+            .map_err(|err| Error::ParseError(format!("{:#}", err)))
+    }
+
+    fn parse_value(value: &Value) -> Result<Self> {
+        let field = Field { value };
+
+        let name =
+            field.str("name")?.context("service has no name")?.to_string();
+        let state = ServiceState::try_from(
+            value.get("state").context("service has no state")?,
+        )?;
+        let want = match value.get("want") {
+            None | Some(Value::Null) => None,
+            Some(want) => Some(ServiceState::try_from(want)?),
+        };
+        let start_time = match value.get("start_time") {
+            None | Some(Value::Null) => {
+                Err(Error::Other(anyhow!("start time unknown")))
+            }
+            Some(t) => Ok(UNIX_EPOCH + Duration::try_from(t)?),
+        };
+        let namespaces = match value.get("namespaces") {
+            None | Some(Value::Null) => None,
+            Some(arr) => {
+                let arr = arr.as_array().context("namespaces not an array")?;
+                let namespaces = arr
+                    .iter()
+                    .map(|ns| intern(ns.as_str(), service::NAMESPACES))
+                    .collect::<Result<_>>()
+                    .context("invalid namespaces")?;
+                Some(namespaces)
+            }
+        };
+        let class = match field.str("class")? {
+            None => None,
+            Some(class) => Some(
+                intern(Some(class), procfs::SCHED_CLASSES)
+                    .context("invalid class")?,
+            ),
+        };
+
+        Ok(Self {
+            name,
+            log: field.bool("log")?.unwrap_or(false),
+            state,
+            want,
+            enabled: field.bool("enabled")?.unwrap_or(false),
+            command: field.str("command")?.map(String::from),
+            pid: field.int("pid")?,
+            start_time,
+            pstree: None,
+            origin: field.str("origin")?.map(String::from),
+            host: field.str("host")?.map(String::from),
+            log_size: field.int("log_size")?,
+            restarts: field.int("restarts")?,
+            check: field.bool("check")?,
+            rss: field.int("rss")?,
+            cpu: field.float("cpu")?,
+            uid: field.int("uid")?,
+            user: field.str("user")?.map(String::from),
+            fds: field.int("fds")?,
+            fds_warn: field.int("fds_warn")?,
+            threads: field.int("threads")?,
+            outdated: field.bool("outdated")?,
+            cgroup: field.str("cgroup")?.map(String::from),
+            cgroup_memory: field.int("cgroup_memory")?,
+            namespaces,
+            nice: field.int("nice")?,
+            class,
+            oom_score: field.int("oom_score")?,
+            oom_score_adj: field.int("oom_score_adj")?,
+            io_read: field.int("io_read")?,
+            io_write: field.int("io_write")?,
+            stale: field.bool("stale")?.unwrap_or(false),
+            children: None,
+            error: field.str("error")?.map(String::from),
+        })
+    }
+}
+
+/// Typed access to the optional fields of an object.
+struct Field<'a> {
+    value: &'a Value,
+}
+
+impl<'a> Field<'a> {
+    /// Get a field, `null` and missing fields are `None`.
+    fn get(&self, key: &str) -> Option<&'a Value> {
+        match self.value.get(key) {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(value),
+        }
+    }
+
+    fn str(&self, key: &str) -> Result<Option<&'a str>> {
+        self.get(key)
+            .map(|v| {
+                v.as_str().with_context(|| format!("{} not a string", key))
+            })
+            .transpose()
+    }
+
+    fn bool(&self, key: &str) -> Result<Option<bool>> {
+        self.get(key)
+            .map(|v| v.as_bool().with_context(|| format!("{} not a bool", key)))
+            .transpose()
+    }
+
+    fn float(&self, key: &str) -> Result<Option<f64>> {
+        self.get(key)
+            .map(|v| {
+                v.as_f64().with_context(|| format!("{} not a number", key))
+            })
+            .transpose()
+    }
+
+    /// Get an integer field that fits in `T` (like `u64` or `pid_t`).
+    fn int<T: TryFrom<i64>>(&self, key: &str) -> Result<Option<T>> {
+        let i = match self.get(key) {
+            None => return Ok(None),
+            Some(v) => {
+                v.as_i64().with_context(|| format!("{} not an integer", key))?
+            }
+        };
+
+        match T::try_from(i) {
+            Ok(n) => Ok(Some(n)),
+            Err(_) => bail!("{} out of range: {}", key, i),
+        }
+    }
+}
+
+/// Find a string in a list of known `'static` strings.
+fn intern(s: Option<&str>, known: &[&'static str]) -> Result<&'static str> {
+    let s = s.context("not a string")?;
+
+    known
+        .iter()
+        .find(|k| **k == s)
+        .copied()
+        .with_context(|| format!("unknown value: '{}'", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::output::json;
+
+    fn service() -> Service {
+        Service::from_value(&Value::Object(vec![
+            ("name".into(), "foo".into()),
+            ("state".into(), "run".into()),
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let mut svc = service();
+        svc.want = Some(ServiceState::Down);
+        svc.pid = Some(123);
+        svc.command = Some("foo-cmd".into());
+        svc.start_time = Ok(UNIX_EPOCH + Duration::from_millis(1_500));
+        svc.rss = Some(1024);
+        svc.cpu = Some(12.5);
+        svc.namespaces = Some(vec!["pid"]);
+        svc.class = Some("TS");
+        svc.stale = true;
+        svc.error = Some("failed to read status".into());
+
+        let s = json::to_string_pretty(&svc.to_value());
+        let have = Service::from_value(&json::from_str(&s)?)?;
+
+        assert_eq!(have.to_value(), svc.to_value(), "round trip");
+        assert_eq!(
+            svc.to_value().get("start_time"),
+            Some(&Value::Float(1.5)),
+            "start time in seconds"
+        );
+        assert_eq!(have.start_time?, svc.start_time?, "start time");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_value_minimal() {
+        let svc = service();
+
+        assert_eq!(svc.name, "foo");
+        assert_eq!(svc.state, ServiceState::Run);
+        assert_eq!(svc.pid, None);
+        assert!(svc.start_time.is_err(), "no start time");
+    }
+
+    #[test]
+    fn test_from_value_invalid() {
+        let bad = [
+            vec![("state", Value::from("run"))],
+            vec![("name", Value::from("foo"))],
+            vec![("name", "foo".into()), ("state", "bogus".into())],
+            vec![
+                ("name", "foo".into()),
+                ("state", "run".into()),
+                ("pid", "123".into()),
+            ],
+            vec![
+                ("name", "foo".into()),
+                ("state", "run".into()),
+                ("rss", Value::Int(-1)),
+            ],
+            vec![
+                ("name", "foo".into()),
+                ("state", "run".into()),
+                ("class", "XX".into()),
+            ],
+        ];
+
+        for obj in bad {
+            let value = Value::Object(
+                obj.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            );
+            assert!(Service::from_value(&value).is_err(), "{:?}", value);
+        }
+    }
+}
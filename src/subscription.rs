@@ -0,0 +1,225 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Subscribe to services changing state.
+ *
+ * ```no_run
+ * use vsv::subscription::Subscription;
+ *
+ * # fn main() -> Result<(), vsv::Error> {
+ * let mut subscription = Subscription::new("/var/service")?;
+ * for event in subscription.events() {
+ *     let event = event?;
+ *     println!(
+ *         "{}: {:?} -> {:?}",
+ *         event.name, event.old_state, event.new_state
+ *     );
+ * }
+ * # Ok(())
+ * # }
+ * ```
+ */
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::error::Error;
+use crate::inotify::Watcher;
+use crate::service::ServiceState;
+use crate::service_dir::ServiceDir;
+
+/**
+ * How often services are re-read when nothing is seen changing, to catch
+ * services that can't be watched with inotify.
+ */
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A service changing state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEvent {
+    pub name: String,
+
+    /// The state before, `None` if the service was just added.
+    pub old_state: Option<ServiceState>,
+
+    /// The state after, `None` if the service was removed.
+    pub new_state: Option<ServiceState>,
+
+    /// When the change was seen.
+    pub timestamp: SystemTime,
+}
+
+/**
+ * The states of the services in a service directory, updated (with inotify,
+ * or polling for services that can't be watched) as they change.
+ */
+pub struct Subscription {
+    dir: ServiceDir,
+    watcher: Option<Watcher>,
+    states: BTreeMap<String, ServiceState>,
+    pending: VecDeque<ServiceEvent>,
+    poll_interval: Duration,
+}
+
+impl Subscription {
+    /// Subscribe to the services in `path`, reading their current states.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let dir = ServiceDir::open(path)?;
+
+        let mut watcher = Watcher::new().ok();
+        if let Some(watcher) = &mut watcher {
+            watcher.watch(&[dir.path()]);
+        }
+
+        let mut subscription = Self {
+            dir,
+            watcher,
+            states: BTreeMap::new(),
+            pending: VecDeque::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        };
+        subscription.states = subscription.read_states()?;
+
+        Ok(subscription)
+    }
+
+    /// Set how often services are re-read when nothing is seen changing.
+    pub fn set_poll_interval(&mut self, interval: Duration) {
+        self.poll_interval = interval;
+    }
+
+    /// Get the current state of every service (by name).
+    pub fn states(&self) -> &BTreeMap<String, ServiceState> {
+        &self.states
+    }
+
+    /**
+     * Iterate state changes as they happen, blocking until the next one.
+     * The iterator never ends on its own.
+     */
+    pub fn events(&mut self) -> Events<'_> {
+        Events { subscription: self }
+    }
+
+    /**
+     * Wait (at most the poll interval) for services to change and get the
+     * state changes since the last call, which may be none.
+     */
+    pub fn poll(&mut self) -> Result<Vec<ServiceEvent>, Error> {
+        match &mut self.watcher {
+            Some(watcher) => {
+                watcher.wait(self.poll_interval)?;
+                // pick up new services
+                watcher.watch(&[self.dir.path()]);
+            }
+            None => thread::sleep(self.poll_interval),
+        }
+
+        let states = self.read_states()?;
+        let events = diff(&self.states, &states, SystemTime::now());
+        self.states = states;
+
+        Ok(events)
+    }
+
+    fn read_states(&self) -> Result<BTreeMap<String, ServiceState>, Error> {
+        let services = self.dir.services()?;
+
+        Ok(services
+            .into_iter()
+            .map(|service| (service.name, service.state))
+            .collect())
+    }
+}
+
+/// An iterator over state changes, see `Subscription::events`.
+pub struct Events<'a> {
+    subscription: &'a mut Subscription,
+}
+
+impl Iterator for Events<'_> {
+    type Item = Result<ServiceEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.subscription.pending.pop_front() {
+                return Some(Ok(event));
+            }
+
+            match self.subscription.poll() {
+                Ok(events) => self.subscription.pending.extend(events),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Get the state changes (sorted by service name) between two readings.
+fn diff(
+    old: &BTreeMap<String, ServiceState>,
+    new: &BTreeMap<String, ServiceState>,
+    timestamp: SystemTime,
+) -> Vec<ServiceEvent> {
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old_state = old.get(name).copied();
+            let new_state = new.get(name).copied();
+            (old_state != new_state).then(|| ServiceEvent {
+                name: name.clone(),
+                old_state,
+                new_state,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let states = |arr: &[(&str, ServiceState)]| {
+            arr.iter().map(|(name, state)| (name.to_string(), *state)).collect()
+        };
+        let old = states(&[
+            ("bar", ServiceState::Run),
+            ("foo", ServiceState::Run),
+            ("gone", ServiceState::Down),
+        ]);
+        let new = states(&[
+            ("bar", ServiceState::Run),
+            ("foo", ServiceState::Down),
+            ("new", ServiceState::Run),
+        ]);
+        let now = SystemTime::now();
+        let event = |name: &str, old_state, new_state| ServiceEvent {
+            name: name.to_string(),
+            old_state,
+            new_state,
+            timestamp: now,
+        };
+
+        assert_eq!(
+            diff(&old, &new, now),
+            [
+                event("foo", Some(ServiceState::Run), Some(ServiceState::Down)),
+                event("gone", Some(ServiceState::Down), None),
+                event("new", None, Some(ServiceState::Run)),
+            ]
+        );
+        assert_eq!(diff(&new, &new, now), [], "no changes");
+    }
+}
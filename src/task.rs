@@ -0,0 +1,131 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Gather services from async code.
+ *
+ * Reading a service directory means blocking `std::fs` calls (and sometimes
+ * running programs), which would stall an async runtime's worker threads.
+ * `gather` does the work on its own thread and returns a plain
+ * `std::future::Future`, so it can be awaited on any runtime (like tokio)
+ * and many directories can be polled at once:
+ *
+ * ```no_run
+ * # async fn f() -> Result<(), vsv::Error> {
+ * let (system, user) = (
+ *     vsv::task::gather("/var/service"),
+ *     vsv::task::gather("/home/dave/runit/service"),
+ * );
+ * let (system, user) = (system.await?, user.await?);
+ * # Ok(())
+ * # }
+ * ```
+ */
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::error::Error;
+use crate::service::Service;
+use crate::service_dir::ServiceDir;
+
+/// The result of gathering a service directory.
+type Output = Result<Vec<Service>, Error>;
+
+/// State shared between a `Gather` future and its thread.
+#[derive(Default)]
+struct Shared {
+    output: Option<Output>,
+    waker: Option<Waker>,
+}
+
+/// A future for the services in a directory, see `gather`.
+pub struct Gather {
+    shared: Arc<Mutex<Shared>>,
+}
+
+/**
+ * Read every service in `path` (like `ServiceDir::open(path)?.services()`)
+ * on a new thread, the returned future resolves when it's done.
+ *
+ * The work starts right away, even if the future is never polled.  If it
+ * panics the future resolves with an `Error::Other`.
+ */
+pub fn gather<P: AsRef<Path>>(path: P) -> Gather {
+    let path: PathBuf = path.as_ref().to_path_buf();
+    spawn(move || ServiceDir::open(path).and_then(|dir| dir.services()))
+}
+
+/// Run `work` on a new thread, resolving the returned future with its output.
+fn spawn<F>(work: F) -> Gather
+where
+    F: FnOnce() -> Output + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared::default()));
+
+    let thread_shared = Arc::clone(&shared);
+    thread::spawn(move || {
+        // a panic would otherwise leave the future pending forever
+        let output = panic::catch_unwind(AssertUnwindSafe(work))
+            .unwrap_or_else(|payload| Err(Error::from_panic(payload)));
+
+        let mut shared =
+            thread_shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.output = Some(output);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+
+    Gather { shared }
+}
+
+impl Future for Gather {
+    type Output = Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared =
+            self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match shared.output.take() {
+            Some(output) => Poll::Ready(output),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_panic() {
+        let mut gather = spawn(|| panic!("oops"));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let output = loop {
+            match Pin::new(&mut gather).poll(&mut cx) {
+                Poll::Ready(output) => break output,
+                Poll::Pending => thread::yield_now(),
+            }
+        };
+
+        match output {
+            Err(Error::Other(err)) => {
+                assert_eq!(err.to_string(), "panicked: oops")
+            }
+            other => panic!("unexpected output: {:?}", other.map(|s| s.len())),
+        }
+    }
+}
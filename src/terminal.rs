@@ -0,0 +1,163 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Minimal raw-mode terminal handling (termios + ANSI escapes) for interactive
+ * commands like `vsv tui`.
+ */
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+
+/// A key read from the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Enter,
+    Tab,
+    Backspace,
+    Esc,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+/**
+ * A terminal in raw mode using the alternate screen.  The original terminal
+ * settings are restored when this is dropped.
+ */
+pub struct RawTerminal {
+    original: libc::termios,
+}
+
+impl RawTerminal {
+    /**
+     * Put the terminal on stdin into raw mode and switch to the alternate
+     * screen (with the cursor hidden).
+     */
+    pub fn new() -> Result<Self> {
+        if !crate::utils::isatty(0) || !crate::utils::isatty(1) {
+            bail!("stdin and stdout must be a tty");
+        }
+
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(0, &mut original) } != 0 {
+            bail!("tcgetattr failed: {}", io::Error::last_os_error());
+        }
+
+        let mut raw = original;
+        unsafe { libc::cfmakeraw(&mut raw) };
+        // keep output processing so "\n" still moves to the next line
+        raw.c_oflag |= libc::OPOST;
+        if unsafe { libc::tcsetattr(0, libc::TCSAFLUSH, &raw) } != 0 {
+            bail!("tcsetattr failed: {}", io::Error::last_os_error());
+        }
+
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+
+        Ok(Self { original })
+    }
+
+    /**
+     * Wait (at most `timeout`) for a key to be pressed, returning `None` if
+     * no key was pressed in time.
+     */
+    pub fn read_key(&self, timeout: Duration) -> Result<Option<Key>> {
+        if !poll_stdin(timeout)? {
+            return Ok(None);
+        }
+
+        let mut buf = [0u8; 16];
+        let n = io::stdin().read(&mut buf)?;
+
+        Ok(parse_key(&buf[..n]))
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        unsafe { libc::tcsetattr(0, libc::TCSAFLUSH, &self.original) };
+    }
+}
+
+/// Get the size of the terminal on stdout as `(rows, columns)`.
+pub fn size() -> (usize, usize) {
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::ioctl(1, libc::TIOCGWINSZ, &mut ws) };
+
+    match ret {
+        0 if ws.ws_row > 0 && ws.ws_col > 0 => {
+            (ws.ws_row.into(), ws.ws_col.into())
+        }
+        _ => (24, 80),
+    }
+}
+
+/// Wait for stdin to become readable, returns `false` on timeout.
+fn poll_stdin(timeout: Duration) -> Result<bool> {
+    let mut fds = libc::pollfd { fd: 0, events: libc::POLLIN, revents: 0 };
+    let ms = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+
+    match unsafe { libc::poll(&mut fds, 1, ms) } {
+        -1 => {
+            let err = io::Error::last_os_error();
+            match err.kind() {
+                // interrupted (probably by SIGWINCH), just redraw
+                io::ErrorKind::Interrupted => Ok(false),
+                _ => Err(err.into()),
+            }
+        }
+        0 => Ok(false),
+        _ => Ok(true),
+    }
+}
+
+/// Parse the bytes of a single key press (including escape sequences).
+fn parse_key(buf: &[u8]) -> Option<Key> {
+    let key = match buf {
+        [] => return None,
+        [b'\r'] | [b'\n'] => Key::Enter,
+        [b'\t'] => Key::Tab,
+        [0x7f] | [0x08] => Key::Backspace,
+        [0x1b] => Key::Esc,
+        [0x1b, b'[', b'A'] | [0x1b, b'O', b'A'] => Key::Up,
+        [0x1b, b'[', b'B'] | [0x1b, b'O', b'B'] => Key::Down,
+        [0x1b, b'[', b'5', b'~'] => Key::PageUp,
+        [0x1b, b'[', b'6', b'~'] => Key::PageDown,
+        [0x1b, b'[', b'H'] | [0x1b, b'[', b'1', b'~'] => Key::Home,
+        [0x1b, b'[', b'F'] | [0x1b, b'[', b'4', b'~'] => Key::End,
+        [0x1b, ..] => return None,
+        [c @ 1..=26] => Key::Ctrl((b'a' + c - 1) as char),
+        _ => Key::Char(std::str::from_utf8(buf).ok()?.chars().next()?),
+    };
+
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key(b"q"), Some(Key::Char('q')));
+        assert_eq!(parse_key(b"\r"), Some(Key::Enter));
+        assert_eq!(parse_key(b"\x03"), Some(Key::Ctrl('c')));
+        assert_eq!(parse_key(b"\x1b[A"), Some(Key::Up));
+        assert_eq!(parse_key(b"\x1b[6~"), Some(Key::PageDown));
+        assert_eq!(parse_key(b"\x1b[99~"), None);
+        assert_eq!(parse_key("é".as_bytes()), Some(Key::Char('é')));
+    }
+}
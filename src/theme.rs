@@ -0,0 +1,447 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * Color themes: the styles used for service states, columns and messages.
+ *
+ * Output is styled through the theme set with `set` (or the default theme),
+ * like yansi's own global `Paint::enable`.  A theme starts from one of the
+ * built-in themes (see `NAMES`) and any style in it can be replaced by a
+ * style string like `"bold red"`, see `parse_style`.
+ */
+
+use std::sync::OnceLock;
+
+use yansi::{Color, Style};
+
+use crate::column::Column;
+use crate::error::{bail, Context, Result};
+use crate::service::ServiceState;
+
+/// The names of the built-in themes.
+pub const NAMES: &[&str] = &["default", "solarized", "high-contrast", "mono"];
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// The styles of service states (and conditions shown like states).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateStyles {
+    pub run: Style,
+    pub down: Style,
+    pub finish: Style,
+    pub zombie: Style,
+    pub stopped: Style,
+    pub unsupervised: Style,
+    pub unknown: Style,
+    pub error: Style,
+
+    /// Not in the state runit wants it in (the `!` status char).
+    pub mismatched: Style,
+
+    /// The pid looks stale (the `≠` status char).
+    pub stale: Style,
+
+    /// Restarting over and over (the `↺` status char).
+    pub flapping: Style,
+
+    /// Down on purpose, with a `./down` file (the `-` status char).
+    pub normally_down: Style,
+}
+
+/// A set of styles for output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Things that are fine, like `done` or a passing check.
+    pub good: Style,
+
+    /// Problems, like `failed`, errors or an uptime under 5 seconds.
+    pub bad: Style,
+
+    /// Things worth a look, like warnings, dry runs or a high nice value.
+    pub warn: Style,
+
+    /// Informational messages, like the `sv` command being run.
+    pub info: Style,
+
+    /// Less important markers, like the char of available services.
+    pub note: Style,
+
+    /// Service states.
+    pub states: StateStyles,
+
+    /// The usual style of columns, values that stand out (like a root user
+    /// or a recent uptime) use `bad` and `warn` instead.
+    pub columns: Vec<(Column, Style)>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let fg = Style::new;
+
+        Self {
+            good: fg(Color::Green),
+            bad: fg(Color::Red),
+            warn: fg(Color::Yellow),
+            info: fg(Color::Cyan),
+            note: fg(Color::Blue),
+            states: StateStyles {
+                run: fg(Color::Green),
+                down: fg(Color::Red),
+                finish: fg(Color::Yellow),
+                zombie: fg(Color::Red),
+                stopped: fg(Color::Yellow),
+                unsupervised: fg(Color::Cyan),
+                unknown: fg(Color::Yellow),
+                error: fg(Color::Red).bold(),
+                mismatched: fg(Color::Magenta),
+                stale: fg(Color::Yellow).bold(),
+                flapping: fg(Color::Red).bold(),
+                normally_down: fg(Color::Blue),
+            },
+            columns: vec![
+                (Column::Pid, fg(Color::Magenta)),
+                (Column::Command, fg(Color::Green)),
+                (Column::Uptime, Style::default().dimmed()),
+                (Column::Origin, fg(Color::Blue)),
+                (Column::Host, fg(Color::Magenta)),
+                (Column::LogSize, fg(Color::Cyan)),
+                (Column::Rss, fg(Color::Cyan)),
+                (Column::CgroupMemory, fg(Color::Cyan)),
+                (Column::Container, fg(Color::Magenta)),
+            ],
+        }
+    }
+}
+
+impl Theme {
+    /// Get a built-in theme by name (one of `NAMES`).
+    pub fn builtin(name: &str) -> Result<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            "solarized" => Self::solarized(),
+            "high-contrast" => Self::high_contrast(),
+            "mono" => Self::mono(),
+            _ => bail!(
+                "unknown theme: '{}' (expected one of {})",
+                name,
+                NAMES.join(", ")
+            ),
+        };
+
+        Ok(theme)
+    }
+
+    /// The default theme with the colors of the solarized palette.
+    fn solarized() -> Self {
+        let fg = |r, g, b| Style::new(Color::RGB(r, g, b));
+        let green = fg(133, 153, 0);
+        let red = fg(220, 50, 47);
+        let yellow = fg(181, 137, 0);
+        let blue = fg(38, 139, 210);
+        let cyan = fg(42, 161, 152);
+        let magenta = fg(211, 54, 130);
+
+        Self {
+            good: green,
+            bad: red,
+            warn: yellow,
+            info: cyan,
+            note: blue,
+            states: StateStyles {
+                run: green,
+                down: red,
+                finish: yellow,
+                zombie: red,
+                stopped: yellow,
+                unsupervised: cyan,
+                unknown: yellow,
+                error: red.bold(),
+                mismatched: magenta,
+                stale: yellow.bold(),
+                flapping: red.bold(),
+                normally_down: blue,
+            },
+            columns: vec![
+                (Column::Pid, magenta),
+                (Column::Command, green),
+                (Column::Uptime, fg(88, 110, 117)),
+                (Column::Origin, blue),
+                (Column::Host, magenta),
+                (Column::LogSize, cyan),
+                (Column::Rss, cyan),
+                (Column::CgroupMemory, cyan),
+                (Column::Container, magenta),
+            ],
+        }
+    }
+
+    /**
+     * Bold colors that don't rely on telling red from green: blue for good
+     * and orange for bad.
+     */
+    fn high_contrast() -> Self {
+        let blue = Style::new(Color::Fixed(33)).bold();
+        let orange = Style::new(Color::Fixed(208)).bold();
+        let yellow = Style::new(Color::Fixed(226)).bold();
+        let magenta = Style::new(Color::Fixed(201)).bold();
+        let white = Style::new(Color::Fixed(231)).bold();
+
+        Self {
+            good: blue,
+            bad: orange,
+            warn: yellow,
+            info: white,
+            note: white,
+            states: StateStyles {
+                run: blue,
+                down: orange,
+                finish: yellow,
+                zombie: orange.underline(),
+                stopped: yellow.underline(),
+                unsupervised: white,
+                unknown: yellow,
+                error: orange.bold(),
+                mismatched: magenta,
+                stale: yellow.invert(),
+                flapping: orange.invert(),
+                normally_down: white,
+            },
+            columns: vec![
+                (Column::Pid, magenta),
+                (Column::Command, white),
+                (Column::Origin, white),
+                (Column::Host, magenta),
+                (Column::Container, magenta),
+            ],
+        }
+    }
+
+    /// No colors, only bold, underlined and dimmed text.
+    fn mono() -> Self {
+        let plain = Style::default();
+        let bold = plain.bold();
+        let underline = plain.underline();
+
+        Self {
+            good: plain,
+            bad: bold,
+            warn: underline,
+            info: plain,
+            note: plain,
+            states: StateStyles {
+                run: plain,
+                down: bold,
+                finish: underline,
+                zombie: bold,
+                stopped: underline,
+                unsupervised: underline,
+                unknown: underline,
+                error: bold.invert(),
+                mismatched: bold.underline(),
+                stale: bold.underline(),
+                flapping: bold.invert(),
+                normally_down: plain.dimmed(),
+            },
+            columns: vec![(Column::Uptime, plain.dimmed())],
+        }
+    }
+
+    /// Get the style of a state.
+    pub fn state(&self, state: ServiceState) -> Style {
+        let states = &self.states;
+
+        match state {
+            ServiceState::Run => states.run,
+            ServiceState::Down => states.down,
+            ServiceState::Finish => states.finish,
+            ServiceState::Zombie => states.zombie,
+            ServiceState::Stopped => states.stopped,
+            ServiceState::Unsupervised => states.unsupervised,
+            ServiceState::Unknown => states.unknown,
+            ServiceState::Error => states.error,
+        }
+    }
+
+    /// Get the usual style of a column (unstyled if the theme has none).
+    pub fn column(&self, column: Column) -> Style {
+        self.columns
+            .iter()
+            .find(|(c, _)| *c == column)
+            .map(|(_, style)| *style)
+            .unwrap_or_default()
+    }
+
+    /**
+     * Replace a style by its key: a role (like `bad`), `states.<state>` (like
+     * `states.run` or `states.flapping`) or `columns.<column>` (like
+     * `columns.pid`).
+     */
+    pub fn set(&mut self, key: &str, style: Style) -> Result<()> {
+        if let Some(name) = key.strip_prefix("columns.") {
+            let column = Column::parse(name)?;
+            if matches!(column, Column::Status | Column::State) {
+                bail!("the {} column is styled by states", column);
+            }
+
+            self.columns.retain(|(c, _)| *c != column);
+            self.columns.push((column, style));
+            return Ok(());
+        }
+
+        let states = &mut self.states;
+        let field = match key {
+            "good" => &mut self.good,
+            "bad" => &mut self.bad,
+            "warn" => &mut self.warn,
+            "info" => &mut self.info,
+            "note" => &mut self.note,
+            "states.run" => &mut states.run,
+            "states.down" => &mut states.down,
+            "states.finish" => &mut states.finish,
+            "states.zombie" => &mut states.zombie,
+            "states.stopped" => &mut states.stopped,
+            "states.unsupervised" => &mut states.unsupervised,
+            "states.unknown" => &mut states.unknown,
+            "states.error" => &mut states.error,
+            "states.mismatched" => &mut states.mismatched,
+            "states.stale" => &mut states.stale,
+            "states.flapping" => &mut states.flapping,
+            "states.normally_down" => &mut states.normally_down,
+            _ => bail!("unknown style '{}'", key),
+        };
+        *field = style;
+
+        Ok(())
+    }
+}
+
+/// Use `theme` for all output, only the first theme set is used.
+pub fn set(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// Get the theme set with `set` (or the default theme).
+pub fn get() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/**
+ * Parse a style like `"bold red"` or `"underline #ff8700 on black"`: any of
+ * the attributes `bold`, `dimmed`, `italic`, `underline`, `blink`, `invert`,
+ * `hidden` and `strikethrough` and a color (optionally followed by `on` and a
+ * background color).  Colors are a name (`black`, `red`, `green`, `yellow`,
+ * `blue`, `magenta`, `cyan` or `white`), a 256-color number or `#rrggbb`.
+ * `"none"` (or `""`) is no style at all.
+ */
+pub fn parse_style(s: &str) -> Result<Style> {
+    let mut style = Style::default();
+    let mut words = s.split_whitespace();
+
+    while let Some(word) = words.next() {
+        style = match word {
+            "none" => style,
+            "bold" => style.bold(),
+            "dimmed" => style.dimmed(),
+            "italic" => style.italic(),
+            "underline" => style.underline(),
+            "blink" => style.blink(),
+            "invert" => style.invert(),
+            "hidden" => style.hidden(),
+            "strikethrough" => style.strikethrough(),
+            "on" => {
+                let color = words.next().context("missing color after 'on'")?;
+                style.bg(parse_color(color)?)
+            }
+            color => style.fg(parse_color(color)?),
+        };
+    }
+
+    Ok(style)
+}
+
+/// Parse a color name, 256-color number or `#rrggbb`.
+fn parse_color(s: &str) -> Result<Color> {
+    let color = match s {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        s => {
+            if let Ok(n) = s.parse::<u8>() {
+                return Ok(Color::Fixed(n));
+            }
+
+            let rgb = s
+                .strip_prefix('#')
+                .filter(|hex| hex.len() == 6 && hex.is_ascii())
+                .and_then(|hex| {
+                    let byte = |i| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+                    Some(Color::RGB(byte(0)?, byte(2)?, byte(4)?))
+                });
+            match rgb {
+                Some(color) => color,
+                None => bail!("unknown color or attribute: '{}'", s),
+            }
+        }
+    };
+
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_style() -> Result<()> {
+        assert_eq!(parse_style("")?, Style::default());
+        assert_eq!(parse_style("none")?, Style::default());
+        assert_eq!(parse_style("red")?, Style::new(Color::Red));
+        assert_eq!(
+            parse_style("bold  underline 208 on #00ff80")?,
+            Style::new(Color::Fixed(208))
+                .bold()
+                .underline()
+                .bg(Color::RGB(0, 255, 128))
+        );
+
+        for bad in ["rouge", "#ff00", "#ff00zz", "256", "red on"] {
+            assert!(parse_style(bad).is_err(), "{}", bad);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_theme() -> Result<()> {
+        for name in NAMES {
+            Theme::builtin(name)?;
+        }
+        assert!(Theme::builtin("neon").is_err());
+
+        let mut theme = Theme::builtin("mono")?;
+        assert_eq!(theme.state(ServiceState::Down), Style::default().bold());
+        assert_eq!(theme.column(Column::Pid), Style::default(), "unstyled");
+
+        let red = Style::new(Color::Red);
+        theme.set("states.run", red)?;
+        theme.set("columns.pid", red)?;
+        theme.set("good", red)?;
+        assert_eq!(theme.state(ServiceState::Run), red);
+        assert_eq!(theme.column(Column::Pid), red);
+        assert_eq!(theme.good, red);
+
+        for bad in ["states.sleeping", "columns.nope", "columns.state", "ok"] {
+            assert!(theme.set(bad, red).is_err(), "{}", bad);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * How times (like the uptime column) are shown.
+ *
+ * Like themes (see `theme`), the time format set with `set` (or the default of
+ * a single unit, like "1 hour") is used for all output.
+ */
+
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use crate::error::{bail, Result};
+use crate::utils;
+
+/// The names of the time styles.
+pub const NAMES: &[&str] = &["relative", "compact", "absolute", "iso8601"];
+
+static TIME_FORMAT: OnceLock<TimeFormat> = OnceLock::new();
+
+/// How durations are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeStyle {
+    /// Like "3 days 4 hours".
+    #[default]
+    Relative,
+
+    /// Like "3d4h".
+    Compact,
+
+    /// The start time in the local time zone, like "2022-02-19 09:05:09" (or
+    /// in the format of `TimeFormat::format`).
+    Absolute,
+
+    /// The start time in UTC, like "2022-02-19T14:05:09Z".
+    Iso8601,
+}
+
+impl TimeStyle {
+    /// Parse a time style by name (one of `NAMES`).
+    pub fn parse(name: &str) -> Result<Self> {
+        let style = match name {
+            "relative" => Self::Relative,
+            "compact" => Self::Compact,
+            "absolute" => Self::Absolute,
+            "iso8601" => Self::Iso8601,
+            _ => bail!(
+                "unknown time style: '{}' (expected one of {})",
+                name,
+                NAMES.join(", ")
+            ),
+        };
+
+        Ok(style)
+    }
+
+    /// The number of units shown when not given, like 2 for "3d4h".
+    pub fn default_units(&self) -> usize {
+        match self {
+            Self::Compact => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// How to show times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeFormat {
+    pub style: TimeStyle,
+
+    /// The number of units in a duration, like 2 for "1 hour 59 minutes".
+    pub units: usize,
+
+    /// A `strftime(3)` format for the `absolute` style.
+    pub format: Option<String>,
+}
+
+impl Default for TimeFormat {
+    fn default() -> Self {
+        let style = TimeStyle::default();
+        Self { style, units: style.default_units(), format: None }
+    }
+}
+
+impl TimeFormat {
+    /**
+     * Format a duration, like "1 hour" (see `utils::relative_duration`).
+     * Durations are relative with the absolute styles, which are only for
+     * points in time.
+     */
+    pub fn duration(&self, t: &Duration) -> String {
+        match self.style {
+            TimeStyle::Compact => utils::compact_duration(t, self.units),
+            _ => utils::relative_duration_units(t, self.units),
+        }
+    }
+
+    /// Format the time of something that started at `start`, `t` ago.
+    pub fn since(&self, start: &SystemTime, t: &Duration) -> String {
+        match self.style {
+            TimeStyle::Absolute => match &self.format {
+                Some(fmt) => utils::format_timestamp_strftime(start, fmt),
+                None => utils::format_timestamp_local(start),
+            },
+            TimeStyle::Iso8601 => utils::format_timestamp_utc(start),
+            _ => self.duration(t),
+        }
+    }
+}
+
+/// Use `time_format` for all output, only the first one set is used.
+pub fn set(time_format: TimeFormat) {
+    let _ = TIME_FORMAT.set(time_format);
+}
+
+/// Get the time format set with `set` (or the default time format).
+pub fn get() -> &'static TimeFormat {
+    TIME_FORMAT.get_or_init(TimeFormat::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_format() -> Result<()> {
+        let t = Duration::from_secs(3 * 24 * 60 * 60 + 4 * 60 * 60 + 5);
+
+        assert_eq!(TimeFormat::default().duration(&t), "3 days");
+
+        let style = TimeStyle::parse("compact")?;
+        let compact = TimeFormat { style, units: 2, format: None };
+        assert_eq!(compact.duration(&t), "3d4h");
+        assert_eq!(
+            TimeFormat { units: 3, ..compact.clone() }.duration(&t),
+            "3d4h"
+        );
+        assert_eq!(
+            TimeFormat { units: 4, ..compact.clone() }.duration(&t),
+            "3d4h5s"
+        );
+
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(86400);
+        let style = TimeStyle::parse("iso8601")?;
+        let iso8601 = TimeFormat { style, units: 1, format: None };
+        assert_eq!(iso8601.since(&start, &t), "1970-01-02T00:00:00Z");
+        assert_eq!(iso8601.duration(&t), "3 days", "durations are relative");
+        assert_eq!(compact.since(&start, &t), "3d4h");
+
+        // the local time zone isn't known here, only that the format is used
+        let format = Some("%Y|%%".to_string());
+        let style = TimeStyle::Absolute;
+        let absolute = TimeFormat { style, units: 1, format };
+        assert!(absolute.since(&start, &t).ends_with("|%"));
+
+        assert!(TimeStyle::parse("short").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,478 @@
+/*
+ * Author: Dave Eddy <dave@daveeddy.com>
+ * Date: October 14, 2026
+ * License: MIT
+ */
+
+/*!
+ * A small TOML parser, enough for the config file.
+ *
+ * Tables (`[name]`), dotted keys, basic and literal strings, integers,
+ * floats, booleans, arrays (which can span lines) and inline tables are
+ * supported.  Multi-line strings, dates and arrays of tables (`[[name]]`)
+ * are not, and fail to parse.
+ */
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+
+use crate::output::Value;
+
+/**
+ * Parse a TOML document into a `Value::Object`, with a nested object for
+ * every table.
+ *
+ * # Example
+ *
+ * ```
+ * let v = from_str("tree = true\n[aliases]\nweb = 'status nginx'\n")?;
+ * assert_eq!(v.get("tree"), Some(&Value::Bool(true)));
+ * ```
+ */
+pub fn from_str(s: &str) -> Result<Value> {
+    let mut parser = Parser { chars: s.chars().peekable(), line: 1 };
+
+    parser.parse_document().map_err(|err| {
+        let line = parser.line;
+        err.context(format!("line {}", line))
+    })
+}
+
+/// A recursive descent TOML parser.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+
+    /// The line being parsed, for errors.
+    line: usize,
+}
+
+impl Parser<'_> {
+    /// Consume the next character, counting lines.
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    /// Skip spaces and tabs (but not newlines).
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+    }
+
+    /// Skip whitespace, newlines and comments (like between array items).
+    fn skip_blank(&mut self) {
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                Some('\n' | '\r') => {
+                    self.next();
+                }
+                Some('#') => self.skip_comment(),
+                _ => return,
+            }
+        }
+    }
+
+    /// Skip a comment up to (but not including) the end of the line.
+    fn skip_comment(&mut self) {
+        while self.chars.next_if(|c| *c != '\n').is_some() {}
+    }
+
+    /// Consume the given character (after any whitespace) or fail.
+    fn expect(&mut self, want: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == want => Ok(()),
+            Some(c) => bail!("expected '{}', found '{}'", want, c),
+            None => bail!("expected '{}', found end of input", want),
+        }
+    }
+
+    /// Consume the rest of a line, which can only hold a comment.
+    fn end_of_line(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'#') {
+            self.skip_comment();
+        }
+        self.chars.next_if_eq(&'\r');
+
+        match self.next() {
+            None | Some('\n') => Ok(()),
+            Some(c) => bail!("expected end of line, found '{}'", c),
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Value> {
+        let mut root = Value::Object(vec![]);
+        let mut table: Vec<String> = vec![];
+        let mut defined: Vec<Vec<String>> = vec![];
+
+        loop {
+            self.skip_blank();
+            match self.chars.peek() {
+                None => return Ok(root),
+                Some('[') => {
+                    self.next();
+                    ensure!(
+                        self.chars.peek() != Some(&'['),
+                        "arrays of tables aren't supported"
+                    );
+                    table = self.parse_key()?;
+                    self.expect(']')?;
+                    self.end_of_line()?;
+
+                    ensure!(
+                        !defined.contains(&table),
+                        "table '{}' defined twice",
+                        table.join(".")
+                    );
+                    defined.push(table.clone());
+                    table_mut(&mut root, &table)?;
+                }
+                Some(_) => {
+                    let key = self.parse_key()?;
+                    self.expect('=')?;
+                    let value = self.parse_value()?;
+                    self.end_of_line()?;
+
+                    insert(table_mut(&mut root, &table)?, &key, value)?;
+                }
+            }
+        }
+    }
+
+    /// Parse a (possibly dotted) key like `a`, `"a b"` or `a.b`.
+    fn parse_key(&mut self) -> Result<Vec<String>> {
+        let mut key = vec![];
+
+        loop {
+            self.skip_whitespace();
+            let part = match self.chars.peek() {
+                Some('"') => self.parse_basic_string()?,
+                Some('\'') => self.parse_literal_string()?,
+                _ => {
+                    let mut s = String::new();
+                    while let Some(c) = self.chars.next_if(|c| {
+                        c.is_ascii_alphanumeric() || *c == '_' || *c == '-'
+                    }) {
+                        s.push(c);
+                    }
+                    ensure!(!s.is_empty(), "expected a key");
+                    s
+                }
+            };
+            key.push(part);
+
+            self.skip_whitespace();
+            if self.chars.next_if_eq(&'.').is_none() {
+                return Ok(key);
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('"') => Ok(Value::String(self.parse_basic_string()?)),
+            Some('\'') => Ok(Value::String(self.parse_literal_string()?)),
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_inline_table(),
+            Some('t' | 'f') => self.parse_bool(),
+            Some(c) if *c == '-' || *c == '+' || c.is_ascii_digit() => {
+                self.parse_number()
+            }
+            Some(c) => bail!("unexpected character: '{}'", c),
+            None => bail!("unexpected end of input"),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value> {
+        self.expect('[')?;
+        let mut arr = vec![];
+
+        loop {
+            self.skip_blank();
+            if self.chars.next_if_eq(&']').is_some() {
+                return Ok(Value::Array(arr));
+            }
+
+            arr.push(self.parse_value()?);
+
+            // a trailing comma is allowed
+            self.skip_blank();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(arr)),
+                _ => bail!("expected ',' or ']' in array"),
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<Value> {
+        self.expect('{')?;
+        let mut obj = Value::Object(vec![]);
+
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Ok(obj);
+        }
+
+        loop {
+            let key = self.parse_key()?;
+            self.expect('=')?;
+            let value = self.parse_value()?;
+            insert(&mut obj, &key, value)?;
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(obj),
+                _ => bail!("expected ',' or '}}' in inline table"),
+            }
+        }
+    }
+
+    fn parse_bool(&mut self) -> Result<Value> {
+        let mut s = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphabetic()) {
+            s.push(c);
+        }
+
+        match s.as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => bail!("invalid value: '{}'", s),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value> {
+        let mut s = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || "+-._:".contains(*c))
+        {
+            s.push(c);
+        }
+
+        // underscores can separate digits, like `1_000`
+        let digits = s.replace('_', "");
+        if let Ok(i) = digits.parse() {
+            return Ok(Value::Int(i));
+        }
+
+        let f = digits
+            .parse()
+            .ok()
+            .filter(|f: &f64| f.is_finite())
+            .ok_or_else(|| anyhow!("invalid number: '{}'", s))?;
+
+        Ok(Value::Float(f))
+    }
+
+    fn parse_basic_string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut s = String::new();
+
+        // `""` is empty, `"""` starts a multi-line string
+        if self.chars.next_if_eq(&'"').is_some() {
+            ensure!(
+                self.chars.peek() != Some(&'"'),
+                "multi-line strings aren't supported"
+            );
+            return Ok(s);
+        }
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => s.push(self.parse_escape()?),
+                Some('\n') | None => bail!("unterminated string"),
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn parse_literal_string(&mut self) -> Result<String> {
+        self.expect('\'')?;
+        let mut s = String::new();
+
+        if self.chars.next_if_eq(&'\'').is_some() {
+            ensure!(
+                self.chars.peek() != Some(&'\''),
+                "multi-line strings aren't supported"
+            );
+            return Ok(s);
+        }
+
+        loop {
+            match self.next() {
+                Some('\'') => return Ok(s),
+                Some('\n') | None => bail!("unterminated string"),
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    /// Parse the character after a `\` in a basic string.
+    fn parse_escape(&mut self) -> Result<char> {
+        let c = match self.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => self.parse_unicode(4)?,
+            Some('U') => self.parse_unicode(8)?,
+            Some(c) => bail!("invalid escape: '\\{}'", c),
+            None => bail!("unterminated string"),
+        };
+
+        Ok(c)
+    }
+
+    /// Parse the hex digits of a `\uXXXX` or `\UXXXXXXXX` escape.
+    fn parse_unicode(&mut self, len: usize) -> Result<char> {
+        let s: String = self.chars.by_ref().take(len).collect();
+        ensure!(s.len() == len, "truncated unicode escape");
+
+        u32::from_str_radix(&s, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .with_context(|| format!("invalid unicode escape: '{}'", s))
+    }
+}
+
+/// Get the table at `path`, creating any tables on the way.
+fn table_mut<'a>(
+    root: &'a mut Value,
+    path: &[String],
+) -> Result<&'a mut Value> {
+    let mut table = root;
+
+    for key in path {
+        let obj = match table {
+            Value::Object(obj) => obj,
+            _ => bail!("'{}' is not a table", key),
+        };
+
+        let i = match obj.iter().position(|(k, _)| k == key) {
+            Some(i) => i,
+            None => {
+                obj.push((key.clone(), Value::Object(vec![])));
+                obj.len() - 1
+            }
+        };
+        table = &mut obj[i].1;
+    }
+
+    if !matches!(table, Value::Object(_)) {
+        bail!("'{}' is not a table", path.join("."));
+    }
+
+    Ok(table)
+}
+
+/// Set the (possibly dotted) `key` in `table`, failing if it's already set.
+fn insert(table: &mut Value, key: &[String], value: Value) -> Result<()> {
+    let (last, path) = key.split_last().expect("keys aren't empty");
+    let table = table_mut(table, path)?;
+
+    if let Value::Object(obj) = table {
+        ensure!(
+            !obj.iter().any(|(k, _)| k == last),
+            "key '{}' defined twice",
+            key.join(".")
+        );
+        obj.push((last.clone(), value));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> Result<()> {
+        let s = r#"
+# a comment
+dir = "/var/service"  # trailing comment
+columns = ['name', "state"]
+exclude = [
+    "agetty-*",  # ttys
+    'udevd',
+]
+tree = true
+lines = 1_000
+ratio = -0.5
+"quoted key" = "tab\there é"
+a.b = 1
+
+[aliases]
+web = "status 'nginx|php-fpm.*'"
+inline = { x = 1, y = false }
+"#;
+        let v = from_str(s)?;
+
+        let strs = |v: &[&str]| {
+            Value::Array(v.iter().map(|s| Value::from(*s)).collect())
+        };
+        assert_eq!(v.get("dir"), Some(&Value::from("/var/service")));
+        assert_eq!(v.get("columns"), Some(&strs(&["name", "state"])));
+        assert_eq!(v.get("exclude"), Some(&strs(&["agetty-*", "udevd"])));
+        assert_eq!(v.get("tree"), Some(&Value::Bool(true)));
+        assert_eq!(v.get("lines"), Some(&Value::Int(1000)));
+        assert_eq!(v.get("ratio"), Some(&Value::Float(-0.5)));
+        assert_eq!(v.get("quoted key"), Some(&Value::from("tab\there é")));
+        assert_eq!(
+            v.get("a").and_then(|a| a.get("b")),
+            Some(&Value::Int(1)),
+            "dotted key"
+        );
+
+        let aliases = v.get("aliases").expect("table");
+        assert_eq!(
+            aliases.get("web"),
+            Some(&Value::from("status 'nginx|php-fpm.*'"))
+        );
+        assert_eq!(
+            aliases.get("inline").and_then(|t| t.get("y")),
+            Some(&Value::Bool(false))
+        );
+
+        assert_eq!(from_str("")?, Value::Object(vec![]), "empty");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        let bad = [
+            ("a = ", "no value"),
+            ("a = 1\na = 2", "duplicate key"),
+            ("[t]\n[t]", "duplicate table"),
+            ("a = 1\n[a]", "value as table"),
+            ("a = 'unterminated", "unterminated"),
+            ("a = \"\"\"\nx\"\"\"", "multi-line string"),
+            ("[[t]]", "array of tables"),
+            ("a = 1979-05-27", "date"),
+            ("a = [1 2]", "missing comma"),
+            ("a = 1 b = 2", "two keys on a line"),
+            ("a = yes", "bare word"),
+        ];
+
+        for (s, desc) in bad {
+            assert!(from_str(s).is_err(), "{}: {:?}", desc, s);
+        }
+
+        let err = from_str("a = 1\n\nb = ?").unwrap_err();
+        assert_eq!(format!("{:#}", err), "line 3: unexpected character: '?'");
+    }
+}
@@ -7,150 +7,150 @@
 //! Contains various util functions for vsv.
 
 use libc::{c_int, pid_t};
+use std::env;
 use std::fs;
+use std::io::Read;
 use std::path::Path;
-use std::process::{Command, ExitStatus};
-use std::time::Duration;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{anyhow, Context, Result};
-use yansi::Style;
+use anyhow::anyhow;
 
-/**
- * A `println!()`-like macro that will only print if `-v` is set.
- */
-macro_rules! verbose {
-    ($cfg:expr, $fmt:expr $(, $args:expr )* $(,)? ) => {
-        if $cfg.verbose > 0 {
-            let s = format!($fmt $(, $args)*);
-            eprintln!(">  {}", ::yansi::Style::default().dimmed().paint(s));
-        }
-    };
-}
-pub(crate) use verbose;
+use crate::error::{bail, Context, Result};
 
 /**
- * Format a status line - made specifically for vsv.
+ * Get the program name (arg0) for a PID.
  *
  * # Example
- * ```
- * use yansi::Style;
- * let style = Style::default();
- * println!(
- *     "{}",
- *     format_status_line(
- *         ("", style.bold()),
- *         ("SERVICE", style.bold()),
- *         ("STATE", style.bold()),
- *         ("ENABLED", style.bold()),
- *         ("PID", style.bold()),
- *         ("COMMAND", style.bold()),
- *         ("TIME", style.bold()),
- *     )
- * );
+ *
+ * ```no_run
+ * use std::path::PathBuf;
+ *
+ * use vsv::utils::cmd_from_pid;
+ *
+ * # fn main() -> anyhow::Result<()> {
+ * let pid = 1;
+ * let proc_path = PathBuf::from("/proc");
+ * let cmd = cmd_from_pid(pid, &proc_path)?;
+ * println!("pid {} program is {}", pid, cmd);
+ * # Ok(())
+ * # }
  * ```
  */
-pub fn format_status_line<T: AsRef<str>>(
-    status_char: (T, Style),
-    name: (T, Style),
-    state: (T, Style),
-    enabled: (T, Style),
-    pid: (T, Style),
-    command: (T, Style),
-    time: (T, Style),
-) -> String {
-    // ( data + style to print, max width, suffix )
-    let data = [
-        (status_char, 1, ""),
-        (name, 20, "..."),
-        (state, 7, "..."),
-        (enabled, 9, "..."),
-        (pid, 8, "..."),
-        (command, 17, "..."),
-        (time, 0, "..."),
-    ];
-
-    let mut line = String::new();
-
-    for ((text, style), max, suffix) in data {
-        let column = if max == 0 {
-            format!(" {}", style.paint(text.as_ref()))
-        } else {
-            let text = trim_long_string(text.as_ref(), max, suffix);
-            format!(" {0:1$}", style.paint(text), max)
-        };
+pub fn cmd_from_pid(pid: pid_t, proc_path: &Path) -> Result<String> {
+    // /<proc_path>/<pid>/cmdline
+    let p = proc_path.join(pid.to_string()).join("cmdline");
 
-        line.push_str(&column);
-    }
+    let data = fs::read(&p)
+        .with_context(|| format!("failed to read pid file: {:?}", p))?;
+
+    // arguments can be any bytes (not just UTF-8)
+    let first = data.split(|b| *b == 0).next().unwrap_or_default();
 
-    line
+    Ok(String::from_utf8_lossy(first).into_owned())
 }
 
 /**
- * Get the program name (arg0) for a PID.
+ * Get the full command line (every argument, joined with spaces) for a PID.
  *
  * # Example
  *
- * ```
+ * ```no_run
  * use std::path::PathBuf;
  *
- * let pid = 1;
+ * use vsv::utils::full_cmd_from_pid;
+ *
+ * # fn main() -> anyhow::Result<()> {
  * let proc_path = PathBuf::from("/proc");
- * let cmd = cmd_from_pid(pid, &proc_path)?;
- * println!("pid {} program is {}", pid, cmd);
+ * let cmd = full_cmd_from_pid(1, &proc_path)?;
+ * println!("pid 1 command line is {}", cmd);
+ * # Ok(())
+ * # }
  * ```
  */
-pub fn cmd_from_pid(pid: pid_t, proc_path: &Path) -> Result<String> {
+pub fn full_cmd_from_pid(pid: pid_t, proc_path: &Path) -> Result<String> {
     // /<proc_path>/<pid>/cmdline
     let p = proc_path.join(pid.to_string()).join("cmdline");
 
-    let data = fs::read_to_string(&p)
+    let data = fs::read(&p)
         .with_context(|| format!("failed to read pid file: {:?}", p))?;
 
-    let first = data.split('\0').next();
+    // arguments can be any bytes (not just UTF-8)
+    let args: Vec<_> = data
+        .split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect();
 
-    match first {
-        Some(f) => Ok(f.to_string()),
-        None => Err(anyhow!("failed to split cmdline data: {:?}", first)),
-    }
+    Ok(args.join(" "))
 }
 
 /**
- * Run a program and get stdout.
+ * Run a program and get stdout.  The program is killed if it runs longer than
+ * `timeout` (if given).
  *
  * # Example
  *
  * ```
+ * use vsv::utils::run_program_get_output;
+ *
+ * # fn main() -> anyhow::Result<()> {
  * let cmd = "echo";
  * let args = ["hello", "world"];
- * let out = run_program_get_output(&cmd, &args)?;
+ * let out = run_program_get_output(&cmd, &args, None)?;
  * println!("stdout is '{}'", out);
+ * # Ok(())
+ * # }
  * ```
  */
-pub fn run_program_get_output<T1, T2>(cmd: &T1, args: &[T2]) -> Result<String>
+pub fn run_program_get_output<T1, T2>(
+    cmd: &T1,
+    args: &[T2],
+    timeout: Option<Duration>,
+) -> Result<String>
 where
     T1: AsRef<str>,
     T2: AsRef<str>,
 {
-    let output = make_command(cmd, args).output()?;
-
-    if !output.status.success() {
-        return Err(anyhow!("program '{}' returned non-zero", cmd.as_ref()));
+    let mut child = make_command(cmd, args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // read stdout in its own thread so a chatty program can't fill the pipe
+    // and block forever while being waited on
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut buf = vec![];
+        stdout.read_to_end(&mut buf).map(|_| buf)
+    });
+
+    let status = wait_timeout(&mut child, timeout)
+        .with_context(|| format!("program '{}' failed", cmd.as_ref()))?;
+
+    if !status.success() {
+        bail!("program '{}' returned non-zero", cmd.as_ref());
     }
 
-    let stdout = String::from_utf8(output.stdout)?;
+    let buf = reader.join().map_err(|_| anyhow!("failed to read stdout"))??;
+    let stdout = String::from_utf8(buf)?;
 
     Ok(stdout)
 }
 
 /**
- * Run a program and get the exit status.
+ * Run a program and get the exit status.  The program is killed if it runs
+ * longer than `timeout` (if given).
  *
  * # Example
  *
  * ```
+ * use vsv::utils::run_program_get_status;
+ *
  * let cmd = "echo";
  * let args = ["hello", "world"];
- * let c = run_program_get_status(&cmd, &args);
+ * let c = run_program_get_status(&cmd, &args, None);
  * match c {
  *     Ok(status) => println!("exited with code: {}",
  *                   status.code().unwrap_or(-1)),
@@ -161,14 +161,44 @@ where
 pub fn run_program_get_status<T1, T2>(
     cmd: &T1,
     args: &[T2],
+    timeout: Option<Duration>,
 ) -> Result<ExitStatus>
 where
     T1: AsRef<str>,
     T2: AsRef<str>,
 {
-    let p = make_command(cmd, args).status()?;
+    let mut child = make_command(cmd, args).spawn()?;
+
+    wait_timeout(&mut child, timeout)
+}
 
-    Ok(p)
+/**
+ * Wait for a child process to exit, killing it if it's still running after
+ * `timeout` (or waiting forever if `None`).
+ */
+pub fn wait_timeout(
+    child: &mut Child,
+    timeout: Option<Duration>,
+) -> Result<ExitStatus> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return Ok(child.wait()?),
+    };
+
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("timed out after {:.1}s", timeout.as_secs_f64());
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
 }
 
 /**
@@ -177,7 +207,7 @@ where
  *
  * # Example
  *
- * ```
+ * ```ignore
  * let cmd = "echo";
  * let args = ["hello", "world"];
  * let c = make_command(&cmd, &args);
@@ -207,78 +237,320 @@ where
  *
  * ```
  * use std::time::Duration;
+ *
+ * use vsv::utils::relative_duration;
+ *
  * let dur = Duration::new(5, 0);
  * assert_eq!(relative_duration(&dur), "5 seconds".to_string());
  * ```
  */
 pub fn relative_duration(t: &Duration) -> String {
-    let secs = t.as_secs();
-
-    let v = [
-        (secs / 60 / 60 / 24 / 365, "year"),
-        (secs / 60 / 60 / 24 / 30, "month"),
-        (secs / 60 / 60 / 24 / 7, "week"),
-        (secs / 60 / 60 / 24, "day"),
-        (secs / 60 / 60, "hour"),
-        (secs / 60, "minute"),
-        (secs, "second"),
-    ];
-
-    let mut plural = "";
-    for (num, name) in v {
-        if num > 1 {
-            plural = "s"
-        }
+    relative_duration_units(t, 1)
+}
+
+/// Length of a year in seconds (365.2425 days, the Gregorian average).
+const YEAR_SECS: u64 = 60 * 60 * 24 * 3652425 / 10000;
+
+/// Units of time (their length in seconds, name and abbreviation) used by
+/// `relative_duration` and `compact_duration`.  A month is a twelfth of a year
+/// so a duration less than a year is never "12 months".
+const DURATION_UNITS: &[(u64, &str, &str)] = &[
+    (YEAR_SECS, "year", "y"),
+    (YEAR_SECS / 12, "month", "mo"),
+    (60 * 60 * 24 * 7, "week", "w"),
+    (60 * 60 * 24, "day", "d"),
+    (60 * 60, "hour", "h"),
+    (60, "minute", "m"),
+    (1, "second", "s"),
+];
 
-        if num > 0 {
-            return format!("{} {}{}", num, name, plural);
+/**
+ * Split a duration into (at most) `units` of `DURATION_UNITS`, starting from
+ * the largest that isn't 0, as the count of each unit and its index.  Each
+ * unit counts what is left over from the larger ones and units that are 0
+ * after the largest are left out (though they still count against `units`).
+ */
+fn duration_parts(t: &Duration, units: usize) -> Vec<(u64, usize)> {
+    let mut secs = t.as_secs();
+    let mut parts = vec![];
+
+    let largest = DURATION_UNITS
+        .iter()
+        .position(|(size, _, _)| secs >= *size)
+        .unwrap_or(DURATION_UNITS.len() - 1);
+    let sizes = DURATION_UNITS.iter().map(|(size, _, _)| size).enumerate();
+    for (i, size) in sizes.skip(largest).take(units.max(1)) {
+        let num = secs / size;
+        secs %= size;
+
+        if num > 0 || parts.is_empty() {
+            parts.push((num, i));
         }
     }
 
-    String::from("0 seconds")
+    parts
 }
 
 /**
- * Trim a string to be (at most) a certain number of characters with an
- * optional suffix.
+ * Convert a duration to a human-readable string with (at most) `units` units,
+ * like "1 hour 59 minutes" for 2.  Each unit counts what is left over from
+ * the larger ones (so 400 days is "1 year 1 month 4 days" with 4 units), and
+ * units that are 0 after the largest (like the minutes in 1 hour and 5
+ * seconds) are left out, though they still count against `units`.
+ *
+ * # Example
+ *
+ * ```
+ * use std::time::Duration;
+ *
+ * use vsv::utils::relative_duration_units;
  *
- * # Examples
+ * let dur = Duration::new(2 * 60 * 60 - 1, 0);
+ * assert_eq!(relative_duration_units(&dur, 1), "1 hour");
+ * assert_eq!(relative_duration_units(&dur, 2), "1 hour 59 minutes");
+ * ```
+ */
+pub fn relative_duration_units(t: &Duration, units: usize) -> String {
+    let parts: Vec<String> = duration_parts(t, units)
+        .into_iter()
+        .map(|(num, i)| {
+            let plural = if num == 1 { "" } else { "s" };
+            format!("{} {}{}", num, DURATION_UNITS[i].1, plural)
+        })
+        .collect();
+
+    parts.join(" ")
+}
+
+/**
+ * Convert a duration to a compact string with (at most) `units` units, like
+ * "3d4h" for 2 (see `relative_duration_units`).
  *
- * Trim the string `"hello world"` to be (at most) 8 characters and add
- * `"..."`:
+ * # Example
  *
  * ```
- * let s = trim_long_string("hello world", 8, "...");
- * assert_eq!(s, "hello...");
+ * use std::time::Duration;
+ *
+ * use vsv::utils::compact_duration;
+ *
+ * let dur = Duration::new(17 * 60 + 5, 0);
+ * assert_eq!(compact_duration(&dur, 1), "17m");
+ * assert_eq!(compact_duration(&dur, 2), "17m5s");
  * ```
+ */
+pub fn compact_duration(t: &Duration, units: usize) -> String {
+    duration_parts(t, units)
+        .into_iter()
+        .map(|(num, i)| format!("{}{}", num, DURATION_UNITS[i].2))
+        .collect()
+}
+
+/**
+ * Format a point in time as an RFC 3339 timestamp in UTC like
+ * `"2022-02-19T14:05:09Z"`.
  *
- * The suffix will only be added if the original string needed to be trimmed:
+ * Times before the unix epoch are clamped to the epoch.
+ *
+ * # Example
  *
  * ```
- * let s = trim_long_string("hello world", 100, "...");
- * assert_eq!(s, "hello world");
+ * use std::time::{Duration, UNIX_EPOCH};
+ *
+ * use vsv::utils::format_timestamp_utc;
+ *
+ * let t = UNIX_EPOCH + Duration::new(86400, 0);
+ * assert_eq!(format_timestamp_utc(&t), "1970-01-02T00:00:00Z");
  * ```
  */
-pub fn trim_long_string(s: &str, limit: usize, suffix: &str) -> String {
-    let suffix_len = suffix.len();
+pub fn format_timestamp_utc(t: &SystemTime) -> String {
+    let secs = t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
 
-    assert!(limit > suffix_len, "number too small");
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+    let (year, month, day) = civil_from_days(days);
 
-    let len = s.len();
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60
+    )
+}
 
-    // don't do anything if string is smaller than limit
-    if len < limit {
-        return s.to_string();
-    }
+/**
+ * Format a point in time in the local time zone like `"2022-02-19 09:05:09"`,
+ * falling back to `format_timestamp_utc` if the local time isn't known.
+ *
+ * Times before the unix epoch are clamped to the epoch.
+ */
+pub fn format_timestamp_local(t: &SystemTime) -> String {
+    let tm = match local_time(t) {
+        Some(tm) => tm,
+        None => return format_timestamp_utc(t),
+    };
 
-    // make new string (without formatting)
     format!(
-        "{}{}",
-        s.chars().take(limit - suffix_len).collect::<String>(),
-        suffix
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        i64::from(tm.tm_year) + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
     )
 }
 
+// not bound by the libc crate
+extern "C" {
+    fn strftime(
+        s: *mut libc::c_char,
+        max: libc::size_t,
+        format: *const libc::c_char,
+        tm: *const libc::tm,
+    ) -> libc::size_t;
+}
+
+/**
+ * Format a point in time in the local time zone with a `strftime(3)` format
+ * like `"%Y-%m-%d %H:%M"`, falling back to `format_timestamp_utc` if the local
+ * time isn't known.  A format with a NUL byte formats as an empty string.
+ *
+ * Times before the unix epoch are clamped to the epoch.
+ */
+pub fn format_timestamp_strftime(t: &SystemTime, fmt: &str) -> String {
+    let tm = match local_time(t) {
+        Some(tm) => tm,
+        None => return format_timestamp_utc(t),
+    };
+    let fmt = match std::ffi::CString::new(fmt) {
+        Ok(fmt) if !fmt.as_bytes().is_empty() => fmt,
+        _ => return String::new(),
+    };
+
+    // 0 is returned if the buffer is too small (or the output is empty)
+    let mut buf = vec![0u8; 256];
+    while buf.len() <= 64 * 1024 {
+        let len = unsafe {
+            strftime(buf.as_mut_ptr().cast(), buf.len(), fmt.as_ptr(), &tm)
+        };
+        if len > 0 {
+            return String::from_utf8_lossy(&buf[..len]).into_owned();
+        }
+        buf.resize(buf.len() * 2, 0);
+    }
+
+    String::new()
+}
+
+/// Convert a point in time to the local time zone (like `localtime(3)`).
+fn local_time(t: &SystemTime) -> Option<libc::tm> {
+    let secs = t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let secs = libc::time_t::try_from(secs).ok()?;
+
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::localtime_r(&secs, &mut tm) };
+    if ret.is_null() {
+        return None;
+    }
+
+    Some(tm)
+}
+
+/**
+ * Convert a number of days since the unix epoch into a (year, month, day)
+ * tuple in the proleptic Gregorian calendar.
+ *
+ * See: <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+ */
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/**
+ * Format a number of bytes in a human readable way (like `du -h`), with one
+ * decimal place for small values.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::utils::human_size;
+ *
+ * assert_eq!(human_size(1536), "1.5K");
+ * assert_eq!(human_size(20 * 1024 * 1024), "20M");
+ * ```
+ */
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["K", "M", "G", "T", "P"];
+
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+
+    if size < 10.0 {
+        format!("{:.1}{}", size, unit)
+    } else {
+        format!("{:.0}{}", size, unit)
+    }
+}
+
+/**
+ * Get the name of a user by uid (from `/etc/passwd` or any other source the
+ * system is configured to use), `None` if the user doesn't exist.
+ *
+ * # Example
+ *
+ * ```
+ * use vsv::utils::user_name;
+ *
+ * assert_eq!(user_name(0).as_deref(), Some("root"));
+ * ```
+ */
+pub fn user_name(uid: libc::uid_t) -> Option<String> {
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; 4096];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+    let ret = unsafe {
+        libc::getpwuid_r(
+            uid,
+            &mut pwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+    if ret != 0 || result.is_null() {
+        return None;
+    }
+
+    let name = unsafe { std::ffi::CStr::from_ptr(pwd.pw_name) };
+
+    Some(name.to_string_lossy().into_owned())
+}
+
 /**
  * Check if the given file descriptor (by number) is a tty.
  *
@@ -287,6 +559,8 @@ pub fn trim_long_string(s: &str, limit: usize, suffix: &str) -> String {
  * Print "hello world" if stdout is a tty:
  *
  * ```
+ * use vsv::utils::isatty;
+ *
  * if isatty(1) {
  *     println!("hello world");
  * }
@@ -296,6 +570,23 @@ pub fn isatty(fd: c_int) -> bool {
     unsafe { libc::isatty(fd) != 0 }
 }
 
+/**
+ * Get the width (in columns) of the terminal attached to stdout.
+ *
+ * The size is taken from the terminal itself if possible, falling back to the
+ * `COLUMNS` environmental variable.  `None` is returned if neither is known.
+ */
+pub fn terminal_width() -> Option<usize> {
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::ioctl(1, libc::TIOCGWINSZ, &mut ws) };
+
+    if ret == 0 && ws.ws_col > 0 {
+        return Some(ws.ws_col.into());
+    }
+
+    env::var("COLUMNS").ok()?.parse().ok().filter(|cols| *cols > 0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,7 +595,7 @@ mod tests {
     fn test_run_program_get_output_good_program_exit_success() -> Result<()> {
         let cmd = "echo";
         let args = ["hello", "world"];
-        let out = run_program_get_output(&cmd, &args)?;
+        let out = run_program_get_output(&cmd, &args, None)?;
 
         assert_eq!(out, "hello world\n", "stdout is correct");
 
@@ -315,7 +606,7 @@ mod tests {
     fn test_run_program_get_output_good_program_exit_failure() -> Result<()> {
         let cmd = "false";
         let args: [&str; 0] = [];
-        let out = run_program_get_output(&cmd, &args);
+        let out = run_program_get_output(&cmd, &args, None);
 
         assert!(out.is_err(), "program generates an error");
 
@@ -326,7 +617,7 @@ mod tests {
     fn test_run_program_get_output_bad_program() -> Result<()> {
         let cmd = "this-command-should-never-exist---seriously";
         let args: [&str; 0] = [];
-        let out = run_program_get_output(&cmd, &args);
+        let out = run_program_get_output(&cmd, &args, None);
 
         assert!(out.is_err(), "program generates an error");
 
@@ -337,7 +628,7 @@ mod tests {
     fn test_run_program_get_status_good_program_exit_success() -> Result<()> {
         let cmd = "true";
         let args: [&str; 0] = [];
-        let c = run_program_get_status(&cmd, &args)?;
+        let c = run_program_get_status(&cmd, &args, None)?;
 
         assert_eq!(c.code().unwrap_or(-1), 0, "program exits successfully");
 
@@ -348,10 +639,9 @@ mod tests {
     fn test_run_program_get_status_good_program_exit_failure() -> Result<()> {
         let cmd = "false";
         let args: [&str; 0] = [];
-        let c = run_program_get_status(&cmd, &args)?;
+        let c = run_program_get_status(&cmd, &args, None)?;
 
-        let code =
-            c.code().ok_or_else(|| anyhow!("failed to get exit code"))?;
+        let code = c.code().context("failed to get exit code")?;
 
         assert_ne!(code, 0, "program exit code is not 0");
 
@@ -362,13 +652,32 @@ mod tests {
     fn test_run_program_get_status_bad_program() -> Result<()> {
         let cmd = "this-command-should-never-exist---seriously";
         let args: [&str; 0] = [];
-        let c = run_program_get_status(&cmd, &args);
+        let c = run_program_get_status(&cmd, &args, None);
 
         assert!(c.is_err(), "program generates an error");
 
         Ok(())
     }
 
+    #[test]
+    fn test_run_program_timeout() {
+        let timeout = Some(Duration::from_millis(100));
+        let start = Instant::now();
+
+        let out = run_program_get_output(&"sleep", &["5"], timeout);
+        assert!(out.is_err(), "output times out");
+
+        let c = run_program_get_status(&"sleep", &["5"], timeout);
+        assert!(c.is_err(), "status times out");
+
+        assert!(start.elapsed() < Duration::from_secs(5), "program killed");
+    }
+
+    #[test]
+    fn test_user_name() {
+        assert_eq!(user_name(0).as_deref(), Some("root"), "root");
+    }
+
     #[test]
     fn test_isatty_bad_fd() {
         let b = isatty(-1);
@@ -376,6 +685,37 @@ mod tests {
         assert!(!b, "fd -1 is not a tty");
     }
 
+    #[test]
+    fn test_format_timestamp_utc() {
+        let arr = [
+            (0, "1970-01-01T00:00:00Z"),
+            (951782400, "2000-02-29T00:00:00Z"),
+            (1645279509, "2022-02-19T14:05:09Z"),
+            (4107542399, "2100-02-28T23:59:59Z"),
+        ];
+
+        for (secs, s) in arr {
+            let t = UNIX_EPOCH + Duration::new(secs, 0);
+            assert_eq!(format_timestamp_utc(&t), s, "timestamp mismatch");
+        }
+    }
+
+    #[test]
+    fn test_human_size() {
+        let arr = [
+            (0, "0B"),
+            (1023, "1023B"),
+            (1024, "1.0K"),
+            (1536, "1.5K"),
+            (100 * 1024, "100K"),
+            (5 * 1024 * 1024 * 1024, "5.0G"),
+        ];
+
+        for (bytes, s) in arr {
+            assert_eq!(human_size(bytes), s, "size mismatch");
+        }
+    }
+
     #[test]
     fn test_relative_durations() {
         use std::time::Duration;
@@ -387,8 +727,8 @@ mod tests {
             (3 * 60 * 60, "3 hours"),
             (3 * 60 * 60 * 24, "3 days"),
             (3 * 60 * 60 * 24 * 7, "3 weeks"),
-            (3 * 60 * 60 * 24 * 30, "3 months"),
-            (3 * 60 * 60 * 24 * 365, "3 years"),
+            (3 * YEAR_SECS / 12, "3 months"),
+            (3 * YEAR_SECS, "3 years"),
         ];
 
         for (secs, s) in arr {
@@ -396,4 +736,56 @@ mod tests {
             assert_eq!(relative_duration(&dur), s, "duration mismatch");
         }
     }
+
+    #[test]
+    fn test_compact_duration() {
+        use std::time::Duration;
+
+        let day = 60 * 60 * 24;
+        let arr = [
+            (0, 2, "0s"),
+            (45, 2, "45s"),
+            (17 * 60, 2, "17m"),
+            (3 * day + 4 * 60 * 60 + 5, 2, "3d4h"),
+            (3 * day + 5, 2, "3d"),
+            (3 * day + 5, 4, "3d5s"),
+            (400 * day, 2, "1y1mo"),
+        ];
+
+        for (secs, units, s) in arr {
+            let dur = Duration::new(secs, 0);
+            assert_eq!(compact_duration(&dur, units), s, "{} secs", secs);
+        }
+    }
+
+    #[test]
+    fn test_relative_duration_units() {
+        use std::time::Duration;
+
+        let day = 60 * 60 * 24;
+        let arr = [
+            (0, 2, "0 seconds"),
+            (1, 2, "1 second"),
+            (2 * 60 * 60 - 1, 1, "1 hour"),
+            (2 * 60 * 60 - 1, 2, "1 hour 59 minutes"),
+            (2 * 60 * 60 - 1, 3, "1 hour 59 minutes 59 seconds"),
+            (60 * 60 + 5, 2, "1 hour"),
+            (60 * 60 + 5, 3, "1 hour 5 seconds"),
+            (400 * day, 3, "1 year 1 month"),
+            (400 * day, 4, "1 year 1 month 4 days"),
+            (YEAR_SECS - day, 3, "11 months 4 weeks 1 day"),
+            (YEAR_SECS, 3, "1 year"),
+            (9 * day, 7, "1 week 2 days"),
+        ];
+
+        for (secs, units, s) in arr {
+            let dur = Duration::new(secs, 0);
+            assert_eq!(
+                relative_duration_units(&dur, units),
+                s,
+                "{} secs",
+                secs
+            );
+        }
+    }
 }
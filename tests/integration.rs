@@ -8,9 +8,16 @@
 
 use std::fs;
 use std::fs::File;
-use std::io::Write;
+use std::future::Future;
+use std::io::{Read, Write};
+use std::net::TcpStream;
 use std::path::{Path, PathBuf};
+use std::process;
 use std::str;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use assert_cmd::Command;
@@ -27,6 +34,12 @@ fn vsv(cfg: &Config) -> Result<Command> {
 
     cmd.env("SVDIR", &cfg.service_path);
     cmd.env("PROC_DIR", &cfg.proc_path);
+    cmd.env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"));
+    cmd.env("XDG_CONFIG_HOME", cfg.service_path.with_file_name("config"));
+    cmd.env_remove("COLUMNS");
+    for var in ["NO_COLOR", "CLICOLOR", "CLICOLOR_FORCE"] {
+        cmd.env_remove(var);
+    }
 
     Ok(cmd)
 }
@@ -42,6 +55,27 @@ fn get_tmp_path() -> PathBuf {
     PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("tests")
 }
 
+/**
+ * Create a fresh (empty) service and proc directory for a single test to use.
+ *
+ * Each test gets its own directory (by name) so tests can be run in parallel.
+ */
+fn setup_test_dirs(name: &str) -> Result<Config> {
+    let tmp_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+
+    let cfg = Config {
+        proc_path: tmp_path.join("proc"),
+        service_path: tmp_path.join("service"),
+    };
+
+    let _ = fs::remove_dir_all(&tmp_path);
+    for p in [&tmp_path, &cfg.proc_path, &cfg.service_path] {
+        fs::create_dir(p)?;
+    }
+
+    Ok(cfg)
+}
+
 fn parse_status_line(line: &str) -> Result<Vec<&str>> {
     let mut vec: Vec<&str> = vec![];
     let mut chars = line.chars().map(|c| c.len_utf8());
@@ -124,10 +158,13 @@ fn create_service(
         let supervise_dir = dir.join("supervise");
         let stat_file = supervise_dir.join("stat");
 
-        fs::create_dir(&dir)?;
+        fs::create_dir(dir)?;
         fs::create_dir(&supervise_dir)?;
         fs::write(&stat_file, format!("{}\n", state))?;
 
+        // a regular file opens fine, making the service look supervised
+        fs::write(supervise_dir.join("ok"), "")?;
+
         // write pid and proc info if supplied
         if let Some(pid) = pid {
             let proc_pid_dir = cfg.proc_path.join(pid);
@@ -143,6 +180,33 @@ fn create_service(
     Ok(())
 }
 
+/**
+ * Write a binary `supervise/status` file (like `runsv` does) for a service.
+ * `state` is 0 (down), 1 (run) or 2 (finish) and `want` is `b'u'` or `b'd'`.
+ */
+fn write_status(
+    cfg: &Config,
+    name: &str,
+    state: u8,
+    want: u8,
+    pid: i32,
+    since: Duration,
+) -> Result<()> {
+    let time = std::time::SystemTime::now() - since;
+    let secs = time.duration_since(std::time::UNIX_EPOCH)?.as_secs();
+
+    let mut status = [0u8; 20];
+    status[0..8].copy_from_slice(&(4611686018427387914 + secs).to_be_bytes());
+    status[12..16].copy_from_slice(&pid.to_le_bytes());
+    status[17] = want;
+    status[19] = state;
+
+    let p = cfg.service_path.join(name).join("supervise/status");
+    fs::write(p, status)?;
+
+    Ok(())
+}
+
 fn remove_service(
     cfg: &Config,
     name: &str,
@@ -328,7 +392,7 @@ fn full_synthetic_test() -> Result<()> {
 
     // test disable
     let mut cmd = vsv(&cfg)?;
-    cmd.args(&["disable", "bar", "baz"]).assert().success();
+    cmd.args(["disable", "bar", "baz"]).assert().success();
 
     let want = &[
         &["✔", "bar", "run", "false", "234", "bar-cmd"],
@@ -340,7 +404,7 @@ fn full_synthetic_test() -> Result<()> {
 
     // test enable
     let mut cmd = vsv(&cfg)?;
-    cmd.args(&["enable", "foo", "bar"]).assert().success();
+    cmd.args(["enable", "foo", "bar"]).assert().success();
     let want = &[
         &["✔", "bar", "run", "true", "234", "bar-cmd"],
         &["✔", "bat", "run", "true", "456", "bat-cmd"],
@@ -351,7 +415,7 @@ fn full_synthetic_test() -> Result<()> {
 
     // test bad disable
     let mut cmd = vsv(&cfg)?;
-    cmd.args(&["disable", "fake-service", "foo"]).assert().failure();
+    cmd.args(["disable", "fake-service", "foo"]).assert().failure();
     let want = &[
         &["✔", "bar", "run", "true", "234", "bar-cmd"],
         &["✔", "bat", "run", "true", "456", "bat-cmd"],
@@ -362,7 +426,7 @@ fn full_synthetic_test() -> Result<()> {
 
     // test bad enable
     let mut cmd = vsv(&cfg)?;
-    cmd.args(&["enable", "fake-service", "foo"]).assert().failure();
+    cmd.args(["enable", "fake-service", "foo"]).assert().failure();
     let want = &[
         &["✔", "bar", "run", "true", "234", "bar-cmd"],
         &["✔", "bat", "run", "true", "456", "bat-cmd"],
@@ -383,16 +447,16 @@ fn full_synthetic_test() -> Result<()> {
     create_service(&cfg, "foo", "run", Some("100"), Some("150"))?;
     let want = &[
         &["✔", "foo", "run", "true", "100", "foo-cmd"],
-        &["✔", "- log", "run", "true", "150", "foo-log"],
+        &["✔", "  - log", "run", "true", "150", "foo-log"],
     ];
     run_command_compare_output(&mut status_cmd_l, want)?;
 
     // disable logger only
     let mut cmd = vsv(&cfg)?;
-    cmd.args(&["disable", "foo/log"]).assert().success();
+    cmd.args(["disable", "foo/log"]).assert().success();
     let want = &[
         &["✔", "foo", "run", "true", "100", "foo-cmd"],
-        &["✔", "- log", "run", "false", "150", "foo-log"],
+        &["✔", "  - log", "run", "false", "150", "foo-log"],
     ];
     run_command_compare_output(&mut status_cmd_l, want)?;
 
@@ -402,21 +466,26 @@ fn full_synthetic_test() -> Result<()> {
     let want = &[&["✔", "foo", "run", "true", "100", "foo-cmd"]];
     run_command_compare_output(&mut status_cmd, want)?;
 
-    // manually create an unknown service (just a dir)
+    // manually create an unsupervised service (just a dir), the state column
+    // grows to fit so the output can't be parsed with the fixed widths
     let dir = cfg.service_path.join("just-a-dir");
     fs::create_dir(dir)?;
-    let want = &[
-        &["✔", "foo", "run", "true", "100", "foo-cmd"],
-        &["?", "just-a-dir", "n/a", "true", "---", "---"],
-    ];
-    run_command_compare_output(&mut status_cmd, want)?;
+    let assert = status_cmd.assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<Vec<&str>> =
+        stdout.lines().map(|line| line.split_whitespace().collect()).collect();
+    assert_eq!(lines[2][..6], ["✔", "foo", "run", "true", "100", "foo-cmd"]);
+    assert_eq!(
+        lines[3][..6],
+        ["~", "just-a-dir", "unsupervised", "true", "---", "---"]
+    );
 
     // create services and use a filter
     create_service(&cfg, "test-1", "run", Some("1"), None)?;
     create_service(&cfg, "test-2", "run", Some("2"), None)?;
     create_service(&cfg, "test-3", "run", Some("3"), None)?;
     let mut cmd = vsv(&cfg)?;
-    cmd.args(&["status", "test"]).assert().success();
+    cmd.args(["status", "test"]).assert().success();
     let want = &[
         &["✔", "test-1", "run", "true", "1", "test-1-cmd"],
         &["✔", "test-2", "run", "true", "2", "test-2-cmd"],
@@ -426,16 +495,3696 @@ fn full_synthetic_test() -> Result<()> {
 
     // status mode should work without "status" when -t or -l is supplied
     let mut cmd = vsv(&cfg)?;
-    cmd.args(&["-l", "test"]).assert().success();
+    cmd.args(["-l", "test"]).assert().success();
     let want = &[
         &["✔", "test-1", "run", "true", "1", "test-1-cmd"],
-        &["✔", "- log", "run", "true", "---", "---"],
+        &["✔", "  - log", "run", "true", "---", "---"],
         &["✔", "test-2", "run", "true", "2", "test-2-cmd"],
-        &["✔", "- log", "run", "true", "---", "---"],
+        &["✔", "  - log", "run", "true", "---", "---"],
         &["✔", "test-3", "run", "true", "3", "test-3-cmd"],
-        &["✔", "- log", "run", "true", "---", "---"],
+        &["✔", "  - log", "run", "true", "---", "---"],
     ];
     run_command_compare_output(&mut cmd, want)?;
 
     Ok(())
 }
+
+#[test]
+fn json_output() -> Result<()> {
+    let cfg = setup_test_dirs("json_output")?;
+
+    // no services is an empty array
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--format", "json"]).assert().success().stdout("[]\n");
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["status", "-F", "json"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+
+    for want in [
+        r#""name": "bar","#,
+        r#""state": "down","#,
+        r#""pid": null,"#,
+        r#""command": null,"#,
+        r#""name": "foo","#,
+        r#""state": "run","#,
+        r#""enabled": true,"#,
+        r#""pid": 123,"#,
+        r#""command": "foo-cmd","#,
+    ] {
+        assert!(stdout.contains(want), "json output contains {}", want);
+    }
+
+    // a service that's down has no pid, it isn't an error
+    assert_eq!(stdout.matches(r#""errors": []"#).count(), 2, "{}", stdout);
+
+    // bar sorts before foo
+    assert!(stdout.find("bar") < stdout.find("foo"), "services sorted");
+
+    // `-o <format>` still picks the format, like before `-F`
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["status", "-o", "json"]).assert().success();
+    assert_eq!(str::from_utf8(&assert.get_output().stdout)?, stdout);
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "json", "-o", "name"]).assert().success();
+    let names = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(!names.contains(r#""state""#), "columns with -F: {}", names);
+
+    // unknown formats are rejected
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--format", "bogus"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn yaml_output() -> Result<()> {
+    let cfg = setup_test_dirs("yaml_output")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--format", "yaml"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines[0], "---", "yaml document start");
+    assert!(lines[1].starts_with("svdir: "), "svdir metadata");
+    assert!(lines[2].starts_with("timestamp: "), "timestamp metadata");
+    assert_eq!(lines[3], "services:", "services key");
+    assert_eq!(lines[4], "  - name: foo", "first service");
+    assert_eq!(lines[5], "    state: run", "service state");
+    assert_eq!(lines[6], "    enabled: true", "service enabled");
+    assert_eq!(lines[7], "    pid: 123", "service pid");
+    assert_eq!(lines[8], "    command: foo-cmd", "service command");
+
+    Ok(())
+}
+
+#[test]
+fn csv_output() -> Result<()> {
+    let cfg = setup_test_dirs("csv_output")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    // commands with commas must be quoted
+    fs::write(cfg.proc_path.join("123").join("cmdline"), "foo,\"cmd\"\0")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--format", "csv"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines.len(), 3, "header + 2 services");
+    assert_eq!(lines[0], "name,state,enabled,pid,command,uptime,errors");
+    assert!(lines[1].starts_with("bar,down,true,,,"), "bar line");
+    assert!(
+        lines[2].starts_with("foo,run,true,123,\"foo,\"\"cmd\"\"\","),
+        "foo line"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn markdown_output() -> Result<()> {
+    let cfg = setup_test_dirs("markdown_output")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", Some("1"), None)?;
+
+    // pipes in commands must be escaped
+    fs::write(cfg.proc_path.join("123").join("cmdline"), "foo|bar\0")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "markdown", "-o", "name,state,command"]);
+    let want = "| name | state | command | errors |
+| --- | --- | --- | --- |
+| bar | down | bar-cmd |  |
+| foo | run | foo\\|bar |  |
+";
+    assert.assert().success().stdout(want);
+
+    Ok(())
+}
+
+#[test]
+fn template_output() -> Result<()> {
+    let cfg = setup_test_dirs("template_output")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let template = cfg.proc_path.with_file_name("report.tmpl");
+    fs::write(
+        &template,
+        "{{#each services}}<li>{{name}}: {{#if pid}}{{pid}}{{else}}down{{/if}}</li>\n{{/each}}",
+    )?;
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.arg("--template").arg(&template);
+    cmd.assert().success().stdout("<li>bar: down</li>\n<li>foo: 123</li>\n");
+
+    // bad templates are caught before anything is gathered
+    fs::write(&template, "{{#each services}}")?;
+    let mut cmd = vsv(&cfg)?;
+    cmd.arg("--template").arg(&template).assert().failure();
+
+    // can't be combined with other formats
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["-F", "json", "--template"]).arg(&template).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn raw_output() -> Result<()> {
+    let cfg = setup_test_dirs("raw_output")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    // spaces in commands must survive intact
+    fs::write(cfg.proc_path.join("123").join("cmdline"), "foo cmd\0")?;
+
+    // tab delimited
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--format", "raw"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<Vec<&str>> =
+        stdout.lines().map(|line| line.split('\t').collect()).collect();
+
+    assert_eq!(lines.len(), 2, "no header");
+    assert_eq!(lines[0][..5], ["bar", "down", "true", "", ""]);
+    assert_eq!(lines[1][..5], ["foo", "run", "true", "123", "foo cmd"]);
+
+    // nul delimited (-0 implies raw)
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.arg("-0").assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let fields: Vec<&str> = stdout.split_terminator('\0').collect();
+
+    assert_eq!(fields.len(), 12, "6 fields per service");
+    assert_eq!(fields[..5], ["bar", "down", "true", "", ""]);
+    assert_eq!(fields[6..11], ["foo", "run", "true", "123", "foo cmd"]);
+
+    // -0 is only valid with raw
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["-0", "-F", "json"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn metrics() -> Result<()> {
+    let cfg = setup_test_dirs("metrics")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.arg("metrics").assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    for want in [
+        "# TYPE vsv_service_up gauge",
+        r#"vsv_service_up{service="bar"} 0"#,
+        r#"vsv_service_up{service="foo"} 1"#,
+        "# TYPE vsv_service_uptime_seconds gauge",
+        "# TYPE vsv_service_pid gauge",
+        r#"vsv_service_pid{service="foo"} 123"#,
+    ] {
+        assert!(lines.contains(&want), "metrics contains {}", want);
+    }
+
+    // a down service has no pid sample
+    assert!(!stdout.contains(r#"vsv_service_pid{service="bar"}"#));
+
+    // write to a textfile
+    let textfile = cfg.service_path.with_file_name("vsv.prom");
+    let mut cmd = vsv(&cfg)?;
+    cmd.arg("metrics").arg("--textfile").arg(&textfile);
+    cmd.assert().success().stdout("");
+
+    assert_eq!(fs::read_to_string(&textfile)?, stdout, "textfile contents");
+
+    // log services are labeled with the service they follow
+    create_service(&cfg, "baz", "run", Some("456"), Some("457"))?;
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-l", "metrics"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    for want in [
+        r#"vsv_service_up{service="baz"} 1"#,
+        r#"vsv_service_up{service="baz",log="true"} 1"#,
+        r#"vsv_service_pid{service="baz",log="true"} 457"#,
+    ] {
+        assert!(lines.contains(&want), "metrics -l contains {}", want);
+    }
+    assert!(!stdout.contains("- log"), "no log service names");
+
+    // every sample has distinct labels
+    let mut samples: Vec<&str> = lines
+        .iter()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.rsplit_once(' ').map(|(series, _)| series))
+        .collect();
+    let count = samples.len();
+    samples.sort_unstable();
+    samples.dedup();
+    assert_eq!(samples.len(), count, "no duplicate series");
+
+    // services from several trees are labeled with their origin
+    let other = cfg.service_path.with_file_name("other");
+    fs::create_dir_all(&other)?;
+    let mut cmd = vsv(&cfg)?;
+    cmd.arg("-d").arg(&cfg.service_path).arg("-d").arg(&other);
+    let assert = cmd.arg("metrics").assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let want = format!(
+        r#"vsv_service_up{{service="foo",origin="{}"}} 1"#,
+        cfg.service_path.display()
+    );
+    assert!(stdout.lines().any(|line| line == want), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn exporter() -> Result<()> {
+    let cfg = setup_test_dirs("exporter")?;
+    let listen = "127.0.0.1:19109";
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .args(["exporter", "--listen", listen])
+        .spawn()?;
+
+    let get = |path: &str| -> Result<String> {
+        let mut stream = TcpStream::connect(listen)?;
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response)
+    };
+
+    // wait for the server to come up
+    let mut response = Err(anyhow!("exporter never started"));
+    for _ in 0..50 {
+        response = get("/metrics");
+        if response.is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let response = response;
+
+    // services are re-read on every scrape
+    create_service(&cfg, "bar", "down", None, None)?;
+    let response2 = get("/metrics");
+    let not_found = get("/nope");
+
+    child.kill()?;
+    child.wait()?;
+
+    let response = response?;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "200 response");
+    assert!(response.contains(r#"vsv_service_up{service="foo"} 1"#));
+    assert!(!response.contains(r#"service="bar""#), "bar not created yet");
+
+    let response2 = response2?;
+    assert!(response2.contains(r#"vsv_service_up{service="bar"} 0"#));
+
+    assert!(not_found?.starts_with("HTTP/1.1 404 "), "404 response");
+
+    Ok(())
+}
+
+/// Send an HTTP request and read the whole response.
+fn http_request(addr: &str, request: &str) -> Result<String> {
+    let mut stream = TcpStream::connect(addr)?;
+    stream.write_all(request.as_bytes())?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+#[test]
+fn serve() -> Result<()> {
+    let cfg = setup_test_dirs("serve")?;
+    let listen = "127.0.0.1:18999";
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+    let control = cfg.service_path.join("foo/supervise/control");
+    fs::write(&control, "")?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .env("VSV_SERVE_TOKEN", "hunter2")
+        .args(["serve", "--listen", listen])
+        .spawn()?;
+
+    let get = |path: &str| {
+        http_request(listen, &format!("GET {} HTTP/1.1\r\n\r\n", path))
+    };
+    let restart = |name: &str, token: &str| {
+        http_request(
+            listen,
+            &format!(
+                "POST /services/{}/restart HTTP/1.1\r\n\
+                 Authorization: Bearer {}\r\n\r\n",
+                name, token
+            ),
+        )
+    };
+
+    // wait for the server to come up
+    let mut services = Err(anyhow!("server never started"));
+    for _ in 0..50 {
+        services = get("/services");
+        if services.is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let services = services;
+    let foo = get("/services/foo");
+    let missing = get("/services/nope");
+    let bad_token = restart("foo", "wrong");
+    let restarted = restart("foo", "hunter2");
+    let not_listed = restart("..", "hunter2");
+
+    child.kill()?;
+    child.wait()?;
+
+    let services = services?;
+    assert!(services.starts_with("HTTP/1.1 200 OK\r\n"), "{}", services);
+    assert!(services.contains("Content-Type: application/json\r\n"));
+    assert!(services.contains("\"name\": \"bar\""), "{}", services);
+    assert!(services.contains("\"name\": \"foo\""), "{}", services);
+
+    let foo = foo?;
+    assert!(foo.contains("\r\n\r\n{\n"), "an object: {}", foo);
+    assert!(foo.contains("\"pid\": 123"), "{}", foo);
+    assert!(!foo.contains("bar"), "{}", foo);
+
+    assert!(missing?.starts_with("HTTP/1.1 404 "), "unknown service");
+    assert!(bad_token?.starts_with("HTTP/1.1 401 "), "bad token");
+    assert!(restarted?.starts_with("HTTP/1.1 200 "), "restarted");
+    assert_eq!(fs::read_to_string(&control)?, "tcu", "restart written");
+    assert!(not_listed?.starts_with("HTTP/1.1 404 "), "not a service");
+
+    Ok(())
+}
+
+#[test]
+fn serve_without_token() -> Result<()> {
+    let cfg = setup_test_dirs("serve_without_token")?;
+    let listen = "127.0.0.1:18998";
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .args(["serve", "--listen", listen])
+        .spawn()?;
+
+    let request = "POST /services/foo/restart HTTP/1.1\r\n\
+                   Authorization: Bearer \r\n\r\n";
+    let mut response = Err(anyhow!("server never started"));
+    for _ in 0..50 {
+        response = http_request(listen, request);
+        if response.is_ok() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    child.kill()?;
+    child.wait()?;
+
+    let response = response?;
+    assert!(response.starts_with("HTTP/1.1 403 "), "{}", response);
+    assert!(response.contains("VSV_SERVE_TOKEN"), "{}", response);
+
+    Ok(())
+}
+
+#[test]
+fn select_columns() -> Result<()> {
+    let cfg = setup_test_dirs("select_columns")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    // table output
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,pid"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(
+        lines,
+        ["", " SERVICE              PID", " foo                  123", ""]
+    );
+
+    // structured output only contains the columns requested
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["status", "-o", "state,name", "-F", "csv"])
+        .assert()
+        .success();
+    assert.stdout("state,name,errors\nrun,foo,\n");
+
+    // the command is never looked up if the column isn't wanted
+    fs::remove_file(cfg.proc_path.join("123").join("cmdline"))?;
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-v", "-o", "name,state"]).assert().success();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(!stderr.contains("failed to get command"), "cmdline not read");
+
+    // bad columns are rejected
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["-o", "name,bogus"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn format_string() -> Result<()> {
+    let cfg = setup_test_dirs("format_string")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--format-string", "%{name:5}|%{state}|%{pid}\\t100%%"]);
+    cmd.assert()
+        .success()
+        .stdout("bar  |down|---\t100%\nfoo  |run|123\t100%\n");
+
+    // bad format strings are rejected
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--format-string", "%{bogus}"]).assert().failure();
+
+    // format strings can't be combined with other formats
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--format-string", "%{name}", "-F", "json"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn sort() -> Result<()> {
+    let cfg = setup_test_dirs("sort")?;
+
+    create_service(&cfg, "a", "run", Some("300"), Some("301"))?;
+    create_service(&cfg, "b", "down", None, None)?;
+    create_service(&cfg, "c", "run", Some("20"), None)?;
+    create_service(&cfg, "d", "run", Some("1000"), None)?;
+
+    let names = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        cmd.args(["--format-string", "%{name}"]).args(args);
+        let assert = cmd.assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        Ok(stdout.lines().collect::<Vec<_>>().join(","))
+    };
+
+    // pids are sorted numerically and missing pids sort last
+    assert_eq!(names(&["--sort", "pid"])?, "c,a,d,b");
+    assert_eq!(names(&["--sort", "pid", "--reverse"])?, "b,d,a,c");
+
+    // the sort column doesn't need to be shown
+    assert_eq!(names(&["--sort", "state"])?, "a,c,d,b");
+
+    // log services stay with their parent
+    assert_eq!(
+        names(&["--sort", "pid", "-l"])?,
+        "c,  - log,a,  - log,d,  - log,b,  - log"
+    );
+
+    // bad columns are rejected
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--sort", "bogus"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn state_filter() -> Result<()> {
+    let cfg = setup_test_dirs("state_filter")?;
+
+    create_service(&cfg, "a", "run", Some("1"), None)?;
+    create_service(&cfg, "b", "down", None, None)?;
+    create_service(&cfg, "c", "finish", None, None)?;
+
+    let names = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        cmd.args(["--format-string", "%{name}"]).args(args);
+        let assert = cmd.assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        Ok(stdout.lines().collect::<Vec<_>>().join(","))
+    };
+
+    assert_eq!(names(&["--state", "down"])?, "b");
+    assert_eq!(names(&["--state", "down", "--state", "finish"])?, "b,c");
+    assert_eq!(names(&["--state", "run,finish"])?, "a,c");
+
+    // nothing is printed (not even a header) when nothing matches
+    fs::remove_dir_all(cfg.service_path.join("b"))?;
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--state", "down"]).assert().success().stdout("");
+
+    // bad states are rejected
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--state", "bogus"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn regex_filter() -> Result<()> {
+    let cfg = setup_test_dirs("regex_filter")?;
+
+    for name in ["nginx", "php-fpm7", "php-fpm8", "my-php-fpm", "sshd"] {
+        create_service(&cfg, name, "run", None, None)?;
+    }
+
+    let names = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        cmd.args(["--format-string", "%{name}"]).args(args);
+        let assert = cmd.assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        Ok(stdout.lines().collect::<Vec<_>>().join(","))
+    };
+
+    // plain strings still work as a substring match
+    assert_eq!(names(&["status", "php"])?, "my-php-fpm,php-fpm7,php-fpm8");
+
+    assert_eq!(names(&["status", "^php-fpm.*"])?, "php-fpm7,php-fpm8");
+    assert_eq!(names(&["--match", "^(nginx|sshd)$"])?, "nginx,sshd");
+
+    // all filters must match
+    assert_eq!(names(&["status", "php", "-m", "8$"])?, "php-fpm8");
+
+    // bad regexes are rejected
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["status", "php-fpm("]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn exclude_filter() -> Result<()> {
+    let cfg = setup_test_dirs("exclude_filter")?;
+
+    for name in ["agetty-tty1", "agetty-tty2", "agetty-ttyS0", "sshd", "udevd"]
+    {
+        create_service(&cfg, name, "run", None, None)?;
+    }
+
+    let names = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        cmd.args(["--format-string", "%{name}"]).args(args);
+        let assert = cmd.assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        Ok(stdout.lines().collect::<Vec<_>>().join(","))
+    };
+
+    assert_eq!(names(&["--exclude", "agetty-tty*"])?, "sshd,udevd");
+    assert_eq!(
+        names(&["-x", "agetty-tty?", "-x", "udevd"])?,
+        "agetty-ttyS0,sshd"
+    );
+    assert_eq!(names(&["-x", "/^agetty-tty\\d$/"])?, "agetty-ttyS0,sshd,udevd");
+
+    // globs must match the whole name
+    assert_eq!(
+        names(&["-x", "agetty"])?,
+        "agetty-tty1,agetty-tty2,agetty-ttyS0,sshd,udevd"
+    );
+
+    // exclusions compose with filters
+    assert_eq!(
+        names(&["status", "agetty", "-x", "*S0"])?,
+        "agetty-tty1,agetty-tty2"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn summary() -> Result<()> {
+    let cfg = setup_test_dirs("summary")?;
+
+    create_service(&cfg, "a", "run", Some("1"), None)?;
+    create_service(&cfg, "b", "run", None, None)?;
+    create_service(&cfg, "c", "down", None, None)?;
+    create_service(&cfg, "d", "finish", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.arg("--summary").assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // header + 4 services surrounded by blank lines, then the summary
+    assert_eq!(lines.len(), 9, "summary line added");
+    assert!(
+        lines[7].starts_with(
+            " 4 services: 2 up, 1 down, 1 other, 1 missing pid, longest down: c ("
+        ),
+        "summary line: {}",
+        lines[7]
+    );
+    assert_eq!(lines[8], "", "trailing blank line");
+
+    // log services aren't counted on their own
+    create_service(&cfg, "e", "run", Some("5"), Some("6"))?;
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--summary", "-l"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let summary = stdout.lines().rev().nth(1).unwrap_or_default();
+    assert!(
+        summary
+            .starts_with(" 5 services: 3 up, 1 down, 1 other, 1 missing pid"),
+        "summary line with logs: {}",
+        summary
+    );
+
+    Ok(())
+}
+
+#[test]
+fn quiet() -> Result<()> {
+    let cfg = setup_test_dirs("quiet")?;
+
+    create_service(&cfg, "a", "run", Some("1"), None)?;
+    create_service(&cfg, "b", "run", Some("2"), None)?;
+
+    // nothing is printed when everything is up
+    let mut cmd = vsv(&cfg)?;
+    cmd.arg("-q").assert().success().stdout("");
+
+    create_service(&cfg, "c", "down", None, None)?;
+    create_service(&cfg, "d", "finish", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.arg("--quiet");
+    let want = &[
+        &["X", "c", "down", "true", "---", "---"],
+        &["X", "d", "finish", "true", "---", "---"],
+    ];
+    run_command_compare_output(&mut cmd, want)?;
+
+    Ok(())
+}
+
+#[test]
+fn no_header() -> Result<()> {
+    let cfg = setup_test_dirs("no_header")?;
+
+    create_service(&cfg, "a", "run", Some("1"), None)?;
+    create_service(&cfg, "b", "down", Some("2"), None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--no-header", "-o", "name,state"]).assert();
+    assert
+        .success()
+        .stdout(" a                    run\n b                    down\n");
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--no-header", "-o", "name", "-F", "csv"]).assert();
+    assert.success().stdout("a,\nb,\n");
+
+    Ok(())
+}
+
+#[test]
+fn wide() -> Result<()> {
+    let cfg = setup_test_dirs("wide")?;
+
+    let name = "prometheus-node-exporter-with-a-really-long-name";
+    create_service(&cfg, name, "run", Some("1"), None)?;
+    create_service(&cfg, "b", "run", Some("2"), None)?;
+
+    let output = |args: &[&str]| -> Result<Vec<String>> {
+        let mut cmd = vsv(&cfg)?;
+        cmd.args(["--no-header", "-o", "name,pid"]).args(args);
+        let assert = cmd.assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        Ok(stdout.lines().map(String::from).collect())
+    };
+
+    assert_eq!(output(&[])?[1], " prometheus-node-e... 1");
+    assert_eq!(
+        output(&["-w"])?[1],
+        " prometheus-node-exporter-with-a-reall... 1"
+    );
+    assert_eq!(
+        output(&["-ww"])?,
+        [format!(" b{} 2", " ".repeat(name.len() - 1)), format!(" {} 1", name)]
+    );
+    assert_eq!(output(&["-w", "--wide"])?, output(&["-ww"])?);
+
+    // fixed columns keep their width
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-header", "-w", "-o", "pid,state"]);
+    let assert = cmd.assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout.lines().nth(1), Some(" 1        run"));
+
+    Ok(())
+}
+
+#[test]
+fn terminal_width() -> Result<()> {
+    let cfg = setup_test_dirs("terminal_width")?;
+
+    let name = "prometheus-node-exporter-with-a-really-long-name";
+    create_service(&cfg, name, "run", Some("1"), None)?;
+    create_service(&cfg, "b", "run", Some("2"), None)?;
+
+    let output = |columns: &str, args: &[&str]| -> Result<Vec<String>> {
+        let mut cmd = vsv(&cfg)?;
+        cmd.env("COLUMNS", columns);
+        cmd.args(["--no-header", "-o", "name,pid,command"]).args(args);
+        let assert = cmd.assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        Ok(stdout.lines().map(String::from).collect())
+    };
+
+    // plenty of room: nothing is truncated
+    let lines = output("200", &[])?;
+    assert_eq!(
+        lines[1],
+        format!(" {} 1        {}-cmd", name, name),
+        "long name fits"
+    );
+
+    // narrow terminal: the widest flexible column is shrunk to fit
+    let lines = output("60", &[])?;
+    for line in &lines {
+        assert!(line.chars().count() <= 60, "line fits: {:?}", line);
+    }
+    assert!(lines[1].starts_with(" prometheus-node-e"), "{:?}", lines[1]);
+    assert!(lines[1].contains("..."), "name truncated: {:?}", lines[1]);
+
+    // -w ignores the terminal width
+    let lines = output("60", &["-w"])?;
+    assert!(lines[1].chars().count() > 60, "-w line: {:?}", lines[1]);
+
+    Ok(())
+}
+
+#[test]
+fn group_by_state() -> Result<()> {
+    let cfg = setup_test_dirs("group_by_state")?;
+
+    create_service(&cfg, "a", "run", Some("1"), None)?;
+    create_service(&cfg, "b", "down", None, None)?;
+    create_service(&cfg, "c", "run", Some("3"), None)?;
+    create_service(&cfg, "d", "finish", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--group-by", "state", "-o", "name,state"]).assert();
+    let want = [
+        "",
+        "down (1)",
+        " SERVICE              STATE",
+        " b                    down",
+        "",
+        "finish (1)",
+        " SERVICE              STATE",
+        " d                    finish",
+        "",
+        "run (2)",
+        " SERVICE              STATE",
+        " a                    run",
+        " c                    run",
+        "",
+        "",
+    ];
+    assert.success().stdout(want.join("\n"));
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--group-by", "bogus"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn watch() -> Result<()> {
+    let cfg = setup_test_dirs("watch")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .args(["--watch=0.1", "-o", "name"])
+        .stdout(process::Stdio::piped())
+        .spawn()?;
+
+    thread::sleep(Duration::from_millis(500));
+
+    child.kill()?;
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    // not a tty: each redraw is appended without clearing the screen
+    assert!(!stdout.contains('\x1b'), "no escape codes: {:?}", stdout);
+    let redraws =
+        stdout.lines().filter(|l| l.starts_with("Every 0.1s")).count();
+    assert!(redraws >= 2, "output redrawn: {:?}", stdout);
+    assert!(stdout.contains(" foo"), "service shown: {:?}", stdout);
+
+    // the interval is only given with `=`, the next word is the subcommand
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .args(["--watch", "status", "-o", "name"])
+        .stdout(process::Stdio::piped())
+        .spawn()?;
+
+    thread::sleep(Duration::from_millis(500));
+
+    child.kill()?;
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.starts_with("Every 2s"), "default: {:?}", stdout);
+    assert!(stdout.contains(" foo"), "service shown: {:?}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--watch=0"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn watch_events() -> Result<()> {
+    let cfg = setup_test_dirs("watch_events")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .args(["--watch=0.1", "--events", "-c", "yes", "-o", "name,state"])
+        .stdout(process::Stdio::piped())
+        .spawn()?;
+
+    thread::sleep(Duration::from_millis(300));
+    let stat_file = cfg.service_path.join("foo").join("supervise").join("stat");
+    fs::write(stat_file, "down\n")?;
+    create_service(&cfg, "bar", "run", Some("456"), None)?;
+    thread::sleep(Duration::from_millis(300));
+
+    child.kill()?;
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    assert!(stdout.contains("Events"), "events shown: {:?}", stdout);
+    assert!(stdout.contains("Z  foo: run -> down"), "state: {:?}", stdout);
+    assert!(stdout.contains("Z  bar: added (run)"), "added: {:?}", stdout);
+
+    // changed cells are highlighted (inverted)
+    assert!(stdout.contains("\x1b[7m"), "highlighted: {:?}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn watch_cache() -> Result<()> {
+    let cfg = setup_test_dirs("watch_cache")?;
+    let log_dir = cfg.service_path.join("foo/log/main");
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    fs::create_dir(&log_dir)?;
+    fs::write(log_dir.join("current"), vec![b'x'; 1000])?;
+    fs::write(cfg.service_path.join("foo/log/run"), "exec svlogd main\n")?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .args(["--watch=0.1", "-F", "csv", "-o", "name,logsize"])
+        .stdout(process::Stdio::piped())
+        .spawn()?;
+
+    thread::sleep(Duration::from_millis(300));
+    // the log directory is found once, but its size is read every refresh
+    let other = cfg.service_path.join("foo/log/other");
+    fs::create_dir(&other)?;
+    fs::write(other.join("current"), "x")?;
+    fs::write(cfg.service_path.join("foo/log/run"), "exec svlogd other\n")?;
+    fs::write(log_dir.join("current"), vec![b'x'; 2000])?;
+    thread::sleep(Duration::from_millis(300));
+
+    child.kill()?;
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    assert!(stdout.contains("\nfoo,1000,"), "first size: {:?}", stdout);
+    assert!(stdout.contains("\nfoo,2000,"), "size re-read: {:?}", stdout);
+    assert!(!stdout.contains("\nfoo,1,"), "dir cached: {:?}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn tui_requires_tty() -> Result<()> {
+    let cfg = setup_test_dirs("tui_requires_tty")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.arg("tui").assert().failure();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("must be a tty"), "tty error: {}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn enable_disable_link() -> Result<()> {
+    let cfg = setup_test_dirs("enable_disable_link")?;
+    let source = cfg.service_path.with_file_name("sv");
+
+    fs::create_dir_all(source.join("foo"))?;
+    create_service(&cfg, "real", "run", Some("1"), None)?;
+
+    let link = cfg.service_path.join("foo");
+
+    // enable creates the symlink (and is fine being run again)
+    for _ in 0..2 {
+        let mut cmd = vsv(&cfg)?;
+        cmd.args(["enable", "--source"]).arg(&source).arg("foo");
+        cmd.assert().success();
+        assert_eq!(fs::read_link(&link)?, source.join("foo"), "foo linked");
+    }
+
+    // services that don't exist in the source directory can't be enabled
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["enable", "--source"]).arg(&source).arg("nope");
+    cmd.assert().failure();
+
+    // disable removes the symlink (and is fine being run again)
+    for _ in 0..2 {
+        let mut cmd = vsv(&cfg)?;
+        cmd.args(["disable", "--link", "foo"]).assert().success();
+        assert!(fs::symlink_metadata(&link).is_err(), "foo unlinked");
+    }
+
+    // real directories are never removed
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["disable", "--link", "real"]).assert().failure();
+    assert!(cfg.service_path.join("real").is_dir(), "real not removed");
+
+    Ok(())
+}
+
+#[test]
+fn control() -> Result<()> {
+    let cfg = setup_test_dirs("control")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), Some("2"))?;
+
+    // a regular file stands in for the fifo runsv would normally create
+    let control = cfg.service_path.join("foo/supervise/control");
+    let log_control = cfg.service_path.join("foo/log/supervise/control");
+    fs::write(&control, "")?;
+    fs::write(&log_control, "")?;
+
+    for (action, want) in [
+        ("down", "d"),
+        ("up", "u"),
+        ("restart", "tcu"),
+        ("once", "o"),
+        ("hup", "h"),
+        ("term", "t"),
+        ("kill", "k"),
+        ("alarm", "a"),
+        ("interrupt", "i"),
+        ("quit", "q"),
+        ("usr1", "1"),
+        ("usr2", "2"),
+    ] {
+        fs::write(&control, "")?;
+
+        let assert = vsv(&cfg)?.args([action, "foo"]).assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+
+        assert_eq!(fs::read_to_string(&control)?, want, "{} written", action);
+        let lines: Vec<&str> =
+            stdout.lines().filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines.len(), 2, "{} header and service printed", action);
+        assert!(lines[1].contains("foo"), "{} status printed", action);
+        assert!(lines[1].ends_with("done"), "{} result printed", action);
+    }
+
+    // log services can be controlled directly
+    vsv(&cfg)?.args(["down", "foo/log"]).assert().success();
+    assert_eq!(fs::read_to_string(&log_control)?, "d", "log down written");
+
+    // invalid services fail (but the others are still controlled)
+    fs::write(&control, "")?;
+    vsv(&cfg)?.args(["up", "-y", "nope", "foo"]).assert().failure();
+    assert_eq!(fs::read_to_string(&control)?, "u", "foo still controlled");
+
+    // at least one service is required
+    vsv(&cfg)?.arg("up").assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn control_glob() -> Result<()> {
+    let cfg = setup_test_dirs("control_glob")?;
+
+    for name in ["php-fpm7", "php-fpm8", "nginx"] {
+        create_service(&cfg, name, "run", None, None)?;
+        fs::write(cfg.service_path.join(name).join("supervise/control"), "")?;
+    }
+    let control = |name: &str| {
+        fs::read_to_string(
+            cfg.service_path.join(name).join("supervise/control"),
+        )
+    };
+
+    // globs are expanded (and duplicates removed), other names are kept as-is
+    let assert = vsv(&cfg)?
+        .args(["hup", "--yes", "php-fpm*", "nope", "php-fpm8"])
+        .assert()
+        .failure();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+
+    assert_eq!(control("php-fpm7")?, "h", "php-fpm7 signaled");
+    assert_eq!(control("php-fpm8")?, "h", "php-fpm8 signaled once");
+    assert_eq!(control("nginx")?, "", "nginx untouched");
+
+    // every service is reported in the table
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines.len(), 4, "header and 3 services");
+    assert!(lines[0].ends_with("RESULT"), "result column");
+    assert!(lines[1].contains("php-fpm7") && lines[1].ends_with("done"));
+    assert!(lines[2].contains("php-fpm8") && lines[2].ends_with("done"));
+    assert!(lines[3].contains("nope") && lines[3].contains("failed"));
+
+    // globs that match nothing are an error
+    vsv(&cfg)?.args(["hup", "bogus*"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn control_confirm() -> Result<()> {
+    let cfg = setup_test_dirs("control_confirm")?;
+
+    for name in ["foo", "bar"] {
+        create_service(&cfg, name, "run", None, None)?;
+        fs::write(cfg.service_path.join(name).join("supervise/control"), "")?;
+    }
+    let control = |name: &str| {
+        fs::read_to_string(
+            cfg.service_path.join(name).join("supervise/control"),
+        )
+    };
+
+    // multiple services need confirmation (which can't be given without a tty)
+    vsv(&cfg)?.args(["down", "*"]).assert().failure();
+    vsv(&cfg)?.args(["down", "--all"]).assert().failure();
+    assert_eq!(control("foo")?, "", "foo untouched");
+    assert_eq!(control("bar")?, "", "bar untouched");
+
+    // a single service doesn't
+    vsv(&cfg)?.args(["hup", "foo"]).assert().success();
+    assert_eq!(control("foo")?, "h", "foo signaled");
+
+    // --all with --yes acts on everything
+    vsv(&cfg)?.args(["hup", "--all", "--yes"]).assert().success();
+    assert_eq!(control("foo")?, "h", "foo signaled");
+    assert_eq!(control("bar")?, "h", "bar signaled");
+
+    // --all can't be given with services
+    vsv(&cfg)?.args(["hup", "--all", "foo"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn dry_run() -> Result<()> {
+    let cfg = setup_test_dirs("dry_run")?;
+    let source = cfg.service_path.with_file_name("sv");
+
+    fs::create_dir_all(source.join("linked"))?;
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&cfg, "bar", "run", None, None)?;
+    let control = cfg.service_path.join("foo/supervise/control");
+    fs::write(&control, "")?;
+    fs::write(cfg.service_path.join("bar/supervise/control"), "")?;
+
+    // control bytes aren't written (and no confirmation is needed)
+    let assert = vsv(&cfg)?.args(["--dry-run", "down", "*"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("would write \"d\""), "control printed");
+    assert_eq!(fs::read_to_string(&control)?, "", "foo untouched");
+
+    // down files aren't created
+    let assert =
+        vsv(&cfg)?.args(["--dry-run", "disable", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("would create"), "down file printed");
+    assert!(!cfg.service_path.join("foo/down").exists(), "foo enabled");
+
+    // symlinks aren't created (but are still checked)
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--dry-run", "enable", "--source"]).arg(&source).arg("linked");
+    let assert = cmd.assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("would link"), "link printed");
+    assert!(!cfg.service_path.join("linked").exists(), "not linked");
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--dry-run", "enable", "--source"]).arg(&source).arg("nope");
+    cmd.assert().failure();
+
+    // sv isn't run
+    let assert = vsv(&cfg)?
+        .env("SV_PROG", "false")
+        .args(["--dry-run", "start", "foo"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("dry run: not running false"), "sv not run");
+
+    Ok(())
+}
+
+#[test]
+fn user_trees() -> Result<()> {
+    let cfg = setup_test_dirs("user_trees")?;
+    let home = cfg.service_path.with_file_name("home");
+    let user_svdir = home.join("runit/service");
+    let xdg_svdir = home.join("xdg/service");
+
+    create_service(&cfg, "sys", "run", Some("1"), None)?;
+
+    // user services live in another tree
+    let user_cfg = Config {
+        proc_path: cfg.proc_path.clone(),
+        service_path: xdg_svdir.clone(),
+    };
+    fs::create_dir_all(&xdg_svdir)?;
+    create_service(&user_cfg, "mine", "down", Some("2"), None)?;
+
+    // -u falls back to $XDG_CONFIG_HOME/service if ~/runit/service is missing
+    let assert = vsv(&cfg)?
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join("xdg"))
+        .args(["-u", "-F", "csv", "-o", "name"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "name,errors\nmine,\n", "xdg user tree");
+
+    // ~/runit/service is preferred
+    fs::create_dir_all(home.join("runit"))?;
+    fs::rename(&xdg_svdir, &user_svdir)?;
+
+    // -U shows both trees with an origin column
+    let assert = vsv(&cfg)?
+        .env("HOME", &home)
+        .args(["-U", "-F", "csv", "-o", "origin,name,state"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "origin,name,state,errors\nsystem,sys,run,\nuser,mine,down,\n",
+        "both trees"
+    );
+
+    let assert = vsv(&cfg)?.env("HOME", &home).arg("-U").assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert!(lines[0].contains("ORIGIN"), "origin column shown by default");
+    assert!(lines[2].contains("user") && lines[2].contains("mine"));
+
+    // -U can't be combined with -d or -u
+    vsv(&cfg)?.args(["-U", "-u"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn multiple_dirs() -> Result<()> {
+    let cfg = setup_test_dirs("multiple_dirs")?;
+    let jail_cfg = Config {
+        proc_path: cfg.proc_path.clone(),
+        service_path: cfg.service_path.with_file_name("jail"),
+    };
+    fs::create_dir_all(&jail_cfg.service_path)?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&jail_cfg, "bar", "down", None, None)?;
+    create_service(&jail_cfg, "baz", "run", None, None)?;
+
+    let svdir = cfg.service_path.display().to_string();
+    let jail = jail_cfg.service_path.display().to_string();
+    let want = [format!("{} (1)", svdir), format!("{} (2)", jail)];
+
+    // -d can be given multiple times or as a colon-separated list
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["-d", &svdir, "-d", &jail]);
+    let mut colons = vsv(&cfg)?;
+    colons.args(["-d", &format!("{}:{}", svdir, jail)]);
+    let mut env = vsv(&cfg)?;
+    env.env("SVDIR", format!("{}:{}", svdir, jail));
+
+    for mut cmd in [cmd, colons, env] {
+        let assert = cmd.assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+
+        // each directory gets its own titled section
+        let titles: Vec<&str> =
+            stdout.lines().filter(|line| line.starts_with('/')).collect();
+        assert_eq!(titles, want, "sections");
+        assert!(stdout.find("foo") < stdout.find("bar"), "foo first");
+        assert!(stdout.find("bar") < stdout.find("baz"), "then bar and baz");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn avail() -> Result<()> {
+    let cfg = setup_test_dirs("avail")?;
+    let source = cfg.service_path.with_file_name("sv");
+
+    for name in ["enabled", "linked", "never", "other"] {
+        fs::create_dir_all(source.join(name))?;
+    }
+    fs::write(source.join("not-a-dir"), "")?;
+    create_service(&cfg, "enabled", "run", None, None)?;
+    std::os::unix::fs::symlink(
+        source.join("linked"),
+        cfg.service_path.join("linked"),
+    )?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["avail", "--source"]).arg(&source).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+
+    assert_eq!(lines.len(), 3, "header and 2 services");
+    assert!(lines[0].contains("SERVICE") && lines[0].contains("SOURCE"));
+    assert!(lines[1].starts_with(" - never"), "never listed");
+    assert!(lines[1].ends_with(&source.join("never").display().to_string()));
+    assert!(lines[2].starts_with(" - other"), "other listed");
+
+    // the first operand is a filter
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-header", "avail", "--source"]).arg(&source).arg("oth");
+    let assert = cmd.assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.starts_with(" - other"), "filtered");
+    assert_eq!(stdout.lines().count(), 1, "only other");
+
+    Ok(())
+}
+
+#[test]
+fn log_rows() -> Result<()> {
+    let cfg = setup_test_dirs("log_rows")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), Some("2"))?;
+    create_service(&cfg, "bar", "run", Some("3"), None)?;
+    create_service(&cfg, "nolog", "run", Some("4"), None)?;
+
+    // a dead logger is shown as down under its service
+    fs::write(cfg.service_path.join("bar/log/supervise/stat"), "down\n")?;
+
+    // services without a log directory don't get a log row
+    fs::remove_dir_all(cfg.service_path.join("nolog/log"))?;
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["status", "--log"]);
+    let want = &[
+        &["✔", "bar", "run", "true", "3", "bar-cmd"],
+        &["X", "  - log", "down", "true", "---", "---"],
+        &["✔", "foo", "run", "true", "1", "foo-cmd"],
+        &["✔", "  - log", "run", "true", "2", "foo-log"],
+        &["✔", "nolog", "run", "true", "4", "nolog-cmd"],
+    ];
+    run_command_compare_output(&mut cmd, want)?;
+
+    Ok(())
+}
+
+#[test]
+fn log() -> Result<()> {
+    let cfg = setup_test_dirs("log")?;
+    let log_dir = cfg.service_path.with_file_name("logs").join("foo");
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&cfg, "bar", "run", None, None)?;
+    fs::create_dir_all(&log_dir)?;
+
+    // absolute directories given to svlogd
+    let lines: Vec<String> = (1..=20).map(|i| format!("line {}", i)).collect();
+    fs::write(log_dir.join("current"), lines.join("\n") + "\n")?;
+    fs::write(
+        cfg.service_path.join("foo/log/run"),
+        format!("#!/bin/sh\nexec svlogd -tt {}\n", log_dir.display()),
+    )?;
+
+    let assert = vsv(&cfg)?.args(["log", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, lines[10..].join("\n") + "\n", "last 10 lines");
+
+    let assert = vsv(&cfg)?.args(["log", "-n", "2", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "line 19\nline 20\n", "last 2 lines");
+
+    // relative directories are relative to the log service
+    let main = cfg.service_path.join("bar/log/main");
+    fs::create_dir(&main)?;
+    fs::write(main.join("current"), "hello\n")?;
+    fs::write(cfg.service_path.join("bar/log/run"), "exec svlogd ./main\n")?;
+
+    let assert = vsv(&cfg)?.args(["log", "bar"]).assert().success();
+    assert_eq!(assert.get_output().stdout, b"hello\n", "relative dir");
+
+    // missing services fail
+    vsv(&cfg)?.args(["log", "nope"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn log_follow() -> Result<()> {
+    let cfg = setup_test_dirs("log_follow")?;
+    let log_dir = cfg.service_path.join("foo/log/main");
+    let current = log_dir.join("current");
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    fs::create_dir(&log_dir)?;
+    fs::write(&current, "old\n")?;
+    fs::write(cfg.service_path.join("foo/log/run"), "exec svlogd main\n")?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("SVDIR", &cfg.service_path)
+        .args(["log", "-f", "foo"])
+        .stdout(process::Stdio::piped())
+        .spawn()?;
+
+    thread::sleep(Duration::from_millis(400));
+
+    // appended lines are printed
+    let mut f = fs::OpenOptions::new().append(true).open(&current)?;
+    f.write_all(b"appended\n")?;
+    thread::sleep(Duration::from_millis(400));
+
+    // svlogd rotates by renaming current and starting a new one
+    f.write_all(b"before rotate\n")?;
+    fs::rename(&current, log_dir.join("@400000000000000000000000.s"))?;
+    fs::write(&current, "after rotate\n")?;
+    thread::sleep(Duration::from_millis(600));
+
+    child.kill()?;
+    let output = child.wait_with_output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    assert_eq!(
+        stdout, "old\nappended\nbefore rotate\nafter rotate\n",
+        "followed across rotation"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn logsize_column() -> Result<()> {
+    let cfg = setup_test_dirs("logsize_column")?;
+    let log_dir = cfg.service_path.join("foo/log/main");
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    fs::create_dir(&log_dir)?;
+    fs::write(log_dir.join("current"), vec![b'x'; 1000])?;
+    fs::write(log_dir.join("@400000000000000000000000.s"), vec![b'x'; 2072])?;
+    fs::write(cfg.service_path.join("foo/log/run"), "exec svlogd main\n")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,logsize"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,logsize,errors\nbar,,\nfoo,3072,\n",
+        "log size in bytes"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,logsize"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("LOGSIZE"), "header");
+    assert!(stdout.contains("3.0K"), "human readable size");
+
+    Ok(())
+}
+
+#[test]
+fn want_mismatch() -> Result<()> {
+    let cfg = setup_test_dirs("want_mismatch")?;
+
+    // (name, state, want byte)
+    let services = [("foo", 1, b'd'), ("bar", 2, b'u'), ("baz", 1, b'u')];
+    for (name, state, want) in services {
+        create_service(&cfg, name, "run", None, None)?;
+        write_status(&cfg, name, state, want, 0, Duration::from_secs(60))?;
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["--no-header", "-o", "status,name"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert!(lines.contains(&" ! bar"), "finish but wanted up");
+    assert!(lines.contains(&" ✔ baz"), "run and wanted up");
+    assert!(lines.contains(&" ! foo"), "run but wanted down");
+
+    Ok(())
+}
+
+#[test]
+fn normally_down() -> Result<()> {
+    let cfg = setup_test_dirs("normally_down")?;
+
+    create_service(&cfg, "foo", "down", None, None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+    fs::write(cfg.service_path.join("foo/down"), "")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["--no-header", "-o", "status,name"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert!(lines.contains(&" X bar"), "down because broken");
+    assert!(lines.contains(&" - foo"), "normally down");
+
+    // normally down services aren't problems
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-q", "--no-header", "-o", "name"]).assert();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout.trim(), "bar", "only broken services with -q");
+
+    Ok(())
+}
+
+#[test]
+fn unsupervised() -> Result<()> {
+    let cfg = setup_test_dirs("unsupervised")?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&cfg, "bar", "run", None, None)?;
+    fs::remove_file(cfg.service_path.join("bar/supervise/ok"))?;
+
+    // just enabled, runsv hasn't created supervise/ yet
+    fs::create_dir(cfg.service_path.join("baz"))?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["--no-header", "-o", "status,name,state"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<Vec<&str>> =
+        stdout.lines().map(|line| line.split_whitespace().collect()).collect();
+
+    assert_eq!(lines[0], ["~", "bar", "unsupervised"], "no ok pipe");
+    assert_eq!(lines[1], ["~", "baz", "unsupervised"], "no supervise dir");
+    assert_eq!(lines[2], ["✔", "foo", "run"], "supervised");
+
+    Ok(())
+}
+
+#[test]
+fn supervise_status() -> Result<()> {
+    let cfg = setup_test_dirs("supervise_status")?;
+
+    // the binary status file wins over the text stat and pid files
+    create_service(&cfg, "foo", "down", None, None)?;
+    write_status(&cfg, "foo", 1, b'u', 42, Duration::from_secs(3 * 60))?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["-F", "csv", "-o", "name,state,pid,uptime"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "name,state,pid,uptime,errors", "header");
+    assert!(lines[1].starts_with("foo,run,42,18"), "{}", lines[1]);
+
+    Ok(())
+}
+
+#[test]
+fn hung_supervisor() -> Result<()> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let cfg = setup_test_dirs("hung_supervisor")?;
+    let control = cfg.service_path.join("foo/supervise/control");
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    assert!(process::Command::new("mkfifo").arg(&control).status()?.success());
+
+    // hold the pipe open for reading (like a hung runsv) and fill it up
+    let _reader = fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(&control)?;
+    let mut writer = fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(&control)?;
+    while writer.write(&[b'x'; 4096]).is_ok() {}
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["up", "foo"]).assert().failure();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("hung supervisor"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn timeout() -> Result<()> {
+    let cfg = setup_test_dirs("timeout")?;
+
+    let start = std::time::Instant::now();
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env("SV_PROG", "sleep")
+        .args(["--timeout", "0.2", "5", "5"])
+        .assert()
+        .failure();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("timed out"), "{}", stderr);
+    assert!(start.elapsed() < Duration::from_secs(5), "sv killed");
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--timeout", "-1"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn jobs() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cfg = setup_test_dirs("jobs")?;
+
+    // a slow pstree, so services read one after another would take 8 seconds
+    let pstree = cfg.service_path.with_file_name("pstree");
+    fs::write(&pstree, "#!/bin/sh\nsleep 1\necho \"tree $2\"\n")?;
+    fs::set_permissions(&pstree, fs::Permissions::from_mode(0o755))?;
+
+    let names: Vec<String> = (0..8).map(|i| format!("svc{}", i)).collect();
+    for (i, name) in names.iter().enumerate() {
+        create_service(&cfg, name, "run", Some(&(100 + i).to_string()), None)?;
+    }
+
+    let start = std::time::Instant::now();
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env("PSTREE_PROG", &pstree)
+        .args(["-t", "-j", "8", "--no-header", "-o", "name"])
+        .assert()
+        .success();
+    assert!(start.elapsed() < Duration::from_secs(4), "read at once");
+
+    // the output is still in order
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> =
+        stdout.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let expected: Vec<String> = names
+        .iter()
+        .enumerate()
+        .flat_map(|(i, name)| [name.clone(), format!("tree {}", 100 + i)])
+        .collect();
+    assert_eq!(lines, expected, "{}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-config", "-j", "0"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn flapping() -> Result<()> {
+    let cfg = setup_test_dirs("flapping")?;
+    let state_file = cfg.service_path.with_file_name("state/vsv/restarts");
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&cfg, "bar", "run", None, None)?;
+    write_status(&cfg, "bar", 1, b'u', 0, Duration::from_secs(3600))?;
+
+    // each invocation sees foo with a new start time
+    for i in 0..4 {
+        let since = Duration::from_secs(100 - i * 10);
+        write_status(&cfg, "foo", 1, b'u', 0, since)?;
+        vsv(&cfg)?.assert().success();
+    }
+    assert!(state_file.exists(), "state file written");
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,restarts"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[1].starts_with("bar,0,"), "not restarted: {}", lines[1]);
+    assert!(lines[2].starts_with("foo,3,"), "restarted: {}", lines[2]);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["--no-header", "-o", "status,name,uptime"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with(" ✔ bar"), "{}", lines[0]);
+    assert!(lines[1].starts_with(" ↺ foo"), "{}", lines[1]);
+    assert!(lines[1].ends_with("(flapping, 3 restarts)"), "{}", lines[1]);
+
+    Ok(())
+}
+
+#[test]
+fn check() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cfg = setup_test_dirs("check")?;
+
+    let checks = [
+        ("foo", Some("#!/bin/sh\nexit 0\n")),
+        ("bar", Some("#!/bin/sh\nexit 1\n")),
+        ("baz", None),
+        ("bat", Some("#!/bin/sh\nexec sleep 5\n")),
+    ];
+    for (name, script) in checks {
+        create_service(&cfg, name, "run", None, None)?;
+
+        if let Some(script) = script {
+            let p = cfg.service_path.join(name).join("check");
+            fs::write(&p, script)?;
+            fs::set_permissions(&p, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["--check", "--timeout", "0.5", "-F", "csv", "-o", "name,check"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines[0], "name,check,errors", "header");
+    assert!(lines[1].starts_with("bar,false,"), "failed: {}", lines[1]);
+    assert!(lines[2].starts_with("bat,false,"), "timed out: {}", lines[2]);
+    assert!(lines[2].contains("timed out"), "timeout error: {}", lines[2]);
+    assert!(lines[3].starts_with("baz,,"), "no check: {}", lines[3]);
+    assert!(lines[4].starts_with("foo,true,"), "passed: {}", lines[4]);
+
+    // --check adds the column to the defaults
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--check", "status", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("CHECK"), "header");
+    assert!(stdout.contains("pass"), "check passed");
+
+    Ok(())
+}
+
+#[test]
+fn watch_hooks() -> Result<()> {
+    let cfg = setup_test_dirs("watch_hooks")?;
+    let out = cfg.service_path.with_file_name("hook.out");
+    let hook = format!(
+        "echo \"$VSV_SERVICE $VSV_OLD_STATE $VSV_NEW_STATE\" >> {:?}",
+        out
+    );
+
+    // hooks from the config file run as well
+    let file_out = cfg.service_path.with_file_name("file-hook.out");
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+    fs::write(
+        config_dir.join("config.toml"),
+        format!("hooks = 'echo \"$VSV_SERVICE\" >> {:?}'\n", file_out),
+    )?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&cfg, "bar", "run", None, None)?;
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("PATH", "/usr/bin:/bin")
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .env("XDG_CONFIG_HOME", cfg.service_path.with_file_name("config"))
+        .args(["--watch=0.1", "--hook", &hook, "-o", "name"])
+        .stdout(process::Stdio::null())
+        .spawn()?;
+
+    thread::sleep(Duration::from_millis(300));
+    fs::write(cfg.service_path.join("foo/supervise/stat"), "down\n")?;
+    thread::sleep(Duration::from_millis(500));
+
+    child.kill()?;
+    child.wait()?;
+
+    let hooks = fs::read_to_string(&out)?;
+    assert_eq!(hooks, "foo run down\n", "hook run once for foo");
+    let hooks = fs::read_to_string(&file_out)?;
+    assert_eq!(hooks, "foo\n", "hook from the config file");
+
+    Ok(())
+}
+
+#[test]
+fn watch_notify() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cfg = setup_test_dirs("watch_notify")?;
+    let out = cfg.service_path.with_file_name("notify.out");
+    let prog = cfg.service_path.with_file_name("notify-send");
+
+    fs::write(&prog, format!("#!/bin/sh\necho \"$@\" >> {:?}\n", out))?;
+    fs::set_permissions(&prog, fs::Permissions::from_mode(0o755))?;
+    create_service(&cfg, "foo", "run", None, None)?;
+
+    // not under a desktop session
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--watch", "--notify"]).assert().failure();
+
+    let mut child = process::Command::new(env!("CARGO_BIN_EXE_vsv"))
+        .env_clear()
+        .env("DISPLAY", ":0")
+        .env("NOTIFY_PROG", &prog)
+        .env("SVDIR", &cfg.service_path)
+        .env("PROC_DIR", &cfg.proc_path)
+        .env("XDG_STATE_HOME", cfg.service_path.with_file_name("state"))
+        .args(["--watch=0.1", "--notify", "-o", "name"])
+        .stdout(process::Stdio::null())
+        .spawn()?;
+
+    thread::sleep(Duration::from_millis(300));
+    fs::write(cfg.service_path.join("foo/supervise/stat"), "down\n")?;
+    thread::sleep(Duration::from_millis(500));
+
+    child.kill()?;
+    child.wait()?;
+
+    let notifications = fs::read_to_string(&out)?;
+    assert_eq!(notifications, "-a vsv foo is down run -> down\n", "notified");
+
+    Ok(())
+}
+
+#[test]
+fn s6_backend() -> Result<()> {
+    let cfg = setup_test_dirs("s6_backend")?;
+    fs::create_dir(cfg.service_path.join(".s6-svscan"))?;
+
+    // s6-supervise holds supervise/control open, a regular file opens fine
+    let write_s6_status = |name: &str, pid: u64, flags: u8| -> Result<()> {
+        let supervise = cfg.service_path.join(name).join("supervise");
+        fs::create_dir_all(&supervise)?;
+        fs::write(supervise.join("control"), "")?;
+
+        let time = std::time::SystemTime::now() - Duration::from_secs(3 * 60);
+        let secs = time.duration_since(std::time::UNIX_EPOCH)?.as_secs();
+
+        let mut status = [0u8; 43];
+        status[0..8]
+            .copy_from_slice(&(4611686018427387914 + secs).to_be_bytes());
+        status[24..32].copy_from_slice(&pid.to_be_bytes());
+        status[42] = flags;
+        fs::write(supervise.join("status"), status)?;
+
+        Ok(())
+    };
+
+    // running and wanted up, down but wanted up
+    write_s6_status("bar", 42, 0x04)?;
+    write_s6_status("foo", 0, 0x04)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["-F", "csv", "-o", "name,state,pid,uptime"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[1].starts_with("bar,run,42,18"), "{}", lines[1]);
+    assert!(lines[2].starts_with("foo,down,,"), "{}", lines[2]);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "status,name"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("! foo"), "want up mismatch: {}", stdout);
+
+    // restart is sent as up then term
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--dry-run", "restart", "bar"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("would write \"ut\""), "{}", stdout);
+
+    // forcing runit finds nothing supervising the services
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["--backend", "runit", "-F", "csv", "-o", "name,state"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("bar,unsupervised"), "{}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--backend", "bogus"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn daemontools_backend() -> Result<()> {
+    let cfg = setup_test_dirs("daemontools_backend")?;
+
+    // supervise writes the first 18 bytes of runit's status file
+    create_service(&cfg, "foo", "run", None, None)?;
+    write_status(&cfg, "foo", 0, b'u', 42, Duration::from_secs(3 * 60))?;
+    let p = cfg.service_path.join("foo/supervise/status");
+    let status = fs::read(&p)?;
+    fs::write(&p, &status[..18])?;
+    fs::remove_file(cfg.service_path.join("foo/supervise/stat"))?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["-F", "csv", "-o", "name,state,pid,uptime"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[1].starts_with("foo,run,42,18"), "{}", lines[1]);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["usr1", "foo"]).assert().failure();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("not supported by daemontools"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn systemd_backend() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cfg = setup_test_dirs("systemd_backend")?;
+
+    // a fake systemctl printing what `systemctl show` would
+    let systemctl = cfg.service_path.with_file_name("systemctl");
+    write_file(
+        &systemctl,
+        "#!/bin/sh\ncat <<EOF\n\
+         Id=cron.service\nActiveState=failed\nMainPID=0\n\
+         ActiveEnterTimestampMonotonic=0\nUnitFileState=enabled\n\n\
+         Id=sshd.service\nActiveState=active\nMainPID=42\n\
+         ActiveEnterTimestampMonotonic=1\nUnitFileState=enabled\nEOF\n",
+    )?;
+    fs::set_permissions(&systemctl, fs::Permissions::from_mode(0o755))?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env("SYSTEMCTL_PROG", &systemctl)
+        .args(["--backend", "systemd", "-F", "csv", "-o", "name,state,pid"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[1].starts_with("cron.service,down,,"), "{}", lines[1]);
+    assert!(lines[2].starts_with("sshd.service,run,42,"), "{}", lines[2]);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env("SYSTEMCTL_PROG", &systemctl)
+        .args(["--backend", "systemd", "down", "sshd.service"])
+        .assert()
+        .failure();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("read-only"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn rss_column() -> Result<()> {
+    let cfg = setup_test_dirs("rss_column")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    fs::write(cfg.proc_path.join("1/status"), "Name:\tfoo\nVmRSS:\t2048 kB\n")?;
+
+    // a process without memory (like a zombie) has no VmRSS
+    fs::write(cfg.proc_path.join("2/status"), "Name:\tbar\n")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "csv", "-o", "name,rss"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,rss,errors\nbar,,\nfoo,2097152,\n",
+        "rss in bytes"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,memory"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("RSS"), "header");
+    assert!(stdout.contains("2.0M"), "human readable size");
+
+    Ok(())
+}
+
+#[test]
+fn cpu_column() -> Result<()> {
+    let cfg = setup_test_dirs("cpu_column")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+    fs::write(
+        cfg.proc_path.join("1/stat"),
+        "1 (foo) S 0 1 1 0 -1 0 0 0 0 0 30 20 0 0 20 0 1 0 100 0 0\n",
+    )?;
+
+    // the fake process uses no cpu time while it is being watched
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "csv", "-o", "name,cpu"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "name,cpu,errors", "header");
+    assert!(lines[1].starts_with("bar,,"), "{}", lines[1]);
+    assert_eq!(lines[2], "foo,0,", "no cpu used");
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,cpu"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("CPU%"), "header");
+    assert!(stdout.contains("0.0"), "formatted percent");
+
+    Ok(())
+}
+
+#[test]
+fn user_column() -> Result<()> {
+    let cfg = setup_test_dirs("user_column")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    fs::write(cfg.proc_path.join("1/status"), "Uid:\t0\t0\t0\t0\n")?;
+    fs::write(cfg.proc_path.join("2/status"), "Uid:\t1000\t4321\t0\t0\n")?;
+
+    // the effective uid is shown
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "csv", "-o", "name,user"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "name,user,errors\nbar,4321,\nfoo,0,\n", "uids");
+
+    // names are resolved (every system has root) and the column added
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["-F", "csv", "-o", "name", "--user-names"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("foo,root,"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn non_utf8_cmdline() -> Result<()> {
+    let cfg = setup_test_dirs("non_utf8_cmdline")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    fs::write(cfg.proc_path.join("1/cmdline"), b"caf\xe9\0--name\0\xff\xfe\0")?;
+    fs::write(cfg.proc_path.join("2/stat"), b"2 (caf\xe9) Z 1 2 2 0 -1\n")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,state,command"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout,
+        "name,state,command,errors\nbar,zombie,bar-cmd,\nfoo,run,caf\u{fffd},\n",
+        "replaced invalid UTF-8"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["--full-command", "-F", "csv", "-o", "name,command"])
+        .args(["status", "foo"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout,
+        "name,command,errors\nfoo,caf\u{fffd} --name \u{fffd}\u{fffd},\n",
+        "every arg"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn full_command() -> Result<()> {
+    let cfg = setup_test_dirs("full_command")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    fs::write(
+        cfg.proc_path.join("1/cmdline"),
+        "python3\0-m\0http.server\0--bind\0localhost\0",
+    )?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,command"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "name,command,errors\nfoo,python3,\n", "first arg");
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["--full-command", "-F", "csv", "-o", "name,command"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout,
+        "name,command,errors\nfoo,python3 -m http.server --bind localhost,\n",
+        "every arg"
+    );
+
+    // the column is as wide as the command
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["--full-command", "-o", "name,command"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(
+        stdout.contains("python3 -m http.server --bind localhost"),
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn children() -> Result<()> {
+    let cfg = setup_test_dirs("children")?;
+
+    create_service(&cfg, "foo", "run", Some("10"), None)?;
+    create_service(&cfg, "bar", "run", Some("20"), None)?;
+    for (pid, name, ppid) in [
+        ("10", "nginx", "1"),
+        ("11", "nginx", "10"),
+        ("12", "nginx", "10"),
+        ("13", "sh", "11"),
+        ("20", "bar", "1"),
+        ("30", "other", "1"),
+    ] {
+        let dir = cfg.proc_path.join(pid);
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join("stat"),
+            format!("{} ({}) S {} {} {} 0 -1 0\n", pid, name, ppid, pid, pid),
+        )?;
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["--children", "-o", "name"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().map(str::trim_end).collect();
+    assert_eq!(
+        &lines[1..],
+        [
+            " SERVICE",
+            // bar has no children
+            " bar",
+            " foo",
+            "    ├─ 11 nginx",
+            "    │  └─ 13 sh",
+            "    └─ 12 nginx",
+            "",
+        ],
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fds_column() -> Result<()> {
+    let cfg = setup_test_dirs("fds_column")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    for (pid, fds, limit) in [("1", 9, "10"), ("2", 3, "unlimited")] {
+        let dir = cfg.proc_path.join(pid);
+        fs::create_dir_all(dir.join("fd"))?;
+        for fd in 0..fds {
+            fs::write(dir.join("fd").join(fd.to_string()), "")?;
+        }
+        fs::write(
+            dir.join("limits"),
+            format!("Max open files            {:<20} 524288 files\n", limit),
+        )?;
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "csv", "-o", "name,fds"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "name,fds,errors\nbar,3,\nfoo,9,\n", "fds counted");
+
+    // 9 of 10 is over the default threshold
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-c", "yes", "-o", "name,fds"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("\x1b[31m9"), "near the limit: {:?}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["-c", "yes", "--fd-threshold", "95", "-o", "name,fds"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(!stdout.contains("\x1b[31m9"), "under the limit: {:?}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--fd-threshold", "0"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn threads_column() -> Result<()> {
+    let cfg = setup_test_dirs("threads_column")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+    fs::write(cfg.proc_path.join("1/status"), "Name:\tfoo\nThreads:\t12\n")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,threads"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "name,threads,errors", "header");
+    assert!(lines[1].starts_with("bar,,"), "{}", lines[1]);
+    assert_eq!(lines[2], "foo,12,", "threads counted");
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,nlwp"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("THREADS"), "header");
+
+    Ok(())
+}
+
+#[test]
+fn zombie_and_stopped_states() -> Result<()> {
+    let cfg = setup_test_dirs("zombie_and_stopped_states")?;
+
+    for (name, pid, state) in
+        [("foo", "1", "S"), ("bar", "2", "Z"), ("baz", "3", "T")]
+    {
+        create_service(&cfg, name, "run", Some(pid), None)?;
+        fs::write(
+            cfg.proc_path.join(pid).join("stat"),
+            format!("{} ({}) {} 1 {} {} 0 -1 0\n", pid, name, state, pid, pid),
+        )?;
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "csv", "-o", "name,state"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,state,errors\nbar,zombie,\nbaz,stopped,\nfoo,run,\n",
+        "states from /proc"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .args(["--state", "zombie", "-F", "csv", "-o", "name"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "name,errors\nbar,\n", "filtered by state");
+
+    // zombies and stopped processes are problems
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-q", "-F", "csv", "-o", "name"]).assert();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "name,errors\nbar,\nbaz,\n", "problems");
+
+    Ok(())
+}
+
+#[test]
+fn outdated_column() -> Result<()> {
+    let cfg = setup_test_dirs("outdated_column")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    std::os::unix::fs::symlink(
+        "/usr/bin/foo (deleted)",
+        cfg.proc_path.join("1/exe"),
+    )?;
+    std::os::unix::fs::symlink("/usr/bin/bar", cfg.proc_path.join("2/exe"))?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,outdated"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,outdated,errors\nbar,false,\nfoo,true,\n",
+        "deleted exe found"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,outdated"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("OUTDATED"), "header");
+    assert!(stdout.contains("yes"), "needs restart");
+
+    Ok(())
+}
+
+#[test]
+fn show_env() -> Result<()> {
+    let cfg = setup_test_dirs("show_env")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+    fs::write(
+        cfg.proc_path.join("1/environ"),
+        "HOME=/root\0API_TOKEN=abc123\0PATH=/bin\0",
+    )?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["show", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("state:    run"), "{}", stdout);
+    assert!(stdout.contains("pid:      1"), "{}", stdout);
+    assert!(!stdout.contains("HOME"), "no env without --env");
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["show", "--env", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(
+        stdout.ends_with(
+            "environment\n  HOME=/root\n  API_TOKEN=abc123\n  PATH=/bin\n"
+        ),
+        "{}",
+        stdout
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["show", "--env", "--redact", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("  API_TOKEN=<redacted>\n"), "{}", stdout);
+    assert!(stdout.contains("  HOME=/root\n"), "{}", stdout);
+
+    // a service without a process has no environment
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["show", "--env", "bar"]).assert().failure();
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["show", "nope"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn cgroup_columns() -> Result<()> {
+    let cfg = setup_test_dirs("cgroup_columns")?;
+    let cgroup_path = cfg.proc_path.with_file_name("cgroup");
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    fs::write(cfg.proc_path.join("1/cgroup"), "0::/runit/foo\n")?;
+    fs::create_dir_all(cgroup_path.join("runit/foo"))?;
+    fs::write(cgroup_path.join("runit/foo/memory.current"), "4194304\n")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env("CGROUP_DIR", &cgroup_path)
+        .args(["-F", "csv", "-o", "name,cgroup,cgmem"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,cgroup,cgmem,errors\nfoo,/runit/foo,4194304,\n",
+        "cgroup found"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env("CGROUP_DIR", &cgroup_path)
+        .args(["-o", "name,cgroup,cgmem"])
+        .assert()
+        .success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("/runit/foo"), "{}", stdout);
+    assert!(stdout.contains("4.0M"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn container_column() -> Result<()> {
+    let cfg = setup_test_dirs("container_column")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    for (dir, pid_ns, mnt_ns) in [
+        ("self", "4026531836", "4026531841"),
+        ("1", "4026531836", "4026531841"),
+        ("2", "4026532201", "4026532199"),
+    ] {
+        let ns = cfg.proc_path.join(dir).join("ns");
+        fs::create_dir_all(&ns)?;
+        std::os::unix::fs::symlink(
+            format!("pid:[{}]", pid_ns),
+            ns.join("pid"),
+        )?;
+        std::os::unix::fs::symlink(
+            format!("mnt:[{}]", mnt_ns),
+            ns.join("mnt"),
+        )?;
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,container"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,container,errors\nbar,true,\nfoo,false,\n",
+        "containers found"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,container"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("pid,mnt"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn nice_and_class_columns() -> Result<()> {
+    let cfg = setup_test_dirs("nice_and_class_columns")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "run", Some("2"), None)?;
+    for (pid, nice, policy) in [("1", "0", "0"), ("2", "10", "3")] {
+        let fields = format!(
+            "S 0 {pid} {pid} 0 -1 0 0 0 0 0 0 0 0 0 20 {nice} 1 0 100 0 0 0 {}",
+            ["0"; 15].join(" ")
+        );
+        fs::write(
+            cfg.proc_path.join(pid).join("stat"),
+            format!("{} (cmd) {} {}\n", pid, fields, policy),
+        )?;
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,nice,class"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,nice,class,errors\nbar,10,B,\nfoo,0,TS,\n",
+        "nice and class"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn oom_columns() -> Result<()> {
+    let cfg = setup_test_dirs("oom_columns")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    fs::write(cfg.proc_path.join("1/oom_score"), "667\n")?;
+    fs::write(cfg.proc_path.join("1/oom_score_adj"), "500\n")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,oom,oomadj"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,oom,oomadj,errors\nfoo,667,500,\n",
+        "oom score read"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-c", "yes", "-o", "name,oom"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("\x1b[31m667"), "high risk: {:?}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn io_columns() -> Result<()> {
+    let cfg = setup_test_dirs("io_columns")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    fs::write(
+        cfg.proc_path.join("1/io"),
+        "rchar: 99999999\nwchar: 99999999\nsyscr: 10\nsyscw: 10\n\
+         read_bytes: 1048576\nwrite_bytes: 2048\ncancelled_write_bytes: 0\n",
+    )?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["-F", "csv", "-o", "name,ioread,iowrite"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(
+        stdout, "name,ioread,iowrite,errors\nfoo,1048576,2048,\n",
+        "storage io read"
+    );
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,read,write"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("1.0M"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn show_limits() -> Result<()> {
+    let cfg = setup_test_dirs("show_limits")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    fs::write(
+        cfg.proc_path.join("1/limits"),
+        "Limit                     Soft Limit           Hard Limit           Units     \n\
+         Max processes             63590                63590                processes \n\
+         Max open files            1024                 524288               files     \n",
+    )?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["show", "--limits", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(
+        stdout.ends_with(
+            "limits\n\
+             \x20 RESOURCE   SOFT    HARD  UNITS      DESCRIPTION\n\
+             \x20 nproc     63590   63590  processes  Max processes\n\
+             \x20 nofile     1024  524288  files      Max open files\n"
+        ),
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lazy_columns() -> Result<()> {
+    let cfg = setup_test_dirs("lazy_columns")?;
+    let state_file = cfg.service_path.with_file_name("state/vsv/restarts");
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    write_status(&cfg, "foo", 1, b'u', 123, Duration::from_secs(60))?;
+    // the process is gone, so anything read from /proc fails (verbosely)
+    fs::remove_dir_all(cfg.proc_path.join("123"))?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-v", "-o", "name,pid"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stdout.contains(" foo "), "{}", stdout);
+    assert!(!stderr.contains("failed to get"), "{}", stderr);
+    assert!(!state_file.exists(), "restarts not tracked");
+
+    // metrics only have the state, pid and uptime
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-v", "metrics"]).assert().success();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(!stderr.contains("failed to get command"), "{}", stderr);
+
+    // the command column (shown by default) is read when shown
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.arg("-v").assert().success();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("failed to get command"), "{}", stderr);
+    assert!(state_file.exists(), "restarts tracked");
+
+    Ok(())
+}
+
+#[test]
+fn stale_pid() -> Result<()> {
+    let cfg = setup_test_dirs("stale_pid")?;
+
+    // both processes started when the system booted, an hour ago
+    let boot = std::time::SystemTime::now() - Duration::from_secs(60 * 60);
+    let btime = boot.duration_since(std::time::UNIX_EPOCH)?.as_secs();
+    fs::write(
+        cfg.proc_path.join("stat"),
+        format!("cpu 0 0\nbtime {}\n", btime),
+    )?;
+
+    for (name, pid, since) in [("foo", 1, 60), ("bar", 2, 60 * 60)] {
+        create_service(&cfg, name, "run", Some(&pid.to_string()), None)?;
+        write_status(&cfg, name, 1, b'u', pid, Duration::from_secs(since))?;
+        fs::write(
+            cfg.proc_path.join(pid.to_string()).join("stat"),
+            format!(
+                "{} ({}) S 1 {} {} 0 -1 0 0 0 0 0 0 0 0 0 20 0 1 0 0 0 0\n",
+                pid, name, pid, pid
+            ),
+        )?;
+    }
+
+    // foo's supervisor says it started a minute ago
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-q", "-F", "csv", "-o", "name"]).assert();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", stdout);
+    assert!(lines[1].starts_with("foo,"), "{}", stdout);
+    assert!(lines[1].contains("reused pid"), "{}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "status,name"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("≠ foo"), "{}", stdout);
+    assert!(stdout.contains("✔ bar"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn show() -> Result<()> {
+    let cfg = setup_test_dirs("show")?;
+    let log_dir = cfg.service_path.with_file_name("log");
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    write_status(&cfg, "foo", 1, b'u', 1, Duration::from_secs(5 * 60))?;
+    fs::write(
+        cfg.proc_path.join("1/status"),
+        "VmRSS:\t1024 kB\nThreads:\t3\n",
+    )?;
+    fs::create_dir_all(cfg.service_path.join("foo/log"))?;
+    fs::create_dir_all(&log_dir)?;
+    fs::write(
+        cfg.service_path.join("foo/log/run"),
+        format!("#!/bin/sh\nexec svlogd -tt {}\n", log_dir.display()),
+    )?;
+    let lines: Vec<String> = (1..=8).map(|i| format!("line {}", i)).collect();
+    fs::write(log_dir.join("current"), lines.join("\n") + "\n")?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["show", "foo"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    for line in [
+        "service:  foo",
+        "state:    run",
+        "want:     run",
+        "enabled:  true",
+        "pid:      1",
+        "command:  foo-cmd",
+        "uptime:   5 minutes",
+        "rss:      1.0M",
+        "threads:  3",
+    ] {
+        assert!(stdout.contains(&format!("{}\n", line)), "{}", stdout);
+    }
+    assert!(
+        stdout.contains(&format!("log:      {}\n", log_dir.display())),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.ends_with(
+            "recent logs\n  line 4\n  line 5\n  line 6\n  line 7\n  line 8\n"
+        ),
+        "{}",
+        stdout
+    );
+
+    Ok(())
+}
+
+#[test]
+fn library_service_dir() -> Result<()> {
+    let cfg = setup_test_dirs("library_service_dir")?;
+
+    create_service(&cfg, "foo", "down", None, None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let dir = vsv::ServiceDir::open(&cfg.service_path)?;
+
+    let have: Vec<(String, vsv::ServiceState)> = dir
+        .services()?
+        .into_iter()
+        .map(|service| (service.name, service.state))
+        .collect();
+    assert_eq!(
+        have,
+        [
+            ("bar".to_string(), vsv::ServiceState::Down),
+            ("foo".to_string(), vsv::ServiceState::Down),
+        ]
+    );
+
+    // lazily, in directory order
+    let mut names = dir
+        .iter()
+        .map(|service| service.map(|service| service.name))
+        .collect::<Result<Vec<String>, vsv::Error>>()?;
+    names.sort();
+    assert_eq!(names, ["bar", "foo"]);
+
+    // each service is yielded once
+    let mut iter = dir.iter();
+    assert!(iter.next().is_some());
+    assert!(iter.next().is_some());
+    assert!(iter.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn library_options() -> Result<()> {
+    let cfg = setup_test_dirs("library_options")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let opts = vsv::Options {
+        proc_path: cfg.proc_path.clone(),
+        columns: vec![vsv::column::Column::Command],
+        ..Default::default()
+    };
+    let dir = vsv::ServiceDir::with_options(&cfg.service_path, opts)?;
+    let foo = dir.service("foo")?;
+    assert_eq!(foo.pid, Some(123));
+    assert_eq!(foo.command.as_deref(), Some("foo-cmd"));
+
+    // only the columns asked for are read
+    let opts = vsv::Options {
+        proc_path: cfg.proc_path.clone(),
+        columns: vec![vsv::column::Column::Pid],
+        ..Default::default()
+    };
+    let dir = vsv::ServiceDir::with_options(&cfg.service_path, opts)?;
+    assert_eq!(dir.service("foo")?.command, None);
+
+    let opts = vsv::Options {
+        proc_path: cfg.proc_path.join("nope"),
+        ..Default::default()
+    };
+    assert!(
+        matches!(
+            vsv::ServiceDir::with_options(&cfg.service_path, opts),
+            Err(vsv::Error::ProcUnavailable(_))
+        ),
+        "missing procfs"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn library_errors() -> Result<()> {
+    let cfg = setup_test_dirs("library_errors")?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    fs::create_dir(cfg.service_path.join("unsupervised"))?;
+
+    let dir = vsv::ServiceDir::open(&cfg.service_path)?;
+    assert_eq!(dir.service("foo")?.state, vsv::ServiceState::Run);
+    assert!(
+        matches!(dir.service("missing"), Err(vsv::Error::NotFound(_))),
+        "missing service"
+    );
+    assert!(
+        matches!(
+            dir.service("unsupervised"),
+            Err(vsv::Error::NotSupervised(name)) if name == "unsupervised"
+        ),
+        "unsupervised service"
+    );
+
+    let missing = cfg.service_path.join("nope");
+    assert!(
+        matches!(
+            vsv::ServiceDir::open(&missing),
+            Err(vsv::Error::NotFound(p)) if p == missing
+        ),
+        "missing service directory"
+    );
+
+    Ok(())
+}
+
+/// Wakes the thread blocked in `block_on`.
+struct ThreadWaker(thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// A tiny executor: poll the future, parking the thread until woken.
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = Box::pin(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn library_gather() -> Result<()> {
+    let cfg = setup_test_dirs("library_gather")?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let services = block_on(vsv::task::gather(&cfg.service_path))?;
+    let have: Vec<(&str, vsv::ServiceState)> =
+        services.iter().map(|s| (s.name.as_str(), s.state)).collect();
+    assert_eq!(
+        have,
+        [("bar", vsv::ServiceState::Down), ("foo", vsv::ServiceState::Run)]
+    );
+
+    let missing = block_on(vsv::task::gather(cfg.service_path.join("nope")));
+    assert!(matches!(missing, Err(vsv::Error::NotFound(_))), "missing dir");
+
+    Ok(())
+}
+
+#[test]
+fn inotify_watcher() -> Result<()> {
+    let cfg = setup_test_dirs("inotify_watcher")?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+
+    let mut watcher = vsv::inotify::Watcher::new()?;
+    assert!(watcher.watch(&[&cfg.service_path]), "everything watched");
+    assert!(!watcher.wait(Duration::from_millis(50))?, "nothing changed");
+
+    // like runsv, write a new file and rename it into place
+    let supervise = cfg.service_path.join("foo").join("supervise");
+    fs::write(supervise.join("stat.new"), "down\n")?;
+    fs::rename(supervise.join("stat.new"), supervise.join("stat"))?;
+    assert!(watcher.wait(Duration::from_secs(5))?, "state changed");
+
+    // other files in supervise aren't watched
+    fs::write(supervise.join("control"), "")?;
+    assert!(!watcher.wait(Duration::from_millis(50))?, "control written");
+
+    // new services are
+    fs::create_dir(cfg.service_path.join("bar"))?;
+    assert!(watcher.wait(Duration::from_secs(5))?, "service added");
+
+    Ok(())
+}
+
+#[test]
+fn library_subscription() -> Result<()> {
+    let cfg = setup_test_dirs("library_subscription")?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    create_service(&cfg, "bar", "run", None, None)?;
+
+    let mut subscription =
+        vsv::subscription::Subscription::new(&cfg.service_path)?;
+    assert_eq!(subscription.states().len(), 2);
+
+    let stat = cfg.service_path.join("foo").join("supervise").join("stat");
+    fs::write(&stat, "down\n")?;
+
+    let event = subscription.events().next().expect("an event")?;
+    assert_eq!(event.name, "foo");
+    assert_eq!(event.old_state, Some(vsv::ServiceState::Run));
+    assert_eq!(event.new_state, Some(vsv::ServiceState::Down));
+
+    Ok(())
+}
+
+#[cfg(feature = "vsv-ffi")]
+#[test]
+fn ffi_services_json() -> Result<()> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    use vsv::ffi;
+
+    let cfg = setup_test_dirs("ffi_services_json")?;
+
+    create_service(&cfg, "foo", "down", None, None)?;
+
+    let svdir = CString::new(cfg.service_path.as_os_str().as_bytes())?;
+
+    // too small, the needed length is returned
+    let mut buf = [0 as std::os::raw::c_char; 8];
+    let len = unsafe {
+        ffi::vsv_services_json(svdir.as_ptr(), buf.as_mut_ptr(), buf.len())
+    };
+    assert!(len >= 8, "json doesn't fit");
+    assert_eq!(buf[0], 0, "nothing written");
+
+    let mut buf = vec![0; len as usize + 1];
+    let have = unsafe {
+        ffi::vsv_services_json(svdir.as_ptr(), buf.as_mut_ptr(), buf.len())
+    };
+    assert_eq!(have, len);
+    let json = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str()?;
+    assert!(json.contains("\"name\": \"foo\""), "json: {}", json);
+    assert!(json.contains("\"state\": \"down\""), "json: {}", json);
+
+    // allocated by vsv
+    let s = unsafe { ffi::vsv_services_json_new(svdir.as_ptr()) };
+    assert_eq!(unsafe { CStr::from_ptr(s) }.to_str()?, json);
+    unsafe { ffi::vsv_string_free(s) };
+
+    // errors
+    let missing = CString::new("/nonexistent/vsv")?;
+    assert!(unsafe { ffi::vsv_services_json_new(missing.as_ptr()) }.is_null());
+    let err = unsafe { CStr::from_ptr(ffi::vsv_last_error()) }.to_str()?;
+    assert!(err.contains("not found"), "error: {}", err);
+
+    Ok(())
+}
+
+#[test]
+fn remote_host() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cfg = setup_test_dirs("remote_host")?;
+    let log = cfg.service_path.with_file_name("ssh.log");
+    let ssh = cfg.service_path.with_file_name("ssh");
+
+    // run the "remote" command here, like ssh would on the host
+    fs::write(
+        &ssh,
+        format!(
+            "#!/bin/sh\necho \"$@\" > {:?}\n\
+             while [ \"$1\" != -- ]; do shift; done\nexec sh -c \"$2\"\n",
+            log
+        ),
+    )?;
+    fs::set_permissions(&ssh, fs::Permissions::from_mode(0o755))?;
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env("SSH_PROG", &ssh)
+        .env("VSV_REMOTE_PROG", env!("CARGO_BIN_EXE_vsv"))
+        .args(["-H", "me@web1", "-o", "name,state,pid", "--no-header"])
+        .args(["-x", "b*"])
+        .assert()
+        .success();
+
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<Vec<&str>> =
+        stdout.lines().map(|l| l.split_whitespace().collect()).collect();
+    assert_eq!(lines, [["foo", "run", "123"]], "rendered locally");
+
+    let args = fs::read_to_string(&log)?;
+    assert!(args.contains("me@web1 --"), "host given to ssh: {}", args);
+    assert!(args.contains("--format snapshot"), "snapshot: {}", args);
+    assert!(args.contains("--exclude 'b*'"), "exclusion quoted: {}", args);
+
+    // the host's error is shown
+    fs::write(&ssh, "#!/bin/sh\necho 'no route to host' >&2\nexit 255\n")?;
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.env("SSH_PROG", &ssh).args(["-H", "web2"]).assert().failure();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("no route to host"), "error shown: {}", stderr);
+
+    // only status is shown remotely
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["-H", "web1", "enable", "foo"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn remote_hosts() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let cfg = setup_test_dirs("remote_hosts")?;
+    let ssh = cfg.service_path.with_file_name("ssh");
+
+    // every host is this machine, except "gone" which can't be reached
+    fs::write(
+        &ssh,
+        "#!/bin/sh\n\
+         while [ \"$1\" != -- ]; do host=$1; shift; done\n\
+         if [ \"$host\" = gone ]; then echo 'no route' >&2; exit 255; fi\n\
+         exec sh -c \"$2\"\n",
+    )?;
+    fs::set_permissions(&ssh, fs::Permissions::from_mode(0o755))?;
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let remote = |hosts: &[&str]| -> Result<Command> {
+        let mut cmd = vsv(&cfg)?;
+        cmd.env("SSH_PROG", &ssh)
+            .env("VSV_REMOTE_PROG", env!("CARGO_BIN_EXE_vsv"))
+            .args(["--no-header", "-o", "status,host,name,state"]);
+        for host in hosts {
+            cmd.args(["-H", host]);
+        }
+        Ok(cmd)
+    };
+
+    let assert = remote(&["web1", "gone", "web2"])?.assert().success();
+    let output = assert.get_output();
+    let stdout = str::from_utf8(&output.stdout)?;
+    let lines: Vec<Vec<&str>> =
+        stdout.lines().map(|l| l.split_whitespace().collect()).collect();
+    assert_eq!(
+        lines,
+        [["✔", "web1", "foo", "run"], ["✔", "web2", "foo", "run"]],
+        "merged in host order"
+    );
+    let stderr = str::from_utf8(&output.stderr)?;
+    assert!(stderr.contains("gone failed: no route"), "warned: {}", stderr);
+
+    // sorted across hosts
+    let assert = remote(&["web1", "web2"])?
+        .args(["--sort", "host", "--reverse"])
+        .assert();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.find("web2") < stdout.find("web1"), "sorted: {}", stdout);
+
+    // nothing to show
+    remote(&["gone"])?.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn config_file() -> Result<()> {
+    let cfg = setup_test_dirs("config_file")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    let other = cfg.service_path.with_file_name("other");
+    fs::create_dir_all(&config_dir)?;
+    fs::create_dir(&other)?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "agetty-tty1", "run", None, None)?;
+    fs::write(
+        config_dir.join("config.toml"),
+        "# defaults\ncolumns = ['name', 'pid']\nexclude = ['agetty-*']\n",
+    )?;
+
+    let status = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd.arg("--no-header").args(args).assert().success();
+        Ok(String::from_utf8(assert.get_output().stdout.clone())?)
+    };
+    let fields = |s: &str| -> Vec<Vec<String>> {
+        s.lines()
+            .map(|l| l.split_whitespace().map(String::from).collect())
+            .collect()
+    };
+
+    assert_eq!(fields(&status(&[])?), [["foo", "123"]], "config defaults");
+    assert_eq!(
+        fields(&status(&["-o", "name"])?),
+        [["foo"]],
+        "flags override the config"
+    );
+    assert_eq!(
+        fields(&status(&["--no-config", "-o", "name"])?),
+        [["agetty-tty1"], ["foo"]],
+        "config skipped"
+    );
+
+    // the config's dir is below SVDIR
+    fs::write(
+        config_dir.join("config.toml"),
+        format!("dir = {:?}\nlog_lines = 1\n", other),
+    )?;
+    assert_eq!(fields(&status(&["-o", "name"])?).len(), 2, "SVDIR wins");
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd
+        .env_remove("SVDIR")
+        .args(["-o", "name", "--no-header"])
+        .assert()
+        .success();
+    assert!(assert.get_output().stdout.is_empty(), "config dir used");
+
+    // a broken config file is an error
+    fs::write(config_dir.join("config.toml"), "colour = 'no'\n")?;
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.assert().failure();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("unknown setting 'colour'"), "{}", stderr);
+    status(&["--no-config"])?;
+
+    Ok(())
+}
+
+#[test]
+fn color_choice() -> Result<()> {
+    let cfg = setup_test_dirs("color_choice")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let colored = |args: &[&str], vars: &[(&str, &str)]| -> Result<bool> {
+        let mut cmd = vsv(&cfg)?;
+        let assert =
+            cmd.args(args).envs(vars.iter().copied()).assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        Ok(stdout.contains('\x1b'))
+    };
+
+    // stdout is a pipe
+    assert!(!colored(&[], &[])?, "auto");
+    assert!(!colored(&["-c", "auto"], &[])?, "auto");
+    assert!(colored(&["-c", "always"], &[])?, "always");
+    assert!(colored(&["--color", "yes"], &[])?, "yes");
+    assert!(colored(&[], &[("CLICOLOR_FORCE", "1")])?, "forced");
+    assert!(!colored(&[], &[("CLICOLOR_FORCE", "0")])?, "not forced");
+    assert!(
+        colored(&[], &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")])?,
+        "forced over NO_COLOR"
+    );
+    assert!(
+        !colored(&["-c", "never"], &[("CLICOLOR_FORCE", "1")])?,
+        "the flag wins"
+    );
+    assert!(
+        !colored(&[], &[("NO_COLOR", "1"), ("VSV_COLOR", "auto")])?,
+        "NO_COLOR"
+    );
+    assert!(
+        colored(&[], &[("NO_COLOR", ""), ("VSV_COLOR", "always")])?,
+        "empty NO_COLOR is unset"
+    );
+
+    fs::write(config_dir.join("config.toml"), "color = 'always'\n")?;
+    assert!(colored(&[], &[("CLICOLOR", "0")])?, "config over CLICOLOR");
+    assert!(!colored(&[], &[("NO_COLOR", "1")])?, "NO_COLOR over config");
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-c", "sometimes"]).assert().failure();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("expected auto, always or never"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn wide_chars() -> Result<()> {
+    let cfg = setup_test_dirs("wide_chars")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "日本語", "run", Some("456"), None)?;
+    create_service(&cfg, "web-🚀", "down", None, None)?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,state,pid"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+
+    // the state column starts at the same terminal column on every line
+    let starts: Vec<usize> = stdout
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let i = [" STATE", " run", " down"]
+                .iter()
+                .find_map(|state| line.find(state))
+                .expect("a state");
+            vsv::formatting::str_width(&line[..i])
+        })
+        .collect();
+    assert_eq!(starts.len(), 4, "{}", stdout);
+    assert!(starts.iter().all(|i| *i == starts[0]), "{:?}\n{}", starts, stdout);
+
+    // and long names are trimmed by their width
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["--format-string", "[%{name:5}]"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert_eq!(stdout, "[foo  ]\n[we...]\n[日...]\n", "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn glyphs() -> Result<()> {
+    let cfg = setup_test_dirs("glyphs")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+
+    create_service(&cfg, "bar", "down", None, None)?;
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+
+    let chars = |args: &[&str]| -> Result<Vec<String>> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd
+            .args(["--no-header", "-o", "status"])
+            .args(args)
+            .assert()
+            .success();
+        let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+        Ok(stdout.lines().map(|l| l.trim().to_string()).collect())
+    };
+
+    assert_eq!(chars(&[])?, ["X", "✔"], "unicode");
+    assert_eq!(chars(&["--glyphs", "ascii"])?, ["X", "+"], "ascii");
+    assert_eq!(
+        chars(&["--glyphs", "nerd-font"])?,
+        ["\u{f00d}", "\u{f00c}"],
+        "nerd font"
+    );
+
+    fs::write(
+        config_dir.join("config.toml"),
+        "[glyphs]\nname = 'ascii'\ndown = 'v'\n",
+    )?;
+    assert_eq!(chars(&[])?, ["v", "+"], "config");
+    assert_eq!(chars(&["--glyphs", "unicode"])?, ["v", "✔"], "flag wins");
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-config", "--glyphs", "emoji"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn themes() -> Result<()> {
+    let cfg = setup_test_dirs("themes")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    let status = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd
+            .args(["-c", "yes", "-o", "name,state"])
+            .args(args)
+            .assert()
+            .success();
+        Ok(String::from_utf8(assert.get_output().stdout.clone())?)
+    };
+
+    let stdout = status(&[])?;
+    assert!(stdout.contains("\x1b[32mrun"), "default: {:?}", stdout);
+    assert!(stdout.contains("\x1b[31mdown"), "default: {:?}", stdout);
+
+    let stdout = status(&["--theme", "high-contrast"])?;
+    assert!(stdout.contains("38;5;33mrun"), "blue: {:?}", stdout);
+    assert!(stdout.contains("38;5;208mdown"), "orange: {:?}", stdout);
+
+    let stdout = status(&["--theme", "mono"])?;
+    assert!(!stdout.contains("\x1b[3"), "no colors: {:?}", stdout);
+    assert!(stdout.contains("\x1b[1mdown"), "bold: {:?}", stdout);
+
+    // the config file picks the theme and replaces its styles
+    fs::write(
+        config_dir.join("config.toml"),
+        "[theme]\nname = 'mono'\nstates.run = 'underline 34'\n",
+    )?;
+    let stdout = status(&[])?;
+    assert!(stdout.contains("\x1b[1mdown"), "mono: {:?}", stdout);
+    assert!(stdout.contains("38;5;34mrun"), "replaced: {:?}", stdout);
+    let stdout = status(&["--theme", "default"])?;
+    assert!(stdout.contains("\x1b[31mdown"), "flag wins: {:?}", stdout);
+
+    fs::write(config_dir.join("config.toml"), "[theme]\nbad = 'rouge'\n")?;
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.assert().failure();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("unknown color"), "{}", stderr);
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-config", "--theme", "neon"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn aliases() -> Result<()> {
+    let cfg = setup_test_dirs("aliases")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+
+    create_service(&cfg, "nginx", "run", Some("123"), None)?;
+    create_service(&cfg, "php-fpm8", "run", Some("456"), None)?;
+    create_service(&cfg, "sshd", "run", Some("789"), None)?;
+    fs::write(
+        config_dir.join("config.toml"),
+        "[aliases]\nweb = \"status 'nginx|php-fpm.*'\"\n",
+    )?;
+
+    let names = |args: &[&str]| -> Result<Vec<String>> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd
+            .args(["--no-header", "-o", "name"])
+            .args(args)
+            .assert()
+            .success();
+        let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+        Ok(stdout.lines().map(|l| l.trim().to_string()).collect())
+    };
+
+    assert_eq!(names(&["web"])?, ["nginx", "php-fpm8"], "expanded");
+    assert_eq!(
+        names(&["web", "--sort", "pid", "--reverse"])?,
+        ["php-fpm8", "nginx"]
+    );
+    assert_eq!(names(&["status"])?.len(), 3, "not an alias");
+
+    // without the config the alias goes to sv like any unknown subcommand
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-config", "web"]).assert().failure();
+
+    // aliases can't shadow a built-in subcommand
+    fs::write(config_dir.join("config.toml"), "[aliases]\nstatus = '-t'\n")?;
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.assert().failure();
+    let stderr = str::from_utf8(&assert.get_output().stderr)?;
+    assert!(stderr.contains("is a built-in subcommand"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn time_units() -> Result<()> {
+    let cfg = setup_test_dirs("time_units")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    let since = Duration::from_secs(2 * 60 * 60 - 30);
+    write_status(&cfg, "foo", 1, b'u', 42, since)?;
+
+    let uptime = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd
+            .args(["--no-header", "-o", "uptime"])
+            .args(args)
+            .assert()
+            .success();
+        let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+        Ok(stdout.trim().to_string())
+    };
+
+    assert_eq!(uptime(&[])?, "1 hour", "default");
+    assert_eq!(uptime(&["--time-units", "2"])?, "1 hour 59 minutes");
+
+    fs::write(config_dir.join("config.toml"), "time_units = 3\n")?;
+    assert!(uptime(&[])?.starts_with("1 hour 59 minutes "), "config");
+    assert_eq!(uptime(&["--time-units", "1"])?, "1 hour", "flag wins");
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-config", "--time-units", "0"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn time_style() -> Result<()> {
+    let cfg = setup_test_dirs("time_style")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    let since = Duration::from_secs(3 * 24 * 60 * 60 + 4 * 60 * 60 + 30);
+    write_status(&cfg, "foo", 1, b'u', 42, since)?;
+
+    let uptime = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd
+            .args(["--no-header", "-o", "uptime"])
+            .args(args)
+            .assert()
+            .success();
+        let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+        Ok(stdout.trim().to_string())
+    };
+
+    assert_eq!(uptime(&["--time-style", "compact"])?, "3d4h");
+    assert_eq!(
+        uptime(&["--time-style", "compact", "--time-units", "1"])?,
+        "3d"
+    );
+
+    fs::write(config_dir.join("config.toml"), "time_style = 'compact'\n")?;
+    assert_eq!(uptime(&[])?, "3d4h", "config");
+    assert_eq!(uptime(&["--time-style", "relative"])?, "3 days", "flag wins");
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["--no-config", "--time-style", "short"]).assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn time_style_absolute() -> Result<()> {
+    let cfg = setup_test_dirs("time_style_absolute")?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    write_status(&cfg, "foo", 1, b'u', 42, Duration::from_secs(3 * 60 * 60))?;
+
+    let time = |style: &str, tz: &str| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd
+            .env("TZ", tz)
+            .args(["--no-header", "-o", "uptime", "--time-style", style])
+            .assert()
+            .success();
+        let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+        Ok(stdout.trim().to_string())
+    };
+
+    let iso8601 = time("iso8601", "UTC")?;
+    assert_eq!(iso8601.len(), "1970-01-01T00:00:00Z".len(), "{}", iso8601);
+    assert!(iso8601.ends_with('Z'), "{}", iso8601);
+
+    let utc = time("absolute", "UTC")?;
+    let want = iso8601.replace('T', " ");
+    assert_eq!(utc, want.trim_end_matches('Z'), "absolute in UTC");
+
+    // a POSIX TZ of 2 hours east of UTC (no tzdata needed)
+    let local = time("absolute", "XYZ-2")?;
+    assert_eq!(local.len(), utc.len(), "{}", local);
+    assert_ne!(local, utc, "local time zone used");
+
+    Ok(())
+}
+
+#[test]
+fn time_format() -> Result<()> {
+    let cfg = setup_test_dirs("time_format")?;
+    let config_dir = cfg.service_path.with_file_name("config").join("vsv");
+    fs::create_dir_all(&config_dir)?;
+
+    create_service(&cfg, "foo", "run", None, None)?;
+    write_status(&cfg, "foo", 1, b'u', 42, Duration::from_secs(3 * 60 * 60))?;
+
+    let time = |args: &[&str]| -> Result<String> {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd
+            .env("TZ", "UTC")
+            .args(["--no-header", "-o", "uptime"])
+            .args(args)
+            .assert()
+            .success();
+        let stdout = String::from_utf8(assert.get_output().stdout.clone())?;
+        Ok(stdout.trim().to_string())
+    };
+
+    // the format implies --time-style absolute
+    let absolute = time(&["--time-style", "absolute"])?;
+    let formatted = time(&["--time-format", "%Y-%m-%d %H:%M"])?;
+    assert_eq!(formatted, absolute[..16], "{}", formatted);
+    assert_eq!(time(&["--time-format", "at %H%%"])?.len(), "at 00%".len());
+
+    fs::write(config_dir.join("config.toml"), "time_format = '%Y'\n")?;
+    assert_eq!(time(&[])?, absolute[..4], "config");
+    assert_eq!(time(&["--time-style", "relative"])?, "3 hours", "ignored");
+
+    Ok(())
+}
+
+#[test]
+fn exit_code() -> Result<()> {
+    let cfg = setup_test_dirs("exit_code")?;
+
+    let code = || -> Result<Option<i32>> {
+        let mut cmd = vsv(&cfg)?;
+        let output = cmd.args(["--exit-code", "-o", "name"]).output()?;
+        Ok(output.status.code())
+    };
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    assert_eq!(code()?, Some(0), "healthy");
+
+    // each invocation sees foo with a new start time
+    for i in 0..4 {
+        let since = Duration::from_secs(100 - i * 10);
+        write_status(&cfg, "foo", 1, b'u', 123, since)?;
+        vsv(&cfg)?.assert().success();
+    }
+    assert_eq!(code()?, Some(4), "flapping");
+
+    create_service(&cfg, "bar", "down", None, None)?;
+    assert_eq!(code()?, Some(4 | 2), "flapping and down");
+    vsv(&cfg)?.assert().success();
+
+    // a service that's down on purpose is fine
+    fs::write(cfg.service_path.join("bar/down"), "")?;
+    assert_eq!(code()?, Some(4), "normally down");
+
+    create_service(&cfg, "baz", "bogus", None, None)?;
+    assert_eq!(code()?, Some(4 | 8), "flapping and unreadable");
+
+    Ok(())
+}
+
+/// Run `vsv --exit-code` and get the exit code.
+fn exit_code_of(cfg: &Config) -> Result<Option<i32>> {
+    let mut cmd = vsv(cfg)?;
+    let output = cmd.args(["--exit-code", "-o", "name"]).output()?;
+
+    Ok(output.status.code())
+}
+
+#[test]
+fn exit_code_down() -> Result<()> {
+    let cfg = setup_test_dirs("exit_code_down")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+    assert_eq!(exit_code_of(&cfg)?, Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn exit_code_flapping() -> Result<()> {
+    let cfg = setup_test_dirs("exit_code_flapping")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    for i in 0..4 {
+        let since = Duration::from_secs(100 - i * 10);
+        write_status(&cfg, "foo", 1, b'u', 123, since)?;
+        vsv(&cfg)?.assert().success();
+    }
+    assert_eq!(exit_code_of(&cfg)?, Some(4));
+
+    Ok(())
+}
+
+#[test]
+fn exit_code_unreadable() -> Result<()> {
+    let cfg = setup_test_dirs("exit_code_unreadable")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "bogus", None, None)?;
+    assert_eq!(exit_code_of(&cfg)?, Some(8), "not down as well");
+
+    Ok(())
+}
+
+#[test]
+fn check_plugin() -> Result<()> {
+    let cfg = setup_test_dirs("check_plugin")?;
+
+    let check = |args: &[&str]| -> Result<(Option<i32>, String)> {
+        let mut cmd = vsv(&cfg)?;
+        let output = cmd.arg("check-plugin").args(args).output()?;
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok((output.status.code(), stdout))
+    };
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+    fs::write(cfg.service_path.join("bar/down"), "")?;
+    let (code, stdout) = check(&[])?;
+    assert_eq!(code, Some(0), "{}", stdout);
+    assert_eq!(
+        stdout,
+        "VSV OK - 2 services healthy | \
+         total=2 run=1 down=0 flapping=0 unknown=0\n"
+    );
+
+    // bar is now down without being disabled
+    fs::remove_file(cfg.service_path.join("bar/down"))?;
+    let (code, stdout) = check(&[])?;
+    assert_eq!(code, Some(1), "{}", stdout);
+    assert!(stdout.starts_with("VSV WARNING - down: bar | "), "{}", stdout);
+    assert!(stdout.contains(" down=1 "), "{}", stdout);
+
+    let (code, stdout) = check(&["--critical-down", "bar,nope"])?;
+    assert_eq!(code, Some(2), "{}", stdout);
+    assert!(
+        stdout.starts_with("VSV CRITICAL - critical: bar, nope (missing) | "),
+        "{}",
+        stdout
+    );
+
+    // filters limit the services checked
+    let (code, stdout) = check(&["--critical-down", "bar", "foo"])?;
+    assert_eq!(code, Some(2), "missing when filtered out: {}", stdout);
+    assert_eq!(check(&["foo"])?.0, Some(0), "bar filtered out");
+
+    let mut cmd = vsv(&cfg)?;
+    let output =
+        cmd.env("SVDIR", "/nonexistent").arg("check-plugin").output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(output.status.code(), Some(3), "{}", stdout);
+    assert!(stdout.starts_with("VSV UNKNOWN - "), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn unreadable_service() -> Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let cfg = setup_test_dirs("unreadable_service")?;
+
+    create_service(&cfg, "foo", "run", Some("123"), None)?;
+    create_service(&cfg, "bar", "run", None, None)?;
+    let status = cfg.service_path.join("bar/supervise/status");
+    fs::write(&status, "corrupt")?;
+
+    // a name that isn't UTF-8 doesn't stop the listing either
+    let name = std::ffi::OsStr::from_bytes(b"ba\xffz");
+    fs::create_dir(cfg.service_path.join(name))?;
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "status,name,state"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines[2].trim_end(),
+        " E bar                  error",
+        "{}",
+        stdout
+    );
+    assert!(lines[3].contains("ba\u{fffd}z"), "{}", stdout);
+    assert!(lines[4].starts_with(" ✔ foo"), "{}", stdout);
+
+    // the errors are listed below the table
+    let errors = stdout.split("\nErrors\n").nth(1).unwrap_or_default();
+    assert!(
+        errors.starts_with("  bar: failed to parse ")
+            && errors.contains("status must be 20 bytes, got 7 bytes"),
+        "{}",
+        stdout
+    );
+
+    // the time can't be read either, it isn't the error
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-o", "name,uptime"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[2].trim_end(), " bar                  ---", "{}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["-F", "csv", "-o", "name,state"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains("\nbar,error,"), "{}", stdout);
+    assert!(!stdout.contains("Errors"), "no footnote: {}", stdout);
+
+    let mut cmd = vsv(&cfg)?;
+    let assert =
+        cmd.args(["--state", "error", "-o", "name"]).assert().success();
+    let stdout = str::from_utf8(&assert.get_output().stdout)?;
+    assert!(stdout.contains(" bar"), "{}", stdout);
+    assert!(!stdout.contains(" foo"), "{}", stdout);
+
+    Ok(())
+}
+
+#[test]
+fn completions() -> Result<()> {
+    let cfg = setup_test_dirs("completions")?;
+
+    create_service(&cfg, "foo", "run", Some("1"), None)?;
+    create_service(&cfg, "bar", "down", None, None)?;
+
+    // the scripts call `vsv completions --services` for service names
+    let mut cmd = vsv(&cfg)?;
+    let assert = cmd.args(["completions", "--services"]).assert().success();
+    assert_eq!(str::from_utf8(&assert.get_output().stdout)?, "bar\nfoo\n");
+
+    for (shell, line) in [
+        ("bash", "complete -F _vsv vsv"),
+        ("zsh", "#compdef vsv"),
+        ("fish", "complete -c vsv -f"),
+    ] {
+        let mut cmd = vsv(&cfg)?;
+        let assert = cmd.args(["completions", shell]).assert().success();
+        let stdout = str::from_utf8(&assert.get_output().stdout)?;
+        assert!(stdout.contains(line), "{}: {}", shell, stdout);
+        assert!(stdout.contains("completions --services"), "{}", shell);
+    }
+
+    let mut cmd = vsv(&cfg)?;
+    cmd.args(["completions", "powershell"]).assert().failure();
+    let mut cmd = vsv(&cfg)?;
+    cmd.arg("completions").assert().failure();
+
+    Ok(())
+}